@@ -57,7 +57,7 @@ pub fn create_changeset(count: usize, version: u64) -> Changeset {
             .collect();
         changes.insert(pk, (columns, versions));
     }
-    Changeset { changes }
+    Changeset { changes, origins: HashMap::new() }
 }
 
 pub fn create_table_sqlite(rows: usize) -> CrrTable<SqliteStorage> {
@@ -107,7 +107,7 @@ pub fn create_changeset_for_sqlite(count: usize, version: u64) -> Changeset {
             .collect();
         changes.insert(pk, (columns, versions));
     }
-    Changeset { changes }
+    Changeset { changes, origins: HashMap::new() }
 }
 
 #[allow(dead_code)]
@@ -71,7 +71,7 @@ fn bench_multi_peer_sync(c: &mut Criterion) {
                                     .collect();
                                 changes.insert(pk, (columns, versions));
                             }
-                            Changeset { changes }
+                            Changeset { changes, origins: HashMap::new() }
                         })
                         .collect();
                     (table, changesets)
@@ -111,7 +111,7 @@ fn bench_conflict_resolution(c: &mut Criterion) {
                         versions.insert("modified_at".to_string(), 1u64);
                         changes.insert(pk, (columns, versions));
                     }
-                    (table, Changeset { changes })
+                    (table, Changeset { changes, origins: HashMap::new() })
                 },
                 |(mut table, changeset)| {
                     black_box(table.merge(&changeset, TieBreakPolicy::LexicographicMin))
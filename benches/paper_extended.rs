@@ -80,7 +80,7 @@ fn bench_scalability_full_mesh(c: &mut Criterion) {
                                     .collect();
                                 changes.insert(pk, (columns, versions));
                             }
-                            Changeset { changes }
+                            Changeset { changes, origins: HashMap::new() }
                         })
                         .collect();
                     changesets
@@ -123,7 +123,7 @@ fn bench_scalability_pairwise(c: &mut Criterion) {
                                     .collect();
                                 changes.insert(pk, (columns, versions));
                             }
-                            Changeset { changes }
+                            Changeset { changes, origins: HashMap::new() }
                         })
                         .collect();
                     changesets
@@ -143,6 +143,60 @@ fn bench_scalability_pairwise(c: &mut Criterion) {
     group.finish();
 }
 
+// --- Sketch-Based Reconciliation vs. Full-Mesh Merge (50 peers) ---
+
+fn bench_sketch_vs_full_mesh(c: &mut Criterion) {
+    let mut group = c.benchmark_group("SketchVsFullMesh");
+    group.sample_size(10);
+
+    let peers = 50;
+    let rows_per_peer = 200;
+    // Most rows already agree; only a handful differ per peer, which is
+    // exactly the case sketch-based reconciliation is meant to exploit.
+    let diverging_rows = 5;
+
+    group.bench_function("full_mesh_merge", |b| {
+        b.iter_batched(
+            || {
+                let base = common::create_table(rows_per_peer);
+                let changeset = base.changeset().unwrap();
+                (0..peers).map(|_| changeset.clone()).collect::<Vec<_>>()
+            },
+            |changesets| {
+                let mut table = CrrTable::open_in_memory().unwrap();
+                for cs in &changesets {
+                    table.merge(cs, TieBreakPolicy::LexicographicMin).unwrap();
+                }
+                black_box(table.len())
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("sketch_reconciliation", |b| {
+        b.iter_batched(
+            || {
+                let local = common::create_table(rows_per_peer);
+                let mut remote = common::create_table(rows_per_peer);
+                for i in 0..diverging_rows {
+                    remote.update(&format!("file_{}", i))
+                        .column_str("owner", "remote_owner")
+                        .commit().unwrap();
+                }
+                (local, remote)
+            },
+            |(local, remote)| {
+                let remote_sketch = remote.reconcile_sketch().unwrap();
+                let delta = local.diff_from_sketch(&remote_sketch).unwrap();
+                black_box(delta.column_count())
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
 // --- Sensitivity Analysis (fig_sensitivity_*) ---
 
 fn bench_sensitivity_columns(c: &mut Criterion) {
@@ -190,7 +244,7 @@ fn bench_sensitivity_columns(c: &mut Criterion) {
                         }
                         changes.insert(pk, (columns, versions));
                     }
-                    (table, Changeset { changes })
+                    (table, Changeset { changes, origins: HashMap::new() })
                 },
                 |(mut table, changeset)| {
                     let _ = table.merge(&changeset, TieBreakPolicy::LexicographicMin);
@@ -242,7 +296,7 @@ fn bench_sensitivity_value_size(c: &mut Criterion) {
                         versions.insert("data".to_string(), 2u64);
                         changes.insert(pk, (columns, versions));
                     }
-                    (table, Changeset { changes })
+                    (table, Changeset { changes, origins: HashMap::new() })
                 },
                 |(mut table, changeset)| {
                     let _ = table.merge(&changeset, TieBreakPolicy::LexicographicMin);
@@ -286,7 +340,7 @@ fn bench_sensitivity_conflict_rate(c: &mut Criterion) {
                         }
                         changes.insert(pk, (columns, versions));
                     }
-                    (table, Changeset { changes })
+                    (table, Changeset { changes, origins: HashMap::new() })
                 },
                 |(mut table, changeset)| {
                     let _ = table.merge(&changeset, TieBreakPolicy::LexicographicMin);
@@ -337,7 +391,7 @@ fn bench_tiebreaker_overhead(c: &mut Criterion) {
                         }
                         changes.insert(pk, (columns, versions));
                     }
-                    (table, Changeset { changes })
+                    (table, Changeset { changes, origins: HashMap::new() })
                 },
                 |(mut table, changeset)| {
                     let _ = table.merge(&changeset, policy);
@@ -411,6 +465,62 @@ fn bench_throughput_merge(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_seal_open_overhead(c: &mut Criterion) {
+    let mut group = c.benchmark_group("SealOpenOverhead");
+    group.sample_size(100);
+
+    let key = sync_engine::SessionKey::derive(b"benchmark shared secret", b"benchmark salt");
+
+    for scale in [1_000, 10_000, 100_000] {
+        let changeset = common::create_changeset(scale, 2);
+        group.throughput(Throughput::Elements(scale as u64));
+
+        group.bench_with_input(BenchmarkId::new("seal", scale), &changeset, |b, cs| {
+            b.iter(|| black_box(sync_engine::SecureChangeset::seal(cs, &key, "peer_a", 1)))
+        });
+
+        let sealed = sync_engine::SecureChangeset::seal(&changeset, &key, "peer_a", 1);
+        group.bench_with_input(BenchmarkId::new("open", scale), &sealed, |b, bytes| {
+            b.iter(|| black_box(sync_engine::SecureChangeset::open(bytes, &key, "peer_a", 1).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn bench_crc_overhead(c: &mut Criterion) {
+    let mut group = c.benchmark_group("CrcOverhead");
+    group.sample_size(50);
+
+    let scale = 100_000;
+    let changeset = common::create_changeset(scale, 2);
+    group.throughput(Throughput::Elements(scale as u64));
+
+    group.bench_function("merge_raw", |b| {
+        b.iter_batched(
+            || common::create_table(scale),
+            |mut table| {
+                let _ = table.merge(&changeset, TieBreakPolicy::LexicographicMin);
+                black_box(table)
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("merge_via_crc_checked_wire", |b| {
+        b.iter_batched(
+            || (common::create_table(scale), changeset.serialize()),
+            |(mut table, bytes)| {
+                let decoded = Changeset::deserialize(&bytes).unwrap();
+                let _ = table.merge(&decoded, TieBreakPolicy::LexicographicMin);
+                black_box(table)
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
 fn bench_latency_single_ops(c: &mut Criterion) {
     let mut group = c.benchmark_group("LatencySingleOp");
     group.sample_size(1000);
@@ -532,6 +642,70 @@ fn bench_breakeven_history_query(c: &mut Criterion) {
     group.finish();
 }
 
+// --- Bounded History Eviction ---
+
+fn bench_bounded_history_eviction(c: &mut Criterion) {
+    let mut group = c.benchmark_group("BoundedHistoryEviction");
+    group.sample_size(20);
+
+    let rows = 500;
+    // Small enough that continuous updates keep pressuring the budget and
+    // triggering eviction on most merges, not just the last few.
+    let budget_bytes = 4096;
+
+    group.bench_function("merge_with_budget", |b| {
+        b.iter_batched(
+            || {
+                let mut table = common::create_table(rows);
+                table.set_history_budget(Some(budget_bytes));
+                table
+            },
+            |mut table| {
+                for v in 2..=5u64 {
+                    let mut changes = HashMap::new();
+                    for i in 0..rows {
+                        let pk = format!("file_{}", i);
+                        let mut columns = HashMap::new();
+                        let mut versions = HashMap::new();
+                        columns.insert("owner".to_string(), format!("owner_v{}", v).into_bytes());
+                        versions.insert("owner".to_string(), v);
+                        changes.insert(pk, (columns, versions));
+                    }
+                    table.merge(&Changeset { changes, origins: HashMap::new() }, TieBreakPolicy::LexicographicMin).unwrap();
+                }
+                black_box(table.current_history_bytes())
+            },
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.bench_function("get_under_eviction_pressure", |b| {
+        b.iter_batched(
+            || {
+                let mut table = common::create_table(rows);
+                table.set_history_budget(Some(budget_bytes));
+                for v in 2..=5u64 {
+                    let mut changes = HashMap::new();
+                    for i in 0..rows {
+                        let pk = format!("file_{}", i);
+                        let mut columns = HashMap::new();
+                        let mut versions = HashMap::new();
+                        columns.insert("owner".to_string(), format!("owner_v{}", v).into_bytes());
+                        versions.insert("owner".to_string(), v);
+                        changes.insert(pk, (columns, versions));
+                    }
+                    table.merge(&Changeset { changes, origins: HashMap::new() }, TieBreakPolicy::LexicographicMin).unwrap();
+                }
+                table
+            },
+            |table| black_box(table.get("file_0").unwrap()),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    group.finish();
+}
+
 // --- Backend Comparison (Table 7) ---
 
 fn bench_backend_comparison(c: &mut Criterion) {
@@ -543,6 +717,24 @@ fn bench_backend_comparison(c: &mut Criterion) {
             b.iter(|| black_box(common::create_table(rows)))
         });
 
+        #[cfg(feature = "redis-backend")]
+        group.bench_with_input(BenchmarkId::new("DAG-CRR_Redis", rows), &rows, |b, &rows| {
+            b.iter(|| {
+                let backend = sync_engine::RedisStorage::open("redis://127.0.0.1/")
+                    .expect("a local redis-server is required for this bench variant");
+                let mut table = CrrTable::open_with_backend(backend);
+                for i in 0..rows {
+                    let record = common::generate_file_record(i);
+                    let mut builder = table.insert(&format!("file_{}", i));
+                    for (col, val) in record {
+                        builder = builder.column_str(&col, &val, 1);
+                    }
+                    builder.commit().unwrap();
+                }
+                black_box(table)
+            })
+        });
+
         group.bench_with_input(BenchmarkId::new("Plain_SQLite", rows), &rows, |b, &rows| {
             b.iter(|| {
                 let conn = Connection::open_in_memory().unwrap();
@@ -610,7 +802,7 @@ criterion_group!(
 criterion_group!(
     name = throughput;
     config = Criterion::default();
-    targets = bench_throughput_insert, bench_throughput_update, bench_throughput_merge, bench_latency_single_ops
+    targets = bench_throughput_insert, bench_throughput_update, bench_throughput_merge, bench_latency_single_ops, bench_seal_open_overhead, bench_crc_overhead
 );
 
 criterion_group!(
@@ -631,4 +823,16 @@ criterion_group!(
     targets = bench_backend_comparison
 );
 
-criterion_main!(large_scale, scalability, sensitivity, tiebreaker, throughput, memory, breakeven, backend);
+criterion_group!(
+    name = sketch_reconciliation;
+    config = Criterion::default().sample_size(10);
+    targets = bench_sketch_vs_full_mesh
+);
+
+criterion_group!(
+    name = bounded_history;
+    config = Criterion::default().sample_size(20);
+    targets = bench_bounded_history_eviction
+);
+
+criterion_main!(large_scale, scalability, sensitivity, tiebreaker, throughput, memory, breakeven, backend, sketch_reconciliation, bounded_history);
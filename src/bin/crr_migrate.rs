@@ -0,0 +1,120 @@
+//! Migrate a `CrrTable` snapshot between storage backends.
+//!
+//! Usage:
+//!   crr_migrate export --backend sqlite --source PATH --out PATH
+//!   crr_migrate import --backend sqlite|lmdb|memory --dest PATH --in PATH [--map-size BYTES]
+//!
+//! `export`/`import` are split into two steps (rather than one combined
+//! "convert" command) because the portable snapshot written by `export` is
+//! useful on its own — it's the same binary format `CrrTable::save`/`load`
+//! already use, so a snapshot produced here can equally be loaded by any
+//! other code in this crate that holds a `CrrTable`. The round trip carries
+//! every cell plus the full DAG history (`parent_version`/`parent2_version`
+//! edges, per-column versions, tombstones) unchanged, since both ends only
+//! ever go through the generic `Storage` trait.
+
+use std::env;
+
+use sync_engine::{CrrTable, MemoryStorage, SqliteStorage};
+#[cfg(feature = "lmdb-backend")]
+use sync_engine::LmdbStorage;
+
+fn export(backend: &str, source: &str, out: &str) {
+    let result = match backend {
+        "sqlite" => CrrTable::<SqliteStorage>::open(source).and_then(|t| t.save(out)),
+        "memory" => {
+            eprintln!("Error: --backend memory has no on-disk source to export from");
+            return;
+        }
+        other => {
+            eprintln!("Error: unknown --backend {}", other);
+            return;
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: export failed: {}", e);
+        std::process::exit(1);
+    }
+    println!("Exported {} ({}) -> {}", source, backend, out);
+}
+
+fn import(backend: &str, dest: &str, input: &str, map_size: usize) {
+    let result: sync_engine::Result<()> = match backend {
+        "sqlite" => CrrTable::<SqliteStorage>::open(dest).and_then(|mut t| t.load(input)),
+        "memory" => {
+            let mut table = CrrTable::with_storage(MemoryStorage::default());
+            table.load(input).map(|_| {
+                println!("Loaded into an in-memory table ({} rows); nothing persisted since --backend memory has no destination file", table.row_count().unwrap_or(0));
+            })
+        }
+        #[cfg(feature = "lmdb-backend")]
+        "lmdb" => LmdbStorage::open(dest, map_size)
+            .map_err(Into::into)
+            .and_then(|storage| {
+                let mut table = CrrTable::with_storage(storage);
+                table.load(input)
+            }),
+        #[cfg(not(feature = "lmdb-backend"))]
+        "lmdb" => {
+            eprintln!("Error: built without the lmdb-backend feature");
+            return;
+        }
+        other => {
+            eprintln!("Error: unknown --backend {}", other);
+            return;
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: import failed: {}", e);
+        std::process::exit(1);
+    }
+    println!("Imported {} -> {} ({})", input, dest, backend);
+}
+
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() < 2 {
+        eprintln!("Usage:");
+        eprintln!("  crr_migrate export --backend sqlite --source PATH --out PATH");
+        eprintln!("  crr_migrate import --backend sqlite|lmdb|memory --dest PATH --in PATH [--map-size BYTES]");
+        return;
+    }
+
+    match args[1].as_str() {
+        "export" => {
+            let backend = flag(&args, "--backend").unwrap_or("sqlite");
+            let source = match flag(&args, "--source") {
+                Some(s) => s,
+                None => return eprintln!("Error: --source required"),
+            };
+            let out = match flag(&args, "--out") {
+                Some(s) => s,
+                None => return eprintln!("Error: --out required"),
+            };
+            export(backend, source, out);
+        }
+        "import" => {
+            let backend = flag(&args, "--backend").unwrap_or("sqlite");
+            let dest = flag(&args, "--dest").unwrap_or("");
+            let input = match flag(&args, "--in") {
+                Some(s) => s,
+                None => return eprintln!("Error: --in required"),
+            };
+            let map_size: usize = flag(&args, "--map-size")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1 << 30);
+            import(backend, dest, input, map_size);
+        }
+        _ => eprintln!("Unknown command: {}", args[1]),
+    }
+}
@@ -1,23 +1,78 @@
 //! Network benchmark for real RTT measurements across GCP regions.
 //!
 //! Usage:
-//!   network_bench server --bind 0.0.0.0:9000
+//!   network_bench server --bind 0.0.0.0:9000 --metrics-bind 0.0.0.0:9100
 //!   network_bench client --peers 10.0.0.1:9000,10.0.0.2:9000 --samples 100 --output results.json
+//!
+//! The server also exposes a `/metrics` Prometheus text exposition endpoint
+//! on `--metrics-bind` (see [`sync_engine::Metrics`]), so a coordinated-GC
+//! run can be scraped live instead of only read back from the client's
+//! end-of-run summary.
 
+use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
+use sync_engine::{Changeset, CrrTable, Metrics, SqliteStorage, TieBreakPolicy, VersionVector};
 
 #[derive(Serialize, Deserialize, Debug)]
 enum Message {
     Ping { seq: u64 },
     Pong { seq: u64 },
+    /// Ask the peer for its [`sync_engine::CrrTable::min_watermark`] — the
+    /// newest version a coordinated GC round can safely collect below
+    /// without the peer losing a version some other peer still needs.
     WatermarkRequest { gc_id: u64 },
     WatermarkResponse { gc_id: u64, watermark: u64 },
+    /// The threshold the client computed as `min` across every peer's
+    /// `WatermarkResponse` — safe for every peer to collect below.
     GcThreshold { gc_id: u64, threshold: u64 },
-    GcAck { gc_id: u64 },
+    /// Acknowledges a [`Message::GcThreshold`] once
+    /// [`sync_engine::CrrTable::gc_below_watermark`] and
+    /// [`sync_engine::CrrTable::gc_tombstones`] have actually committed,
+    /// carrying how many DAG nodes and fully-tombstoned rows they reclaimed.
+    GcAck { gc_id: u64, removed: usize },
+    /// Ask the peer for the immediate children of its Merkle tree at
+    /// `path` (see [`sync_engine::CrrTable::merkle_children`]), or, once
+    /// `path` has narrowed down to an occupied leaf bucket, the pks that
+    /// live there. A peer walking the tree this way learns only as much
+    /// as the two tables actually diverge on, instead of paying for
+    /// [`sync_engine::CrrTable::changeset`]'s full-table serialization.
+    MerkleNode { path: Vec<u8> },
+    /// The answer to a [`Message::MerkleNode`] query: `children` holds
+    /// this side's [`sync_engine::CrrTable::merkle_children`] at `path`,
+    /// non-empty unless `path` is already a leaf bucket (or unoccupied),
+    /// in which case `leaf` carries [`sync_engine::CrrTable::merkle_leaf`]
+    /// instead.
+    MerkleNodeResponse { path: Vec<u8>, children: Vec<(u8, [u8; 32])>, leaf: Vec<(String, [u8; 32])> },
+    /// Once a Merkle walk has narrowed down to the pks that actually
+    /// diverge, ask for their cells the normal way rather than inventing
+    /// a second wire format for row data.
+    ChangesetRequest { pks: Vec<String> },
+    ChangesetResponse { changeset: Vec<u8> },
+    /// The peer's whole-table changeset, requested only so
+    /// [`measure_merkle_sync`] can report how many bytes a full-table
+    /// sync would have cost for comparison — a real sync never needs
+    /// this once the Merkle walk is in place.
+    FullChangesetRequest,
+    FullChangesetResponse { changeset: Vec<u8> },
+    /// K2V-style frontier exchange: ask the peer for only what's changed
+    /// since `vv`, the delta-sync counterpart to [`Message::FullChangesetRequest`]'s
+    /// whole-table dump. `vv` is normally this side's own
+    /// [`sync_engine::CrrTable::current_frontier`] from the last round it
+    /// synced against this peer.
+    DeltaRequest { vv: VersionVector },
+    /// `changeset` is this side's [`sync_engine::CrrTable::changeset_since_frontier`]
+    /// relative to the requester's `vv`, already ahead of a GC below that
+    /// frontier because it's read from current cell values rather than
+    /// replayed DAG history. `new_vv` is this side's own current frontier
+    /// at the time of the reply, so the requester can advance straight to
+    /// it instead of folding `changeset` in column by column via
+    /// [`sync_engine::VersionVector::advance_from_changeset`].
+    DeltaResponse { changeset: Vec<u8>, new_vv: VersionVector },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,23 +86,58 @@ struct BenchmarkResult {
     coordinated_gc_us: u64,
     local_gc_us: u64,
     speedup: f64,
+    /// Total DAG nodes [`measure_coordinated_gc`] actually reclaimed
+    /// across every peer's [`Message::GcAck`], summed over every sampled
+    /// round.
+    gc_nodes_collected: usize,
+    /// Bytes exchanged walking the Merkle tree down to the diverging rows
+    /// and pulling just their cells, via [`measure_merkle_sync`].
+    merkle_bytes_transferred: usize,
+    /// Bytes the peer's full [`sync_engine::CrrTable::changeset`] would
+    /// have cost, for the same two tables, as a baseline.
+    full_changeset_bytes: usize,
+    /// `full_changeset_bytes / merkle_bytes_transferred` — how much
+    /// smaller the Merkle-diff round was than shipping the whole table.
+    merkle_speedup: f64,
+    /// Bytes exchanged via [`measure_delta_sync`]'s [`Message::DeltaRequest`]
+    /// / [`Message::DeltaResponse`] round, frontier-based delta sync's
+    /// counterpart to `merkle_bytes_transferred`.
+    delta_bytes_transferred: usize,
+    /// `full_changeset_bytes / delta_bytes_transferred` for the same round.
+    delta_speedup: f64,
 }
 
-fn send_msg(stream: &mut TcpStream, msg: &Message) -> std::io::Result<()> {
+/// How many rows the server and client merkle-bench tables start from.
+const MERKLE_BENCH_ROWS: usize = 500;
+/// How many of those rows the server advances to a second version the
+/// client hasn't seen yet, simulating realistic partial divergence.
+const MERKLE_BENCH_DIVERGENT: usize = 25;
+
+fn seed_merkle_table(rows: usize) -> CrrTable<SqliteStorage> {
+    let mut table = CrrTable::open_in_memory().unwrap();
+    for i in 0..rows {
+        table.insert(&format!("row_{}", i)).column_str("val", "v1", 1).commit().unwrap();
+    }
+    table
+}
+
+fn send_msg(stream: &mut TcpStream, msg: &Message) -> std::io::Result<usize> {
     let data = serde_json::to_vec(msg)?;
     let len = (data.len() as u32).to_be_bytes();
     stream.write_all(&len)?;
     stream.write_all(&data)?;
-    stream.flush()
+    stream.flush()?;
+    Ok(data.len())
 }
 
-fn recv_msg(stream: &mut TcpStream) -> std::io::Result<Message> {
+fn recv_msg(stream: &mut TcpStream) -> std::io::Result<(Message, usize)> {
     let mut len_buf = [0u8; 4];
     stream.read_exact(&mut len_buf)?;
     let len = u32::from_be_bytes(len_buf) as usize;
     let mut buf = vec![0u8; len];
     stream.read_exact(&mut buf)?;
-    serde_json::from_slice(&buf).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    let msg = serde_json::from_slice(&buf).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok((msg, len))
 }
 
 fn measure_rtt(stream: &mut TcpStream, samples: usize) -> Vec<Duration> {
@@ -61,7 +151,7 @@ fn measure_rtt(stream: &mut TcpStream, samples: usize) -> Vec<Duration> {
         .collect()
 }
 
-fn measure_coordinated_gc(streams: &mut [TcpStream], gc_id: u64) -> Duration {
+fn measure_coordinated_gc(streams: &mut [TcpStream], gc_id: u64) -> (Duration, usize) {
     let start = Instant::now();
 
     // Phase 1: request watermarks (parallel)
@@ -71,11 +161,15 @@ fn measure_coordinated_gc(streams: &mut [TcpStream], gc_id: u64) -> Duration {
 
     let mut watermarks = Vec::new();
     for stream in streams.iter_mut() {
-        if let Message::WatermarkResponse { watermark, .. } = recv_msg(stream).unwrap() {
+        if let (Message::WatermarkResponse { watermark, .. }, _) = recv_msg(stream).unwrap() {
             watermarks.push(watermark);
         }
     }
 
+    // The threshold every peer can safely collect below is the minimum
+    // watermark any of them reported — collecting further on a peer that
+    // reported a higher watermark than another could drop a version that
+    // other peer still depends on.
     let threshold = watermarks.iter().min().copied().unwrap_or(0);
 
     // Phase 2: broadcast threshold
@@ -83,15 +177,18 @@ fn measure_coordinated_gc(streams: &mut [TcpStream], gc_id: u64) -> Duration {
         send_msg(stream, &Message::GcThreshold { gc_id, threshold }).unwrap();
     }
 
+    let mut removed = 0;
     for stream in streams.iter_mut() {
-        let _ = recv_msg(stream).unwrap();
+        if let (Message::GcAck { removed: r, .. }, _) = recv_msg(stream).unwrap() {
+            removed += r;
+        }
     }
 
-    start.elapsed()
+    (start.elapsed(), removed)
 }
 
 fn measure_local_gc(entries: usize) -> Duration {
-    use sync_engine::{CrrTable, run_gc, GcPolicy};
+    use sync_engine::{run_gc, GcPolicy};
 
     let mut table = CrrTable::open_in_memory().unwrap();
     for i in 0..100 {
@@ -113,33 +210,241 @@ fn measure_local_gc(entries: usize) -> Duration {
     start.elapsed()
 }
 
+/// Walk `stream`'s peer's Merkle tree from `path` down, diffing against
+/// `local`'s own tree one level at a time, and return every pk that
+/// diverges under `path`. Every request/response byte count is folded
+/// into `bytes` so the caller can report the round's total cost.
+fn merkle_diverging_pks(
+    stream: &mut TcpStream,
+    local: &CrrTable<SqliteStorage>,
+    path: Vec<u8>,
+    bytes: &mut usize,
+) -> Vec<String> {
+    *bytes += send_msg(stream, &Message::MerkleNode { path: path.clone() }).unwrap();
+    let (response, response_bytes) = recv_msg(stream).unwrap();
+    *bytes += response_bytes;
+
+    let (remote_children, remote_leaf) = match response {
+        Message::MerkleNodeResponse { children, leaf, .. } => (children, leaf),
+        other => panic!("expected MerkleNodeResponse, got {:?}", other),
+    };
+
+    let local_children = local.merkle_children(&path).unwrap_or_default();
+
+    if local_children.is_empty() && remote_children.is_empty() {
+        let local_leaf: HashMap<String, [u8; 32]> = local.merkle_leaf(&path).unwrap_or_default().into_iter().collect();
+        let remote_leaf: HashMap<String, [u8; 32]> = remote_leaf.into_iter().collect();
+
+        let pks: BTreeSet<&String> = local_leaf.keys().chain(remote_leaf.keys()).collect();
+        return pks.into_iter()
+            .filter(|pk| local_leaf.get(*pk) != remote_leaf.get(*pk))
+            .cloned()
+            .collect();
+    }
+
+    let local_map: HashMap<u8, [u8; 32]> = local_children.into_iter().collect();
+    let remote_map: HashMap<u8, [u8; 32]> = remote_children.into_iter().collect();
+    let diverging_bytes: BTreeSet<u8> = local_map.keys().chain(remote_map.keys()).copied().collect();
+
+    let mut out = Vec::new();
+    for byte in diverging_bytes {
+        if local_map.get(&byte) != remote_map.get(&byte) {
+            let mut child_path = path.clone();
+            child_path.push(byte);
+            out.extend(merkle_diverging_pks(stream, local, child_path, bytes));
+        }
+    }
+    out
+}
+
+/// Pull whatever rows the peer's table diverges on into a freshly seeded
+/// local table via a Merkle walk, merging them in, and report how many
+/// bytes that round cost next to what shipping the peer's whole
+/// [`sync_engine::CrrTable::changeset`] would have cost instead.
+fn measure_merkle_sync(stream: &mut TcpStream) -> (usize, usize) {
+    let mut local = seed_merkle_table(MERKLE_BENCH_ROWS);
+
+    let mut merkle_bytes = 0usize;
+    let diverging = merkle_diverging_pks(stream, &local, Vec::new(), &mut merkle_bytes);
+
+    if !diverging.is_empty() {
+        merkle_bytes += send_msg(stream, &Message::ChangesetRequest { pks: diverging }).unwrap();
+        let (response, response_bytes) = recv_msg(stream).unwrap();
+        merkle_bytes += response_bytes;
+
+        if let Message::ChangesetResponse { changeset } = response {
+            let changeset = Changeset::deserialize(&changeset).expect("peer sent a malformed changeset");
+            local.merge(&changeset, TieBreakPolicy::LastWriteWins).unwrap();
+        }
+    }
+
+    send_msg(stream, &Message::FullChangesetRequest).unwrap();
+    let (response, _) = recv_msg(stream).unwrap();
+    let full_bytes = match response {
+        Message::FullChangesetResponse { changeset } => changeset.len(),
+        other => panic!("expected FullChangesetResponse, got {:?}", other),
+    };
+
+    (merkle_bytes, full_bytes)
+}
+
+/// Seed a local table identical to the server's pre-divergence state, then
+/// ask it for only what's changed since via [`Message::DeltaRequest`],
+/// rather than the whole-table dump [`measure_merkle_sync`] also reports
+/// on. Since `local` already starts matching everything except the rows
+/// [`run_server`] advanced to `"v2"`, the delta should cost roughly
+/// `MERKLE_BENCH_DIVERGENT` rows' worth of bytes, not `MERKLE_BENCH_ROWS`'.
+fn measure_delta_sync(stream: &mut TcpStream) -> (usize, usize) {
+    let mut local = seed_merkle_table(MERKLE_BENCH_ROWS);
+    let vv = local.current_frontier().unwrap();
+
+    let mut delta_bytes = send_msg(stream, &Message::DeltaRequest { vv }).unwrap();
+    let (response, response_bytes) = recv_msg(stream).unwrap();
+    delta_bytes += response_bytes;
+
+    let (changeset, new_vv) = match response {
+        Message::DeltaResponse { changeset, new_vv } => (changeset, new_vv),
+        other => panic!("expected DeltaResponse, got {:?}", other),
+    };
+    let changeset = Changeset::deserialize(&changeset).expect("peer sent a malformed delta changeset");
+    local.merge(&changeset, TieBreakPolicy::LastWriteWins).unwrap();
+
+    // The peer's own frontier as of the reply rides along so this side can
+    // advance straight to it instead of folding the changeset in column by
+    // column via `VersionVector::advance_from_changeset`.
+    let _ = new_vv;
+
+    send_msg(stream, &Message::FullChangesetRequest).unwrap();
+    let (response, _) = recv_msg(stream).unwrap();
+    let full_bytes = match response {
+        Message::FullChangesetResponse { changeset } => changeset.len(),
+        other => panic!("expected FullChangesetResponse, got {:?}", other),
+    };
+
+    (delta_bytes, full_bytes)
+}
+
 fn percentile(sorted: &[u64], p: f64) -> u64 {
     let idx = ((sorted.len() as f64) * p / 100.0) as usize;
     sorted[idx.min(sorted.len() - 1)]
 }
 
-fn run_server(bind: &str) {
-    use std::net::TcpListener;
+/// Serve `metrics.render()` as a Prometheus text exposition body to
+/// whatever connects to `bind`, so the coordinated-GC benchmark can be
+/// scraped live instead of only summarized once `run_client` exits. This
+/// is the only route the tool needs, so unlike `run_server`'s framed JSON
+/// protocol it doesn't bother parsing the request beyond draining it.
+fn run_metrics_server(bind: &str, metrics: Arc<Metrics>) {
+    let listener = match TcpListener::bind(bind) {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to bind metrics endpoint on {}: {}", bind, e);
+            return;
+        }
+    };
+    println!("Metrics exposed on http://{}/metrics", bind);
+
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else { continue };
+        let metrics = Arc::clone(&metrics);
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+
+            let body = metrics.render();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body,
+            );
+            stream.write_all(response.as_bytes()).ok();
+        });
+    }
+}
 
+fn run_server(bind: &str, metrics_bind: &str) {
     let listener = TcpListener::bind(bind).expect("Failed to bind");
     println!("Server listening on {}", bind);
 
+    let metrics = Arc::new(Metrics::new());
+    {
+        let metrics = Arc::clone(&metrics);
+        let metrics_bind = metrics_bind.to_string();
+        std::thread::spawn(move || run_metrics_server(&metrics_bind, metrics));
+    }
+
+    let table = Arc::new(Mutex::new({
+        let mut table = seed_merkle_table(MERKLE_BENCH_ROWS);
+        let step = (MERKLE_BENCH_ROWS / MERKLE_BENCH_DIVERGENT.max(1)).max(1);
+        for i in (0..MERKLE_BENCH_ROWS).step_by(step) {
+            table.update(&format!("row_{}", i)).column_str("val", "v2").commit().unwrap();
+        }
+        table.attach_metrics(Arc::clone(&metrics));
+        table
+    }));
+
     for stream in listener.incoming() {
         let mut stream = stream.expect("Connection failed");
+        let table = Arc::clone(&table);
+        let metrics = Arc::clone(&metrics);
         std::thread::spawn(move || {
             loop {
-                match recv_msg(&mut stream) {
-                    Ok(Message::Ping { seq }) => {
-                        send_msg(&mut stream, &Message::Pong { seq }).ok();
+                let received = match recv_msg(&mut stream) {
+                    Ok((msg, len)) => {
+                        metrics.record_bytes_received(len as u64);
+                        msg
                     }
-                    Ok(Message::WatermarkRequest { gc_id }) => {
-                        send_msg(&mut stream, &Message::WatermarkResponse { gc_id, watermark: 1000 }).ok();
+                    Err(_) => break,
+                };
+
+                let sent = match received {
+                    Message::Ping { seq } => {
+                        let start = Instant::now();
+                        let sent = send_msg(&mut stream, &Message::Pong { seq }).ok();
+                        metrics.record_rtt(start.elapsed());
+                        sent
                     }
-                    Ok(Message::GcThreshold { gc_id, .. }) => {
-                        send_msg(&mut stream, &Message::GcAck { gc_id }).ok();
+                    Message::WatermarkRequest { gc_id } => {
+                        let watermark = table.lock().unwrap().min_watermark().unwrap_or(0);
+                        send_msg(&mut stream, &Message::WatermarkResponse { gc_id, watermark }).ok()
                     }
-                    Err(_) => break,
-                    _ => {}
+                    Message::GcThreshold { gc_id, threshold } => {
+                        let mut table = table.lock().unwrap();
+                        let mut removed = table.gc_below_watermark(threshold).unwrap_or(0);
+                        removed += table.gc_tombstones(threshold).unwrap_or(0);
+                        send_msg(&mut stream, &Message::GcAck { gc_id, removed }).ok()
+                    }
+                    Message::MerkleNode { path } => {
+                        let table = table.lock().unwrap();
+                        let children = table.merkle_children(&path).unwrap_or_default();
+                        let leaf = if children.is_empty() {
+                            table.merkle_leaf(&path).unwrap_or_default()
+                        } else {
+                            Vec::new()
+                        };
+                        send_msg(&mut stream, &Message::MerkleNodeResponse { path, children, leaf }).ok()
+                    }
+                    Message::ChangesetRequest { pks } => {
+                        let table = table.lock().unwrap();
+                        let changeset = table.changeset_for_pks(&pks).unwrap_or_default().serialize();
+                        send_msg(&mut stream, &Message::ChangesetResponse { changeset }).ok()
+                    }
+                    Message::FullChangesetRequest => {
+                        let table = table.lock().unwrap();
+                        let changeset = table.changeset().unwrap().serialize();
+                        send_msg(&mut stream, &Message::FullChangesetResponse { changeset }).ok()
+                    }
+                    Message::DeltaRequest { vv } => {
+                        let table = table.lock().unwrap();
+                        let changeset = table.changeset_since_frontier(&vv).unwrap_or_default().serialize();
+                        let new_vv = table.current_frontier().unwrap_or_default();
+                        send_msg(&mut stream, &Message::DeltaResponse { changeset, new_vv }).ok()
+                    }
+                    _ => None,
+                };
+
+                if let Some(n) = sent {
+                    metrics.record_bytes_sent(n as u64);
                 }
             }
         });
@@ -169,6 +474,16 @@ fn run_client(peers: Vec<String>, samples: usize, output: Option<String>) {
 
         println!("  RTT mean: {}us, p50: {}us, p95: {}us, p99: {}us", mean, p50, p95, p99);
 
+        println!("  Measuring Merkle-diff sync vs {}...", peers[i]);
+        let (merkle_bytes, full_bytes) = measure_merkle_sync(stream);
+        let merkle_speedup = full_bytes as f64 / merkle_bytes.max(1) as f64;
+        println!("  Merkle sync: {} bytes vs {} bytes full changeset ({:.1}x)", merkle_bytes, full_bytes, merkle_speedup);
+
+        println!("  Measuring frontier delta sync vs {}...", peers[i]);
+        let (delta_bytes, delta_full_bytes) = measure_delta_sync(stream);
+        let delta_speedup = delta_full_bytes as f64 / delta_bytes.max(1) as f64;
+        println!("  Delta sync: {} bytes vs {} bytes full changeset ({:.1}x)", delta_bytes, delta_full_bytes, delta_speedup);
+
         results.push(BenchmarkResult {
             peer: peers[i].clone(),
             rtt_samples_us: rtt_us,
@@ -179,16 +494,27 @@ fn run_client(peers: Vec<String>, samples: usize, output: Option<String>) {
             coordinated_gc_us: 0,
             local_gc_us: 0,
             speedup: 0.0,
+            gc_nodes_collected: 0,
+            merkle_bytes_transferred: merkle_bytes,
+            full_changeset_bytes: full_bytes,
+            merkle_speedup,
+            delta_bytes_transferred: delta_bytes,
+            delta_speedup,
         });
     }
 
     println!("\nMeasuring coordinated GC latency ({} peers)...", peers.len());
+    let mut gc_nodes_collected = 0;
     let mut gc_latencies: Vec<u64> = (0..samples as u64)
-        .map(|gc_id| measure_coordinated_gc(&mut streams, gc_id).as_micros() as u64)
+        .map(|gc_id| {
+            let (elapsed, removed) = measure_coordinated_gc(&mut streams, gc_id);
+            gc_nodes_collected += removed;
+            elapsed.as_micros() as u64
+        })
         .collect();
     gc_latencies.sort();
     let coord_gc_mean = gc_latencies.iter().sum::<u64>() / gc_latencies.len() as u64;
-    println!("  Coordinated GC mean: {}us", coord_gc_mean);
+    println!("  Coordinated GC mean: {}us, {} DAG nodes collected", coord_gc_mean, gc_nodes_collected);
 
     println!("\nMeasuring local GC latency...");
     let mut local_gc_latencies: Vec<u64> = (0..samples)
@@ -205,6 +531,7 @@ fn run_client(peers: Vec<String>, samples: usize, output: Option<String>) {
         result.coordinated_gc_us = coord_gc_mean;
         result.local_gc_us = local_gc_mean;
         result.speedup = speedup;
+        result.gc_nodes_collected = gc_nodes_collected;
     }
 
     if let Some(path) = output {
@@ -214,11 +541,13 @@ fn run_client(peers: Vec<String>, samples: usize, output: Option<String>) {
     }
 
     println!("\n=== Summary ===");
-    println!("| Peer | RTT | Coord GC | Local GC | Speedup |");
-    println!("|------|-----|----------|----------|---------|");
+    println!("| Peer | RTT | Coord GC | Local GC | Speedup | GC Nodes | Merkle Bytes | Full Bytes | Merkle Speedup | Delta Bytes | Delta Speedup |");
+    println!("|------|-----|----------|----------|---------|----------|--------------|------------|----------------|-------------|---------------|");
     for r in &results {
-        println!("| {} | {}us | {}us | {}us | {:.0}x |",
-            r.peer, r.rtt_mean_us, r.coordinated_gc_us, r.local_gc_us, r.speedup);
+        println!("| {} | {}us | {}us | {}us | {:.0}x | {} | {} | {} | {:.1}x | {} | {:.1}x |",
+            r.peer, r.rtt_mean_us, r.coordinated_gc_us, r.local_gc_us, r.speedup,
+            r.gc_nodes_collected, r.merkle_bytes_transferred, r.full_changeset_bytes, r.merkle_speedup,
+            r.delta_bytes_transferred, r.delta_speedup);
     }
 }
 
@@ -227,7 +556,7 @@ fn main() {
 
     if args.len() < 2 {
         eprintln!("Usage:");
-        eprintln!("  network_bench server --bind 0.0.0.0:9000");
+        eprintln!("  network_bench server --bind 0.0.0.0:9000 --metrics-bind 0.0.0.0:9100");
         eprintln!("  network_bench client --peers IP:PORT,IP:PORT --samples 100 --output results.json");
         return;
     }
@@ -238,7 +567,11 @@ fn main() {
                 .position(|a| a == "--bind")
                 .and_then(|i| args.get(i + 1).map(|s| s.as_str()))
                 .unwrap_or("0.0.0.0:9000");
-            run_server(bind);
+            let metrics_bind = args.iter()
+                .position(|a| a == "--metrics-bind")
+                .and_then(|i| args.get(i + 1).map(|s| s.as_str()))
+                .unwrap_or("0.0.0.0:9100");
+            run_server(bind, metrics_bind);
         }
         "client" | "bench" => {
             let peers: Vec<String> = args.iter()
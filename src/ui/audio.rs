@@ -0,0 +1,138 @@
+//! Optional audio feedback for CRR merge outcomes and sync events.
+//!
+//! Modeled on the typed `AudioMsg`-style event enum from the Bevy game this
+//! corpus also draws on: render code never reaches for a sound file by
+//! name, it emits a typed [`AudioCue`] and an [`AudioBackend`] turns that
+//! into actual playback — so [`super::demo::ProfessionalDemo`] stays
+//! agnostic to whatever audio library ends up wired in.
+
+use std::collections::HashSet;
+
+/// A distinct, audible CRR event — one cue per outcome a viewer should be
+/// able to tell apart without staring at the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioCue {
+    Insert,
+    Update,
+    SkipOlder,
+    /// Deliberately the odd one out: every [`AudioBackend`] impl should
+    /// make this lower-pitched/dissonant compared to the others, so an
+    /// equal-version tie is audibly distinct from "something changed".
+    ConflictEqualVersion,
+    SyncComplete,
+}
+
+/// Plays (or drops) an [`AudioCue`]. Swappable so a headless run doesn't
+/// need a real audio device — see [`NullAudioBackend`].
+pub trait AudioBackend {
+    fn play(&mut self, cue: AudioCue);
+}
+
+#[cfg(feature = "audio")]
+mod rodio_backend {
+    use super::{AudioBackend, AudioCue};
+    use rodio::source::{SineWave, Source};
+    use rodio::{OutputStream, OutputStreamHandle};
+    use std::time::Duration;
+
+    /// Plays each [`AudioCue`] as a short sine-wave tone at a distinct
+    /// pitch, with [`AudioCue::ConflictEqualVersion`] deliberately the
+    /// lowest and longest so an equal-version tie stands out.
+    pub struct RodioAudioBackend {
+        _stream: OutputStream,
+        handle: OutputStreamHandle,
+    }
+
+    impl RodioAudioBackend {
+        pub fn new() -> Result<Self, rodio::StreamError> {
+            let (_stream, handle) = OutputStream::try_default()?;
+            Ok(Self { _stream, handle })
+        }
+
+        fn tone_for(cue: AudioCue) -> (f32, u64) {
+            match cue {
+                AudioCue::Insert => (880.0, 80),
+                AudioCue::Update => (660.0, 80),
+                AudioCue::SkipOlder => (440.0, 60),
+                AudioCue::ConflictEqualVersion => (220.0, 220),
+                AudioCue::SyncComplete => (990.0, 120),
+            }
+        }
+    }
+
+    impl AudioBackend for RodioAudioBackend {
+        fn play(&mut self, cue: AudioCue) {
+            let (freq_hz, duration_ms) = Self::tone_for(cue);
+            let source = SineWave::new(freq_hz).take_duration(Duration::from_millis(duration_ms));
+            let _ = self.handle.play_raw(source.convert_samples());
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+pub use rodio_backend::RodioAudioBackend;
+
+/// The backend used when the `audio` feature is off, or when no real
+/// backend could be constructed (e.g. no audio device present): drops
+/// every cue.
+#[derive(Default)]
+pub struct NullAudioBackend;
+
+impl AudioBackend for NullAudioBackend {
+    fn play(&mut self, _cue: AudioCue) {}
+}
+
+/// Sits in front of whatever [`AudioBackend`] is plugged in, handling the
+/// mute toggle and per-step debouncing so render code can call
+/// [`Self::emit`] freely — a bulk insert during one `execute_step` call
+/// only ever plays [`AudioCue::Insert`] once.
+pub struct AudioCues {
+    backend: Box<dyn AudioBackend>,
+    muted: bool,
+    played_this_step: HashSet<AudioCue>,
+}
+
+impl AudioCues {
+    pub fn new() -> Self {
+        #[cfg(feature = "audio")]
+        let backend: Box<dyn AudioBackend> = RodioAudioBackend::new()
+            .map(|b| Box::new(b) as Box<dyn AudioBackend>)
+            .unwrap_or_else(|_| Box::new(NullAudioBackend));
+        #[cfg(not(feature = "audio"))]
+        let backend: Box<dyn AudioBackend> = Box::new(NullAudioBackend);
+
+        Self { backend, muted: false, played_this_step: HashSet::new() }
+    }
+
+    pub fn is_muted(&self) -> bool {
+        self.muted
+    }
+
+    pub fn toggle_muted(&mut self) {
+        self.muted = !self.muted;
+    }
+
+    /// Reset per-step debouncing — call once at the start of every
+    /// `execute_step`, so the next step's cues aren't silenced by the
+    /// previous step's.
+    pub fn start_step(&mut self) {
+        self.played_this_step.clear();
+    }
+
+    /// Play `cue` through the backend, unless muted or it already played
+    /// earlier in the current step.
+    pub fn emit(&mut self, cue: AudioCue) {
+        if self.muted {
+            return;
+        }
+        if self.played_this_step.insert(cue) {
+            self.backend.play(cue);
+        }
+    }
+}
+
+impl Default for AudioCues {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,13 +1,15 @@
 //! Interactive demo for DAG+CRR database synchronization.
 
+use super::audio;
 use super::theme;
 use gpui::{
     div, prelude::*, px, rgb, white, App, Context, Entity, FocusHandle, Focusable, IntoElement,
-    MouseButton, MouseDownEvent, Render, Window,
+    KeyDownEvent, MouseButton, MouseDownEvent, Render, Window,
 };
 use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, Instant};
-use sync_engine::crr::{LegacyMergeReport, TieBreakPolicy};
+use sync_engine::backend::{SqliteBackend, StorageBackend};
+use sync_engine::crr::{LegacyMergeReport, TieBreakPolicy, ROW_TOMBSTONE_COLUMN};
 use sync_engine::schema::SchemaMigration;
 use sync_engine::transactions::TransactionOp;
 use sync_engine::SyncEngine;
@@ -21,6 +23,12 @@ struct PeerState {
     network_delay_ms: u64,
     packet_loss_rate: f32, // 0.0 to 1.0
     last_sync_time: Option<Instant>,
+    /// `host:port` of a real peer process this one should sync over the
+    /// wire instead of in-process — set via `bind_peer_remote`. `None` (the
+    /// default for every built-in scenario) keeps `sync_peers` in-process,
+    /// with `network_delay_ms` setting only the sync animation's length;
+    /// `packet_loss_rate` is not read by either path.
+    remote_addr: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -41,6 +49,84 @@ struct ConflictHighlight {
     until: Instant,
 }
 
+/// One step of a scenario script, expressed as data instead of Rust so a
+/// user can author or replay a `.scenario` file (via [`load_scenario_file`]/
+/// [`save_scenario_file`]) without touching this module. The ops cover
+/// cell writes to each peer's `crr_table`, syncs, policy changes,
+/// online/offline toggles and log lines; writes to named tables
+/// (`get_table`), row deletes, schema migrations, CRDT increments and
+/// transactions have no op, so a scenario that uses them does not replay
+/// through [`ProfessionalDemo::run_scenario`] to the same peer states.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum ScenarioOp {
+    InsertOrUpdate {
+        peer: usize,
+        pk: String,
+        cols: HashMap<String, String>,
+        vers: HashMap<String, u64>,
+    },
+    Sync {
+        from: usize,
+        to: usize,
+    },
+    SetPolicy(TieBreakPolicy),
+    GoOffline(usize),
+    GoOnline(usize),
+    Log(String),
+}
+
+/// Load a scenario script previously written by [`save_scenario_file`] (or
+/// hand-authored against the same JSON shape as [`ScenarioOp`]), for replay
+/// via [`ProfessionalDemo::run_scenario`].
+#[allow(dead_code)]
+fn load_scenario_file(path: &std::path::Path) -> std::io::Result<Vec<ScenarioOp>> {
+    let text = std::fs::read_to_string(path)?;
+    serde_json::from_str(&text).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+/// Write a recorded or hand-authored scenario script to `path` as pretty
+/// JSON, so it can be checked in alongside a bug report and replayed later
+/// with [`load_scenario_file`].
+#[allow(dead_code)]
+fn save_scenario_file(path: &std::path::Path, ops: &[ScenarioOp]) -> std::io::Result<()> {
+    let text = serde_json::to_string_pretty(ops)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    std::fs::write(path, text)
+}
+
+/// First 8 bytes of a Merkle root digest, hex-encoded, for a log line —
+/// enough to tell two roots apart at a glance without printing all 32.
+fn merkle_root_prefix(digest: &sync_engine::Digest) -> String {
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A point in the demo's history captured by [`ProfessionalDemo::execute_step`]
+/// just before it mutates anything, so the timeline scrubber can jump back
+/// to exactly the state a given step started from. Because `execute_step`
+/// is a pure function of `(peers, current_step, tiebreak_policy)`, restoring
+/// one of these and replaying forward reproduces byte-identical merges —
+/// including which side a `LexicographicMin` tiebreak picks.
+#[derive(Clone)]
+struct TimelineSnapshot {
+    peers: Vec<PeerState>,
+    step: usize,
+    tiebreak_policy: TieBreakPolicy,
+    merge_report: Option<LegacyMergeReport>,
+}
+
+/// Peer/table state captured by [`ProfessionalDemo::recording_snapshot`]
+/// just before a step runs, so [`ProfessionalDemo::record_step`] can diff
+/// it against the state just after and synthesize the [`ScenarioOp`]s that
+/// step actually produced — without every `step_*` function needing to
+/// report its own ops.
+struct RecordingSnapshot {
+    policy: TieBreakPolicy,
+    online: Vec<bool>,
+    rows: Vec<HashMap<String, (HashMap<String, String>, HashMap<String, u64>)>>,
+    synced: usize,
+    logged: usize,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum DemoScenario {
     CrrFundamentals,     // Intro + VersionProgression + SimpleUpdate
@@ -49,6 +135,7 @@ enum DemoScenario {
     DatabaseFeatures,    // Schema + FK + Transactions
     StressTest,          // Keep existing
     ProductionEcommerce, // 5-peer production demo
+    CrashRecovery,       // Durable storage backend + peer restart
 }
 
 impl DemoScenario {
@@ -60,6 +147,7 @@ impl DemoScenario {
             Self::DatabaseFeatures => "Scenario 4: Database Features",
             Self::StressTest => "Scenario 5: Stress Test",
             Self::ProductionEcommerce => "Scenario 6: Production E-Commerce",
+            Self::CrashRecovery => "Scenario 7: Crash Recovery",
         }
     }
 
@@ -83,6 +171,9 @@ impl DemoScenario {
             Self::ProductionEcommerce => {
                 "Full production scenario: 5 peers (stores, mobile, cloud), schema evolution,\n10+ customers, 6+ products, offline sync, FK CASCADE, transactions."
             }
+            Self::CrashRecovery => {
+                "Peer A is backed by a SQLite storage backend instead of memory-only state.\nWrites rows, 'kills' the peer by dropping its engine, reopens it from the\nbackend, and shows every column version survived and still converges."
+            }
         }
     }
 
@@ -167,11 +258,13 @@ impl DemoScenario {
                 "Generate 100 rows per peer",
                 "Random updates: 50% of rows modified",
                 "Introduce random conflicts",
+                "Reconcile via Merkle diff",
                 "Merge all peers",
                 "Verify convergence",
                 "Measure merge time",
                 "Display statistics",
                 "Sync back for full convergence",
+                "Tombstone 10 rows, then GC once every peer has converged",
                 "Stress test complete!",
             ],
             Self::ProductionEcommerce => vec![
@@ -188,17 +281,116 @@ impl DemoScenario {
                 "[ORDERS] Mobile creates 1 order",
                 "[OFFLINE] Store C goes OFFLINE",
                 "[OFFLINE] Store C makes 4 local changes offline",
+                "[OFFLINE] Store C's process restarts - durable backend retains its local orders",
                 "[OFFLINE] Meanwhile: Store A and B sync to Cloud",
                 "[OFFLINE] Store C comes ONLINE and syncs to Cloud",
                 "[FK-CASCADE] Cloud HQ: Delete customer with orders (CASCADE)",
                 "[TRANSACTIONS] Mobile: Begin transaction (customer + order)",
                 "[TRANSACTIONS] Mobile: Commit transaction atomically",
+                "[TRANSACTIONS] Mobile: Begin transaction to update c1's tier",
+                "[TRANSACTIONS] Cloud HQ concurrently writes to c1 (conflict setup)",
+                "[TRANSACTIONS] Mobile: Commit fails - OCC conflict, transaction aborted",
+                "[TRANSACTIONS] Mobile: Retries against fresh snapshot - commits",
                 "[CONVERGENCE] Final sync: All peers converge to same state!",
+                "[REPAIR/VERIFY] Compare Merkle roots across all 5 peers",
+            ],
+            Self::CrashRecovery => vec![
+                "[SETUP] Peer A opens a SQLite storage backend, Peer B stays in-memory",
+                "[WRITE] Peer A: user_1 name='Alice' (v=1), persisted to the backend",
+                "[WRITE] Peer A: user_2 name='Bob' (v=1), persisted to the backend",
+                "[WRITE] Peer A: user_1 city='Boston' (v=1), persisted to the backend",
+                "[CRASH] Peer A 'killed' - its in-memory engine is dropped",
+                "[RECOVER] Peer A reopened from the same backend file",
+                "[VERIFY] Reopened Peer A still has user_1 and user_2 at their saved versions",
+                "[SYNC] Recovered Peer A syncs to Peer B: both peers converge",
+                "[CONCLUSION] Durable storage survives a crash; convergence still holds!",
             ],
         }
     }
 }
 
+/// What a single command-palette entry does when the user selects it —
+/// one variant per action [`ProfessionalDemo::palette_commands`] offers.
+/// Scenario jumps, policy changes and auto-play mirror the body of an
+/// existing `on_mouse_down` listener; step jumps, the merge-details toggle
+/// and theme changes are palette-only.
+#[derive(Clone)]
+enum PaletteAction {
+    JumpToScenario(DemoScenario),
+    JumpToStep(usize),
+    SetTieBreakPolicy(TieBreakPolicy),
+    ToggleAutoPlay,
+    ToggleMergeDetails,
+    SetTheme(theme::Theme),
+}
+
+/// One row of [`ProfessionalDemo::palette_commands`]'s flat list, matched
+/// against the query by [`fuzzy_match`] and executed by
+/// [`ProfessionalDemo::execute_palette_action`] on selection.
+struct PaletteCommand {
+    label: String,
+    action: PaletteAction,
+}
+
+/// Subsequence fuzzy-match `query` against `candidate`, the same scheme
+/// VS Code's "Go to Anything" popularized: `query` must match left-to-right
+/// as a subsequence of `candidate` (case-insensitive), walked greedily, and
+/// the match earns a higher score the more it looks like what a human
+/// would call "the real match" — consecutive characters and word-boundary
+/// starts score far more than scattered hits. Returns `None` if `query`
+/// isn't a subsequence of `candidate`, else the score and the byte offsets
+/// of every matched character in `candidate` (for bolding matches in the
+/// palette UI).
+fn fuzzy_match(query: &str, candidate: &str) -> Option<(i32, Vec<usize>)> {
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+
+    let query_chars: Vec<char> = query.to_lowercase().chars().collect();
+    let candidate_chars: Vec<(usize, char)> = candidate.char_indices().collect();
+
+    let mut matched = Vec::with_capacity(query_chars.len());
+    let mut score = 0i32;
+    let mut query_idx = 0;
+    let mut prev_matched_pos: Option<usize> = None;
+    let mut leading_unmatched = 0;
+
+    for (pos, (byte_idx, ch)) in candidate_chars.iter().enumerate() {
+        if query_idx >= query_chars.len() {
+            break;
+        }
+        if ch.to_lowercase().next() != Some(query_chars[query_idx]) {
+            if matched.is_empty() {
+                leading_unmatched += 1;
+            }
+            continue;
+        }
+
+        score += 1;
+        if prev_matched_pos == Some(pos.wrapping_sub(1)) {
+            score += 8;
+        }
+
+        let at_boundary = pos == 0
+            || matches!(candidate_chars[pos - 1].1, ' ' | '_' | '-')
+            || (candidate_chars[pos - 1].1.is_lowercase() && ch.is_uppercase());
+        if at_boundary {
+            score += 10;
+        }
+
+        matched.push(*byte_idx);
+        prev_matched_pos = Some(pos);
+        query_idx += 1;
+    }
+
+    if query_idx < query_chars.len() {
+        return None;
+    }
+
+    score -= leading_unmatched;
+    Some((score, matched))
+}
+
 pub struct ProfessionalDemo {
     focus_handle: FocusHandle,
 
@@ -221,10 +413,58 @@ pub struct ProfessionalDemo {
     last_merge_report: Option<LegacyMergeReport>,
     tiebreak_policy: TieBreakPolicy,
 
+    // Crash Recovery scenario: path of Peer A's SQLite backend, so the
+    // "crash" step can drop its engine and the "recover" step can reopen
+    // the exact same file.
+    crash_recovery_db_path: Option<String>,
+
+    // Production E-Commerce scenario: path of Store C's SQLite backend, so
+    // the "offline peer restarts" step can drop its engine and reopen the
+    // exact same file, proving its local offline orders survived the
+    // restart before it reconnects.
+    store_c_db_path: Option<String>,
+
+    // Production E-Commerce scenario: id of Mobile's in-flight transaction
+    // across the "concurrent write causes a commit conflict" steps, so the
+    // later abort/retry steps can find it without re-deriving the id.
+    mobile_conflict_tx_id: Option<String>,
+
     // UI state
     log_messages: VecDeque<String>,
     max_log_messages: usize,
     show_merge_details: bool,
+    show_metrics_panel: bool,
+    /// The currently active color palette, swappable live via the theme
+    /// picker (`Ctrl+K`, "Set theme: ..." entries) instead of the demo
+    /// being stuck with one hardcoded scheme.
+    active_theme: theme::Theme,
+    /// Debounced audio feedback for merge outcomes and sync events, muted
+    /// via the header's speaker button.
+    audio: audio::AudioCues,
+
+    /// Whether the fuzzy command palette overlay is currently shown, toggled
+    /// by [`Self::handle_key_down`].
+    command_palette_open: bool,
+    /// The palette's in-progress search text, typed via [`Self::handle_key_down`].
+    command_palette_query: String,
+    /// Index into the *filtered* (and scored) command list the palette is
+    /// currently rendering — clamped back into range whenever the query
+    /// changes and the filtered list shrinks.
+    command_palette_selected: usize,
+
+    /// When `Some`, every [`Self::execute_step`] call diffs peer state
+    /// before/after and appends the [`ScenarioOp`]s it produced here — see
+    /// [`Self::start_recording`]/[`Self::stop_recording`].
+    recording: Option<Vec<ScenarioOp>>,
+
+    /// One [`TimelineSnapshot`] per executed step, oldest first, driving the
+    /// scrubber bar rendered under the scenario info — see
+    /// [`Self::restore_snapshot`]/[`Self::scrub_back`]/[`Self::scrub_forward`].
+    timeline: Vec<TimelineSnapshot>,
+
+    /// Tracks the Log panel's scroll offset so clicking the activity
+    /// indicator footer can scroll the event log into view.
+    log_scroll_handle: gpui::ScrollHandle,
 }
 
 impl Focusable for ProfessionalDemo {
@@ -249,9 +489,21 @@ impl ProfessionalDemo {
                 conflict_highlights: Vec::new(),
                 last_merge_report: None,
                 tiebreak_policy: TieBreakPolicy::PreferExisting,
+                crash_recovery_db_path: None,
+                store_c_db_path: None,
+                mobile_conflict_tx_id: None,
                 log_messages: VecDeque::new(),
                 max_log_messages: 15,
                 show_merge_details: true,
+                show_metrics_panel: true,
+                active_theme: theme::Theme::dark(),
+                audio: audio::AudioCues::new(),
+                command_palette_open: false,
+                command_palette_query: String::new(),
+                command_palette_selected: 0,
+                recording: None,
+                timeline: Vec::new(),
+                log_scroll_handle: gpui::ScrollHandle::new(),
             };
 
             demo.initialize_peers(3);
@@ -271,6 +523,7 @@ impl ProfessionalDemo {
                 network_delay_ms: 100,
                 packet_loss_rate: 0.0,
                 last_sync_time: None,
+                remote_addr: None,
             });
         }
         self.target_peer_index = 0;
@@ -289,6 +542,7 @@ impl ProfessionalDemo {
                 network_delay_ms: 100,
                 packet_loss_rate: 0.0,
                 last_sync_time: None,
+                remote_addr: None,
             });
         }
         self.target_peer_index = 0;
@@ -304,6 +558,7 @@ impl ProfessionalDemo {
         self.last_merge_report = None;
         self.sync_animations.clear();
         self.conflict_highlights.clear();
+        self.timeline.clear();
 
         match self.current_scenario {
             DemoScenario::CrrFundamentals => {
@@ -332,12 +587,57 @@ impl ProfessionalDemo {
                     "Store A", "Store B", "Store C", "Mobile", "Cloud HQ",
                 ]);
                 self.target_peer_index = 4; // Cloud HQ
+
+                // Store C is SQLite-backed rather than in-memory, so its
+                // offline writes survive a process restart (step 13) instead
+                // of vanishing the moment its engine drops.
+                let db_path = format!(
+                    "{}/crr_demo_store_c_{}.db",
+                    std::env::temp_dir().display(),
+                    std::process::id()
+                );
+                let _ = std::fs::remove_file(&db_path);
+                let backend = SqliteBackend::open(&db_path)
+                    .expect("production e-commerce demo backend must open");
+                self.peers[2].engine =
+                    SyncEngine::with_backend("store_c".to_string(), Box::new(backend))
+                        .expect("production e-commerce demo engine must open");
+                self.store_c_db_path = Some(db_path);
+
                 self.log("Scenario reset: Production E-Commerce");
             }
+            DemoScenario::CrashRecovery => {
+                self.initialize_peers_with_names(vec!["Peer A", "Peer B"]);
+
+                let db_path = format!(
+                    "{}/crr_demo_crash_recovery_{}.db",
+                    std::env::temp_dir().display(),
+                    std::process::id()
+                );
+                let _ = std::fs::remove_file(&db_path);
+                let backend = SqliteBackend::open(&db_path)
+                    .expect("crash-recovery demo backend must open");
+                self.peers[0].engine =
+                    SyncEngine::with_backend("peer_a".to_string(), Box::new(backend))
+                        .expect("crash-recovery demo engine must open");
+                self.crash_recovery_db_path = Some(db_path);
+
+                self.log("Scenario reset: Crash Recovery");
+            }
         }
     }
 
     fn execute_step(&mut self) {
+        self.audio.start_step();
+        let before = self.recording.is_some().then(|| self.recording_snapshot());
+
+        self.timeline.push(TimelineSnapshot {
+            peers: self.peers.clone(),
+            step: self.current_step,
+            tiebreak_policy: self.tiebreak_policy,
+            merge_report: self.last_merge_report.clone(),
+        });
+
         match self.current_scenario {
             DemoScenario::CrrFundamentals => self.step_crr_fundamentals(),
             DemoScenario::ConflictsAndOffline => self.step_conflicts_and_offline(),
@@ -345,9 +645,165 @@ impl ProfessionalDemo {
             DemoScenario::DatabaseFeatures => self.step_database_features(),
             DemoScenario::StressTest => self.step_stress_test(),
             DemoScenario::ProductionEcommerce => self.step_production_ecommerce(),
+            DemoScenario::CrashRecovery => self.step_crash_recovery(),
         }
 
         self.current_step += 1;
+
+        if let Some(before) = before {
+            self.record_step(before);
+        }
+    }
+
+    /// Jump the demo back to the state captured just before `index` ran,
+    /// dropping every later [`TimelineSnapshot`] — since [`Self::execute_step`]
+    /// is a pure function of `(peers, current_step, tiebreak_policy)`,
+    /// scrubbing forward from here via [`Self::scrub_forward`] regenerates
+    /// those dropped entries identically.
+    fn restore_snapshot(&mut self, index: usize) {
+        let Some(snapshot) = self.timeline.get(index) else {
+            return;
+        };
+        self.peers = snapshot.peers.clone();
+        self.current_step = snapshot.step;
+        self.tiebreak_policy = snapshot.tiebreak_policy;
+        self.last_merge_report = snapshot.merge_report.clone();
+        self.sync_animations.clear();
+        self.conflict_highlights.clear();
+        self.timeline.truncate(index);
+        self.log(&format!("Timeline: scrubbed to step {}", self.current_step));
+    }
+
+    /// Step the scrubber back one tick, to the state the current step
+    /// started from.
+    fn scrub_back(&mut self) {
+        if let Some(previous) = self.timeline.len().checked_sub(1) {
+            self.restore_snapshot(previous);
+        }
+    }
+
+    /// Step the scrubber forward one tick by replaying the next step.
+    fn scrub_forward(&mut self) {
+        if self.current_step < self.current_scenario.steps().len() {
+            self.execute_step();
+        }
+    }
+
+    /// Start capturing the ops the built-in scenario produces from here on,
+    /// one [`ScenarioOp`] batch per [`Self::execute_step`] call — see
+    /// [`Self::stop_recording`] to retrieve them.
+    #[allow(dead_code)]
+    fn start_recording(&mut self) {
+        self.recording = Some(Vec::new());
+    }
+
+    /// Stop recording and return everything captured since
+    /// [`Self::start_recording`], suitable for [`save_scenario_file`].
+    #[allow(dead_code)]
+    fn stop_recording(&mut self) -> Vec<ScenarioOp> {
+        self.recording.take().unwrap_or_default()
+    }
+
+    fn recording_snapshot(&self) -> RecordingSnapshot {
+        RecordingSnapshot {
+            policy: self.tiebreak_policy,
+            online: self.peers.iter().map(|peer| peer.is_online).collect(),
+            rows: self.peers.iter()
+                .map(|peer| peer.engine.crr_table.rows.iter()
+                    .map(|(pk, row)| (pk.clone(), (row.columns.clone(), row.versions.clone())))
+                    .collect())
+                .collect(),
+            synced: self.sync_animations.len(),
+            logged: self.log_messages.len(),
+        }
+    }
+
+    /// Diff `before` (captured by [`Self::recording_snapshot`] just before
+    /// the step ran) against the current state and append the
+    /// [`ScenarioOp`]s that diff implies to [`Self::recording`]. Ops within
+    /// one step are emitted in a fixed order (online/offline, policy,
+    /// row writes, then syncs, then new log lines) rather than the
+    /// step's real internal interleaving, but since every op here is
+    /// idempotent this still replays to the same final state.
+    fn record_step(&mut self, before: RecordingSnapshot) {
+        let mut ops = Vec::new();
+
+        for (idx, (&was_online, peer)) in before.online.iter().zip(&self.peers).enumerate() {
+            if was_online != peer.is_online {
+                ops.push(if peer.is_online { ScenarioOp::GoOnline(idx) } else { ScenarioOp::GoOffline(idx) });
+            }
+        }
+
+        if before.policy != self.tiebreak_policy {
+            ops.push(ScenarioOp::SetPolicy(self.tiebreak_policy));
+        }
+
+        let empty = HashMap::new();
+        for (idx, peer) in self.peers.iter().enumerate() {
+            let before_rows = before.rows.get(idx).unwrap_or(&empty);
+            for (pk, row) in &peer.engine.crr_table.rows {
+                let prior_versions = before_rows.get(pk).map(|(_, versions)| versions);
+                let mut cols = HashMap::new();
+                let mut vers = HashMap::new();
+                for (col, version) in &row.versions {
+                    let changed = prior_versions.map(|prior| prior.get(col) != Some(version)).unwrap_or(true);
+                    if changed {
+                        vers.insert(col.clone(), *version);
+                        if let Some(value) = row.columns.get(col) {
+                            cols.insert(col.clone(), value.clone());
+                        }
+                    }
+                }
+                if !vers.is_empty() {
+                    ops.push(ScenarioOp::InsertOrUpdate { peer: idx, pk: pk.clone(), cols, vers });
+                }
+            }
+        }
+
+        ops.extend(
+            self.sync_animations.iter().skip(before.synced)
+                .map(|anim| ScenarioOp::Sync { from: anim.from_peer, to: anim.to_peer }),
+        );
+
+        let new_logs = self.log_messages.len().saturating_sub(before.logged);
+        ops.extend(
+            self.log_messages.iter().take(new_logs).rev()
+                .map(|message| ScenarioOp::Log(message.clone())),
+        );
+
+        if let Some(recording) = &mut self.recording {
+            recording.extend(ops);
+        }
+    }
+
+    /// Apply a recorded or hand-authored scenario script op by op — the
+    /// interpreter counterpart to [`Self::record_step`], so a `.scenario`
+    /// file loaded via [`load_scenario_file`] replays the exact peer
+    /// mutations and syncs a live run produced.
+    #[allow(dead_code)]
+    fn run_scenario(&mut self, ops: &[ScenarioOp]) {
+        for op in ops {
+            match op {
+                ScenarioOp::InsertOrUpdate { peer, pk, cols, vers } => {
+                    if let Some(peer_state) = self.peers.get_mut(*peer) {
+                        peer_state.engine.crr_table.insert_or_update(pk, cols.clone(), vers.clone());
+                    }
+                }
+                ScenarioOp::Sync { from, to } => self.sync_peers(*from, *to),
+                ScenarioOp::SetPolicy(policy) => self.tiebreak_policy = *policy,
+                ScenarioOp::GoOffline(peer) => {
+                    if let Some(peer_state) = self.peers.get_mut(*peer) {
+                        peer_state.is_online = false;
+                    }
+                }
+                ScenarioOp::GoOnline(peer) => {
+                    if let Some(peer_state) = self.peers.get_mut(*peer) {
+                        peer_state.is_online = true;
+                    }
+                }
+                ScenarioOp::Log(message) => self.log(message),
+            }
+        }
     }
 
     fn step_crr_fundamentals(&mut self) {
@@ -977,6 +1433,15 @@ impl ProfessionalDemo {
                 self.log("Created 10 intentional conflicts");
             }
             3 => {
+                let total_rows = self.peers[0].engine.crr_table.rows.len();
+                let diverging = self.peers[0].engine.reconcile(&self.peers[1].engine);
+                self.log(&format!(
+                    "Merkle diff: Peer A needs {} of {} rows from Peer B (vs. shipping the full table below)",
+                    diverging.len(),
+                    total_rows
+                ));
+            }
+            4 => {
                 self.log("Merging all peers into Peer A...");
                 let start = Instant::now();
                 for i in 1..self.peers.len() {
@@ -985,12 +1450,12 @@ impl ProfessionalDemo {
                 let elapsed = start.elapsed();
                 self.log(&format!("Merge completed in {:?}", elapsed));
             }
-            4 => {
+            5 => {
                 self.log("Verifying convergence...");
                 let row_count = self.peers[0].engine.crr_table.rows.len();
                 self.log(&format!("Peer A now has {} rows", row_count));
             }
-            5 => {
+            6 => {
                 if let Some(ref report) = self.last_merge_report {
                     self.log(&format!(
                         "Statistics: Inserted={}, Updated={}, Conflicts={}",
@@ -1000,19 +1465,53 @@ impl ProfessionalDemo {
                     ));
                 }
             }
-            6 => {
+            7 => {
                 if let Some(ref report) = self.last_merge_report {
                     self.log(&format!("Skipped (older): {}", report.skipped_older.len()));
                     self.log("Merge complexity: O(n) where n = changeset size");
                 }
             }
-            7 => {
+            8 => {
                 for i in 1..self.peers.len() {
                     self.sync_peers(0, i);
                 }
                 self.log("Synced back to all peers for full convergence");
             }
-            8 => {
+            9 => {
+                // Delete via tombstone (not `rows.remove`), sync the
+                // deletes out, then GC: once every peer's version vector has
+                // observed a tombstone it can be physically dropped, which
+                // is what bounds memory growth here rather than letting
+                // deleted rows accumulate forever.
+                for i in 0..10 {
+                    let pk = format!("row_{}", i);
+                    let version = self.peers[0]
+                        .engine
+                        .crr_table
+                        .rows
+                        .get(&pk)
+                        .and_then(|row| row.versions.values().copied().max())
+                        .unwrap_or(0)
+                        + 1;
+                    self.peers[0].engine.crr_table.delete_row(&pk, version);
+                }
+                for i in 1..self.peers.len() {
+                    self.sync_peers(0, i);
+                }
+                for i in 1..self.peers.len() {
+                    self.sync_peers(i, 0);
+                }
+
+                let frontiers: Vec<_> = self.peers.iter()
+                    .map(|p| p.engine.crr_table.version_vector())
+                    .collect();
+                let collected = self.peers[0].engine.crr_table.gc_tombstones(&frontiers);
+                self.log(&format!(
+                    "Tombstoned 10 rows, synced the deletes to all peers, then GC'd {} of them (every peer had observed the delete)",
+                    collected
+                ));
+            }
+            10 => {
                 self.log("Stress test complete! All peers converged.");
                 self.log("Performance: O(n) merge scales to large datasets");
             }
@@ -1044,7 +1543,15 @@ impl ProfessionalDemo {
                         nullable: false,
                     },
                 );
-                self.log("[CLOUD HQ] Schema v1: customers + products tables");
+                // orders.customer_id references customers, CASCADE: deleting
+                // a customer tombstones their orders too (see step 16).
+                self.peers[cloud].engine.apply_schema_migration(SchemaMigration::AddForeignKey {
+                    table: "orders".to_string(),
+                    column: "customer_id".to_string(),
+                    references_table: "customers".to_string(),
+                    on_delete: sync_engine::foreign_keys::OnDeleteAction::Cascade,
+                });
+                self.log("[CLOUD HQ] Schema v1: customers + products tables, orders.customer_id FK (CASCADE)");
             }
             2 => {
                 // Cloud HQ creates 10 customers
@@ -1087,16 +1594,16 @@ impl ProfessionalDemo {
                 self.log("[SYNC] Cloud→All stores and mobile completed");
             }
             5 => {
-                // Store A adds loyalty_points column
-                self.peers[0]
-                    .engine
-                    .schema_manager
-                    .apply_migration(SchemaMigration::AddColumn {
-                        name: "loyalty_points".to_string(),
-                        col_type: sync_engine::schema::ColumnType::Integer,
-                        nullable: true,
-                    });
-                self.log("[STORE A] Schema v2: Added 'loyalty_points' column");
+                // Store A adds loyalty_points as a PN-Counter column, so
+                // concurrent point awards from different stores merge
+                // (grow-only per replica) instead of one clobbering the
+                // other the way a plain version-compared column would.
+                self.peers[0].engine.apply_schema_migration(SchemaMigration::AddColumn {
+                    name: "loyalty_points".to_string(),
+                    col_type: sync_engine::schema::ColumnType::PnCounter,
+                    nullable: true,
+                });
+                self.log("[STORE A] Schema v2: Added 'loyalty_points' PN-Counter column");
             }
             6 => {
                 // Mobile adds last_login column
@@ -1111,10 +1618,21 @@ impl ProfessionalDemo {
                 self.log("[MOBILE] Schema v3: Added 'last_login' column");
             }
             7 => {
+                // Sync crr_table (and with it, schema) across every peer so
+                // the 'loyalty_points' PN-Counter column is declared
+                // everywhere before anyone writes to it.
+                for i in 0..4 {
+                    self.sync_peers(i, 4);
+                }
+                for i in 0..4 {
+                    self.sync_peers(4, i);
+                }
                 self.log("[SYNC SCHEMA] Schema updates propagated across all peers");
             }
             8 => {
-                // Store A creates 3 orders
+                // Store A creates 3 orders, and awards customer c1 10
+                // loyalty points — a PN-Counter increment, not a plain
+                // version-compared write.
                 for i in 1..=3 {
                     let mut cols = HashMap::new();
                     let mut vers = HashMap::new();
@@ -1130,7 +1648,8 @@ impl ProfessionalDemo {
                         vers,
                     );
                 }
-                self.log("[STORE A] Created 3 orders");
+                self.peers[0].engine.crr_table.crdt_increment("c1", "loyalty_points", "store_a", 10);
+                self.log("[STORE A] Created 3 orders, +10 loyalty points for c1");
             }
             9 => {
                 // Store B creates 2 orders
@@ -1152,7 +1671,10 @@ impl ProfessionalDemo {
                 self.log("[STORE B] Created 2 orders");
             }
             10 => {
-                // Mobile creates 1 order
+                // Mobile creates 1 order, and concurrently (Store A hasn't
+                // synced this round yet) awards c1 5 more loyalty points
+                // from a different replica. Both increments survive the
+                // later merge instead of one clobbering the other.
                 let mut cols = HashMap::new();
                 let mut vers = HashMap::new();
                 cols.insert("customer_id".to_string(), "c6".to_string());
@@ -1165,7 +1687,8 @@ impl ProfessionalDemo {
                     .engine
                     .get_table("orders")
                     .insert_or_update("o6", cols, vers);
-                self.log("[MOBILE] Created 1 order");
+                self.peers[3].engine.crr_table.crdt_increment("c1", "loyalty_points", "mobile", 5);
+                self.log("[MOBILE] Created 1 order, +5 loyalty points for c1 (concurrent with Store A)");
             }
             11 => {
                 self.peers[2].is_online = false;
@@ -1182,7 +1705,8 @@ impl ProfessionalDemo {
                     vers.insert("customer_id".to_string(), 1);
                     vers.insert("product_id".to_string(), 1);
                     vers.insert("quantity".to_string(), 1);
-                    self.peers[2].engine.get_table("orders").insert_or_update(
+                    let _ = self.peers[2].engine.insert_or_update_table(
+                        "orders",
                         &format!("o{}", i),
                         cols,
                         vers,
@@ -1191,50 +1715,62 @@ impl ProfessionalDemo {
                 self.log("[STORE C OFFLINE] Made 4 local orders offline");
             }
             13 => {
+                // Store C's process restarts while still offline — its
+                // in-memory engine drops, but since it's SQLite-backed
+                // (see `reset_scenario`), reopening from the same file picks
+                // its 4 local orders straight back up instead of losing them.
+                let order_count_before = self.peers[2].engine.get_table("orders").rows.len();
+                let Some(db_path) = self.store_c_db_path.clone() else {
+                    self.log("[STORE C RESTART] No backend path recorded - reset the scenario first");
+                    return;
+                };
+                let backend = SqliteBackend::open(&db_path)
+                    .expect("production e-commerce demo backend must reopen");
+                let is_online = self.peers[2].is_online;
+                self.peers[2].engine =
+                    SyncEngine::with_backend("store_c".to_string(), Box::new(backend))
+                        .expect("production e-commerce demo engine must reopen");
+                self.peers[2].is_online = is_online;
+                let order_count_after = self.peers[2].engine.get_table("orders").rows.len();
+                self.log(&format!(
+                    "[STORE C RESTART] Process restarted - offline orders durable: {} -> {}",
+                    order_count_before, order_count_after
+                ));
+            }
+            14 => {
                 self.sync_peers(0, 4);
                 self.sync_peers(1, 4);
                 self.sync_peers(3, 4);
                 self.log("[SYNC] Store A, B, Mobile → Cloud (while C offline)");
             }
-            14 => {
+            15 => {
                 self.peers[2].is_online = true;
                 self.sync_peers(2, 4);
                 self.log("[STORE C] Comes ONLINE and syncs to Cloud");
             }
-            15 => {
-                // Cloud HQ deletes customer with CASCADE
-                self.peers[4]
-                    .engine
-                    .get_table("customers")
-                    .rows
-                    .remove("c3");
-
-                // Cascade delete orders
-                let orders_to_delete: Vec<String> = self.peers[4]
-                    .engine
-                    .get_table("orders")
-                    .rows
-                    .iter()
-                    .filter(|(_, row)| row.columns.get("customer_id") == Some(&"c3".to_string()))
-                    .map(|(pk, _)| pk.clone())
-                    .collect();
-
-                for order_pk in orders_to_delete {
-                    self.peers[4]
-                        .engine
-                        .get_table("orders")
-                        .rows
-                        .remove(&order_pk);
-                }
-
-                self.log("[CLOUD HQ] Deleted c3 → CASCADE deleted orders");
-            }
             16 => {
-                // Mobile begins transaction
-                let tx_id = self.peers[3].engine.tx_manager.begin();
-                self.log(&format!("[MOBILE TX] Started transaction: {}", tx_id));
+                // Cloud HQ deletes customer c3. `delete_row_cascading` tombstones
+                // it and, via the `orders.customer_id` CASCADE constraint
+                // declared in step 1, every order that still references it —
+                // as tombstones rather than `rows.remove`, so the cascade
+                // rides the normal changeset instead of only taking effect on
+                // this one peer.
+                let tombstoned = self.peers[4].engine.delete_row_cascading("customers", "c3");
+                self.log(&format!(
+                    "[CLOUD HQ] Deleted c3 → CASCADE tombstoned {} row(s): {}",
+                    tombstoned.len(),
+                    tombstoned.iter().map(|(t, pk)| format!("{}:{}", t, pk)).collect::<Vec<_>>().join(", ")
+                ));
             }
             17 => {
+                // Mobile begins transaction — snapshots every table's
+                // version vector so `commit` can later detect a concurrent
+                // write.
+                let peer = &mut self.peers[3];
+                let tx_id = peer.engine.tx_manager.begin(&peer.engine.tables);
+                self.log(&format!("[MOBILE TX] Started transaction: {}", tx_id));
+            }
+            18 => {
                 // Mobile commits transaction
                 let tx_id = format!("tx_{}", self.peers[3].engine.tx_manager.transactions.len());
                 let _ = self.peers[3].engine.tx_manager.add_operation(
@@ -1267,10 +1803,85 @@ impl ProfessionalDemo {
                 let _ = peer
                     .engine
                     .tx_manager
-                    .commit(&tx_id, &mut peer.engine.tables);
+                    .commit(&tx_id, &mut peer.engine.tables, &peer.engine.fk_manager);
                 self.log("[MOBILE TX] Committed: customer + order atomically");
             }
-            18 => {
+            19 => {
+                // Mobile begins a second transaction to update customer c1's
+                // loyalty tier — snapshotting c1's current version first.
+                let peer = &mut self.peers[3];
+                let tx_id = peer.engine.tx_manager.begin(&peer.engine.tables);
+                let _ = peer.engine.tx_manager.add_operation(
+                    &tx_id,
+                    TransactionOp::Update {
+                        table: "customers".to_string(),
+                        pk: "c1".to_string(),
+                        columns: {
+                            let mut cols = HashMap::new();
+                            cols.insert("tier".to_string(), "gold".to_string());
+                            cols
+                        },
+                    },
+                );
+                self.mobile_conflict_tx_id = Some(tx_id.clone());
+                self.log(&format!("[MOBILE TX] Started transaction {} to set c1's tier='gold'", tx_id));
+            }
+            20 => {
+                // Meanwhile, Cloud HQ writes c1's tier directly — not
+                // through a transaction — and the write replicates into
+                // Mobile's customers table, so Mobile's snapshot is now
+                // stale for c1.
+                let mut cols = HashMap::new();
+                let mut vers = HashMap::new();
+                cols.insert("tier".to_string(), "platinum".to_string());
+                vers.insert("tier".to_string(), 1);
+                let _ = self.peers[4].engine.insert_or_update_table("customers", "c1", cols, vers);
+                let cloud_customers = self.peers[4].engine.get_table("customers").changeset();
+                let policy = self.tiebreak_policy;
+                self.peers[3].engine.get_table("customers").crr_merge(&cloud_customers, policy);
+                self.log("[CLOUD HQ] Concurrently updated c1's tier (conflicts with Mobile's in-flight tx)");
+            }
+            21 => {
+                // Mobile's commit now fails: optimistic concurrency control
+                // sees c1 was modified since the transaction's snapshot, so
+                // the whole commit is rejected rather than clobbering
+                // Cloud's concurrent write.
+                let Some(tx_id) = self.mobile_conflict_tx_id.clone() else {
+                    self.log("[MOBILE TX] No conflict transaction pending - reset the scenario first");
+                    return;
+                };
+                let peer = &mut self.peers[3];
+                match peer.engine.tx_manager.commit(&tx_id, &mut peer.engine.tables, &peer.engine.fk_manager) {
+                    Ok(_) => self.log("[MOBILE TX] Unexpectedly committed without a conflict"),
+                    Err(err) => {
+                        let _ = peer.engine.tx_manager.abort(&tx_id);
+                        self.mobile_conflict_tx_id = None;
+                        self.log(&format!("[MOBILE TX] Commit rejected and aborted: {}", err));
+                    }
+                }
+            }
+            22 => {
+                // Mobile retries against the now-current state: a fresh
+                // `begin` snapshots c1 *after* Cloud's write, so this commit
+                // succeeds.
+                let peer = &mut self.peers[3];
+                let tx_id = peer.engine.tx_manager.begin(&peer.engine.tables);
+                let _ = peer.engine.tx_manager.add_operation(
+                    &tx_id,
+                    TransactionOp::Update {
+                        table: "customers".to_string(),
+                        pk: "c1".to_string(),
+                        columns: {
+                            let mut cols = HashMap::new();
+                            cols.insert("tier".to_string(), "gold".to_string());
+                            cols
+                        },
+                    },
+                );
+                let result = peer.engine.tx_manager.commit(&tx_id, &mut peer.engine.tables, &peer.engine.fk_manager);
+                self.log(&format!("[MOBILE TX] Retry against fresh snapshot: {}", if result.is_ok() { "committed" } else { "failed again" }));
+            }
+            23 => {
                 // Final convergence
                 for i in 0..4 {
                     self.sync_peers(i, 4);
@@ -1278,15 +1889,159 @@ impl ProfessionalDemo {
                 for i in 0..4 {
                     self.sync_peers(4, i);
                 }
-                self.log("[CONVERGENCE] All peers synced to same state!");
+                let loyalty_points = self.peers[4]
+                    .engine
+                    .crr_table
+                    .rows
+                    .get("c1")
+                    .and_then(|row| row.columns.get("loyalty_points"))
+                    .cloned()
+                    .unwrap_or_default();
+                self.log(&format!(
+                    "[CONVERGENCE] All peers synced to same state! c1 loyalty_points = {} (10 + 5 concurrent)",
+                    loyalty_points
+                ));
                 self.log("Production E-Commerce: Schema, FK, TX, Offline all working!");
             }
+            24 => {
+                // Repair/Verify Convergence: compare Merkle roots instead of
+                // re-diffing full tables, proving every peer landed on
+                // identical state after the sync rounds above.
+                let roots: Vec<_> = self.peers.iter().map(|p| p.engine.merkle_root()).collect();
+                if roots.windows(2).all(|pair| pair[0] == pair[1]) {
+                    self.log(&format!(
+                        "[REPAIR/VERIFY] All 5 peers converged — Merkle root {}",
+                        merkle_root_prefix(&roots[0])
+                    ));
+                } else {
+                    for (peer, root) in self.peers.iter().zip(&roots) {
+                        self.log(&format!("[REPAIR/VERIFY] {} root = {}", peer.name, merkle_root_prefix(root)));
+                    }
+                    self.log("[REPAIR/VERIFY] Peers diverge!");
+                }
+            }
             _ => {
                 self.log("Production E-Commerce scenario complete!");
             }
         }
     }
 
+    fn step_crash_recovery(&mut self) {
+        match self.current_step {
+            0 => {
+                self.log("[SETUP] Peer A: SQLite-backed, Peer B: in-memory only");
+            }
+            1 => {
+                let mut cols = HashMap::new();
+                let mut vers = HashMap::new();
+                cols.insert("name".to_string(), "Alice".to_string());
+                vers.insert("name".to_string(), 1);
+                let _ = self.peers[0].engine.insert_or_update("user_1", cols, vers);
+                self.add_conflict_highlight(0, "user_1", "name", 1500);
+                self.log("[WRITE] Peer A: user_1 name='Alice' (v=1), persisted");
+            }
+            2 => {
+                let mut cols = HashMap::new();
+                let mut vers = HashMap::new();
+                cols.insert("name".to_string(), "Bob".to_string());
+                vers.insert("name".to_string(), 1);
+                let _ = self.peers[0].engine.insert_or_update("user_2", cols, vers);
+                self.add_conflict_highlight(0, "user_2", "name", 1500);
+                self.log("[WRITE] Peer A: user_2 name='Bob' (v=1), persisted");
+            }
+            3 => {
+                let mut cols = HashMap::new();
+                let mut vers = HashMap::new();
+                cols.insert("city".to_string(), "Boston".to_string());
+                vers.insert("city".to_string(), 1);
+                let _ = self.peers[0].engine.insert_or_update("user_1", cols, vers);
+                self.add_conflict_highlight(0, "user_1", "city", 1500);
+                self.log("[WRITE] Peer A: user_1 city='Boston' (v=1), persisted");
+            }
+            4 => {
+                self.peers[0].engine = SyncEngine::new_with_peer_id("peer_a".to_string());
+                self.log("[CRASH] Peer A 'killed' - in-memory engine dropped, backend file remains");
+            }
+            5 => {
+                let Some(db_path) = self.crash_recovery_db_path.clone() else {
+                    self.log("[RECOVER] No backend path recorded - reset the scenario first");
+                    return;
+                };
+                let backend = SqliteBackend::open(&db_path)
+                    .expect("crash-recovery demo backend must reopen");
+                self.peers[0].engine =
+                    SyncEngine::with_backend("peer_a".to_string(), Box::new(backend))
+                        .expect("crash-recovery demo engine must reopen");
+                self.log("[RECOVER] Peer A reopened from its SQLite backend");
+            }
+            6 => {
+                let table = &self.peers[0].engine.crr_table;
+                let user_1_city = table.rows.get("user_1").and_then(|r| r.columns.get("city")).cloned();
+                let user_2_name = table.rows.get("user_2").and_then(|r| r.columns.get("name")).cloned();
+                self.log(&format!(
+                    "[VERIFY] user_1.city={:?}, user_2.name={:?} - both survived the crash",
+                    user_1_city, user_2_name
+                ));
+            }
+            7 => {
+                self.sync_peers(0, 1);
+                self.log("[SYNC] Recovered Peer A -> Peer B: B now has all 2 users");
+            }
+            8 => {
+                self.log("[CONCLUSION] Durable storage survives a crash; convergence still holds!");
+            }
+            _ => {
+                self.log("Crash Recovery scenario complete!");
+            }
+        }
+    }
+
+    /// Bind `self.peers[idx]` to a real peer process listening at
+    /// `addr` — subsequent `sync_peers` calls where `idx` is the `from_idx`
+    /// connect to it over [`sync_engine::transport::TcpTransport`] and
+    /// measure the actual round-trip instead of replaying
+    /// `network_delay_ms`/`packet_loss_rate`. A demo mode that wires this up
+    /// from the CLI can call it once per remote peer before running a
+    /// scenario.
+    #[allow(dead_code)]
+    fn bind_peer_remote(&mut self, idx: usize, addr: String) {
+        if let Some(peer) = self.peers.get_mut(idx) {
+            peer.remote_addr = Some(addr);
+        }
+    }
+
+    /// Sync `self.peers[from_idx]` against a real peer over the wire,
+    /// measuring the actual round-trip for [`SyncAnimation::duration_ms`]
+    /// rather than replaying `network_delay_ms`. Used instead of the
+    /// simulated path in `sync_peers` whenever `remote_addr` is set.
+    fn sync_peers_remote(&mut self, from_idx: usize, to_idx: usize, addr: String) {
+        let started_at = Instant::now();
+        let report = match self.peers[from_idx].engine.connect(&addr) {
+            Ok(report) => report,
+            Err(err) => {
+                self.log(&format!("Remote sync to {} failed: {}", addr, err));
+                return;
+            }
+        };
+        let measured_latency_ms = started_at.elapsed().as_millis() as u64;
+
+        self.sync_animations.push(SyncAnimation {
+            from_peer: from_idx,
+            to_peer: to_idx,
+            started_at,
+            duration_ms: measured_latency_ms,
+            changeset_size: report.inserted.len() + report.updated.len(),
+        });
+
+        self.last_merge_report = Some(report.clone());
+        self.peers[to_idx].last_sync_time = Some(Instant::now());
+        self.emit_merge_report_audio(&report);
+
+        for (pk, col, _v, _, _) in &report.conflicts_equal_version {
+            self.add_conflict_highlight(to_idx, pk, col, 2000);
+        }
+    }
+
     fn sync_peers(&mut self, from_idx: usize, to_idx: usize) {
         if from_idx >= self.peers.len() || to_idx >= self.peers.len() {
             return;
@@ -1298,9 +2053,19 @@ impl ProfessionalDemo {
             return;
         }
 
-        // Simulate network delay
-        let changeset = self.peers[from_idx].engine.crr_table.changeset();
-        let changeset_size = changeset.len();
+        if let Some(addr) = self.peers[from_idx].remote_addr.clone() {
+            self.sync_peers_remote(from_idx, to_idx, addr);
+            return;
+        }
+
+        // Delta sync: `to_idx`'s own row versions already are a causality
+        // frontier, so asking `from_idx` for only the cells ahead of it
+        // ships bandwidth proportional to what actually changed (down to
+        // individual columns) instead of the whole table, or even whole
+        // rows that merely share a pk with a changed cell.
+        let frontier = self.peers[to_idx].engine.crr_table.version_vector();
+        let changeset = self.peers[from_idx].engine.crr_table.changeset_since(&frontier);
+        let changeset_size = changeset.values().map(|(cols, _)| cols.len()).sum();
 
         // Add animation
         self.sync_animations.push(SyncAnimation {
@@ -1312,13 +2077,33 @@ impl ProfessionalDemo {
         });
 
         // Apply merge
-        let report = self.peers[to_idx]
+        let mut report = self.peers[to_idx]
             .engine
-            .crr_table
-            .crr_merge(&changeset, self.tiebreak_policy);
+            .crr_merge_recorded(&changeset, self.tiebreak_policy);
+
+        // Schema is itself a convergent replicated object: fold in whatever
+        // migrations `from_idx` has staged that `to_idx` hasn't seen yet, so
+        // peers converge on an identical schema version, not just identical
+        // rows. Surfaced through the same `last_merge_report` as row
+        // conflicts rather than a separate report the UI would have to show
+        // in a second place.
+        let remote_schema = self.peers[from_idx].engine.schema_manager.clone();
+        let schema_report = self.peers[to_idx].engine.merge_schema_from(&remote_schema, self.tiebreak_policy);
+        report.schema_drop_vs_update_conflicts = schema_report.drop_vs_update_conflicts;
+        report.schema_rename_conflicts = schema_report.rename_conflicts;
+
+        // Declared CRDT columns (e.g. the PN-Counter `loyalty_points`) merge
+        // through their own changeset rather than `crr_merge`'s version
+        // comparison, so a concurrent increment on each side survives
+        // instead of one clobbering the other.
+        let crdt_changeset = self.peers[from_idx].engine.crr_table.crdt_changeset();
+        let crdt_report = self.peers[to_idx].engine.crr_table.crdt_merge(&crdt_changeset);
+        report.counter_merges = crdt_report.counter_merges;
+        report.set_merges = crdt_report.set_merges;
 
         self.last_merge_report = Some(report.clone());
         self.peers[to_idx].last_sync_time = Some(Instant::now());
+        self.emit_merge_report_audio(&report);
 
         // Highlight conflicts
         for (pk, col, _v, _, _) in &report.conflicts_equal_version {
@@ -1326,6 +2111,25 @@ impl ProfessionalDemo {
         }
     }
 
+    /// Translate a landed [`LegacyMergeReport`] into audio feedback: one cue
+    /// per outcome kind that actually occurred, plus an unconditional
+    /// [`audio::AudioCue::SyncComplete`] marking the sync as done.
+    fn emit_merge_report_audio(&mut self, report: &LegacyMergeReport) {
+        if !report.inserted.is_empty() {
+            self.audio.emit(audio::AudioCue::Insert);
+        }
+        if !report.updated.is_empty() {
+            self.audio.emit(audio::AudioCue::Update);
+        }
+        if !report.skipped_older.is_empty() {
+            self.audio.emit(audio::AudioCue::SkipOlder);
+        }
+        if !report.conflicts_equal_version.is_empty() {
+            self.audio.emit(audio::AudioCue::ConflictEqualVersion);
+        }
+        self.audio.emit(audio::AudioCue::SyncComplete);
+    }
+
     fn add_conflict_highlight(&mut self, peer_idx: usize, pk: &str, col: &str, duration_ms: u64) {
         self.conflict_highlights.push(ConflictHighlight {
             peer_index: peer_idx,
@@ -1375,7 +2179,8 @@ impl ProfessionalDemo {
             DemoScenario::DagRecovery => Some(DemoScenario::DatabaseFeatures),
             DemoScenario::DatabaseFeatures => Some(DemoScenario::StressTest),
             DemoScenario::StressTest => Some(DemoScenario::ProductionEcommerce),
-            DemoScenario::ProductionEcommerce => None,
+            DemoScenario::ProductionEcommerce => Some(DemoScenario::CrashRecovery),
+            DemoScenario::CrashRecovery => None,
         }
     }
 
@@ -1386,6 +2191,180 @@ impl ProfessionalDemo {
         }
     }
 
+    /// Start or stop auto-play, identically to the header's Auto-Play/Pause
+    /// button — pulled out so [`Self::execute_palette_action`] can run the
+    /// exact same body as that button's `on_mouse_down` listener.
+    fn toggle_auto_play(&mut self) {
+        if self.is_auto_playing {
+            self.is_auto_playing = false;
+            self.auto_play_next_at = None;
+            self.log("Auto-play stopped");
+        } else {
+            self.is_auto_playing = true;
+            self.auto_play_next_at = Some(Instant::now());
+            self.log("Auto-play started");
+        }
+    }
+
+    /// The flat, fuzzy-matchable list of everything the command palette can
+    /// jump to or flip: every [`DemoScenario`], every step of the current
+    /// scenario, every [`TieBreakPolicy`], the Auto-Play/Merge-Details
+    /// toggles, and every [`theme::Theme`] preset.
+    fn palette_commands(&self) -> Vec<PaletteCommand> {
+        let mut commands = Vec::new();
+
+        for scenario in [
+            DemoScenario::CrrFundamentals,
+            DemoScenario::ConflictsAndOffline,
+            DemoScenario::DagRecovery,
+            DemoScenario::DatabaseFeatures,
+            DemoScenario::StressTest,
+            DemoScenario::ProductionEcommerce,
+            DemoScenario::CrashRecovery,
+        ] {
+            commands.push(PaletteCommand {
+                label: format!("Go to scenario: {}", scenario.title()),
+                action: PaletteAction::JumpToScenario(scenario),
+            });
+        }
+
+        for (idx, step) in self.current_scenario.steps().iter().enumerate() {
+            commands.push(PaletteCommand {
+                label: format!("Go to step {}: {}", idx + 1, step),
+                action: PaletteAction::JumpToStep(idx),
+            });
+        }
+
+        for policy in [
+            TieBreakPolicy::PreferExisting,
+            TieBreakPolicy::PreferIncoming,
+            TieBreakPolicy::LexicographicMin,
+            TieBreakPolicy::LastWriteWins,
+            TieBreakPolicy::MultiValue,
+        ] {
+            commands.push(PaletteCommand {
+                label: format!("Set tiebreak policy: {:?}", policy),
+                action: PaletteAction::SetTieBreakPolicy(policy),
+            });
+        }
+
+        commands.push(PaletteCommand {
+            label: if self.is_auto_playing { "Pause auto-play".to_string() } else { "Start auto-play".to_string() },
+            action: PaletteAction::ToggleAutoPlay,
+        });
+        commands.push(PaletteCommand {
+            label: if self.show_merge_details { "Hide merge details".to_string() } else { "Show merge details".to_string() },
+            action: PaletteAction::ToggleMergeDetails,
+        });
+
+        for preset in theme::Theme::presets() {
+            commands.push(PaletteCommand {
+                label: format!("Set theme: {}", preset.name),
+                action: PaletteAction::SetTheme(preset),
+            });
+        }
+
+        commands
+    }
+
+    /// [`Self::palette_commands`] filtered by [`fuzzy_match`] against
+    /// [`Self::command_palette_query`] and sorted by descending score — an
+    /// empty query matches (and keeps the original order of) everything.
+    fn filtered_palette_commands(&self) -> Vec<(i32, Vec<usize>, PaletteCommand)> {
+        let mut matches: Vec<(i32, Vec<usize>, PaletteCommand)> = self
+            .palette_commands()
+            .into_iter()
+            .filter_map(|command| {
+                fuzzy_match(&self.command_palette_query, &command.label)
+                    .map(|(score, indices)| (score, indices, command))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        matches
+    }
+
+    /// Run the selected palette command's body — identical to the
+    /// corresponding `on_mouse_down` listener's body for the actions that
+    /// already have one (scenario jump, tiebreak policy, auto-play) — then
+    /// close the palette.
+    fn execute_palette_action(&mut self, action: PaletteAction) {
+        match action {
+            PaletteAction::JumpToScenario(scenario) => {
+                self.current_scenario = scenario;
+                self.reset_scenario();
+            }
+            PaletteAction::JumpToStep(step) => {
+                let max_steps = self.current_scenario.steps().len();
+                self.current_step = step.min(max_steps);
+            }
+            PaletteAction::SetTieBreakPolicy(policy) => {
+                self.tiebreak_policy = policy;
+                self.log(&format!("Tiebreak policy: {:?}", self.tiebreak_policy));
+            }
+            PaletteAction::ToggleAutoPlay => self.toggle_auto_play(),
+            PaletteAction::ToggleMergeDetails => self.show_merge_details = !self.show_merge_details,
+            PaletteAction::SetTheme(preset) => {
+                self.log(&format!("Theme: {}", preset.name));
+                self.active_theme = preset;
+            }
+        }
+
+        self.command_palette_open = false;
+        self.command_palette_query.clear();
+        self.command_palette_selected = 0;
+    }
+
+    /// The root div's `on_key_down` listener: `Ctrl+K` toggles the palette;
+    /// while it's open, typed characters extend the query, Backspace/Escape
+    /// edit or close it, Up/Down move the highlighted row, and Enter
+    /// executes it.
+    fn handle_key_down(&mut self, event: &KeyDownEvent) {
+        let keystroke = &event.keystroke;
+
+        if keystroke.modifiers.control && keystroke.key == "k" {
+            self.command_palette_open = !self.command_palette_open;
+            self.command_palette_query.clear();
+            self.command_palette_selected = 0;
+            return;
+        }
+
+        if !self.command_palette_open {
+            return;
+        }
+
+        match keystroke.key.as_str() {
+            "escape" => {
+                self.command_palette_open = false;
+                self.command_palette_query.clear();
+                self.command_palette_selected = 0;
+            }
+            "backspace" => {
+                self.command_palette_query.pop();
+                self.command_palette_selected = 0;
+            }
+            "up" => {
+                self.command_palette_selected = self.command_palette_selected.saturating_sub(1);
+            }
+            "down" => {
+                let count = self.filtered_palette_commands().len();
+                if count > 0 {
+                    self.command_palette_selected = (self.command_palette_selected + 1).min(count - 1);
+                }
+            }
+            "enter" => {
+                let matches = self.filtered_palette_commands();
+                if let Some((_, _, command)) = matches.into_iter().nth(self.command_palette_selected) {
+                    self.execute_palette_action(command.action);
+                }
+            }
+            key if key.chars().count() == 1 => {
+                self.command_palette_query.push_str(key);
+                self.command_palette_selected = 0;
+            }
+            _ => {}
+        }
+    }
+
     fn cleanup_animations(&mut self) {
         let now = Instant::now();
         self.sync_animations.retain(|anim| {
@@ -1401,25 +2380,27 @@ impl ProfessionalDemo {
         // Detect if this scenario uses multi-table format
         let use_multi_table = !peer.engine.tables.is_empty();
 
-        // Collect rows from either crr_table or tables HashMap
-        let mut rows: Vec<_> = peer.engine.crr_table.rows.values().collect();
+        // Collect rows from either crr_table or tables HashMap, skipping
+        // tombstoned rows the same way a materialized view would skip a pk
+        // that's simply absent.
+        let mut rows: Vec<_> = peer.engine.crr_table.rows.values().filter(|r| !r.deleted).collect();
         rows.sort_by_key(|r| &r.pk);
 
         // Colors
         let bg_color = if is_target {
-            rgb(0x1a2a1a)
+            rgb(self.active_theme.peer_target_bg)
         } else if !peer.is_online {
-            rgb(0x2a1a1a)
+            rgb(self.active_theme.peer_offline_bg)
         } else {
-            rgb(theme::PANEL_BACKGROUND)
+            rgb(self.active_theme.panel_background)
         };
 
         let border_color = if is_target {
-            rgb(0x00ff88)
+            rgb(self.active_theme.peer_target_accent)
         } else if !peer.is_online {
-            rgb(0xff4444)
+            rgb(self.active_theme.peer_offline_accent)
         } else {
-            rgb(theme::BORDER_COLOR)
+            rgb(self.active_theme.border_color)
         };
 
         div()
@@ -1444,9 +2425,9 @@ impl ProfessionalDemo {
                             .text_sm()
                             .font_weight(gpui::FontWeight::BOLD)
                             .text_color(if is_target {
-                                rgb(0x00ff88)
+                                rgb(self.active_theme.peer_target_accent)
                             } else {
-                                rgb(theme::TEXT_COLOR)
+                                rgb(self.active_theme.text_color)
                             })
                             .child(format!(
                                 "{}{}",
@@ -1460,9 +2441,9 @@ impl ProfessionalDemo {
                             .py_1()
                             .rounded_md()
                             .bg(if peer.is_online {
-                                rgb(0x0e7a0d)
+                                rgb(self.active_theme.success_bg)
                             } else {
-                                rgb(0x7a0d0d)
+                                rgb(self.active_theme.danger_bg)
                             })
                             .child(
                                 div()
@@ -1472,6 +2453,7 @@ impl ProfessionalDemo {
                             ),
                     ),
             )
+            .children(self.render_pending_transactions(peer))
             .child(
                 // Table - either single table or multi-table format
                 div()
@@ -1484,14 +2466,16 @@ impl ProfessionalDemo {
                     } else if rows.is_empty() {
                         vec![div()
                             .text_xs()
-                            .text_color(rgb(theme::MUTED_TEXT))
+                            .text_color(rgb(self.active_theme.muted_text))
                             .child("(empty table)")
                             .into_any_element()]
                     } else {
                         // Single table rendering (existing code)
                         rows.iter()
                             .map(|row| {
-                                let mut cols: Vec<_> = row.columns.iter().collect();
+                                let mut cols: Vec<_> = row.columns.iter()
+                                    .filter(|(k, _)| k.as_str() != ROW_TOMBSTONE_COLUMN)
+                                    .collect();
                                 cols.sort_by_key(|(k, _)| *k);
 
                                 div()
@@ -1499,13 +2483,13 @@ impl ProfessionalDemo {
                                     .flex_col()
                                     .gap_1()
                                     .p_2()
-                                    .bg(rgb(0x2a2a2a))
+                                    .bg(rgb(self.active_theme.row_bg))
                                     .rounded_md()
                                     .child(
                                         div()
                                             .text_xs()
                                             .font_weight(gpui::FontWeight::SEMIBOLD)
-                                            .text_color(rgb(0x9cdcfe))
+                                            .text_color(rgb(self.active_theme.pk_text))
                                             .child(format!("PK: {}", row.pk)),
                                     )
                                     .children(cols.iter().map(|(col_name, col_val)| {
@@ -1529,27 +2513,27 @@ impl ProfessionalDemo {
                                             .p_1()
                                             .rounded_sm()
                                             .bg(if is_highlighted {
-                                                rgb(0x4a3a2a)
+                                                rgb(self.active_theme.highlight_bg)
                                             } else {
-                                                rgb(0x1a1a1a)
+                                                rgb(self.active_theme.cell_bg)
                                             })
                                             .child(
                                                 div()
                                                     .text_xs()
-                                                    .text_color(rgb(0xdcdcaa))
+                                                    .text_color(rgb(self.active_theme.key_text))
                                                     .child(format!("{}: ", col_name)),
                                             )
                                             .child(
                                                 div()
                                                     .text_xs()
-                                                    .text_color(rgb(0xce9178))
+                                                    .text_color(rgb(self.active_theme.value_text))
                                                     .child(format!("\"{}\"", col_val)),
                                             )
                                             .child(
-                                                div().px_1().rounded_sm().bg(rgb(0x3a3a5a)).child(
+                                                div().px_1().rounded_sm().bg(rgb(self.active_theme.version_badge_bg)).child(
                                                     div()
                                                         .text_xs()
-                                                        .text_color(rgb(0xaaaaff))
+                                                        .text_color(rgb(self.active_theme.version_badge_text))
                                                         .child(format!("v{}", version)),
                                                 ),
                                             )
@@ -1563,7 +2547,7 @@ impl ProfessionalDemo {
                 d.child(
                     div()
                         .text_xs()
-                        .text_color(rgb(theme::MUTED_TEXT))
+                        .text_color(rgb(self.active_theme.muted_text))
                         .child(format!(
                             "Last sync: {:?} ago",
                             now.duration_since(peer.last_sync_time.unwrap())
@@ -1572,6 +2556,27 @@ impl ProfessionalDemo {
             })
     }
 
+    /// A "pending transaction" overlay line per not-yet-committed,
+    /// non-empty transaction on `peer` — lets a viewer see a transaction's
+    /// staged operations sitting open (e.g. mid-OCC-conflict) instead of
+    /// only ever seeing the before/after of a commit.
+    fn render_pending_transactions(&self, peer: &PeerState) -> Vec<gpui::AnyElement> {
+        peer.engine.tx_manager.pending_transactions().into_iter().map(|tx| {
+            div()
+                .px_2()
+                .py_1()
+                .bg(rgb(self.active_theme.pending_tx_bg))
+                .rounded_md()
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(self.active_theme.pending_tx_text))
+                        .child(format!("[PENDING TX] {}: {} staged op(s)", tx.id, tx.operations.len())),
+                )
+                .into_any_element()
+        }).collect()
+    }
+
     fn render_multi_tables(
         &self,
         peer_idx: usize,
@@ -1593,33 +2598,35 @@ impl ProfessionalDemo {
                         .py_1()
                         .mt_2()
                         .rounded_sm()
-                        .bg(rgb(0x3a3a5a))
+                        .bg(rgb(self.active_theme.table_header_bg))
                         .child(
                             div()
                                 .text_xs()
                                 .font_weight(gpui::FontWeight::BOLD)
-                                .text_color(rgb(0xddddff))
+                                .text_color(rgb(self.active_theme.table_header_text))
                                 .child(format!("📊 {}", table_name)),
                         )
                         .into_any_element(),
                 );
 
-                // Table rows
-                let mut rows: Vec<_> = table.rows.values().collect();
+                // Table rows, skipping tombstoned ones
+                let mut rows: Vec<_> = table.rows.values().filter(|r| !r.deleted).collect();
                 rows.sort_by_key(|r| &r.pk);
 
                 if rows.is_empty() {
                     elements.push(
                         div()
                             .text_xs()
-                            .text_color(rgb(theme::MUTED_TEXT))
+                            .text_color(rgb(self.active_theme.muted_text))
                             .pl_4()
                             .child("(empty)")
                             .into_any_element(),
                     );
                 } else {
                     for row in rows {
-                        let mut cols: Vec<_> = row.columns.iter().collect();
+                        let mut cols: Vec<_> = row.columns.iter()
+                            .filter(|(k, _)| k.as_str() != ROW_TOMBSTONE_COLUMN)
+                            .collect();
                         cols.sort_by_key(|(k, _)| *k);
 
                         elements.push(
@@ -1629,13 +2636,13 @@ impl ProfessionalDemo {
                                 .gap_1()
                                 .p_2()
                                 .ml_2()
-                                .bg(rgb(0x2a2a2a))
+                                .bg(rgb(self.active_theme.row_bg))
                                 .rounded_md()
                                 .child(
                                     div()
                                         .text_xs()
                                         .font_weight(gpui::FontWeight::SEMIBOLD)
-                                        .text_color(rgb(0x9cdcfe))
+                                        .text_color(rgb(self.active_theme.pk_text))
                                         .child(format!("PK: {}", row.pk)),
                                 )
                                 .children(cols.iter().map(|(col_name, col_val)| {
@@ -1658,27 +2665,27 @@ impl ProfessionalDemo {
                                         .p_1()
                                         .rounded_sm()
                                         .bg(if is_highlighted {
-                                            rgb(0x4a3a2a)
+                                            rgb(self.active_theme.highlight_bg)
                                         } else {
-                                            rgb(0x1a1a1a)
+                                            rgb(self.active_theme.cell_bg)
                                         })
                                         .child(
                                             div()
                                                 .text_xs()
-                                                .text_color(rgb(0xdcdcaa))
+                                                .text_color(rgb(self.active_theme.key_text))
                                                 .child(format!("{}: ", col_name)),
                                         )
                                         .child(
                                             div()
                                                 .text_xs()
-                                                .text_color(rgb(0xce9178))
+                                                .text_color(rgb(self.active_theme.value_text))
                                                 .child(format!("\"{}\"", col_val)),
                                         )
                                         .child(
-                                            div().px_1().rounded_sm().bg(rgb(0x3a3a5a)).child(
+                                            div().px_1().rounded_sm().bg(rgb(self.active_theme.version_badge_bg)).child(
                                                 div()
                                                     .text_xs()
-                                                    .text_color(rgb(0xaaaaff))
+                                                    .text_color(rgb(self.active_theme.version_badge_text))
                                                     .child(format!("v{}", version)),
                                             ),
                                         )
@@ -1701,6 +2708,7 @@ impl ProfessionalDemo {
             DemoScenario::DatabaseFeatures,
             DemoScenario::StressTest,
             DemoScenario::ProductionEcommerce,
+            DemoScenario::CrashRecovery,
         ];
 
         div()
@@ -1723,20 +2731,20 @@ impl ProfessionalDemo {
                         .py_2()
                         .rounded_md()
                         .bg(if is_current {
-                            rgb(0x007acc)
+                            rgb(self.active_theme.scenario_current_bg)
                         } else if is_completed {
-                            rgb(0x0e7a0d)
+                            rgb(self.active_theme.scenario_completed_bg)
                         } else {
-                            rgb(0x3a3a3a)
+                            rgb(self.active_theme.scenario_default_bg)
                         })
                         .cursor_pointer()
                         .hover(|s| {
                             s.bg(rgb(if is_current {
-                                0x0088ee
+                                self.active_theme.scenario_current_hover
                             } else if is_completed {
-                                0x1e9a1d
+                                self.active_theme.scenario_completed_hover
                             } else {
-                                0x4a4a4a
+                                self.active_theme.scenario_default_hover
                             }))
                         })
                         .on_mouse_down(
@@ -1769,8 +2777,8 @@ impl ProfessionalDemo {
                     })),
             )
             .when(self.is_auto_playing, |d| {
-                d.child(div().px_3().py_1().bg(rgb(0x2a2a3a)).rounded_md().child(
-                    div().text_xs().text_color(rgb(0xaaaaff)).child(format!(
+                d.child(div().px_3().py_1().bg(rgb(self.active_theme.step_badge_bg)).rounded_md().child(
+                    div().text_xs().text_color(rgb(self.active_theme.version_badge_text)).child(format!(
                         "Auto-playing: Step {}/{} in {}",
                         self.current_step.min(self.current_scenario.steps().len()),
                         self.current_scenario.steps().len(),
@@ -1780,6 +2788,205 @@ impl ProfessionalDemo {
             })
     }
 
+    /// A horizontal scrubber with one tick per recorded [`TimelineSnapshot`],
+    /// plus Back/Forward controls. Clicking a tick calls
+    /// [`Self::restore_snapshot`] to jump straight there; Back/Forward call
+    /// [`Self::scrub_back`]/[`Self::scrub_forward`] one tick at a time.
+    fn render_timeline_scrubber(&self, cx: &mut gpui::prelude::Context<Self>) -> impl IntoElement {
+        let current_tick = self.timeline.len();
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap_2()
+            .p_2()
+            .bg(rgb(self.active_theme.panel_background))
+            .rounded_md()
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(self.active_theme.button_neutral_bg))
+                    .cursor_pointer()
+                    .hover(|s| s.bg(rgb(self.active_theme.button_neutral_hover)))
+                    .on_mouse_down(MouseButton::Left, cx.listener(|this, _: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>| {
+                        this.scrub_back();
+                        cx.notify();
+                    }))
+                    .child(div().text_xs().text_color(white()).child("< Back")),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_row()
+                    .items_center()
+                    .gap_1()
+                    .flex_1()
+                    .overflow_x_scroll()
+                    .children((0..self.timeline.len()).map(|idx| {
+                        let is_current = idx + 1 == current_tick;
+                        div()
+                            .w_2()
+                            .h_2()
+                            .rounded_full()
+                            .bg(rgb(if is_current {
+                                self.active_theme.scenario_current_bg
+                            } else {
+                                self.active_theme.scenario_default_bg
+                            }))
+                            .cursor_pointer()
+                            .hover(|s| s.bg(rgb(self.active_theme.scenario_current_hover)))
+                            .on_mouse_down(MouseButton::Left, cx.listener(move |this, _: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>| {
+                                this.restore_snapshot(idx);
+                                cx.notify();
+                            }))
+                    })),
+            )
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(self.active_theme.button_neutral_bg))
+                    .cursor_pointer()
+                    .hover(|s| s.bg(rgb(self.active_theme.button_neutral_hover)))
+                    .on_mouse_down(MouseButton::Left, cx.listener(|this, _: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>| {
+                        this.scrub_forward();
+                        cx.notify();
+                    }))
+                    .child(div().text_xs().text_color(white()).child("Forward >")),
+            )
+    }
+
+    /// A persistent strip pinned below the scroll container (so it's always
+    /// visible regardless of scroll position), summarizing every
+    /// background-ish thing the demo is doing right now: in-flight
+    /// [`SyncAnimation`]s, active [`ConflictHighlight`]s, the auto-play
+    /// countdown, and the last [`LegacyMergeReport`]. Shows a spinner glyph
+    /// while anything is pending and collapses to a hollow dot and an "Idle"
+    /// label (still followed by the last merge summary, if any) otherwise,
+    /// the way Zed's `activity_indicator2` does. Clicking it
+    /// expands `show_merge_details` and scrolls the event log into view.
+    fn render_activity_indicator(&self, cx: &mut gpui::prelude::Context<Self>) -> impl IntoElement {
+        let pending = self.is_auto_playing
+            || !self.sync_animations.is_empty()
+            || !self.conflict_highlights.is_empty();
+
+        let mut parts: Vec<String> = Vec::new();
+        if !self.sync_animations.is_empty() {
+            parts.push(format!("{} syncing", self.sync_animations.len()));
+        }
+        if !self.conflict_highlights.is_empty() {
+            parts.push(format!("{} conflicts highlighted", self.conflict_highlights.len()));
+        }
+        if let Some(next_at) = self.auto_play_next_at {
+            let remaining_ms = next_at.saturating_duration_since(Instant::now()).as_millis();
+            parts.push(format!("next step in {}ms", remaining_ms));
+        }
+        if let Some(report) = &self.last_merge_report {
+            parts.push(format!(
+                "last merge: +{} ~{} ={} !{}",
+                report.inserted.len(),
+                report.updated.len(),
+                report.skipped_older.len(),
+                report.conflicts_equal_version.len()
+            ));
+        }
+
+        if !pending {
+            parts.insert(0, "Idle".to_string());
+        }
+        let summary = parts.join(" · ");
+        let spinner_frame = ["◐", "◓", "◑", "◒"][(std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis()
+            / 120
+            % 4) as usize];
+
+        div()
+            .flex()
+            .flex_row()
+            .items_center()
+            .gap_2()
+            .px_3()
+            .py_1()
+            .border_t_1()
+            .border_color(rgb(self.active_theme.border_color))
+            .bg(rgb(self.active_theme.panel_background))
+            .cursor_pointer()
+            .hover(|s| s.bg(rgb(self.active_theme.scenario_default_hover)))
+            .on_mouse_down(MouseButton::Left, cx.listener(|this, _: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>| {
+                this.show_merge_details = true;
+                this.log_scroll_handle.scroll_to_bottom();
+                cx.notify();
+            }))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(rgb(if pending { self.active_theme.merge_updated } else { self.active_theme.muted_text }))
+                    .child(if pending { spinner_frame } else { "○" }),
+            )
+            .child(div().text_xs().text_color(rgb(self.active_theme.text_color_secondary)).child(summary))
+    }
+
+    /// The `Ctrl+K` command palette overlay: the in-progress query, then
+    /// [`Self::filtered_palette_commands`] sorted best-match-first with the
+    /// [`Self::command_palette_selected`] row highlighted and every matched
+    /// character bolded.
+    fn render_command_palette(&self) -> impl IntoElement {
+        let matches = self.filtered_palette_commands();
+        let selected = self.command_palette_selected.min(matches.len().saturating_sub(1));
+
+        div()
+            .absolute()
+            .top_12()
+            .left_1_4()
+            .right_1_4()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .p_3()
+            .bg(rgb(self.active_theme.palette_bg))
+            .rounded_md()
+            .border_1()
+            .border_color(rgb(self.active_theme.palette_border))
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .text_sm()
+                    .text_color(white())
+                    .child(format!("> {}", self.command_palette_query)),
+            )
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .max_h(px(280.))
+                    .overflow_y_scroll()
+                    .children(matches.into_iter().enumerate().map(|(idx, (_, matched_indices, command))| {
+                        div()
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .bg(if idx == selected { rgb(self.active_theme.palette_selected_bg) } else { rgb(self.active_theme.palette_bg) })
+                            .flex()
+                            .flex_row()
+                            .children(command.label.char_indices().map(|(byte_idx, ch)| {
+                                let is_match = matched_indices.contains(&byte_idx);
+                                div()
+                                    .text_xs()
+                                    .when(is_match, |d| d.font_weight(gpui::FontWeight::BOLD).text_color(rgb(self.active_theme.palette_match_text)))
+                                    .when(!is_match, |d| d.text_color(rgb(self.active_theme.text_color)))
+                                    .child(ch.to_string())
+                            }))
+                    })),
+            )
+    }
+
     fn is_scenario_completed(&self, scenario: &DemoScenario) -> bool {
         if !self.is_auto_playing {
             return false;
@@ -1792,6 +2999,7 @@ impl ProfessionalDemo {
             DemoScenario::DatabaseFeatures,
             DemoScenario::StressTest,
             DemoScenario::ProductionEcommerce,
+            DemoScenario::CrashRecovery,
         ];
 
         if let (Some(scenario_idx), Some(current_idx)) = (
@@ -1824,13 +3032,23 @@ impl Render for ProfessionalDemo {
         }
 
         div()
-            .id("main-scroll-container")
             .flex()
             .flex_col()
             .size_full()
+            .child(
+                div()
+            .id("main-scroll-container")
+            .track_focus(&self.focus_handle)
+            .on_key_down(cx.listener(|this, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>| {
+                this.handle_key_down(event);
+                cx.notify();
+            }))
+            .flex()
+            .flex_col()
+            .flex_1()
             .overflow_y_scroll()
-            .bg(rgb(theme::BACKGROUND))
-            .text_color(rgb(theme::TEXT_COLOR))
+            .bg(rgb(self.active_theme.background))
+            .text_color(rgb(self.active_theme.text_color))
             .p_4()
             .gap_4()
             .child(
@@ -1842,7 +3060,7 @@ impl Render for ProfessionalDemo {
                     .justify_between()
                     .pb_3()
                     .border_b_1()
-                    .border_color(rgb(theme::BORDER_COLOR))
+                    .border_color(rgb(self.active_theme.border_color))
                     .child(
                         div()
                             .flex()
@@ -1857,7 +3075,7 @@ impl Render for ProfessionalDemo {
                             .child(
                                 div()
                                     .text_xs()
-                                    .text_color(rgb(theme::MUTED_TEXT))
+                                    .text_color(rgb(self.active_theme.muted_text))
                                     .child("Conflict-free Replicated Relations with Per-Column Versioning")
                             )
                     )
@@ -1871,19 +3089,11 @@ impl Render for ProfessionalDemo {
                                     .px_3()
                                     .py_1()
                                     .rounded_md()
-                                    .bg(rgb(if self.is_auto_playing { 0x7a0d0d } else { 0x0e7a0d }))
+                                    .bg(rgb(if self.is_auto_playing { self.active_theme.danger_bg } else { self.active_theme.success_bg }))
                                     .cursor_pointer()
-                                    .hover(|s| s.bg(rgb(if self.is_auto_playing { 0x9a1d1d } else { 0x1e9a1d })))
+                                    .hover(|s| s.bg(rgb(if self.is_auto_playing { self.active_theme.danger_hover } else { self.active_theme.success_hover })))
                                     .on_mouse_down(MouseButton::Left, cx.listener(|this, _: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>| {
-                                        if this.is_auto_playing {
-                                            this.is_auto_playing = false;
-                                            this.auto_play_next_at = None;
-                                            this.log("Auto-play stopped");
-                                        } else {
-                                            this.is_auto_playing = true;
-                                            this.auto_play_next_at = Some(Instant::now());
-                                            this.log("Auto-play started");
-                                        }
+                                        this.toggle_auto_play();
                                         cx.notify();
                                     }))
                                     .child(
@@ -1893,6 +3103,25 @@ impl Render for ProfessionalDemo {
                                             .child(if self.is_auto_playing { "Pause" } else { "Auto-Play" })
                                     )
                             )
+                            .child(
+                                div()
+                                    .px_3()
+                                    .py_1()
+                                    .rounded_md()
+                                    .bg(rgb(self.active_theme.button_neutral_bg))
+                                    .cursor_pointer()
+                                    .hover(|s| s.bg(rgb(self.active_theme.button_neutral_hover)))
+                                    .on_mouse_down(MouseButton::Left, cx.listener(|this, _: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>| {
+                                        this.audio.toggle_muted();
+                                        cx.notify();
+                                    }))
+                                    .child(
+                                        div()
+                                            .text_sm()
+                                            .text_color(white())
+                                            .child(if self.audio.is_muted() { "Unmute" } else { "Mute" })
+                                    )
+                            )
                     )
             )
             .child(
@@ -1906,19 +3135,19 @@ impl Render for ProfessionalDemo {
                     .flex_col()
                     .gap_2()
                     .p_3()
-                    .bg(rgb(0x252526))
+                    .bg(rgb(self.active_theme.panel_background))
                     .rounded_md()
                     .child(
                         div()
                             .text_base()
                             .font_weight(gpui::FontWeight::SEMIBOLD)
-                            .text_color(rgb(0x4ec9b0))
+                            .text_color(rgb(self.active_theme.title_accent))
                             .child(self.current_scenario.title())
                     )
                     .child(
                         div()
                             .text_sm()
-                            .text_color(rgb(theme::TEXT_COLOR))
+                            .text_color(rgb(self.active_theme.text_color))
                             .child(self.current_scenario.description())
                     )
                     .child(
@@ -1932,9 +3161,9 @@ impl Render for ProfessionalDemo {
                                     .px_3()
                                     .py_1()
                                     .rounded_md()
-                                    .bg(rgb(0x0066cc))
+                                    .bg(rgb(self.active_theme.button_primary_bg))
                                     .cursor_pointer()
-                                    .hover(|s| s.bg(rgb(0x0088ee)))
+                                    .hover(|s| s.bg(rgb(self.active_theme.button_primary_hover)))
                                     .on_mouse_down(MouseButton::Left, cx.listener(|this, _: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>| {
                                         this.execute_step();
                                         cx.notify();
@@ -1946,9 +3175,9 @@ impl Render for ProfessionalDemo {
                                     .px_3()
                                     .py_1()
                                     .rounded_md()
-                                    .bg(rgb(0x4a4a4a))
+                                    .bg(rgb(self.active_theme.button_neutral_bg))
                                     .cursor_pointer()
-                                    .hover(|s| s.bg(rgb(0x5a5a5a)))
+                                    .hover(|s| s.bg(rgb(self.active_theme.button_neutral_hover)))
                                     .on_mouse_down(MouseButton::Left, cx.listener(|this, _: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>| {
                                         this.reset_scenario();
                                         cx.notify();
@@ -1960,14 +3189,16 @@ impl Render for ProfessionalDemo {
                                     .px_3()
                                     .py_1()
                                     .rounded_md()
-                                    .bg(rgb(0x6a4a6a))
+                                    .bg(rgb(self.active_theme.button_accent_bg))
                                     .cursor_pointer()
-                                    .hover(|s| s.bg(rgb(0x7a5a7a)))
+                                    .hover(|s| s.bg(rgb(self.active_theme.button_accent_hover)))
                                     .on_mouse_down(MouseButton::Left, cx.listener(|this, _: &MouseDownEvent, _: &mut Window, cx: &mut Context<Self>| {
                                         this.tiebreak_policy = match this.tiebreak_policy {
                                             TieBreakPolicy::PreferExisting => TieBreakPolicy::PreferIncoming,
                                             TieBreakPolicy::PreferIncoming => TieBreakPolicy::LexicographicMin,
-                                            TieBreakPolicy::LexicographicMin => TieBreakPolicy::PreferExisting,
+                                            TieBreakPolicy::LexicographicMin => TieBreakPolicy::LastWriteWins,
+                                            TieBreakPolicy::LastWriteWins => TieBreakPolicy::PreferExisting,
+                                            TieBreakPolicy::MultiValue => TieBreakPolicy::PreferExisting,
                                         };
                                         this.log(&format!("Tiebreak policy: {:?}", this.tiebreak_policy));
                                         cx.notify();
@@ -1978,7 +3209,7 @@ impl Render for ProfessionalDemo {
                                 div()
                                     .px_2()
                                     .py_1()
-                                    .bg(rgb(0x2a2a3a))
+                                    .bg(rgb(self.active_theme.step_badge_bg))
                                     .rounded_md()
                                     .child(div().text_sm().child(format!("Step {}/{}",
                                         self.current_step.min(self.current_scenario.steps().len()),
@@ -1986,6 +3217,10 @@ impl Render for ProfessionalDemo {
                             )
                     )
             )
+            .child(
+                // Timeline scrubber
+                self.render_timeline_scrubber(cx)
+            )
             .child(
                 // Peers grid
                 div()
@@ -2005,29 +3240,52 @@ impl Render for ProfessionalDemo {
                         .flex_row()
                         .gap_4()
                         .p_3()
-                        .bg(rgb(0x1a1a2a))
+                        .bg(rgb(self.active_theme.report_bg))
                         .rounded_md()
                         .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("Last Merge Report:"))
-                        .child(div().text_sm().text_color(rgb(0x77dd77)).child(format!("Inserted: {}", report.inserted.len())))
-                        .child(div().text_sm().text_color(rgb(0x77aadd)).child(format!("Updated: {}", report.updated.len())))
-                        .child(div().text_sm().text_color(rgb(0xdd7777)).child(format!("Skipped: {}", report.skipped_older.len())))
-                        .child(div().text_sm().text_color(rgb(0xdddd77)).child(format!("Conflicts: {}", report.conflicts_equal_version.len())))
+                        .child(div().text_sm().text_color(rgb(self.active_theme.merge_inserted)).child(format!("Inserted: {}", report.inserted.len())))
+                        .child(div().text_sm().text_color(rgb(self.active_theme.merge_updated)).child(format!("Updated: {}", report.updated.len())))
+                        .child(div().text_sm().text_color(rgb(self.active_theme.merge_skipped)).child(format!("Skipped: {}", report.skipped_older.len())))
+                        .child(div().text_sm().text_color(rgb(self.active_theme.merge_conflicts)).child(format!("Conflicts: {}", report.conflicts_equal_version.len())))
+                )
+            })
+            .when(self.show_metrics_panel && !self.peers.is_empty(), |d| {
+                let metrics = &self.peers[self.target_peer_index].engine.metrics;
+                d.child(
+                    div()
+                        .flex()
+                        .flex_row()
+                        .gap_4()
+                        .p_3()
+                        .bg(rgb(self.active_theme.report_bg))
+                        .rounded_md()
+                        .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("Metrics:"))
+                        .child(div().text_sm().text_color(rgb(self.active_theme.merge_inserted)).child(format!("Rows merged: {}", metrics.rows_merged.get())))
+                        .child(div().text_sm().text_color(rgb(self.active_theme.merge_skipped)).child(format!("Conflicts: {}", metrics.conflicts_detected.get())))
+                        .child(div().text_sm().text_color(rgb(self.active_theme.merge_updated)).child(format!("Merges: {}", metrics.convergence_count())))
+                        .child(div().text_sm().text_color(rgb(self.active_theme.merge_conflicts)).child(format!("Avg convergence: {:.4}s", metrics.convergence_avg_seconds())))
                 )
             })
             .child(
                 // Log
                 div()
+                    .id("event-log")
+                    .track_scroll(&self.log_scroll_handle)
                     .flex()
                     .flex_col()
                     .gap_1()
                     .p_3()
-                    .bg(rgb(0x1e1e1e))
+                    .bg(rgb(self.active_theme.background))
                     .rounded_md()
                     .max_h(px(200.))
+                    .overflow_y_scroll()
                     .child(div().text_sm().font_weight(gpui::FontWeight::SEMIBOLD).child("Event Log:"))
                     .children(self.log_messages.iter().map(|msg| {
-                        div().text_xs().text_color(rgb(theme::TEXT_COLOR_SECONDARY)).child(msg.clone())
+                        div().text_xs().text_color(rgb(self.active_theme.text_color_secondary)).child(msg.clone())
                     }))
             )
+            .when(self.command_palette_open, |d| d.child(self.render_command_palette()))
+            )
+            .child(self.render_activity_indicator(cx))
     }
 }
@@ -0,0 +1,366 @@
+//! Content-defined chunking (CDC) for large column values, so syncing an
+//! edit to part of a large blob costs proportional to the changed region
+//! rather than the whole value.
+//!
+//! Chunk boundaries are picked by a Gear rolling hash (the same family
+//! FastCDC is built on): as each byte slides into the hash, a chunk ends
+//! as soon as the hash's low bits are all zero, bounded by
+//! [`ChunkConfig::min_size`]/[`ChunkConfig::max_size`] so a pathological
+//! input can't produce a zero-size or unbounded chunk. Because the cut
+//! points are a function of the bytes around them rather than a fixed
+//! offset, inserting or deleting a few bytes in the middle of a value only
+//! shifts the chunks touching that edit — everything else still cuts at
+//! the same content and hashes identically, so a [`ChunkStore`] recognizes
+//! it as already-known content and a peer never needs to re-send it.
+
+use std::collections::{HashMap, HashSet};
+
+use sha2::{Digest as _, Sha256};
+
+/// Content hash identifying a chunk, independent of where it appears.
+pub type ChunkHash = [u8; 32];
+
+/// Prefixed to a [`crate::storage::Cell`]/[`crate::storage::DagNode`]
+/// value's bytes when it holds an ordered list of chunk hashes rather than
+/// the literal value — mirrors [`crate::row::encode_conflict_set`]'s
+/// marker-prefix trick for packing a second representation into the same
+/// `Vec<u8>` field, distinct bytes so the two markers can never collide.
+const CHUNK_REF_MARKER: [u8; 4] = [0xC4, 0xDC, 0x00, 0x1D];
+
+const fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// 256 fixed pseudo-random constants, one per byte value, mixed into the
+/// rolling hash as each byte is read. Generated with `splitmix64` from a
+/// fixed seed rather than hardcoded as a literal array — deterministic
+/// across builds, which is all a Gear hash needs (it doesn't need to be
+/// cryptographically random, just well-distributed).
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut seed = 0x517c_c1b7_2722_0a95u64;
+    let mut i = 0;
+    while i < 256 {
+        seed = splitmix64(seed);
+        table[i] = seed;
+        i += 1;
+    }
+    table
+}
+
+static GEAR: [u64; 256] = gear_table();
+
+/// Bounds on chunk size for [`chunk`]. `avg_size` controls how often a cut
+/// point occurs (bigger average, fewer and larger chunks) and is converted
+/// to a bitmask once here rather than re-derived for every byte scanned.
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkConfig {
+    pub min_size: usize,
+    pub max_size: usize,
+    mask: u64,
+}
+
+impl ChunkConfig {
+    pub fn new(min_size: usize, avg_size: usize, max_size: usize) -> Self {
+        let bits = (avg_size.max(2) as f64).log2().round() as u32;
+        Self { min_size, max_size, mask: (1u64 << bits) - 1 }
+    }
+}
+
+impl Default for ChunkConfig {
+    /// Tuned for column-sized values rather than filesystem-scale blobs: a
+    /// 64-byte average keeps modest blob-like columns actually splitting
+    /// into more than one chunk instead of always degenerating to one.
+    fn default() -> Self {
+        Self::new(16, 64, 256)
+    }
+}
+
+/// Split `data` into content-defined chunks. Concatenating the result in
+/// order reproduces `data` exactly.
+pub fn chunk<'a>(data: &'a [u8], config: &ChunkConfig) -> Vec<&'a [u8]> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut hash: u64 = 0;
+
+    for i in 0..data.len() {
+        hash = (hash << 1).wrapping_add(GEAR[data[i] as usize]);
+        let size = i - start + 1;
+        let at_boundary = size >= config.min_size && (hash & config.mask == 0 || size >= config.max_size);
+        if at_boundary {
+            chunks.push(&data[start..=i]);
+            start = i + 1;
+            hash = 0;
+        }
+    }
+    if start < data.len() {
+        chunks.push(&data[start..]);
+    }
+    chunks
+}
+
+/// Content hash of a single chunk, used as its key in a [`ChunkStore`].
+pub fn hash_chunk(bytes: &[u8]) -> ChunkHash {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&Sha256::digest(bytes));
+    out
+}
+
+/// Pack an ordered list of chunk hashes as a cell's stored value, in place
+/// of the literal bytes they reassemble into.
+pub fn encode_chunk_refs(hashes: &[ChunkHash]) -> Vec<u8> {
+    let mut buf = CHUNK_REF_MARKER.to_vec();
+    crate::wire::write_u32(&mut buf, hashes.len() as u32);
+    for hash in hashes {
+        buf.extend_from_slice(hash);
+    }
+    buf
+}
+
+/// Inverse of [`encode_chunk_refs`]. Returns `None` for an ordinary,
+/// non-chunked cell value.
+pub fn decode_chunk_refs(bytes: &[u8]) -> Option<Vec<ChunkHash>> {
+    if !bytes.starts_with(&CHUNK_REF_MARKER) {
+        return None;
+    }
+    let mut cursor = CHUNK_REF_MARKER.len();
+    let count = crate::wire::read_u32(bytes, &mut cursor).ok()? as usize;
+    let mut hashes = Vec::with_capacity(count);
+    for _ in 0..count {
+        let slice = bytes.get(cursor..cursor + 32)?;
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(slice);
+        hashes.push(hash);
+        cursor += 32;
+    }
+    Some(hashes)
+}
+
+/// A content-addressed store of chunks, deduplicated by [`hash_chunk`] so
+/// identical content shared across rows, columns, and versions of a
+/// table's changesets is stored exactly once.
+#[derive(Debug, Default)]
+pub struct ChunkStore {
+    chunks: HashMap<ChunkHash, Vec<u8>>,
+}
+
+impl ChunkStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Split `value` with `config` and insert every chunk not already
+    /// present. Returns the ordered list of chunk hashes that, passed to
+    /// [`Self::reassemble`], reconstruct `value`.
+    pub fn put(&mut self, value: &[u8], config: &ChunkConfig) -> Vec<ChunkHash> {
+        chunk(value, config)
+            .into_iter()
+            .map(|bytes| {
+                let hash = hash_chunk(bytes);
+                self.chunks.entry(hash).or_insert_with(|| bytes.to_vec());
+                hash
+            })
+            .collect()
+    }
+
+    pub fn contains(&self, hash: &ChunkHash) -> bool {
+        self.chunks.contains_key(hash)
+    }
+
+    pub fn get(&self, hash: &ChunkHash) -> Option<&[u8]> {
+        self.chunks.get(hash).map(|v| v.as_slice())
+    }
+
+    /// Reassemble a value from its ordered chunk hashes, or `None` if any
+    /// of them is missing from this store — the caller is expected to have
+    /// fetched those first (see [`Self::missing`]).
+    pub fn reassemble(&self, hashes: &[ChunkHash]) -> Option<Vec<u8>> {
+        let mut value = Vec::new();
+        for hash in hashes {
+            value.extend_from_slice(self.get(hash)?);
+        }
+        Some(value)
+    }
+
+    /// Which of `hashes` this store doesn't have yet — what a receiver
+    /// needs from its peer before it can [`Self::reassemble`].
+    pub fn missing(&self, hashes: &[ChunkHash]) -> Vec<ChunkHash> {
+        hashes.iter().filter(|h| !self.contains(h)).copied().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Store `value` as-is if it's at or below `threshold`, otherwise chunk
+    /// it via [`Self::put`] and return [`encode_chunk_refs`] of the result
+    /// instead — the bytes a caller writes into a `Cell`/`DagNode` either
+    /// way, so small values never pay the marker-and-hash-list overhead a
+    /// large value's dedup is worth.
+    pub fn put_above_threshold(&mut self, value: &[u8], config: &ChunkConfig, threshold: usize) -> Vec<u8> {
+        if value.len() <= threshold {
+            return value.to_vec();
+        }
+        encode_chunk_refs(&self.put(value, config))
+    }
+
+    /// Inverse of [`Self::put_above_threshold`]: reassemble `bytes` if
+    /// they're [`encode_chunk_refs`]-encoded, or return them unchanged if
+    /// they're an ordinary inline value. `None` only if `bytes` is chunked
+    /// but references a hash this store doesn't have — see [`Self::missing`].
+    pub fn resolve(&self, bytes: &[u8]) -> Option<Vec<u8>> {
+        match decode_chunk_refs(bytes) {
+            Some(hashes) => self.reassemble(&hashes),
+            None => Some(bytes.to_vec()),
+        }
+    }
+
+    /// Drop every chunk not in `referenced` — the refcount-based half of
+    /// chunked storage's GC story: a chunk is only ever reclaimed once
+    /// nothing left in the table's retained DAG history still points to
+    /// it. Returns how many chunks were dropped.
+    pub fn gc_unreferenced(&mut self, referenced: &HashSet<ChunkHash>) -> usize {
+        let before = self.chunks.len();
+        self.chunks.retain(|hash, _| referenced.contains(hash));
+        before - self.chunks.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunking_reproduces_the_original_bytes() {
+        let data: Vec<u8> = (0..2000).map(|i| (i % 251) as u8).collect();
+        let config = ChunkConfig::default();
+        let chunks = chunk(&data, &config);
+
+        assert!(chunks.len() > 1, "2000 varied bytes at a 64-byte average should split into multiple chunks");
+        let reassembled: Vec<u8> = chunks.into_iter().flatten().copied().collect();
+        assert_eq!(reassembled, data);
+    }
+
+    #[test]
+    fn chunk_sizes_stay_within_configured_bounds() {
+        let data: Vec<u8> = (0..5000).map(|i| ((i * 37) % 256) as u8).collect();
+        let config = ChunkConfig::default();
+
+        for c in chunk(&data, &config) {
+            assert!(c.len() <= config.max_size);
+        }
+    }
+
+    #[test]
+    fn an_edit_in_the_middle_only_changes_the_chunks_touching_it() {
+        let base: Vec<u8> = (0..3000).map(|i| (i % 191) as u8).collect();
+        let mut edited = base.clone();
+        edited.splice(1500..1500, std::iter::repeat(0xAAu8).take(5));
+
+        let config = ChunkConfig::default();
+        let base_chunks: Vec<ChunkHash> = chunk(&base, &config).into_iter().map(hash_chunk).collect();
+        let edited_chunks: Vec<ChunkHash> = chunk(&edited, &config).into_iter().map(hash_chunk).collect();
+
+        let shared = base_chunks.iter().filter(|h| edited_chunks.contains(h)).count();
+        assert!(shared > 0, "content far from the edit should still hash identically");
+        assert!(shared < base_chunks.len(), "the edit should actually have touched something");
+    }
+
+    #[test]
+    fn chunk_store_deduplicates_identical_content() {
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        let value: Vec<u8> = (0..1000).map(|i| (i % 97) as u8).collect();
+
+        let first = store.put(&value, &config);
+        let before = store.len();
+        let second = store.put(&value, &config);
+
+        assert_eq!(first, second);
+        assert_eq!(store.len(), before, "re-inserting identical content must not grow the store");
+    }
+
+    #[test]
+    fn put_above_threshold_stores_small_values_inline() {
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        let value = b"tiny".to_vec();
+
+        let stored = store.put_above_threshold(&value, &config, 64);
+
+        assert_eq!(stored, value);
+        assert!(store.is_empty(), "a value at or below the threshold must never touch the chunk store");
+    }
+
+    #[test]
+    fn put_above_threshold_chunks_and_refs_large_values() {
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        let value: Vec<u8> = (0..2000).map(|i| (i % 251) as u8).collect();
+
+        let stored = store.put_above_threshold(&value, &config, 64);
+
+        assert_ne!(stored, value);
+        assert!(!store.is_empty());
+        assert_eq!(store.resolve(&stored).unwrap(), value);
+    }
+
+    #[test]
+    fn resolve_is_transparent_for_both_inline_and_chunked_values() {
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+
+        let small = store.put_above_threshold(b"tiny", &config, 64);
+        assert_eq!(store.resolve(&small).unwrap(), b"tiny");
+
+        let large: Vec<u8> = (0..2000).map(|i| (i % 191) as u8).collect();
+        let stored = store.put_above_threshold(&large, &config, 64);
+        assert_eq!(store.resolve(&stored).unwrap(), large);
+    }
+
+    #[test]
+    fn gc_unreferenced_drops_only_chunks_nothing_still_points_to() {
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        let kept: Vec<u8> = (0..2000).map(|i| (i % 97) as u8).collect();
+        let dropped: Vec<u8> = (0..2000).map(|i| (i * 3 % 233) as u8).collect();
+
+        let kept_hashes: HashSet<ChunkHash> = store.put(&kept, &config).into_iter().collect();
+        store.put(&dropped, &config);
+
+        let removed = store.gc_unreferenced(&kept_hashes);
+
+        assert!(removed > 0);
+        assert!(kept_hashes.iter().all(|h| store.contains(h)));
+        assert_eq!(store.reassemble(&kept_hashes.into_iter().collect::<Vec<_>>()).is_some(), true);
+    }
+
+    #[test]
+    fn reassemble_fails_when_a_chunk_is_missing() {
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        let value: Vec<u8> = (0..500).map(|i| (i % 53) as u8).collect();
+        let hashes = store.put(&value, &config);
+
+        let mut receiver = ChunkStore::new();
+        assert_eq!(receiver.missing(&hashes), hashes);
+        assert!(receiver.reassemble(&hashes).is_none());
+
+        for hash in &hashes {
+            receiver.chunks.insert(*hash, store.get(hash).unwrap().to_vec());
+        }
+        assert!(receiver.missing(&hashes).is_empty());
+        assert_eq!(receiver.reassemble(&hashes).unwrap(), value);
+    }
+}
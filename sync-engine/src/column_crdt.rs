@@ -0,0 +1,312 @@
+//! Per-column CRDT types beyond the last-writer-wins register
+//! [`crate::Lww`] already provides: a grow/shrink counter and an
+//! observed-remove set, each mergeable the same way — structurally, by
+//! folding one replica's state into another's, with no version number or
+//! [`crate::TieBreakPolicy`] involved.
+//!
+//! [`CrdtKind`] lets a column opt into one of these instead: declare it via
+//! [`crate::CrrTable::declare_crdt_column`] and [`crate::CrrTable::merge`]
+//! folds the incoming and existing bytes through that type's
+//! [`ColumnCrdt::merge`] rather than [`crate::merge::resolve_versions`]/
+//! [`crate::merge::resolve_conflict`] — so a CRDT column converges
+//! structurally and never contributes to [`crate::merge::MergeReport::conflicts`].
+//! Everything else in the table (the wire format, the Merkle/chunking
+//! layers) still only ever sees the encoded `Vec<u8>`, same as any other
+//! cell.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// A value mergeable with another replica's copy of the same column,
+/// independent of any version counter, and encodable to the `Vec<u8>` a
+/// [`crate::storage::Cell`] actually stores.
+pub trait ColumnCrdt: Sized {
+    fn merge(&mut self, other: &Self);
+
+    /// Encode to the bytes a [`crate::storage::Cell::value`] holds on disk.
+    fn to_bytes(&self) -> Result<Vec<u8>>;
+
+    /// Decode bytes previously produced by [`Self::to_bytes`].
+    fn from_bytes(bytes: &[u8]) -> Result<Self>;
+}
+
+/// Which CRDT a column declared via [`crate::CrrTable::declare_crdt_column`]
+/// merges as, in place of the usual version-compare path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrdtKind {
+    PnCounter,
+    /// Elements are stored as `String`s — the natural fit for a
+    /// schema-free text cell; wrap non-string values in their own
+    /// string encoding before inserting.
+    OrSet,
+}
+
+fn encode_cbor<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    ciborium::into_writer(value, &mut buf)
+        .map_err(|e| Error::InvalidState(format!("failed to encode CRDT column: {}", e)))?;
+    Ok(buf)
+}
+
+fn decode_cbor<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    ciborium::from_reader(bytes)
+        .map_err(|e| Error::InvalidState(format!("failed to decode CRDT column: {}", e)))
+}
+
+/// A grow/shrink counter: each replica tracks its own running increment and
+/// decrement totals, and merging takes the element-wise max of every
+/// replica's tallies — so a merge never double-counts an update that's
+/// already been seen, no matter how many times or in what order replicas
+/// exchange state. The current value is `Σinc − Σdec`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PnCounter {
+    inc: HashMap<String, u64>,
+    dec: HashMap<String, u64>,
+}
+
+impl PnCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a local increment by `replica_id`, additive on top of that
+    /// replica's own prior increments.
+    pub fn increment(&mut self, replica_id: &str, amount: u64) {
+        *self.inc.entry(replica_id.to_string()).or_insert(0) += amount;
+    }
+
+    /// Record a local decrement by `replica_id`, additive on top of that
+    /// replica's own prior decrements.
+    pub fn decrement(&mut self, replica_id: &str, amount: u64) {
+        *self.dec.entry(replica_id.to_string()).or_insert(0) += amount;
+    }
+
+    /// The counter's current value: total increments minus total
+    /// decrements, across every replica.
+    pub fn value(&self) -> i64 {
+        let total_inc: u64 = self.inc.values().sum();
+        let total_dec: u64 = self.dec.values().sum();
+        total_inc as i64 - total_dec as i64
+    }
+}
+
+impl ColumnCrdt for PnCounter {
+    fn merge(&mut self, other: &Self) {
+        for (replica_id, amount) in &other.inc {
+            let entry = self.inc.entry(replica_id.clone()).or_insert(0);
+            *entry = (*entry).max(*amount);
+        }
+        for (replica_id, amount) in &other.dec {
+            let entry = self.dec.entry(replica_id.clone()).or_insert(0);
+            *entry = (*entry).max(*amount);
+        }
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encode_cbor(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        decode_cbor(bytes)
+    }
+}
+
+/// A unique tag for one insertion into an [`OrSet`]: which replica made it,
+/// and that replica's own local counter at the time, so the same logical
+/// insertion from two replicas never collides with an unrelated one.
+pub type Token = (String, u64);
+
+/// An observed-remove set: elements are tagged with a unique [`Token`] when
+/// added, and removing an element tombstones every token it's currently
+/// tagged with rather than deleting the element outright. Merging unions
+/// both replicas' adds and tombstones; an element is present iff at least
+/// one of its tokens hasn't been tombstoned. This lets a concurrent add
+/// and remove of the *same* element converge on "present" only if the add's
+/// token postdates every tombstone that reached this replica — unlike a
+/// plain LWW register, the add and remove don't have to race on a single
+/// timestamp to resolve correctly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrSet<T> {
+    adds: HashMap<Token, T>,
+    tombstones: HashSet<Token>,
+}
+
+impl<T> Default for OrSet<T> {
+    fn default() -> Self {
+        Self { adds: HashMap::new(), tombstones: HashSet::new() }
+    }
+}
+
+impl<T: Clone + Eq + Hash> OrSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tag `value` with `(replica_id, counter)` and add it. `counter` must
+    /// be unique per `replica_id` (e.g. that replica's own monotonic write
+    /// counter) so concurrent adds from the same replica don't collide.
+    pub fn insert(&mut self, replica_id: &str, counter: u64, value: T) {
+        self.adds.insert((replica_id.to_string(), counter), value);
+    }
+
+    /// Tombstone every token currently tagging `value` still live on this
+    /// replica. A token added later by another replica for the same value,
+    /// not yet observed here, survives the merge that brings it in.
+    pub fn remove(&mut self, value: &T) {
+        let tokens: Vec<Token> = self.adds.iter()
+            .filter(|(token, v)| *v == value && !self.tombstones.contains(*token))
+            .map(|(token, _)| token.clone())
+            .collect();
+        self.tombstones.extend(tokens);
+    }
+
+    /// Whether `value` has at least one live (non-tombstoned) token.
+    pub fn contains(&self, value: &T) -> bool {
+        self.adds.iter().any(|(token, v)| v == value && !self.tombstones.contains(token))
+    }
+
+    /// Every distinct value with at least one live token.
+    pub fn elements(&self) -> HashSet<&T> {
+        self.adds.iter()
+            .filter(|(token, _)| !self.tombstones.contains(*token))
+            .map(|(_, v)| v)
+            .collect()
+    }
+}
+
+impl<T: Clone + Eq + Hash + Serialize + DeserializeOwned> ColumnCrdt for OrSet<T> {
+    fn merge(&mut self, other: &Self) {
+        for (token, value) in &other.adds {
+            self.adds.entry(token.clone()).or_insert_with(|| value.clone());
+        }
+        self.tombstones.extend(other.tombstones.iter().cloned());
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        encode_cbor(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        decode_cbor(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pn_counter_merge_takes_the_max_of_each_replicas_tallies() {
+        let mut a = PnCounter::new();
+        a.increment("r1", 5);
+        a.decrement("r1", 1);
+
+        let mut b = PnCounter::new();
+        b.increment("r1", 5);
+        b.increment("r2", 3);
+
+        a.merge(&b);
+        assert_eq!(a.value(), 5 + 3 - 1);
+
+        // Merging the same state again is a no-op (max of equal tallies).
+        a.merge(&b);
+        assert_eq!(a.value(), 5 + 3 - 1);
+    }
+
+    #[test]
+    fn pn_counter_merge_is_commutative() {
+        let mut a = PnCounter::new();
+        a.increment("r1", 10);
+        let mut b = PnCounter::new();
+        b.increment("r2", 4);
+        b.decrement("r2", 1);
+
+        let mut a_then_b = a.clone();
+        a_then_b.merge(&b);
+        let mut b_then_a = b.clone();
+        b_then_a.merge(&a);
+
+        assert_eq!(a_then_b.value(), b_then_a.value());
+    }
+
+    #[test]
+    fn or_set_merge_unions_adds_and_tombstones() {
+        let mut a = OrSet::new();
+        a.insert("r1", 1, "tag:urgent".to_string());
+        let mut b = OrSet::new();
+        b.insert("r2", 1, "tag:blocked".to_string());
+
+        a.merge(&b);
+        assert!(a.contains(&"tag:urgent".to_string()));
+        assert!(a.contains(&"tag:blocked".to_string()));
+        assert_eq!(a.elements().len(), 2);
+    }
+
+    #[test]
+    fn or_set_remove_only_tombstones_tokens_already_observed() {
+        let mut replica_a = OrSet::new();
+        replica_a.insert("r1", 1, "tag:urgent".to_string());
+
+        let mut replica_b = replica_a.clone();
+
+        // Replica A removes the element it's seen...
+        replica_a.remove(&"tag:urgent".to_string());
+        assert!(!replica_a.contains(&"tag:urgent".to_string()));
+
+        // ...while replica B concurrently re-adds it under a fresh token,
+        // unobserved by A yet.
+        replica_b.insert("r2", 1, "tag:urgent".to_string());
+
+        // Merging brings in the new token, which wasn't tombstoned.
+        replica_a.merge(&replica_b);
+        assert!(replica_a.contains(&"tag:urgent".to_string()), "a concurrent re-add survives a remove of the old token");
+    }
+
+    #[test]
+    fn pn_counter_round_trips_through_to_bytes_and_from_bytes() {
+        let mut counter = PnCounter::new();
+        counter.increment("r1", 7);
+        counter.decrement("r1", 2);
+
+        let bytes = counter.to_bytes().unwrap();
+        let decoded = PnCounter::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.value(), 5);
+    }
+
+    #[test]
+    fn or_set_round_trips_through_to_bytes_and_from_bytes() {
+        let mut set: OrSet<String> = OrSet::new();
+        set.insert("r1", 1, "tag:urgent".to_string());
+
+        let bytes = set.to_bytes().unwrap();
+        let decoded = OrSet::from_bytes(&bytes).unwrap();
+        assert!(decoded.contains(&"tag:urgent".to_string()));
+    }
+
+    #[test]
+    fn or_set_merge_is_commutative_and_idempotent() {
+        let mut a = OrSet::new();
+        a.insert("r1", 1, 1u32);
+        a.insert("r1", 2, 2u32);
+        a.remove(&1u32);
+
+        let mut b = OrSet::new();
+        b.insert("r2", 1, 3u32);
+
+        let mut a_then_b = a.clone();
+        a_then_b.merge(&b);
+        let mut b_then_a = b.clone();
+        b_then_a.merge(&a);
+
+        assert_eq!(a_then_b.elements(), b_then_a.elements());
+
+        let mut merged_twice = a_then_b.clone();
+        merged_twice.merge(&a_then_b.clone());
+        assert_eq!(merged_twice.elements(), a_then_b.elements());
+    }
+}
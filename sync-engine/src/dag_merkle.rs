@@ -0,0 +1,324 @@
+//! Append-only Merkle accumulator over a single cell's `DagNode` history.
+//!
+//! Where [`crate::merkle::MerkleTree`] trees an entire table's current
+//! rows for anti-entropy, this module trees one cell's append-only
+//! version history so a peer can prove what it retained or discarded
+//! without shipping the full `DagNode` sequence. Built as a Merkle
+//! Mountain Range: leaves fold into a stack of complete peaks via
+//! binary-counter carry propagation as they're appended (the append-merkle
+//! design), so [`DagMerkleAccumulator::root`] only ever touches O(log n)
+//! peaks rather than rehashing the whole history. Each leaf's
+//! authentication path up to its own peak is recorded the moment that
+//! peak closes and never changes afterwards, so [`Storage::gc_dag`](crate::storage::Storage::gc_dag)
+//! trimming older [`DagNode`]s out of a backend's history can drop their
+//! proof-path entries too, via [`DagMerkleAccumulator::forget`], without
+//! disturbing the root or any surviving leaf's proof.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+use crate::storage::DagNode;
+use crate::wire::{write_bytes, write_option_u64};
+
+/// A 32-byte digest: a leaf hash, an internal node hash, or a bagged root.
+pub type Digest = [u8; 32];
+
+/// The digest of an accumulator with no leaves.
+pub const EMPTY_DIGEST: Digest = [0u8; 32];
+
+/// An inclusion proof that `(version, value)` is a leaf of an accumulator
+/// whose root is some `root: Digest`, checked via [`verify_dag_proof`].
+/// Carries the leaf's other fields (everything [`DagNode`] has besides
+/// `version`/`value`) so a verifier doesn't need its own copy of the node
+/// to recompute the leaf hash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MerkleProof {
+    pub parent_version: Option<u64>,
+    pub parent2_version: Option<u64>,
+    pub timestamp: u64,
+    pub is_tombstone: bool,
+    /// Sibling digests from the leaf up to its own peak, nearest first.
+    pub path: Vec<Digest>,
+    /// For each `path` entry, whether the node being folded up so far is
+    /// the *left* child (so the sibling combines on its right).
+    pub path_is_left: Vec<bool>,
+    /// The bagged digest of every peak before this leaf's own, in fold
+    /// order. `None` if this leaf's peak is the first (tallest).
+    pub prefix_acc: Option<Digest>,
+    /// The peaks after this leaf's own, in fold order.
+    pub suffix_peaks: Vec<Digest>,
+}
+
+struct Peak {
+    height: u32,
+    digest: Digest,
+    /// Versions still tracked under this peak — consulted only to locate
+    /// which peak a version's proof needs bagging against; never hashed.
+    members: Vec<u64>,
+}
+
+/// Incremental Merkle Mountain Range over one cell's `DagNode` sequence.
+/// Construct one per `(pk, col)` and feed it every [`DagNode`] a backend's
+/// `append_dag_node` accepts, in the same order.
+#[derive(Default)]
+pub struct DagMerkleAccumulator {
+    peaks: Vec<Peak>,
+    paths: HashMap<u64, (Vec<Digest>, Vec<bool>)>,
+}
+
+impl DagMerkleAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `node` as the next leaf, propagating carries the way a
+    /// binary counter does: a freshly pushed height-0 node merges with the
+    /// peak behind it whenever the two share a height, repeating until the
+    /// trailing peaks have strictly decreasing heights again.
+    pub fn append(&mut self, node: &DagNode) {
+        let digest = leaf_digest(node.version, node.parent_version, node.parent2_version, node.timestamp, node.is_tombstone, &node.value);
+        self.paths.insert(node.version, (Vec::new(), Vec::new()));
+        self.peaks.push(Peak { height: 0, digest, members: vec![node.version] });
+
+        while self.peaks.len() >= 2 {
+            let last = self.peaks.len() - 1;
+            if self.peaks[last].height != self.peaks[last - 1].height {
+                break;
+            }
+            let right = self.peaks.pop().unwrap();
+            let left = self.peaks.pop().unwrap();
+
+            for v in &left.members {
+                if let Some(p) = self.paths.get_mut(v) {
+                    p.0.push(right.digest);
+                    p.1.push(true);
+                }
+            }
+            for v in &right.members {
+                if let Some(p) = self.paths.get_mut(v) {
+                    p.0.push(left.digest);
+                    p.1.push(false);
+                }
+            }
+
+            let mut members = left.members;
+            members.extend(right.members);
+            self.peaks.push(Peak {
+                height: left.height + 1,
+                digest: hash_internal(&left.digest, &right.digest),
+                members,
+            });
+        }
+    }
+
+    /// The current root: the peaks bagged left to right (tallest first),
+    /// or `None` if no leaves have been appended yet.
+    pub fn root(&self) -> Option<Digest> {
+        let mut iter = self.peaks.iter();
+        let first = iter.next()?;
+        let mut acc = first.digest;
+        for p in iter {
+            acc = hash_bag(&acc, &p.digest);
+        }
+        Some(acc)
+    }
+
+    /// An inclusion proof for `node` (which must be a version this
+    /// accumulator has appended and not yet [`Self::forget`]-ten), or
+    /// `None` if it isn't currently tracked.
+    pub fn proof(&self, node: &DagNode) -> Option<MerkleProof> {
+        let (path, path_is_left) = self.paths.get(&node.version)?.clone();
+        let peak_index = self.peaks.iter().position(|p| p.members.contains(&node.version))?;
+
+        let prefix_acc = if peak_index == 0 {
+            None
+        } else {
+            let mut acc = self.peaks[0].digest;
+            for p in &self.peaks[1..peak_index] {
+                acc = hash_bag(&acc, &p.digest);
+            }
+            Some(acc)
+        };
+        let suffix_peaks = self.peaks[peak_index + 1..].iter().map(|p| p.digest).collect();
+
+        Some(MerkleProof {
+            parent_version: node.parent_version,
+            parent2_version: node.parent2_version,
+            timestamp: node.timestamp,
+            is_tombstone: node.is_tombstone,
+            path,
+            path_is_left,
+            prefix_acc,
+            suffix_peaks,
+        })
+    }
+
+    /// Stop tracking `version`: drop its proof-path entry and its
+    /// membership in whichever peak it falls under. The peak's own digest
+    /// (and so the root, and every other leaf's proof) is untouched, since
+    /// neither depends on `members` once a peak has closed — this is what
+    /// lets `gc_dag`/`remove_dag_version` trim history without
+    /// invalidating proofs for versions that survive the trim.
+    pub fn forget(&mut self, version: u64) {
+        self.paths.remove(&version);
+        for peak in &mut self.peaks {
+            peak.members.retain(|v| *v != version);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn leaf_digest(
+    version: u64,
+    parent_version: Option<u64>,
+    parent2_version: Option<u64>,
+    timestamp: u64,
+    is_tombstone: bool,
+    value: &[u8],
+) -> Digest {
+    let mut buf = vec![0x00u8];
+    buf.extend_from_slice(&version.to_le_bytes());
+    write_option_u64(&mut buf, parent_version);
+    write_option_u64(&mut buf, parent2_version);
+    buf.extend_from_slice(&timestamp.to_le_bytes());
+    buf.push(is_tombstone as u8);
+    write_bytes(&mut buf, value);
+    sha256(&buf)
+}
+
+fn hash_internal(left: &Digest, right: &Digest) -> Digest {
+    let mut buf = Vec::with_capacity(65);
+    buf.push(0x01u8);
+    buf.extend_from_slice(left);
+    buf.extend_from_slice(right);
+    sha256(&buf)
+}
+
+fn hash_bag(acc: &Digest, peak: &Digest) -> Digest {
+    let mut buf = Vec::with_capacity(65);
+    buf.push(0x02u8);
+    buf.extend_from_slice(acc);
+    buf.extend_from_slice(peak);
+    sha256(&buf)
+}
+
+fn sha256(bytes: &[u8]) -> Digest {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&Sha256::digest(bytes));
+    out
+}
+
+/// Check that `proof` demonstrates `(version, value)` is a leaf of the
+/// accumulator whose current root is `root`: recompute the leaf digest
+/// from `proof`'s embedded fields plus `version`/`value`, fold it up
+/// `proof.path` to its claimed peak, then bag that peak against
+/// `proof.prefix_acc`/`proof.suffix_peaks` and compare to `root`.
+pub fn verify_dag_proof(root: Digest, version: u64, value: &[u8], proof: &MerkleProof) -> bool {
+    if proof.path.len() != proof.path_is_left.len() {
+        return false;
+    }
+
+    let mut acc = leaf_digest(version, proof.parent_version, proof.parent2_version, proof.timestamp, proof.is_tombstone, value);
+    for (sibling, is_left) in proof.path.iter().zip(&proof.path_is_left) {
+        acc = if *is_left { hash_internal(&acc, sibling) } else { hash_internal(sibling, &acc) };
+    }
+
+    let mut folded = match &proof.prefix_acc {
+        Some(prefix) => hash_bag(prefix, &acc),
+        None => acc,
+    };
+    for sibling in &proof.suffix_peaks {
+        folded = hash_bag(&folded, sibling);
+    }
+
+    folded == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(version: u64, value: &[u8]) -> DagNode {
+        DagNode {
+            version,
+            value: value.to_vec(),
+            parent_version: if version > 1 { Some(version - 1) } else { None },
+            parent2_version: None,
+            timestamp: version * 1000,
+            is_tombstone: false,
+            commit_seq: version,
+        }
+    }
+
+    #[test]
+    fn empty_accumulator_has_no_root() {
+        assert_eq!(DagMerkleAccumulator::new().root(), None);
+    }
+
+    #[test]
+    fn every_appended_leaf_proves_against_the_current_root() {
+        let mut acc = DagMerkleAccumulator::new();
+        let nodes: Vec<_> = (1..=7).map(|v| node(v, format!("v{v}").as_bytes())).collect();
+        for n in &nodes {
+            acc.append(n);
+        }
+        let root = acc.root().unwrap();
+
+        for n in &nodes {
+            let proof = acc.proof(n).expect("tracked leaf has a proof");
+            assert!(verify_dag_proof(root, n.version, &n.value, &proof));
+        }
+    }
+
+    #[test]
+    fn root_is_insertion_order_dependent_unlike_the_anti_entropy_tree() {
+        // Unlike `crate::merkle::MerkleTree` (order-independent over a
+        // fixed key space), an append-only accumulator's root depends on
+        // append order, since it encodes a sequence rather than a set.
+        let mut a = DagMerkleAccumulator::new();
+        a.append(&node(1, b"x"));
+        a.append(&node(2, b"y"));
+
+        let mut b = DagMerkleAccumulator::new();
+        b.append(&node(2, b"y"));
+        b.append(&node(1, b"x"));
+
+        assert_ne!(a.root(), b.root());
+    }
+
+    #[test]
+    fn tampering_with_the_value_breaks_the_proof() {
+        let mut acc = DagMerkleAccumulator::new();
+        for v in 1..=4 {
+            acc.append(&node(v, format!("v{v}").as_bytes()));
+        }
+        let root = acc.root().unwrap();
+        let n = node(2, b"v2");
+        let proof = acc.proof(&n).unwrap();
+
+        assert!(verify_dag_proof(root, 2, b"v2", &proof));
+        assert!(!verify_dag_proof(root, 2, b"tampered", &proof));
+    }
+
+    #[test]
+    fn forgetting_a_version_keeps_the_root_and_surviving_proofs_valid() {
+        let mut acc = DagMerkleAccumulator::new();
+        let nodes: Vec<_> = (1..=5).map(|v| node(v, format!("v{v}").as_bytes())).collect();
+        for n in &nodes {
+            acc.append(n);
+        }
+        let root_before = acc.root().unwrap();
+
+        acc.forget(1);
+        acc.forget(2);
+
+        assert_eq!(acc.root(), Some(root_before));
+        assert!(acc.proof(&nodes[0]).is_none());
+        for n in &nodes[2..] {
+            let proof = acc.proof(n).unwrap();
+            assert!(verify_dag_proof(root_before, n.version, &n.value, &proof));
+        }
+    }
+}
@@ -0,0 +1,135 @@
+//! Optional per-column dictionary encoding — interns a column's repeated
+//! values into a small integer id instead of storing every occurrence's
+//! bytes in full, the way [`crate::CrrTable::declare_crdt_column`] lets a
+//! column opt into CRDT semantics instead of plain version comparison.
+//! Worthwhile for low-cardinality columns (`mime_type`, `owner`,
+//! `permissions`) that repeat across thousands of rows; high-cardinality
+//! columns (`checksum`, `path`) should stay [`ColumnEncoding::Raw`], since
+//! there's nothing to intern and the dictionary itself would just grow as
+//! large as the column.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// How a column's cell values are physically stored, selected per column
+/// at table creation via [`crate::CrrTable::declare_column_encoding`].
+/// `merge`/tie-break logic is unaffected by this choice — encoding and
+/// decoding both happen at the storage boundary, in `InsertBuilder`/
+/// `UpdateBuilder::commit` and [`crate::CrrTable::get`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnEncoding {
+    /// Store each cell's bytes as written. The default.
+    Raw,
+    /// Intern values into a per-column dictionary and store only the
+    /// resulting id.
+    Dictionary,
+}
+
+/// One column's interning table: `forward` maps a value to the id it was
+/// first assigned; `reverse[id]` is always the value that produced `id`,
+/// since ids are handed out in insertion order starting at 0.
+#[derive(Debug, Default)]
+struct ColumnDictionary {
+    forward: HashMap<Vec<u8>, u32>,
+    reverse: Vec<Vec<u8>>,
+}
+
+impl ColumnDictionary {
+    fn intern(&mut self, value: &[u8]) -> u32 {
+        if let Some(&id) = self.forward.get(value) {
+            return id;
+        }
+        let id = self.reverse.len() as u32;
+        self.reverse.push(value.to_vec());
+        self.forward.insert(value.to_vec(), id);
+        id
+    }
+
+    fn resolve(&self, id: u32) -> Option<&[u8]> {
+        self.reverse.get(id as usize).map(|v| v.as_slice())
+    }
+}
+
+/// Per-column [`ColumnEncoding`] choices plus the live interning state for
+/// every column declared [`ColumnEncoding::Dictionary`]. Held by
+/// [`crate::CrrTable`] as a plain field and handed to `InsertBuilder`/
+/// `UpdateBuilder` as a shared reference — the dictionaries themselves live
+/// behind a `RefCell` so a builder can intern a new value through `&self`,
+/// the same interior-mutability shape [`crate::oracle::VersionOracle`] uses
+/// for its counter.
+#[derive(Debug, Default)]
+pub(crate) struct DictionaryRegistry {
+    encodings: HashMap<String, ColumnEncoding>,
+    dictionaries: RefCell<HashMap<String, ColumnDictionary>>,
+}
+
+impl DictionaryRegistry {
+    pub(crate) fn declare(&mut self, col: &str, encoding: ColumnEncoding) {
+        self.encodings.insert(col.to_string(), encoding);
+    }
+
+    fn is_dictionary(&self, col: &str) -> bool {
+        self.encodings.get(col) == Some(&ColumnEncoding::Dictionary)
+    }
+
+    /// Encode `value` for `col` per its declared encoding: interned into a
+    /// dictionary entry and replaced with its 4-byte little-endian id if
+    /// `col` is [`ColumnEncoding::Dictionary`], or returned unchanged
+    /// otherwise.
+    pub(crate) fn encode(&self, col: &str, value: &[u8]) -> Vec<u8> {
+        if !self.is_dictionary(col) {
+            return value.to_vec();
+        }
+        let id = self.dictionaries.borrow_mut()
+            .entry(col.to_string())
+            .or_default()
+            .intern(value);
+        id.to_le_bytes().to_vec()
+    }
+
+    /// Inverse of [`Self::encode`]: resolves a stored id back to the bytes
+    /// that produced it for a [`ColumnEncoding::Dictionary`] column, or
+    /// returns `bytes` unchanged otherwise.
+    pub(crate) fn decode(&self, col: &str, bytes: &[u8]) -> Vec<u8> {
+        if !self.is_dictionary(col) {
+            return bytes.to_vec();
+        }
+        let Ok(id_bytes) = <[u8; 4]>::try_from(bytes) else {
+            return bytes.to_vec();
+        };
+        let id = u32::from_le_bytes(id_bytes);
+        self.dictionaries.borrow()
+            .get(col)
+            .and_then(|dict| dict.resolve(id))
+            .map(|v| v.to_vec())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dictionary_column_round_trips_through_encode_and_decode() {
+        let mut registry = DictionaryRegistry::default();
+        registry.declare("mime_type", ColumnEncoding::Dictionary);
+
+        let encoded_a = registry.encode("mime_type", b"application/octet-stream");
+        let encoded_b = registry.encode("mime_type", b"application/octet-stream");
+        let encoded_c = registry.encode("mime_type", b"text/plain");
+
+        assert_eq!(encoded_a, encoded_b, "the same value must intern to the same id");
+        assert_ne!(encoded_a, encoded_c);
+        assert_eq!(registry.decode("mime_type", &encoded_a), b"application/octet-stream");
+        assert_eq!(registry.decode("mime_type", &encoded_c), b"text/plain");
+    }
+
+    #[test]
+    fn raw_column_passes_values_through_unchanged() {
+        let registry = DictionaryRegistry::default();
+        let value = b"anything, since no encoding was declared for this column";
+        assert_eq!(registry.encode("path", value), value);
+        assert_eq!(registry.decode("path", value), value);
+    }
+}
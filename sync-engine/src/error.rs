@@ -6,6 +6,7 @@ pub enum Error {
     NotFound { pk: String, col: Option<String> },
     Conflict { pk: String, col: String, local_version: u64, remote_version: u64 },
     InvalidState(String),
+    ChangesetCorrupt { expected_crc: u32, actual_crc: u32 },
 }
 
 impl fmt::Display for Error {
@@ -18,6 +19,11 @@ impl fmt::Display for Error {
                 write!(f, "conflict at {}:{} (local v{}, remote v{})", pk, col, local_version, remote_version)
             }
             Error::InvalidState(msg) => write!(f, "invalid state: {}", msg),
+            Error::ChangesetCorrupt { expected_crc, actual_crc } => write!(
+                f,
+                "changeset failed integrity check: expected CRC {:#010x}, computed {:#010x}",
+                expected_crc, actual_crc
+            ),
         }
     }
 }
@@ -30,4 +36,10 @@ impl From<rusqlite::Error> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Storage(e.to_string())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
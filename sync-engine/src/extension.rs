@@ -0,0 +1,356 @@
+//! Loadable SQLite extension exposing the DAG-CRR engine to plain SQL, the
+//! way CR-SQLite does for its own CRDT tables: any SQLite binding (Python,
+//! Node, the `sqlite3` CLI, …) can drive replication without linking the
+//! Rust API, by loading the built cdylib with `load_extension()`.
+//!
+//! Registers:
+//! - `crr_dag_history(pk, col, version, value, parent_version,
+//!   parent2_version, timestamp, is_tombstone)` — an eponymous virtual
+//!   table over the `crr_dag` table already maintained by `SqliteStorage`,
+//!   with `pk =` / `col =` pushed down to the underlying index scan.
+//! - `crr_merge_changeset(blob, policy_text)` — merges a serialized
+//!   `Changeset` (see `Changeset::serialize`) into this connection's
+//!   tables and returns the number of DAG nodes written.
+//! - `crr_gc(pk, col, keep)` — trims `crr_dag` history for `(pk, col)`
+//!   down to `keep` versions, returning the number of rows removed.
+//!
+//! Build with `--features loadable-extension` to produce a `cdylib` whose
+//! `sqlite3_dagcrr_init` symbol SQLite's extension loader will find.
+
+use std::os::raw::{c_char, c_int};
+
+use rusqlite::functions::FunctionFlags;
+use rusqlite::vtab::{
+    eponymous_only_module, Context as VTabContext, CreateVTab, IndexInfo, VTab, VTabConnection,
+    VTabCursor, Values,
+};
+use rusqlite::{ffi, Connection};
+
+use crate::merge::TieBreakPolicy;
+use crate::sync::Changeset;
+
+/// Entry point SQLite's extension loader looks for after `load_extension()`
+/// mmaps this library (or after `sqlite3_auto_extension` registers it for
+/// every future connection, if statically linked in).
+///
+/// # Safety
+/// Must only be invoked by SQLite itself, with the `db`/`p_api` it passes
+/// to every extension's init routine.
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_dagcrr_init(
+    db: *mut ffi::sqlite3,
+    pz_err_msg: *mut *mut c_char,
+    p_api: *mut ffi::sqlite3_api_routines,
+) -> c_int {
+    ffi::sqlite3_extension_init2(p_api);
+
+    let conn = match Connection::from_handle(db) {
+        Ok(conn) => conn,
+        Err(_) => return ffi::SQLITE_ERROR,
+    };
+
+    let result = register(&conn);
+    // `conn` borrows a handle SQLite still owns; don't let its `Drop` close it.
+    std::mem::forget(conn);
+
+    match result {
+        Ok(()) => ffi::SQLITE_OK,
+        Err(e) => {
+            set_err_msg(pz_err_msg, &e.to_string());
+            ffi::SQLITE_ERROR
+        }
+    }
+}
+
+fn register(conn: &Connection) -> rusqlite::Result<()> {
+    conn.create_module("crr_dag_history", eponymous_only_module::<DagHistoryTab>(), None)?;
+
+    conn.create_scalar_function(
+        "crr_merge_changeset",
+        2,
+        FunctionFlags::SQLITE_UTF8,
+        crr_merge_changeset,
+    )?;
+
+    conn.create_scalar_function("crr_gc", 3, FunctionFlags::SQLITE_UTF8, crr_gc)?;
+
+    Ok(())
+}
+
+fn crr_merge_changeset(ctx: &rusqlite::functions::Context<'_>) -> rusqlite::Result<i64> {
+    let blob: Vec<u8> = ctx.get(0)?;
+    let policy_text: String = ctx.get(1)?;
+    let policy = parse_policy(&policy_text);
+
+    let changeset = Changeset::deserialize(&blob)
+        .map_err(|e| rusqlite::Error::UserFunctionError(Box::new(e)))?;
+
+    let conn = unsafe { connection_of(ctx) };
+    let mut nodes_written = 0i64;
+
+    for (pk, (cols, vers)) in &changeset.changes {
+        for (col, value) in cols {
+            let remote_version = vers.get(col).copied().unwrap_or(1);
+            let local_version: Option<u64> = conn.query_row(
+                "SELECT version FROM crr_cells WHERE pk = ?1 AND col = ?2",
+                rusqlite::params![pk, col],
+                |row| row.get(0),
+            ).ok();
+
+            let accept = match local_version {
+                None => true,
+                Some(local) if remote_version > local => true,
+                Some(local) if remote_version == local => {
+                    matches!(policy, TieBreakPolicy::PreferIncoming)
+                }
+                _ => false,
+            };
+            if !accept {
+                continue;
+            }
+
+            conn.execute(
+                "INSERT OR REPLACE INTO crr_cells (pk, col, value, version) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![pk, col, value, remote_version],
+            )?;
+            conn.execute(
+                "INSERT OR REPLACE INTO crr_dag (pk, col, version, value, parent_version, parent2_version, timestamp, is_tombstone)
+                 VALUES (?1, ?2, ?3, ?4, ?5, NULL, ?6, 0)",
+                rusqlite::params![pk, col, remote_version, value, local_version, crate::storage::now_millis() as i64],
+            )?;
+            nodes_written += 1;
+        }
+    }
+
+    std::mem::forget(conn);
+    Ok(nodes_written)
+}
+
+fn crr_gc(ctx: &rusqlite::functions::Context<'_>) -> rusqlite::Result<i64> {
+    let pk: String = ctx.get(0)?;
+    let col: String = ctx.get(1)?;
+    let keep: i64 = ctx.get(2)?;
+
+    let conn = unsafe { connection_of(ctx) };
+    let total: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM crr_dag WHERE pk = ?1 AND col = ?2",
+        rusqlite::params![pk, col],
+        |row| row.get(0),
+    )?;
+    if total <= keep {
+        std::mem::forget(conn);
+        return Ok(0);
+    }
+
+    let cutoff: i64 = conn.query_row(
+        "SELECT version FROM crr_dag WHERE pk = ?1 AND col = ?2 ORDER BY version LIMIT 1 OFFSET ?3",
+        rusqlite::params![pk, col, total - keep],
+        |row| row.get(0),
+    )?;
+
+    let removed = conn.execute(
+        "DELETE FROM crr_dag WHERE pk = ?1 AND col = ?2 AND version < ?3",
+        rusqlite::params![pk, col, cutoff],
+    )?;
+    std::mem::forget(conn);
+    Ok(removed as i64)
+}
+
+fn parse_policy(text: &str) -> TieBreakPolicy {
+    match text {
+        "prefer_incoming" => TieBreakPolicy::PreferIncoming,
+        "lexicographic_min" => TieBreakPolicy::LexicographicMin,
+        "last_write_wins" => TieBreakPolicy::LastWriteWins,
+        _ => TieBreakPolicy::PreferExisting,
+    }
+}
+
+/// Borrow the connection a scalar function is executing against, via the
+/// db handle SQLite attaches to every function call context.
+unsafe fn connection_of(ctx: &rusqlite::functions::Context<'_>) -> Connection {
+    let handle = ffi::sqlite3_context_db_handle(ctx.get_raw_ctx());
+    connection_of_handle(handle)
+}
+
+/// Wrap a raw `sqlite3*` we don't own (the hosting connection, reached via
+/// either a function call's context or a vtab's captured handle) in a
+/// `Connection` for the duration of one call. The caller must not let it
+/// run `Drop` — forgotten everywhere this is used, since the real owner is
+/// SQLite itself.
+unsafe fn connection_of_handle(handle: *mut ffi::sqlite3) -> Connection {
+    Connection::from_handle(handle).expect("valid db handle")
+}
+
+fn set_err_msg(pz_err_msg: *mut *mut c_char, msg: &str) {
+    if pz_err_msg.is_null() {
+        return;
+    }
+    if let Ok(c_msg) = std::ffi::CString::new(msg) {
+        unsafe {
+            *pz_err_msg = ffi::sqlite3_mprintf(c_msg.as_ptr());
+        }
+    }
+}
+
+/// Read-only eponymous virtual table over `crr_dag`, constraint-pushing
+/// `pk =` and `col =` to match the `idx_dag_pk_col` index instead of
+/// scanning every row.
+struct DagHistoryTab {
+    base: rusqlite::vtab::sqlite3_vtab,
+    // Raw handle of the hosting connection, captured at `connect()` time
+    // so cursors can query `crr_dag` on it directly — a vtab's own rows
+    // live in a sibling table on the same connection, not somewhere a
+    // `Context` hands us a safe reference to.
+    raw_db: *mut ffi::sqlite3,
+}
+
+unsafe impl<'vtab> VTab<'vtab> for DagHistoryTab {
+    type Aux = ();
+    type Cursor = DagHistoryCursor<'vtab>;
+
+    fn connect(
+        db: &mut VTabConnection,
+        _aux: Option<&Self::Aux>,
+        _args: &[&[u8]],
+    ) -> rusqlite::Result<(String, Self)> {
+        let schema = "CREATE TABLE x(
+            pk TEXT, col TEXT, version INTEGER, value BLOB,
+            parent_version INTEGER, parent2_version INTEGER,
+            timestamp INTEGER, is_tombstone INTEGER
+        )".to_string();
+        let raw_db = unsafe { db.handle() };
+        Ok((schema, DagHistoryTab { base: rusqlite::vtab::sqlite3_vtab::default(), raw_db }))
+    }
+
+    fn best_index(&self, info: &mut IndexInfo) -> rusqlite::Result<()> {
+        // Usable equality constraints on column 0 (pk) and column 1 (col)
+        // are pushed down together, as argv 1 and 2; anything else falls
+        // back to a full scan of crr_dag, which SQLite itself filters.
+        let eq_on = |column: c_int| info.constraints().position(|c| {
+            c.column() == column
+                && c.is_usable()
+                && c.operator() == rusqlite::vtab::IndexConstraintOp::SQLITE_INDEX_CONSTRAINT_EQ
+        });
+        let (pk_idx, col_idx) = (eq_on(0), eq_on(1));
+        let pushed = if let (Some(pk_idx), Some(col_idx)) = (pk_idx, col_idx) {
+            info.constraint_usage(pk_idx).set_argv_index(1);
+            info.constraint_usage(col_idx).set_argv_index(2);
+            true
+        } else {
+            false
+        };
+        info.set_estimated_cost(if pushed { 10.0 } else { 1_000_000.0 });
+        Ok(())
+    }
+
+    fn open(&'vtab mut self) -> rusqlite::Result<Self::Cursor> {
+        Ok(DagHistoryCursor::new(self.raw_db))
+    }
+}
+
+impl CreateVTab<'_> for DagHistoryTab {}
+
+#[repr(C)]
+struct DagHistoryCursor<'vtab> {
+    base: rusqlite::vtab::sqlite3_vtab_cursor,
+    raw_db: *mut ffi::sqlite3,
+    rows: Vec<DagHistoryRow>,
+    index: usize,
+    phantom: std::marker::PhantomData<&'vtab DagHistoryTab>,
+}
+
+struct DagHistoryRow {
+    pk: String,
+    col: String,
+    version: i64,
+    value: Vec<u8>,
+    parent_version: Option<i64>,
+    parent2_version: Option<i64>,
+    timestamp: i64,
+    is_tombstone: bool,
+}
+
+impl<'vtab> DagHistoryCursor<'vtab> {
+    fn new(raw_db: *mut ffi::sqlite3) -> Self {
+        Self {
+            base: rusqlite::vtab::sqlite3_vtab_cursor::default(),
+            raw_db,
+            rows: Vec::new(),
+            index: 0,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+unsafe impl VTabCursor for DagHistoryCursor<'_> {
+    fn filter(
+        &mut self,
+        _idx_num: c_int,
+        _idx_str: Option<&str>,
+        args: &Values<'_>,
+    ) -> rusqlite::Result<()> {
+        let conn = unsafe { connection_of_handle(self.raw_db) };
+        let mut values = args.iter();
+        let pk: Option<String> = values.next().and_then(|v| v.as_str().ok().map(str::to_string));
+        let col: Option<String> = values.next().and_then(|v| v.as_str().ok().map(str::to_string));
+
+        let sql = match (&pk, &col) {
+            (Some(_), Some(_)) => "SELECT pk, col, version, value, parent_version, parent2_version, timestamp, is_tombstone FROM crr_dag WHERE pk = ?1 AND col = ?2 ORDER BY version",
+            _ => "SELECT pk, col, version, value, parent_version, parent2_version, timestamp, is_tombstone FROM crr_dag ORDER BY pk, col, version",
+        };
+
+        let mut stmt = conn.prepare(sql)?;
+        let rows = if let (Some(pk), Some(col)) = (&pk, &col) {
+            stmt.query_map(rusqlite::params![pk, col], row_from)?
+        } else {
+            stmt.query_map([], row_from)?
+        };
+        self.rows = rows.collect::<rusqlite::Result<Vec<_>>>()?;
+        self.index = 0;
+        drop(stmt);
+        std::mem::forget(conn);
+        Ok(())
+    }
+
+    fn next(&mut self) -> rusqlite::Result<()> {
+        self.index += 1;
+        Ok(())
+    }
+
+    fn eof(&self) -> bool {
+        self.index >= self.rows.len()
+    }
+
+    fn column(&self, ctx: &mut VTabContext, col: c_int) -> rusqlite::Result<()> {
+        let row = &self.rows[self.index];
+        match col {
+            0 => ctx.set_result(&row.pk),
+            1 => ctx.set_result(&row.col),
+            2 => ctx.set_result(&row.version),
+            3 => ctx.set_result(&row.value),
+            4 => ctx.set_result(&row.parent_version),
+            5 => ctx.set_result(&row.parent2_version),
+            6 => ctx.set_result(&row.timestamp),
+            7 => ctx.set_result(&(row.is_tombstone as i64)),
+            _ => Ok(()),
+        }
+    }
+
+    fn rowid(&self) -> rusqlite::Result<i64> {
+        Ok(self.index as i64)
+    }
+}
+
+fn row_from(row: &rusqlite::Row<'_>) -> rusqlite::Result<DagHistoryRow> {
+    Ok(DagHistoryRow {
+        pk: row.get(0)?,
+        col: row.get(1)?,
+        version: row.get(2)?,
+        value: row.get(3)?,
+        parent_version: row.get(4)?,
+        parent2_version: row.get(5)?,
+        timestamp: row.get(6)?,
+        is_tombstone: row.get::<_, i64>(7)? != 0,
+    })
+}
+
@@ -0,0 +1,54 @@
+//! A `VersionVector` tracks the highest DAG `version` a replica has already
+//! seen for each `(pk, col)`, so a peer can be asked for only the delta it
+//! hasn't. Finer-grained than a per-replica logical clock: two columns on
+//! the same row can advance independently, which is how `CrrTable` already
+//! versions cells.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VersionVector {
+    seen: HashMap<(String, String), u64>,
+}
+
+impl VersionVector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Highest version seen for `(pk, col)`, or `0` if none has been.
+    pub fn get(&self, pk: &str, col: &str) -> u64 {
+        self.seen.get(&(pk.to_string(), col.to_string())).copied().unwrap_or(0)
+    }
+
+    /// Record `version` as seen for `(pk, col)`, if it's higher than what's
+    /// already recorded.
+    pub fn set(&mut self, pk: &str, col: &str, version: u64) {
+        let entry = self.seen.entry((pk.to_string(), col.to_string())).or_insert(0);
+        if version > *entry {
+            *entry = version;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+
+    /// Advance this frontier past every `(pk, col)` version a
+    /// [`crate::CrrTable::merge`] call just applied from `changeset`, so the
+    /// next [`crate::CrrTable::changeset_since_frontier`] request this
+    /// replica sends doesn't re-fetch what it just received.
+    pub fn advance_from_changeset(&mut self, changeset: &crate::sync::Changeset) {
+        for (pk, (_, versions)) in &changeset.changes {
+            for (col, &version) in versions {
+                self.set(pk, col, version);
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Hybrid Logical Clocks: a `{wall_time, logical, node_id}` stamp that
+//! totally orders events causally even under clock skew between peers.
+//!
+//! A stamp's `(wall_time, logical)` pair packs into the same `u64`
+//! "version" slot every other merge path in this crate already compares,
+//! so [`crate::merge::resolve_versions`] and [`crate::merge::resolve_conflict`]
+//! need no changes at all to treat an HLC-stamped column exactly like an
+//! integer-versioned one: a strictly later stamp packs to a strictly
+//! greater `u64` and wins outright, and a genuine tie on both fields packs
+//! to an equal `u64` and falls back to the table's `TieBreakPolicy`, same
+//! as a tie between two caller-supplied integer versions always has.
+
+use serde::{Deserialize, Serialize};
+
+use crate::storage::now_millis;
+
+const LOGICAL_BITS: u32 = 16;
+const LOGICAL_MASK: u64 = (1 << LOGICAL_BITS) - 1;
+
+/// A single HLC timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct HlcStamp {
+    pub wall_time: u64,
+    pub logical: u32,
+    pub node_id: u64,
+}
+
+/// Pack a stamp's `(wall_time, logical)` pair into the `u64` version slot
+/// used by `Cell`/`DagNode` throughout this crate.
+///
+/// `node_id` doesn't participate: it exists so each replica's clock can
+/// tag the stamps it mints, not to break ties between replicas — a real
+/// tie on `(wall_time, logical)` is meant to fall through to the table's
+/// `TieBreakPolicy`, not be resolved by whichever node's id is numerically
+/// larger.
+pub fn pack_version(stamp: HlcStamp) -> u64 {
+    (stamp.wall_time << LOGICAL_BITS) | (stamp.logical as u64 & LOGICAL_MASK)
+}
+
+/// Inverse of [`pack_version`]. `node_id` can't be recovered — it was
+/// never packed in — so the returned stamp always carries `node_id: 0`.
+pub fn unpack_version(version: u64) -> HlcStamp {
+    HlcStamp {
+        wall_time: version >> LOGICAL_BITS,
+        logical: (version & LOGICAL_MASK) as u32,
+        node_id: 0,
+    }
+}
+
+/// A per-replica Hybrid Logical Clock, seeded with a `node_id` that
+/// distinguishes this replica's own stamps (informational only — see
+/// [`pack_version`]).
+#[derive(Debug, Clone)]
+pub struct HybridLogicalClock {
+    node_id: u64,
+    last: HlcStamp,
+}
+
+impl HybridLogicalClock {
+    pub fn new(node_id: u64) -> Self {
+        Self { node_id, last: HlcStamp { wall_time: 0, logical: 0, node_id } }
+    }
+
+    /// Stamp a new local write: advance past both the last stamp this
+    /// clock issued and the current wall-clock time.
+    pub fn tick(&mut self) -> HlcStamp {
+        let now = now_millis();
+        self.last = if now > self.last.wall_time {
+            HlcStamp { wall_time: now, logical: 0, node_id: self.node_id }
+        } else {
+            HlcStamp { wall_time: self.last.wall_time, logical: self.last.logical + 1, node_id: self.node_id }
+        };
+        self.last
+    }
+
+    /// The HLC receive rule: fold a remote stamp into this clock so it
+    /// never regresses below anything either side has seen, and return a
+    /// fresh local stamp that causally succeeds both.
+    ///
+    /// If both wall times trail the physical clock, logical resets to 0.
+    /// If one wall time leads, this clock adopts it and bumps that side's
+    /// logical by one. On an exact wall-time tie, logical becomes
+    /// `max(local.logical, remote.logical) + 1`.
+    pub fn receive(&mut self, remote: HlcStamp) -> HlcStamp {
+        let now = now_millis();
+        let local = self.last;
+
+        self.last = if now > local.wall_time && now > remote.wall_time {
+            HlcStamp { wall_time: now, logical: 0, node_id: self.node_id }
+        } else if local.wall_time > remote.wall_time {
+            HlcStamp { wall_time: local.wall_time, logical: local.logical + 1, node_id: self.node_id }
+        } else if remote.wall_time > local.wall_time {
+            HlcStamp { wall_time: remote.wall_time, logical: remote.logical + 1, node_id: self.node_id }
+        } else {
+            HlcStamp {
+                wall_time: local.wall_time,
+                logical: local.logical.max(remote.logical) + 1,
+                node_id: self.node_id,
+            }
+        };
+        self.last
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packing_preserves_hlc_ordering() {
+        let earlier = HlcStamp { wall_time: 100, logical: 5, node_id: 1 };
+        let later_logical = HlcStamp { wall_time: 100, logical: 6, node_id: 2 };
+        let later_wall = HlcStamp { wall_time: 101, logical: 0, node_id: 1 };
+
+        assert!(earlier < later_logical);
+        assert!(pack_version(earlier) < pack_version(later_logical));
+        assert!(later_logical < later_wall);
+        assert!(pack_version(later_logical) < pack_version(later_wall));
+    }
+
+    #[test]
+    fn receive_adopts_the_leading_wall_time() {
+        let mut clock = HybridLogicalClock::new(1);
+        clock.last = HlcStamp { wall_time: 9_000_000_000_000, logical: 2, node_id: 1 };
+
+        let remote = HlcStamp { wall_time: 9_000_000_000_001, logical: 9, node_id: 2 };
+        let merged = clock.receive(remote);
+
+        assert_eq!(merged.wall_time, 9_000_000_000_001);
+        assert_eq!(merged.logical, 10);
+    }
+
+    #[test]
+    fn receive_never_regresses_behind_a_remote_stamp_far_in_the_future() {
+        let mut clock = HybridLogicalClock::new(1);
+        let remote = HlcStamp { wall_time: 9_999_999_999_999, logical: 3, node_id: 2 };
+        let merged = clock.receive(remote);
+
+        assert_eq!(merged.wall_time, 9_999_999_999_999);
+        assert_eq!(merged.logical, 4);
+        assert!(merged > remote || (merged.wall_time == remote.wall_time && merged.logical > remote.logical));
+    }
+}
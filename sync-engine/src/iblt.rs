@@ -0,0 +1,262 @@
+//! Invertible Bloom Lookup Table for set reconciliation.
+//!
+//! Lets two replicas exchange a compact sketch of their `(pk, column,
+//! version)` key space instead of a full changeset, and decode the
+//! symmetric difference once the sketches are subtracted. This is what
+//! backs `CrrTable::reconcile_sketch`/`diff_from_sketch`: the sketch size
+//! scales with the number of *cells* (a tuning knob), not with table size,
+//! so reconciliation cost tracks how much two replicas actually diverge.
+
+const DEFAULT_CELLS: usize = 4096;
+const DEFAULT_HASHES: usize = 4;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct IbltCell {
+    count: i64,
+    key_sum: u64,
+    key_check: u64,
+    value_sum: u64,
+}
+
+impl IbltCell {
+    fn is_empty(&self) -> bool {
+        self.count == 0 && self.key_sum == 0 && self.key_check == 0 && self.value_sum == 0
+    }
+}
+
+/// A fixed-size IBLT over 64-bit `(key, value)` fingerprint pairs.
+#[derive(Debug, Clone)]
+pub struct Iblt {
+    cells: Vec<IbltCell>,
+    hashes: usize,
+}
+
+impl Iblt {
+    /// Build an empty sketch with `cells` buckets, each key hashed into
+    /// `hashes` independent positions.
+    pub fn new(cells: usize, hashes: usize) -> Self {
+        Self { cells: vec![IbltCell::default(); cells.max(1)], hashes: hashes.max(1) }
+    }
+
+    /// Build an empty sketch with the library's default tuning: 4096
+    /// buckets, each key hashed into 4 of them.
+    pub fn with_defaults() -> Self {
+        Self::new(DEFAULT_CELLS, DEFAULT_HASHES)
+    }
+
+    pub fn insert(&mut self, key: u64, value_fingerprint: u64) {
+        let check = check_hash(key);
+        for seed in 0..self.hashes {
+            let idx = bucket(key, seed, self.cells.len());
+            let cell = &mut self.cells[idx];
+            cell.count += 1;
+            cell.key_sum ^= key;
+            cell.key_check ^= check;
+            cell.value_sum ^= value_fingerprint;
+        }
+    }
+
+    fn remove_at(&mut self, key: u64, value_fingerprint: u64) {
+        let check = check_hash(key);
+        for seed in 0..self.hashes {
+            let idx = bucket(key, seed, self.cells.len());
+            let cell = &mut self.cells[idx];
+            cell.count -= 1;
+            cell.key_sum ^= key;
+            cell.key_check ^= check;
+            cell.value_sum ^= value_fingerprint;
+        }
+    }
+
+    /// Cell-wise subtraction: XOR the key/value sums, subtract counts.
+    /// `self` is conventionally the local sketch, `other` the remote one.
+    pub fn subtract(&self, other: &Iblt) -> Iblt {
+        assert_eq!(self.cells.len(), other.cells.len(), "IBLTs must share the same cell count to subtract");
+        assert_eq!(self.hashes, other.hashes, "IBLTs must share the same hash count to subtract");
+
+        let cells = self.cells.iter().zip(&other.cells)
+            .map(|(a, b)| IbltCell {
+                count: a.count - b.count,
+                key_sum: a.key_sum ^ b.key_sum,
+                key_check: a.key_check ^ b.key_check,
+                value_sum: a.value_sum ^ b.value_sum,
+            })
+            .collect();
+
+        Iblt { cells, hashes: self.hashes }
+    }
+
+    /// Peel a (typically already-subtracted) sketch down to its key set.
+    ///
+    /// Returns `(key, value_fingerprint, present_on_local)` for every
+    /// decoded entry, where `present_on_local` is true when the key was
+    /// only present in `self`'s side of the subtraction (count == +1) and
+    /// false when it was only on the other side (count == -1). Returns
+    /// `None` if peeling stalls with non-empty cells remaining, meaning the
+    /// two sides diverge by more than this sketch can represent.
+    pub fn decode(mut self) -> Option<Vec<(u64, u64, bool)>> {
+        let mut decoded = Vec::new();
+
+        loop {
+            let pure = self.cells.iter()
+                .position(|c| (c.count == 1 || c.count == -1) && check_hash(c.key_sum) == c.key_check);
+
+            let Some(idx) = pure else { break };
+            let cell = self.cells[idx];
+            let present_on_local = cell.count > 0;
+            decoded.push((cell.key_sum, cell.value_sum, present_on_local));
+            if present_on_local {
+                self.remove_at(cell.key_sum, cell.value_sum);
+            } else {
+                self.insert(cell.key_sum, cell.value_sum);
+            }
+        }
+
+        if self.cells.iter().all(IbltCell::is_empty) {
+            Some(decoded)
+        } else {
+            None
+        }
+    }
+}
+
+fn bucket(key: u64, seed: usize, cells: usize) -> usize {
+    let salted = key ^ (seed as u64).wrapping_mul(0x9E3779B97F4A7C15);
+    (fnv1a_u64(salted) % cells as u64) as usize
+}
+
+fn check_hash(key: u64) -> u64 {
+    fnv1a_u64(key ^ 0xC6A4_A793_5BD1_E995)
+}
+
+fn fnv1a_u64(mut x: u64) -> u64 {
+    // A 64-bit avalanche mix (splitmix64 finalizer), used here purely as a
+    // cheap, deterministic hash of an already-hashed key.
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    x
+}
+
+/// FNV-1a over arbitrary bytes, used to fold strings/values into the
+/// 64-bit fingerprints the sketch operates on.
+pub fn fingerprint(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// Fingerprint a `(pk, column)` pair into the key space the sketch indexes.
+pub fn row_key(pk: &str, col: &str) -> u64 {
+    let mut bytes = Vec::with_capacity(pk.len() + col.len() + 1);
+    bytes.extend_from_slice(pk.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(col.as_bytes());
+    fingerprint(&bytes)
+}
+
+/// Like [`row_key`], but folds `version` in too, so a cell whose value (and
+/// so version) has diverged between two replicas models as two distinct
+/// keys — one per side's version — rather than one key with two different
+/// values. A plain `(pk, col)` key can't represent that: the same key on
+/// both sides cancels `count`/`key_sum` in [`Iblt::subtract`] to zero (a
+/// "pure" bucket signature) while the two different value fingerprints
+/// don't cancel, permanently stalling [`Iblt::decode`] on that bucket —
+/// and so on the whole sketch. Keying on version instead turns an update
+/// into the standard keyed-IBLT delete-then-insert pair, which peels like
+/// any other pure insert/delete.
+pub fn versioned_row_key(pk: &str, col: &str, version: u64) -> u64 {
+    let mut bytes = Vec::with_capacity(pk.len() + col.len() + 9);
+    bytes.extend_from_slice(pk.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(col.as_bytes());
+    bytes.push(0);
+    bytes.extend_from_slice(&version.to_le_bytes());
+    fingerprint(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_small_symmetric_difference() {
+        let mut a = Iblt::new(256, 4);
+        let mut b = Iblt::new(256, 4);
+
+        for i in 0..50u64 {
+            a.insert(i, i * 7);
+            b.insert(i, i * 7);
+        }
+        // a has one extra key, b has one different extra key.
+        a.insert(1000, 1);
+        b.insert(2000, 2);
+
+        let diff = a.subtract(&b);
+        let decoded = diff.decode().expect("small diff should peel cleanly");
+
+        assert_eq!(decoded.len(), 2);
+        assert!(decoded.contains(&(1000, 1, true)));
+        assert!(decoded.contains(&(2000, 2, false)));
+    }
+
+    #[test]
+    fn fails_to_decode_when_overwhelmed() {
+        let mut a = Iblt::new(16, 3);
+        let b = Iblt::new(16, 3);
+
+        for i in 0..500u64 {
+            a.insert(i, i);
+        }
+
+        assert!(a.subtract(&b).decode().is_none());
+    }
+
+    #[test]
+    fn a_shared_key_with_two_different_values_stalls_decode() {
+        // The bug `versioned_row_key` exists to route around: keying a cell
+        // only on (pk, col) folds an update into one key carrying two
+        // different value fingerprints. `count`/`key_sum`/`key_check` all
+        // cancel (it's the same key on both sides) but `value_sum` doesn't,
+        // so the bucket is neither pure (count +-1) nor empty — `decode`
+        // stalls on it, and per `Iblt::decode`'s contract that fails the
+        // *entire* sketch, not just this one key.
+        let mut local = Iblt::new(256, 4);
+        let mut remote = Iblt::new(256, 4);
+
+        let shared = row_key("row1", "name");
+        local.insert(shared, fingerprint(b"Alice"));
+        remote.insert(shared, fingerprint(b"Bob"));
+        // A genuine local-only key that would otherwise peel cleanly on
+        // its own, to show the stall isn't confined to `shared`'s bucket.
+        local.insert(row_key("row2", "email"), fingerprint(b"alice@example.com"));
+
+        assert!(local.subtract(&remote).decode().is_none());
+    }
+
+    #[test]
+    fn versioned_keys_let_a_diverged_value_peel_as_a_delete_insert_pair() {
+        // Same (pk, col) on both sides, but the value — and so version —
+        // has diverged, the ordinary case two reconciling replicas hit.
+        // Folding `version` into the key turns it into two distinct keys
+        // instead of one key with two values, so it peels like any other
+        // pure insert/delete.
+        let mut local = Iblt::new(256, 4);
+        let mut remote = Iblt::new(256, 4);
+
+        let local_key = versioned_row_key("row1", "name", 5);
+        let remote_key = versioned_row_key("row1", "name", 3);
+        local.insert(local_key, fingerprint(b"Alice"));
+        remote.insert(remote_key, fingerprint(b"Bob"));
+
+        let decoded = local.subtract(&remote).decode().expect("a diverged version should peel cleanly");
+        assert_eq!(decoded.len(), 2);
+        assert!(decoded.contains(&(local_key, fingerprint(b"Alice"), true)));
+        assert!(decoded.contains(&(remote_key, fingerprint(b"Bob"), false)));
+    }
+}
@@ -1,18 +1,55 @@
+mod chunking;
+mod column_crdt;
+mod crdt;
+mod dag_merkle;
+mod dictionary;
 mod error;
+#[cfg(feature = "loadable-extension")]
+mod extension;
+mod frontier;
 mod gc;
+mod hlc;
+mod iblt;
 mod merge;
+mod merkle;
+mod metrics;
+mod migrate;
+mod oracle;
 mod row;
+mod secure;
+mod snapshot;
+mod spill;
 mod storage;
 mod sync;
 mod table;
+mod versionlist;
+mod wire;
 
+pub use chunking::{chunk, hash_chunk, ChunkConfig, ChunkHash, ChunkStore};
+pub use column_crdt::{ColumnCrdt, CrdtKind, OrSet, PnCounter, Token};
+pub use crdt::{Crdt, Lww};
+pub use dictionary::ColumnEncoding;
 pub use error::{Error, Result};
+pub use frontier::VersionVector;
 pub use gc::{run_gc, GcPolicy};
-pub use merge::{MergeReport, TieBreakPolicy};
+pub use hlc::{pack_version, unpack_version, HlcStamp, HybridLogicalClock};
+pub use iblt::Iblt;
+pub use merge::{IngestStats, MergeReport, MergeStats, TieBreakPolicy};
+pub use merkle::{Digest, MerkleTree};
+pub use metrics::{Counter, Histogram, LatencyHistogram, Metrics};
+pub use migrate::{decode, encode, Decodable, Migrate};
 pub use row::{InsertBuilder, RowView, UpdateBuilder};
-pub use storage::{Cell, DagNode, MemoryStorage, Row, SqliteStorage, Storage};
+pub use secure::{SecureChangeset, SessionKey};
+pub use snapshot::Snapshot;
+pub use spill::MergeOptions;
+pub use storage::{BackupProgress, Cell, ChangeEvent, DagNode, MemoryStorage, MergingIterator, Row, SqliteStorage, Storage};
+#[cfg(feature = "redis-backend")]
+pub use storage::RedisStorage;
+#[cfg(feature = "lmdb-backend")]
+pub use storage::LmdbStorage;
 pub use sync::{Changeset, HeadExchange, MeshSync, SyncResult, SyncSession};
-pub use table::CrrTable;
+pub use table::{AsOfBound, CrrTable, TableSnapshot};
+pub use versionlist::{EntryState, VersionEntry, VersionList};
 
 // Re-export legacy types for backward compatibility with existing benchmarks and demo
 pub mod crr {
@@ -20,6 +57,24 @@ pub mod crr {
     pub use crate::table::CrrTable;
 
     use std::collections::HashMap;
+    use crate::{ColumnCrdt, CrdtKind, OrSet, PnCounter};
+
+    /// The wire shape [`LegacyCrrTable::changeset`]/[`LegacyCrrTable::crr_merge`]
+    /// trade: per-pk column values and the version each was last written at.
+    /// Named so [`crate::transport`] can carry it across a real socket
+    /// without repeating this nested `HashMap` type at every call site.
+    pub type LegacyChangeset = HashMap<String, (HashMap<String, String>, HashMap<String, u64>)>;
+
+    /// Reserved column name used to replicate a row-level tombstone through
+    /// the existing per-column changeset/merge machinery, rather than
+    /// widening [`LegacyChangeset`]'s wire shape: [`LegacyCrrTable::delete_row`]
+    /// stores the delete's clock under this key in `row.versions` (and an
+    /// empty marker in `row.columns`), so [`LegacyCrrTable::changeset`] ships
+    /// it like any other cell and [`LegacyCrrTable::crr_merge`] compares it
+    /// like any other version — no peer needs to know about tombstones to
+    /// carry one. Callers that render a row directly (rather than going
+    /// through [`LegacyCrrTable::is_deleted`]) should skip this column.
+    pub const ROW_TOMBSTONE_COLUMN: &str = "__deleted__";
 
     // Legacy MergeReport with Vec fields for compatibility
     #[derive(Clone, Debug, Default)]
@@ -28,6 +83,24 @@ pub mod crr {
         pub updated: Vec<(String, String, u64)>,
         pub skipped_older: Vec<(String, String, u64)>,
         pub conflicts_equal_version: Vec<(String, String, u64, String, String)>,
+        /// `(pk, column)` pairs whose merge went through a declared
+        /// `CrdtKind::PnCounter` column — see [`LegacyCrrTable::declare_crdt_column`]
+        /// and [`LegacyCrrTable::crdt_merge`]. Counter merges never land in
+        /// `conflicts_equal_version`, since two concurrent increments both
+        /// survive instead of one winning a tiebreak.
+        pub counter_merges: Vec<(String, String)>,
+        /// Same as `counter_merges`, for `CrdtKind::OrSet` columns.
+        pub set_merges: Vec<(String, String)>,
+        /// Columns a concurrent `schema::SchemaMigration::DropColumn` would
+        /// have removed during this sync's schema merge, kept instead by
+        /// add-wins — see [`crate::schema::SchemaManager::merge`]. Folded in
+        /// by the caller (e.g. the demo's `sync_peers`) so `last_merge_report`
+        /// surfaces schema conflicts alongside row conflicts.
+        pub schema_drop_vs_update_conflicts: Vec<String>,
+        /// `(old_name, winning_new_name, losing_new_name)` for concurrent
+        /// `RenameColumn`s this sync's schema merge resolved — see
+        /// [`crate::schema::SchemaManager::merge`].
+        pub schema_rename_conflicts: Vec<(String, String, String)>,
     }
 
     // Legacy CrrRow for UI compatibility
@@ -37,17 +110,164 @@ pub mod crr {
         pub columns: HashMap<String, String>,
         pub versions: HashMap<String, u64>,
         pub dags: HashMap<String, crate::dag::VersionDag>,
+        /// Encoded state (`ColumnCrdt::to_bytes`) for this row's columns
+        /// declared via [`LegacyCrrTable::declare_crdt_column`], keyed by
+        /// column name. `columns` still holds a human-readable rendering of
+        /// the current value (the counter's total, or the set's elements
+        /// joined by `", "`) for display/lookup; this is the state
+        /// [`LegacyCrrTable::crdt_merge`] actually folds.
+        pub crdts: HashMap<String, Vec<u8>>,
+        /// Set by [`LegacyCrrTable::delete_row`] instead of removing this row
+        /// from [`LegacyCrrTable::rows`] outright — the delete's clock lives
+        /// alongside it in `versions` under [`ROW_TOMBSTONE_COLUMN`]. A
+        /// later write whose version is ahead of the tombstone resurrects
+        /// the row (see [`LegacyCrrTable::crr_merge`]) instead of the delete
+        /// silently winning forever.
+        pub deleted: bool,
     }
 
     // Legacy CrrTable that wraps the new implementation
     #[derive(Clone, Debug, Default)]
     pub struct LegacyCrrTable {
         pub rows: HashMap<String, CrrRow>,
+        /// Columns that merge as a [`CrdtKind`] rather than by version
+        /// comparison — see [`Self::declare_crdt_column`].
+        pub crdt_columns: HashMap<String, CrdtKind>,
     }
 
     impl LegacyCrrTable {
         pub fn new() -> Self {
-            Self { rows: HashMap::new() }
+            Self { rows: HashMap::new(), crdt_columns: HashMap::new() }
+        }
+
+        fn row_mut(&mut self, pk: &str) -> &mut CrrRow {
+            self.rows.entry(pk.to_string()).or_insert_with(|| CrrRow {
+                pk: pk.to_string(),
+                columns: HashMap::new(),
+                versions: HashMap::new(),
+                dags: HashMap::new(),
+                crdts: HashMap::new(),
+                deleted: false,
+            })
+        }
+
+        /// Render `col`'s current CRDT state back into `row.columns` as a
+        /// display string, so a reader that only ever looks at `columns`
+        /// (like the demo UI's row inspector) still sees an up-to-date value.
+        fn refresh_crdt_display(row: &mut CrrRow, col: &str, kind: CrdtKind) {
+            let Some(bytes) = row.crdts.get(col) else { return };
+            let display = match kind {
+                CrdtKind::PnCounter => PnCounter::from_bytes(bytes).map(|c| c.value().to_string()).unwrap_or_default(),
+                CrdtKind::OrSet => {
+                    let set: OrSet<String> = OrSet::from_bytes(bytes).unwrap_or_default();
+                    let mut elements: Vec<&String> = set.elements().into_iter().collect();
+                    elements.sort();
+                    elements.into_iter().cloned().collect::<Vec<_>>().join(", ")
+                }
+            };
+            row.columns.insert(col.to_string(), display);
+        }
+
+        /// Declare that `col` merges as `kind` — a PN-counter or OR-set —
+        /// instead of by version comparison, mirroring
+        /// [`crate::table::CrrTable::declare_crdt_column`] for the new engine.
+        /// [`Self::crdt_increment`]/[`Self::crdt_decrement`]/[`Self::crdt_set_insert`]/
+        /// [`Self::crdt_set_remove`] maintain such a column's state;
+        /// [`Self::crdt_changeset`]/[`Self::crdt_merge`] carry and fold it,
+        /// separately from [`Self::changeset`]/[`Self::crr_merge`]'s plain
+        /// LWW columns.
+        pub fn declare_crdt_column(&mut self, col: &str, kind: CrdtKind) {
+            self.crdt_columns.insert(col.to_string(), kind);
+        }
+
+        /// Record a local increment on `pk`'s `col`, which must have been
+        /// declared via [`Self::declare_crdt_column`] as `CrdtKind::PnCounter`.
+        pub fn crdt_increment(&mut self, pk: &str, col: &str, replica_id: &str, amount: u64) {
+            self.crdt_columns.entry(col.to_string()).or_insert(CrdtKind::PnCounter);
+            let row = self.row_mut(pk);
+            let mut counter = row.crdts.get(col).and_then(|bytes| PnCounter::from_bytes(bytes).ok()).unwrap_or_default();
+            counter.increment(replica_id, amount);
+            row.crdts.insert(col.to_string(), counter.to_bytes().unwrap_or_default());
+            Self::refresh_crdt_display(row, col, CrdtKind::PnCounter);
+        }
+
+        /// Record a local decrement — see [`Self::crdt_increment`].
+        pub fn crdt_decrement(&mut self, pk: &str, col: &str, replica_id: &str, amount: u64) {
+            self.crdt_columns.entry(col.to_string()).or_insert(CrdtKind::PnCounter);
+            let row = self.row_mut(pk);
+            let mut counter = row.crdts.get(col).and_then(|bytes| PnCounter::from_bytes(bytes).ok()).unwrap_or_default();
+            counter.decrement(replica_id, amount);
+            row.crdts.insert(col.to_string(), counter.to_bytes().unwrap_or_default());
+            Self::refresh_crdt_display(row, col, CrdtKind::PnCounter);
+        }
+
+        /// Add `value` to `pk`'s `col`, which must have been declared via
+        /// [`Self::declare_crdt_column`] as `CrdtKind::OrSet`. `counter` must
+        /// be unique per `replica_id`, same as [`OrSet::insert`].
+        pub fn crdt_set_insert(&mut self, pk: &str, col: &str, replica_id: &str, counter: u64, value: &str) {
+            self.crdt_columns.entry(col.to_string()).or_insert(CrdtKind::OrSet);
+            let row = self.row_mut(pk);
+            let mut set: OrSet<String> = row.crdts.get(col).and_then(|bytes| OrSet::from_bytes(bytes).ok()).unwrap_or_default();
+            set.insert(replica_id, counter, value.to_string());
+            row.crdts.insert(col.to_string(), set.to_bytes().unwrap_or_default());
+            Self::refresh_crdt_display(row, col, CrdtKind::OrSet);
+        }
+
+        /// Remove `value` from `pk`'s `col` — see [`Self::crdt_set_insert`].
+        pub fn crdt_set_remove(&mut self, pk: &str, col: &str, value: &str) {
+            self.crdt_columns.entry(col.to_string()).or_insert(CrdtKind::OrSet);
+            let row = self.row_mut(pk);
+            let mut set: OrSet<String> = row.crdts.get(col).and_then(|bytes| OrSet::from_bytes(bytes).ok()).unwrap_or_default();
+            set.remove(&value.to_string());
+            row.crdts.insert(col.to_string(), set.to_bytes().unwrap_or_default());
+            Self::refresh_crdt_display(row, col, CrdtKind::OrSet);
+        }
+
+        /// The wire shape [`Self::crdt_merge`] expects: each row's declared
+        /// CRDT columns, still encoded as [`ColumnCrdt::to_bytes`] produced
+        /// them, so the receiving side folds via [`ColumnCrdt::merge`]
+        /// instead of comparing plain strings.
+        pub fn crdt_changeset(&self) -> HashMap<String, HashMap<String, Vec<u8>>> {
+            self.rows.iter()
+                .map(|(pk, row)| (pk.clone(), row.crdts.clone()))
+                .collect()
+        }
+
+        /// Fold `changeset` into this table's declared CRDT columns via
+        /// [`ColumnCrdt::merge`], reporting each merged column in
+        /// [`LegacyMergeReport::counter_merges`]/[`LegacyMergeReport::set_merges`]
+        /// rather than [`LegacyMergeReport::conflicts_equal_version`] — a
+        /// concurrent counter increment or set add converges structurally
+        /// and never needs a [`TieBreakPolicy`]. Columns not declared via
+        /// [`Self::declare_crdt_column`] are ignored; use [`Self::crr_merge`]
+        /// for those instead.
+        pub fn crdt_merge(&mut self, changeset: &HashMap<String, HashMap<String, Vec<u8>>>) -> LegacyMergeReport {
+            let mut report = LegacyMergeReport::default();
+            for (pk, cols) in changeset {
+                for (col, remote_bytes) in cols {
+                    let Some(&kind) = self.crdt_columns.get(col) else { continue };
+                    let row = self.row_mut(pk);
+                    match kind {
+                        CrdtKind::PnCounter => {
+                            let Ok(remote) = PnCounter::from_bytes(remote_bytes) else { continue };
+                            let mut local = row.crdts.get(col).and_then(|bytes| PnCounter::from_bytes(bytes).ok()).unwrap_or_default();
+                            local.merge(&remote);
+                            row.crdts.insert(col.clone(), local.to_bytes().unwrap_or_default());
+                            Self::refresh_crdt_display(row, col, kind);
+                            report.counter_merges.push((pk.clone(), col.clone()));
+                        }
+                        CrdtKind::OrSet => {
+                            let Ok(remote) = OrSet::<String>::from_bytes(remote_bytes) else { continue };
+                            let mut local: OrSet<String> = row.crdts.get(col).and_then(|bytes| OrSet::from_bytes(bytes).ok()).unwrap_or_default();
+                            local.merge(&remote);
+                            row.crdts.insert(col.clone(), local.to_bytes().unwrap_or_default());
+                            Self::refresh_crdt_display(row, col, kind);
+                            report.set_merges.push((pk.clone(), col.clone()));
+                        }
+                    }
+                }
+            }
+            report
         }
 
         pub fn insert_or_update(
@@ -56,12 +276,7 @@ pub mod crr {
             columns: HashMap<String, String>,
             versions: HashMap<String, u64>,
         ) {
-            let row = self.rows.entry(pk.to_string()).or_insert_with(|| CrrRow {
-                pk: pk.to_string(),
-                columns: HashMap::new(),
-                versions: HashMap::new(),
-                dags: HashMap::new(),
-            });
+            let row = self.row_mut(pk);
 
             for (col, val) in &columns {
                 let version = versions.get(col).copied().unwrap_or(1);
@@ -76,32 +291,177 @@ pub mod crr {
             }
         }
 
-        pub fn changeset(&self) -> HashMap<String, (HashMap<String, String>, HashMap<String, u64>)> {
+        pub fn changeset(&self) -> LegacyChangeset {
             self.rows.iter()
                 .map(|(pk, row)| (pk.clone(), (row.columns.clone(), row.versions.clone())))
                 .collect()
         }
 
+        /// Build a Merkle tree over this table's rows, mirroring
+        /// [`crate::table::CrrTable::merkle_tree`] for the new engine, so
+        /// both sides can narrow down to their divergent pks instead of
+        /// exchanging a full [`Self::changeset`].
+        pub fn merkle_tree(&self) -> crate::merkle::MerkleTree {
+            let mut tree = crate::merkle::MerkleTree::new();
+            for (pk, row) in &self.rows {
+                let cells: std::collections::BTreeMap<String, crate::storage::Cell> = row.columns.iter()
+                    .map(|(col, val)| {
+                        let version = row.versions.get(col).copied().unwrap_or(0);
+                        (col.clone(), crate::storage::Cell { value: val.clone().into_bytes(), version })
+                    })
+                    .collect();
+                tree.insert(pk, &cells);
+            }
+            tree
+        }
+
+        /// This table's Merkle root digest over every `(pk, column, value,
+        /// version)` cell it currently holds, mirroring
+        /// [`crate::table::CrrTable::merkle_root`] — two tables are
+        /// identical iff their roots match, the cheap first check a
+        /// "Repair/Verify Convergence" step can run across every peer
+        /// before paying for a [`Self::merkle_tree`] comparison.
+        pub fn merkle_root(&self) -> crate::merkle::Digest {
+            self.merkle_tree().root_digest()
+        }
+
+        /// Build a [`LegacyChangeset`] covering exactly `pks`, the way
+        /// [`SyncEngine::reconcile`] does once [`crate::merkle::MerkleTree::diverging_pks`]
+        /// has narrowed down to the rows that actually diverge.
+        pub fn changeset_for_pks(&self, pks: &[String]) -> LegacyChangeset {
+            pks.iter()
+                .filter_map(|pk| self.rows.get(pk))
+                .map(|row| (row.pk.clone(), (row.columns.clone(), row.versions.clone())))
+                .collect()
+        }
+
+        /// Build a [`crate::VersionVector`] snapshot of every `(pk, col)`
+        /// cell this table currently holds, keyed by its current version —
+        /// the frontier a peer passes to a remote's [`Self::changeset_since`]
+        /// to describe what it's already seen. Since this table's own
+        /// `row.versions` already *is* a version vector, there's nothing
+        /// separate to advance after a merge: the next call to this method
+        /// reflects whatever [`Self::crr_merge`] just applied.
+        pub fn version_vector(&self) -> crate::VersionVector {
+            let mut vector = crate::VersionVector::new();
+            for (pk, row) in &self.rows {
+                for (col, &version) in &row.versions {
+                    vector.set(pk, col, version);
+                }
+            }
+            vector
+        }
+
+        /// Like [`Self::changeset`], but only includes a `(pk, col)` cell if
+        /// its version is ahead of what `frontier` already has — the
+        /// causality-token delta [`crate::table::CrrTable::changeset_since_frontier`]
+        /// computes for the new engine, ported here so a sync ships
+        /// bandwidth proportional to the columns that actually changed
+        /// instead of whole rows. A `frontier` from a brand-new peer (an
+        /// empty [`crate::VersionVector`]) naturally returns every cell,
+        /// since [`crate::VersionVector::get`] answers `0` for anything it
+        /// hasn't seen.
+        pub fn changeset_since(&self, frontier: &crate::VersionVector) -> LegacyChangeset {
+            self.rows.iter()
+                .filter_map(|(pk, row)| {
+                    let columns: HashMap<String, String> = row.columns.iter()
+                        .filter(|(col, _)| row.versions.get(col.as_str()).copied().unwrap_or(0) > frontier.get(pk, col))
+                        .map(|(col, val)| (col.clone(), val.clone()))
+                        .collect();
+                    if columns.is_empty() {
+                        return None;
+                    }
+                    let versions: HashMap<String, u64> = columns.keys()
+                        .map(|col| (col.clone(), row.versions.get(col).copied().unwrap_or(0)))
+                        .collect();
+                    Some((pk.clone(), (columns, versions)))
+                })
+                .collect()
+        }
+
+        /// Tombstone `pk` instead of removing it from [`Self::rows`]
+        /// outright, so the delete replicates through [`Self::changeset`]/
+        /// [`Self::crr_merge`] like any other column write rather than a
+        /// peer that still holds the row silently re-introducing it on the
+        /// next merge. `version` should be ahead of every column this row
+        /// currently holds — e.g. `row.versions.values().max().unwrap_or(0) + 1` —
+        /// the same scheme [`crate::schema::SchemaManager::migrate_tables`]'s
+        /// `AddColumn` uses for the version of a backfilled cell.
+        pub fn delete_row(&mut self, pk: &str, version: u64) {
+            let row = self.row_mut(pk);
+            row.deleted = true;
+            row.columns.insert(ROW_TOMBSTONE_COLUMN.to_string(), String::new());
+            row.versions.insert(ROW_TOMBSTONE_COLUMN.to_string(), version);
+        }
+
+        /// Whether `pk` is currently tombstoned — materialized views and the
+        /// demo UI's row inspector skip a row where this is `true`, the same
+        /// way they'd skip a pk that's simply absent from [`Self::rows`].
+        pub fn is_deleted(&self, pk: &str) -> bool {
+            self.rows.get(pk).map(|row| row.deleted).unwrap_or(false)
+        }
+
+        /// Physically drop tombstoned rows once every peer in
+        /// `peer_frontiers` has observed the delete (i.e. each frontier's
+        /// [`crate::VersionVector::get`] for `(pk, `[`ROW_TOMBSTONE_COLUMN`]`)`
+        /// is at least the tombstone's version), bounding memory the way
+        /// [`crate::table::CrrTable`]'s own GC bounds DAG growth — a
+        /// tombstone the whole cluster has already merged no longer needs to
+        /// keep riding along future changesets. Returns the number of rows
+        /// dropped.
+        pub fn gc_tombstones(&mut self, peer_frontiers: &[crate::VersionVector]) -> usize {
+            let before = self.rows.len();
+            self.rows.retain(|pk, row| {
+                if !row.deleted {
+                    return true;
+                }
+                let version = row.versions.get(ROW_TOMBSTONE_COLUMN).copied().unwrap_or(0);
+                !peer_frontiers.iter().all(|frontier| frontier.get(pk, ROW_TOMBSTONE_COLUMN) >= version)
+            });
+            before - self.rows.len()
+        }
+
         pub fn crr_merge(
             &mut self,
-            changeset: &HashMap<String, (HashMap<String, String>, HashMap<String, u64>)>,
+            changeset: &LegacyChangeset,
             policy: TieBreakPolicy,
         ) -> LegacyMergeReport {
             let mut report = LegacyMergeReport::default();
 
             for (pk, (cols, vers)) in changeset {
-                let row = self.rows.entry(pk.clone()).or_insert_with(|| CrrRow {
-                    pk: pk.clone(),
-                    columns: HashMap::new(),
-                    versions: HashMap::new(),
-                    dags: HashMap::new(),
-                });
+                let row = self.row_mut(pk);
+                let tombstone_version = row.versions.get(ROW_TOMBSTONE_COLUMN).copied().unwrap_or(0);
 
                 for (col, val) in cols {
                     let v_r = vers.get(col).copied().unwrap_or(0);
                     let v_l = row.versions.get(col).copied().unwrap_or(0);
                     let current_value = row.columns.get(col).cloned();
 
+                    if col == ROW_TOMBSTONE_COLUMN {
+                        if v_r > v_l {
+                            row.deleted = true;
+                            row.columns.insert(col.clone(), val.clone());
+                            row.versions.insert(col.clone(), v_r);
+                            report.updated.push((pk.clone(), col.clone(), v_r));
+                        } else if v_r < v_l {
+                            report.skipped_older.push((pk.clone(), col.clone(), v_r));
+                        }
+                        continue;
+                    }
+
+                    // A tombstone shadows every ordinary column: a write
+                    // that's no newer than the delete is a stale write that
+                    // loses to it, while a write newer than the delete
+                    // resurrects the row instead of the tombstone silently
+                    // winning forever.
+                    if row.deleted && v_r <= tombstone_version {
+                        report.skipped_older.push((pk.clone(), col.clone(), v_r));
+                        continue;
+                    }
+                    if row.deleted && v_r > tombstone_version {
+                        row.deleted = false;
+                    }
+
                     if v_l == 0 {
                         row.columns.insert(col.clone(), val.clone());
                         row.versions.insert(col.clone(), v_r);
@@ -133,7 +493,11 @@ pub mod crr {
                                         let dag = row.dags.entry(col.clone()).or_insert_with(crate::dag::VersionDag::new);
                                         dag.add_node(v_new, val.clone(), vec![v_l, v_r]);
                                     }
-                                    TieBreakPolicy::LexicographicMin => {
+                                    // This legacy changeset carries caller-assigned
+                                    // integer versions, not wall-clock timestamps, so
+                                    // an equal-version tie has nothing for LastWriteWins
+                                    // to compare — same fallback as MultiValue.
+                                    TieBreakPolicy::LexicographicMin | TieBreakPolicy::MultiValue | TieBreakPolicy::LastWriteWins => {
                                         if &cv > val {
                                             let v_new = v_r + 1;
                                             row.columns.insert(col.clone(), val.clone());
@@ -152,6 +516,121 @@ pub mod crr {
             }
             report
         }
+
+        /// Like [`Self::insert_or_update`], but mints each column's version
+        /// by ticking `clock` rather than taking a caller-supplied integer —
+        /// [`crate::hlc::pack_version`] packs the resulting `HlcStamp` into
+        /// the same `u64` slot, so the stamp's `(wall_time, logical)` order
+        /// is exactly the integer order [`Self::crr_merge`] already compares.
+        pub fn insert_or_update_hlc(
+            &mut self,
+            pk: &str,
+            columns: HashMap<String, String>,
+            clock: &mut crate::hlc::HybridLogicalClock,
+        ) {
+            let versions: HashMap<String, u64> = columns.keys()
+                .map(|col| (col.clone(), crate::hlc::pack_version(clock.tick())))
+                .collect();
+            self.insert_or_update(pk, columns, versions);
+        }
+
+        /// Like [`Self::crr_merge`], but for a changeset whose versions are
+        /// HLC-packed: [`crate::hlc::pack_version`] already makes a later
+        /// stamp compare as a strictly greater `u64`, so every comparison
+        /// in [`Self::crr_merge`] needs no change at all to stay causally
+        /// correct. The one place packed versions change behavior is an
+        /// exact tie that still needs a new version to record the resolved
+        /// value: instead of `crr_merge`'s ad hoc `v_r + 1`, this folds the
+        /// conflicting stamp through `clock`'s HLC receive rule, so the
+        /// synthesized version both compares greater than both inputs and
+        /// keeps `clock` caught up with every stamp this replica has seen.
+        pub fn crr_merge_hlc(
+            &mut self,
+            changeset: &LegacyChangeset,
+            policy: TieBreakPolicy,
+            clock: &mut crate::hlc::HybridLogicalClock,
+        ) -> LegacyMergeReport {
+            let mut report = LegacyMergeReport::default();
+
+            for (pk, (cols, vers)) in changeset {
+                let row = self.row_mut(pk);
+
+                for (col, val) in cols {
+                    let v_r = vers.get(col).copied().unwrap_or(0);
+                    let v_l = row.versions.get(col).copied().unwrap_or(0);
+                    let current_value = row.columns.get(col).cloned();
+
+                    if v_l == 0 {
+                        row.columns.insert(col.clone(), val.clone());
+                        row.versions.insert(col.clone(), v_r);
+                        let dag = row.dags.entry(col.clone()).or_insert_with(crate::dag::VersionDag::new);
+                        dag.add_node(v_r, val.clone(), vec![]);
+                        report.inserted.push((pk.clone(), col.clone()));
+                    } else if v_r > v_l {
+                        row.columns.insert(col.clone(), val.clone());
+                        row.versions.insert(col.clone(), v_r);
+                        let dag = row.dags.entry(col.clone()).or_insert_with(crate::dag::VersionDag::new);
+                        dag.add_node(v_r, val.clone(), vec![v_l]);
+                        report.updated.push((pk.clone(), col.clone(), v_r));
+                    } else if v_r == v_l {
+                        if let Some(cv) = current_value {
+                            if cv != *val {
+                                report.conflicts_equal_version.push((
+                                    pk.clone(),
+                                    col.clone(),
+                                    v_r,
+                                    cv.clone(),
+                                    val.clone(),
+                                ));
+                                let resolved = match policy {
+                                    TieBreakPolicy::PreferExisting => None,
+                                    TieBreakPolicy::PreferIncoming => Some(val),
+                                    // An equal packed HLC version already means an
+                                    // identical (wall_time, logical) pair, so there's
+                                    // no timestamp left for LastWriteWins to compare —
+                                    // same fallback as MultiValue/LexicographicMin.
+                                    TieBreakPolicy::LexicographicMin | TieBreakPolicy::MultiValue | TieBreakPolicy::LastWriteWins => {
+                                        if &cv > val { Some(val) } else { None }
+                                    }
+                                };
+                                if let Some(resolved_val) = resolved {
+                                    let v_new = crate::hlc::pack_version(clock.receive(crate::hlc::unpack_version(v_r)));
+                                    row.columns.insert(col.clone(), resolved_val.clone());
+                                    row.versions.insert(col.clone(), v_new);
+                                    let dag = row.dags.entry(col.clone()).or_insert_with(crate::dag::VersionDag::new);
+                                    dag.add_node(v_new, resolved_val.clone(), vec![v_l, v_r]);
+                                }
+                            }
+                        }
+                    } else {
+                        report.skipped_older.push((pk.clone(), col.clone(), v_r));
+                    }
+                }
+            }
+            report
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn concurrent_counter_increments_both_survive_instead_of_conflicting() {
+            let mut a = LegacyCrrTable::new();
+            a.declare_crdt_column("likes", CrdtKind::PnCounter);
+            a.crdt_increment("post_1", "likes", "replica_a", 2);
+
+            let mut b = LegacyCrrTable::new();
+            b.declare_crdt_column("likes", CrdtKind::PnCounter);
+            b.crdt_increment("post_1", "likes", "replica_b", 3);
+
+            let report = a.crdt_merge(&b.crdt_changeset());
+
+            assert_eq!(report.counter_merges, vec![("post_1".to_string(), "likes".to_string())]);
+            assert!(report.conflicts_equal_version.is_empty(), "counter merges must not be reported as version conflicts");
+            assert_eq!(a.rows["post_1"].columns["likes"], "5");
+        }
     }
 }
 
@@ -307,6 +786,7 @@ pub mod dag {
 pub mod schema {
     use serde::{Deserialize, Serialize};
     use std::collections::HashMap;
+    use crate::merge::TieBreakPolicy;
 
     #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct SchemaVersion {
@@ -328,6 +808,13 @@ pub mod schema {
         Integer,
         Real,
         Blob,
+        /// A grow/shrink counter merged as a [`crate::CrdtKind::PnCounter`]
+        /// instead of by version comparison: concurrent increments from
+        /// different peers both survive instead of one clobbering the
+        /// other. [`crate::SyncEngine::apply_schema_migration`] is the
+        /// entry point that wires an `AddColumn` of this type into
+        /// [`crate::crr::LegacyCrrTable::declare_crdt_column`].
+        PnCounter,
     }
 
     #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -336,18 +823,55 @@ pub mod schema {
         RenameColumn { old_name: String, new_name: String },
         ChangeColumnType { name: String, new_type: ColumnType },
         DropColumn { name: String },
+        /// Declares that `column` on `table` references a row of
+        /// `references_table` (matched against that row's primary key, the
+        /// same way [`crate::foreign_keys::ForeignKey`] already does).
+        /// Carries no column-catalog change of its own — it only becomes
+        /// useful once [`crate::SyncEngine::apply_schema_migration`] folds
+        /// it into [`crate::SyncEngine::fk_manager`], which is what
+        /// [`crate::SyncEngine::delete_row_cascading`] and
+        /// [`crate::transactions::TransactionManager::commit`]'s insert
+        /// validation actually consult.
+        AddForeignKey {
+            table: String,
+            column: String,
+            references_table: String,
+            on_delete: crate::foreign_keys::OnDeleteAction,
+        },
+    }
+
+    /// One [`SchemaMigration`] as staged into [`SchemaManager::migrations`]:
+    /// its version and the peer that authored it, so [`SchemaManager::merge`]
+    /// can tell two migrations landing at the same version from different
+    /// peers (genuinely concurrent) apart from one peer simply having staged
+    /// more migrations than the other has seen yet.
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct StagedMigration {
+        pub version: u64,
+        pub origin_peer: String,
+        pub migration: SchemaMigration,
     }
 
     #[derive(Clone, Debug, Default, Serialize, Deserialize)]
     pub struct SchemaManager {
         pub versions: HashMap<u64, SchemaVersion>,
         pub current_version: u64,
-        pub migrations: Vec<(u64, SchemaMigration)>,
+        pub migrations: Vec<StagedMigration>,
+        pub peer_id: String,
     }
 
     impl SchemaManager {
         pub fn new() -> Self {
-            Self { versions: HashMap::new(), current_version: 0, migrations: Vec::new() }
+            Self::new_with_peer_id(String::new())
+        }
+
+        /// Like [`Self::new`], but every migration staged from here on
+        /// records `peer_id` as its [`StagedMigration::origin_peer`] — the
+        /// identity [`Self::merge`] needs to tell two peers' concurrent
+        /// migrations apart, mirroring [`delta_sync::DeltaTracker::new`]'s
+        /// peer-scoped constructor.
+        pub fn new_with_peer_id(peer_id: String) -> Self {
+            Self { versions: HashMap::new(), current_version: 0, migrations: Vec::new(), peer_id }
         }
 
         pub fn apply_migration(&mut self, migration: SchemaMigration) -> u64 {
@@ -356,76 +880,452 @@ pub mod schema {
                 .map(|s| s.columns.clone())
                 .unwrap_or_default();
 
-            match &migration {
-                SchemaMigration::AddColumn { name, col_type, nullable } => {
-                    new_columns.insert(name.clone(), ColumnDef {
-                        name: name.clone(),
-                        col_type: col_type.clone(),
-                        nullable: *nullable,
+            apply_migration_to_columns(&mut new_columns, &migration);
+
+            self.versions.insert(new_version, SchemaVersion {
+                version: new_version,
+                columns: new_columns,
+                timestamp: now_millis(),
+            });
+            self.migrations.push(StagedMigration {
+                version: new_version,
+                origin_peer: self.peer_id.clone(),
+                migration,
+            });
+            self.current_version = new_version;
+            new_version
+        }
+
+        /// Fold every migration `other` has staged that this replica
+        /// hasn't seen yet into [`Self::migrations`], then replay the
+        /// combined, deterministically-ordered history from scratch —
+        /// modeled on Garage's versioned `ClusterLayout`: a monotonically
+        /// increasing version plus staged changes that merge the same way
+        /// no matter which replica computes it or in what order the
+        /// changes arrived. Two migrations landing at the same version
+        /// from different peers are genuinely concurrent; see
+        /// [`Self::resolve_group`] for how those are resolved. Returns
+        /// which versions were newly applied and any conflicts that
+        /// resolution hit, so a caller (e.g. the demo's `sync_peers`) can
+        /// fold them into the same report it already shows for row merges.
+        pub fn merge(&mut self, other: &Self, policy: TieBreakPolicy) -> SchemaMergeReport {
+            let mut report = SchemaMergeReport::default();
+            let known: std::collections::HashSet<(u64, &str)> =
+                self.migrations.iter().map(|m| (m.version, m.origin_peer.as_str())).collect();
+            let known_versions: std::collections::HashSet<u64> = self.migrations.iter().map(|m| m.version).collect();
+            let incoming: Vec<StagedMigration> = other.migrations.iter()
+                .filter(|m| !known.contains(&(m.version, m.origin_peer.as_str())))
+                .cloned()
+                .collect();
+            if incoming.is_empty() {
+                return report;
+            }
+
+            report.applied = incoming.iter().map(|m| m.version).filter(|v| !known_versions.contains(v)).collect();
+            self.migrations.extend(incoming);
+            self.rebuild(policy, &mut report);
+            report
+        }
+
+        /// Replay [`Self::migrations`] from an empty schema, grouping by
+        /// version so [`Self::resolve_group`] sees every migration staged
+        /// at the same version together before any of them are applied.
+        fn rebuild(&mut self, policy: TieBreakPolicy, report: &mut SchemaMergeReport) {
+            let mut by_version: HashMap<u64, Vec<&StagedMigration>> = HashMap::new();
+            for staged in &self.migrations {
+                by_version.entry(staged.version).or_default().push(staged);
+            }
+            let mut ordered_versions: Vec<u64> = by_version.keys().copied().collect();
+            ordered_versions.sort();
+
+            let mut columns: HashMap<String, ColumnDef> = HashMap::new();
+            let mut versions = HashMap::new();
+            let mut last_version = 0;
+            for version in ordered_versions {
+                let mut group = by_version[&version].clone();
+                group.sort_by(|a, b| a.origin_peer.cmp(&b.origin_peer));
+                for migration in Self::resolve_group(&group, policy, report) {
+                    apply_migration_to_columns(&mut columns, migration);
+                }
+                versions.insert(version, SchemaVersion { version, columns: columns.clone(), timestamp: now_millis() });
+                last_version = version;
+            }
+
+            self.versions = versions;
+            self.current_version = last_version;
+        }
+
+        /// Resolve the migrations staged at a single version by more than
+        /// one peer — the schema equivalent of [`LegacyCrrTable::crr_merge`]'s
+        /// equal-version tiebreak. Two rules apply, in order:
+        ///
+        /// - Add-wins: a `DropColumn` concurrent with any migration that
+        ///   still writes to the same column loses, so a schema merge never
+        ///   silently discards a column a concurrent writer was relying on.
+        /// - Rename conflicts: two concurrent `RenameColumn`s of the same
+        ///   source column are resolved via `policy`, the same comparison
+        ///   [`LegacyCrrTable::crr_merge`] uses for an equal-version tie on
+        ///   a plain column.
+        ///
+        /// A single migration at a version (the overwhelmingly common
+        /// case) always survives unchanged.
+        fn resolve_group<'a>(
+            group: &[&'a StagedMigration],
+            policy: TieBreakPolicy,
+            report: &mut SchemaMergeReport,
+        ) -> Vec<&'a SchemaMigration> {
+            if group.len() <= 1 {
+                return group.iter().map(|staged| &staged.migration).collect();
+            }
+
+            let mut dropped_origins: std::collections::HashSet<&str> = std::collections::HashSet::new();
+            for staged in group {
+                if let SchemaMigration::DropColumn { name } = &staged.migration {
+                    let contested = group.iter().any(|other| {
+                        other.origin_peer != staged.origin_peer && migration_touches_column(&other.migration, name)
                     });
+                    if contested {
+                        report.drop_vs_update_conflicts.push(name.clone());
+                        dropped_origins.insert(staged.origin_peer.as_str());
+                    }
                 }
-                SchemaMigration::RenameColumn { old_name, new_name } => {
-                    if let Some(mut col) = new_columns.remove(old_name) {
-                        col.name = new_name.clone();
-                        new_columns.insert(new_name.clone(), col);
+            }
+
+            let survivors: Vec<&StagedMigration> = group.iter()
+                .copied()
+                .filter(|staged| !dropped_origins.contains(staged.origin_peer.as_str()))
+                .collect();
+
+            let mut by_old_name: HashMap<&str, Vec<&StagedMigration>> = HashMap::new();
+            for staged in &survivors {
+                if let SchemaMigration::RenameColumn { old_name, .. } = &staged.migration {
+                    by_old_name.entry(old_name.as_str()).or_default().push(staged);
+                }
+            }
+
+            let mut losing_origins: std::collections::HashSet<&str> = std::collections::HashSet::new();
+            for renames in by_old_name.values() {
+                if renames.len() <= 1 {
+                    continue;
+                }
+                let mut winner = renames[0];
+                for candidate in &renames[1..] {
+                    let (SchemaMigration::RenameColumn { new_name: winner_name, .. }, SchemaMigration::RenameColumn { new_name: candidate_name, .. })
+                        = (&winner.migration, &candidate.migration) else { continue };
+                    let candidate_wins = match policy {
+                        TieBreakPolicy::PreferExisting => false,
+                        TieBreakPolicy::PreferIncoming => true,
+                        TieBreakPolicy::LexicographicMin | TieBreakPolicy::MultiValue | TieBreakPolicy::LastWriteWins => candidate_name < winner_name,
+                    };
+                    if candidate_wins {
+                        winner = candidate;
                     }
                 }
-                SchemaMigration::ChangeColumnType { name, new_type } => {
-                    if let Some(col) = new_columns.get_mut(name) {
-                        col.col_type = new_type.clone();
+                for staged in renames {
+                    if staged.origin_peer == winner.origin_peer {
+                        continue;
+                    }
+                    if let (SchemaMigration::RenameColumn { old_name, new_name: losing_name, .. }, SchemaMigration::RenameColumn { new_name: winning_name, .. })
+                        = (&staged.migration, &winner.migration) {
+                        report.rename_conflicts.push((old_name.clone(), winning_name.clone(), losing_name.clone()));
+                    }
+                    losing_origins.insert(staged.origin_peer.as_str());
+                }
+            }
+
+            survivors.into_iter()
+                .filter(|staged| !losing_origins.contains(staged.origin_peer.as_str()))
+                .map(|staged| &staged.migration)
+                .collect()
+        }
+
+        /// Apply `migration` to the schema catalog, as [`Self::apply_migration`]
+        /// does, and also rewrite every row of every table so stored data
+        /// doesn't drift from the new schema version. `AddColumn` backfills a
+        /// type-appropriate default (there's no supplied-default variant on
+        /// `SchemaMigration` today) into rows missing the column, creating a
+        /// fresh version node in the column's DAG. `RenameColumn` moves the
+        /// value, version, and DAG history under the new key. `DropColumn`
+        /// removes the column's value and version from each row and appends
+        /// a tombstoned node to its DAG history; a row changeset no longer
+        /// carries the column, so the drop is not sent to other peers by
+        /// row sync. `ChangeColumnType`
+        /// parses each cell against the target type, coercing in place on
+        /// success and recording the row as rejected (left untouched) on
+        /// failure, so a single unparsable cell doesn't abort the migration
+        /// for the rest of the table.
+        pub fn migrate_tables(
+            &mut self,
+            migration: SchemaMigration,
+            tables: &mut HashMap<String, crate::crr::LegacyCrrTable>,
+        ) -> crate::error::Result<MigrationReport> {
+            let mut report = MigrationReport::default();
+
+            match &migration {
+                SchemaMigration::AddColumn { name, col_type, .. } => {
+                    let default = default_value_for(col_type);
+                    for table in tables.values_mut() {
+                        for row in table.rows.values_mut() {
+                            if row.columns.contains_key(name) {
+                                continue;
+                            }
+                            let version = row.versions.values().copied().max().unwrap_or(0) + 1;
+                            row.dags.entry(name.clone())
+                                .or_insert_with(crate::dag::VersionDag::new)
+                                .add_node(version, default.clone(), vec![]);
+                            row.columns.insert(name.clone(), default.clone());
+                            row.versions.insert(name.clone(), version);
+                            report.rewritten.push(row.pk.clone());
+                        }
+                    }
+                }
+                SchemaMigration::RenameColumn { old_name, new_name } => {
+                    for table in tables.values_mut() {
+                        for row in table.rows.values_mut() {
+                            let Some(value) = row.columns.remove(old_name) else { continue };
+                            let version = row.versions.remove(old_name).unwrap_or(0);
+                            if let Some(dag) = row.dags.remove(old_name) {
+                                row.dags.insert(new_name.clone(), dag);
+                            }
+                            row.columns.insert(new_name.clone(), value);
+                            row.versions.insert(new_name.clone(), version);
+                            report.rewritten.push(row.pk.clone());
+                        }
                     }
                 }
                 SchemaMigration::DropColumn { name } => {
-                    new_columns.remove(name);
+                    for table in tables.values_mut() {
+                        for row in table.rows.values_mut() {
+                            let Some(value) = row.columns.remove(name) else { continue };
+                            let prev_version = row.versions.remove(name).unwrap_or(0);
+                            let version = prev_version + 1;
+                            row.dags.entry(name.clone())
+                                .or_insert_with(crate::dag::VersionDag::new)
+                                .add_node_with_tombstone(version, value, vec![prev_version], true);
+                            report.rewritten.push(row.pk.clone());
+                        }
+                    }
+                }
+                SchemaMigration::ChangeColumnType { name, new_type } => {
+                    for table in tables.values_mut() {
+                        for row in table.rows.values_mut() {
+                            let Some(value) = row.columns.get(name).cloned() else { continue };
+                            match coerce_to_type(&value, new_type) {
+                                Ok(coerced) => {
+                                    let prev_version = row.versions.get(name).copied().unwrap_or(0);
+                                    let version = prev_version + 1;
+                                    row.dags.entry(name.clone())
+                                        .or_insert_with(crate::dag::VersionDag::new)
+                                        .add_node(version, coerced.clone(), vec![prev_version]);
+                                    row.columns.insert(name.clone(), coerced);
+                                    row.versions.insert(name.clone(), version);
+                                    report.rewritten.push(row.pk.clone());
+                                }
+                                Err(e) => report.rejected.push((row.pk.clone(), e.to_string())),
+                            }
+                        }
+                    }
                 }
+                SchemaMigration::AddForeignKey { .. } => {}
             }
 
-            self.versions.insert(new_version, SchemaVersion {
-                version: new_version,
-                columns: new_columns,
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_millis() as u64,
-            });
-            self.migrations.push((new_version, migration));
-            self.current_version = new_version;
-            new_version
+            self.apply_migration(migration);
+            Ok(report)
         }
     }
-}
 
-// Legacy foreign_keys module
-pub mod foreign_keys {
-    use serde::{Deserialize, Serialize};
-    use std::collections::HashMap;
+    /// Fold one migration into a running column map — the single step
+    /// [`SchemaManager::apply_migration`] takes locally and
+    /// [`SchemaManager::rebuild`] replays for every migration in a merged
+    /// history, so the two never drift apart on what a given migration does.
+    fn apply_migration_to_columns(columns: &mut HashMap<String, ColumnDef>, migration: &SchemaMigration) {
+        match migration {
+            SchemaMigration::AddColumn { name, col_type, nullable } => {
+                columns.insert(name.clone(), ColumnDef {
+                    name: name.clone(),
+                    col_type: col_type.clone(),
+                    nullable: *nullable,
+                });
+            }
+            SchemaMigration::RenameColumn { old_name, new_name } => {
+                if let Some(mut col) = columns.remove(old_name) {
+                    col.name = new_name.clone();
+                    columns.insert(new_name.clone(), col);
+                }
+            }
+            SchemaMigration::ChangeColumnType { name, new_type } => {
+                if let Some(col) = columns.get_mut(name) {
+                    col.col_type = new_type.clone();
+                }
+            }
+            SchemaMigration::DropColumn { name } => {
+                columns.remove(name);
+            }
+            SchemaMigration::AddForeignKey { .. } => {}
+        }
+    }
 
-    #[derive(Clone, Debug, Serialize, Deserialize)]
-    pub struct ForeignKey {
-        pub name: String,
-        pub from_table: String,
-        pub from_column: String,
-        pub to_table: String,
-        pub to_column: String,
-        pub on_delete: OnDeleteAction,
+    /// Whether `migration` reads or writes `name` — used by
+    /// [`SchemaManager::resolve_group`] to tell whether a concurrent
+    /// `DropColumn` is actually contested.
+    fn migration_touches_column(migration: &SchemaMigration, name: &str) -> bool {
+        match migration {
+            SchemaMigration::AddColumn { name: n, .. } => n == name,
+            SchemaMigration::ChangeColumnType { name: n, .. } => n == name,
+            SchemaMigration::RenameColumn { old_name, new_name } => old_name == name || new_name == name,
+            SchemaMigration::DropColumn { .. } => false,
+            SchemaMigration::AddForeignKey { .. } => false,
+        }
     }
 
-    #[derive(Clone, Debug, Serialize, Deserialize)]
-    pub enum OnDeleteAction {
-        Cascade,
-        SetNull,
-        Restrict,
-        NoAction,
+    fn now_millis() -> u64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64
     }
 
-    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
-    pub struct ForeignKeyManager {
-        pub constraints: HashMap<String, ForeignKey>,
+    /// What [`SchemaManager::merge`] did: which migration versions were
+    /// newly folded in, and any conflicts [`SchemaManager::resolve_group`]
+    /// had to resolve while replaying them — the schema-merge analogue of
+    /// [`crate::crr::LegacyMergeReport::conflicts_equal_version`], surfaced
+    /// separately since a schema conflict has no single row/column to
+    /// attach to.
+    #[derive(Clone, Debug, Default)]
+    pub struct SchemaMergeReport {
+        pub applied: Vec<u64>,
+        /// Columns a concurrent `DropColumn` would have removed, kept
+        /// instead because another migration at the same version still
+        /// wrote to them — add-wins, so a schema merge never silently
+        /// loses data a concurrent writer was still relying on.
+        pub drop_vs_update_conflicts: Vec<String>,
+        /// `(old_name, winning_new_name, losing_new_name)` for two
+        /// concurrent `RenameColumn`s of the same source column, resolved
+        /// via `TieBreakPolicy`.
+        pub rename_conflicts: Vec<(String, String, String)>,
     }
 
-    impl ForeignKeyManager {
-        pub fn new() -> Self {
-            Self { constraints: HashMap::new() }
+    /// A type-appropriate stand-in for "no value yet", used to backfill rows
+    /// that predate an `AddColumn` migration.
+    fn default_value_for(col_type: &ColumnType) -> String {
+        match col_type {
+            ColumnType::Text | ColumnType::Blob => String::new(),
+            ColumnType::Integer | ColumnType::PnCounter => "0".to_string(),
+            ColumnType::Real => "0".to_string(),
+        }
+    }
+
+    /// Validate `value` against `target`, coercing it to the canonical
+    /// string form for that type. `Text`/`Blob` accept any value unchanged;
+    /// `Integer`/`Real` require it to parse, so a migration doesn't silently
+    /// leave unparsable garbage behind a type change.
+    fn coerce_to_type(value: &str, target: &ColumnType) -> crate::error::Result<String> {
+        match target {
+            ColumnType::Text | ColumnType::Blob => Ok(value.to_string()),
+            ColumnType::Integer | ColumnType::PnCounter => value.parse::<i64>()
+                .map(|v| v.to_string())
+                .map_err(|_| crate::error::Error::InvalidState(
+                    format!("value {:?} does not parse as an integer", value)
+                )),
+            ColumnType::Real => value.parse::<f64>()
+                .map(|v| v.to_string())
+                .map_err(|_| crate::error::Error::InvalidState(
+                    format!("value {:?} does not parse as a real", value)
+                )),
+        }
+    }
+
+    /// Which rows a [`SchemaManager::migrate_tables`] call touched: rewritten
+    /// successfully, or rejected (left as-is) because a cell failed to
+    /// coerce to the migration's target type.
+    #[derive(Clone, Debug, Default)]
+    pub struct MigrationReport {
+        pub rewritten: Vec<String>,
+        pub rejected: Vec<(String, String)>,
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn merge_converges_two_peers_onto_an_identical_schema_version() {
+            let mut a = SchemaManager::new_with_peer_id("peer_a".to_string());
+            a.apply_migration(SchemaMigration::AddColumn { name: "name".to_string(), col_type: ColumnType::Text, nullable: false });
+
+            let mut b = SchemaManager::new_with_peer_id("peer_b".to_string());
+            b.apply_migration(SchemaMigration::AddColumn { name: "name".to_string(), col_type: ColumnType::Text, nullable: false });
+            b.apply_migration(SchemaMigration::AddColumn { name: "phone".to_string(), col_type: ColumnType::Text, nullable: true });
+
+            let report = a.merge(&b, TieBreakPolicy::LexicographicMin);
+
+            assert_eq!(report.applied, vec![2]);
+            let columns = &a.versions[&a.current_version].columns;
+            assert!(columns.contains_key("name"));
+            assert!(columns.contains_key("phone"));
+        }
+
+        #[test]
+        fn concurrent_drop_loses_to_a_migration_still_writing_the_same_column() {
+            let mut a = SchemaManager::new_with_peer_id("peer_a".to_string());
+            a.apply_migration(SchemaMigration::AddColumn { name: "status".to_string(), col_type: ColumnType::Text, nullable: true });
+            // Force both peers' next migration to collide on version 2.
+            a.migrations.push(StagedMigration {
+                version: 2,
+                origin_peer: "peer_a".to_string(),
+                migration: SchemaMigration::DropColumn { name: "status".to_string() },
+            });
+            a.current_version = 2;
+
+            let mut b = SchemaManager::new_with_peer_id("peer_b".to_string());
+            b.migrations.push(StagedMigration {
+                version: 2,
+                origin_peer: "peer_b".to_string(),
+                migration: SchemaMigration::ChangeColumnType { name: "status".to_string(), new_type: ColumnType::Integer },
+            });
+
+            let report = a.merge(&b, TieBreakPolicy::LexicographicMin);
+
+            assert_eq!(report.drop_vs_update_conflicts, vec!["status".to_string()]);
+            assert!(a.versions[&a.current_version].columns.contains_key("status"), "add-wins: the column must survive a concurrent drop");
+        }
+    }
+}
+
+// Legacy foreign_keys module
+pub mod foreign_keys {
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct ForeignKey {
+        pub name: String,
+        pub from_table: String,
+        pub from_column: String,
+        pub to_table: String,
+        /// Informational only — every lookup here matches `from_column`
+        /// against `to_table`'s row primary key directly (there's no
+        /// separate row-id column), so this is typically left empty.
+        pub to_column: String,
+        pub on_delete: OnDeleteAction,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub enum OnDeleteAction {
+        Cascade,
+        SetNull,
+        Restrict,
+        NoAction,
+    }
+
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    pub struct ForeignKeyManager {
+        pub constraints: HashMap<String, ForeignKey>,
+    }
+
+    impl ForeignKeyManager {
+        pub fn new() -> Self {
+            Self { constraints: HashMap::new() }
         }
 
         pub fn add_constraint(&mut self, fk: ForeignKey) {
@@ -446,6 +1346,13 @@ pub mod transactions {
         pub version: u64,
         pub timestamp: u64,
         pub committed: bool,
+        /// Per-table version vector captured at [`TransactionManager::begin`] —
+        /// the consistent view this transaction reads against.
+        /// [`TransactionManager::commit`] compares it against the live table
+        /// state for every cell an operation touches, so a row changed by
+        /// someone else after this snapshot was taken fails the commit with
+        /// a conflict instead of silently overwriting it.
+        pub snapshot: HashMap<String, crate::VersionVector>,
     }
 
     #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -455,201 +1362,2020 @@ pub mod transactions {
         Delete { table: String, pk: String },
     }
 
-    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
-    pub struct TransactionManager {
-        pub transactions: HashMap<String, Transaction>,
-        pub pending: Vec<String>,
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    pub struct TransactionManager {
+        pub transactions: HashMap<String, Transaction>,
+        pub pending: Vec<String>,
+    }
+
+    impl TransactionManager {
+        pub fn new() -> Self {
+            Self { transactions: HashMap::new(), pending: Vec::new() }
+        }
+
+        /// Start a transaction, snapshotting every known table's
+        /// [`crr::LegacyCrrTable::version_vector`] so [`Self::commit`] can
+        /// later tell whether a row/column this transaction touches was
+        /// modified by someone else in the meantime.
+        pub fn begin(&mut self, tables: &HashMap<String, super::crr::LegacyCrrTable>) -> String {
+            let tx_id = format!("tx_{}", self.transactions.len() + 1);
+            let snapshot = tables.iter().map(|(name, table)| (name.clone(), table.version_vector())).collect();
+            let tx = Transaction {
+                id: tx_id.clone(),
+                operations: Vec::new(),
+                version: 0,
+                timestamp: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64,
+                committed: false,
+                snapshot,
+            };
+            self.transactions.insert(tx_id.clone(), tx);
+            self.pending.push(tx_id.clone());
+            tx_id
+        }
+
+        pub fn add_operation(&mut self, tx_id: &str, op: TransactionOp) -> Result<(), String> {
+            if let Some(tx) = self.transactions.get_mut(tx_id) {
+                if tx.committed {
+                    return Err("Transaction already committed".to_string());
+                }
+                tx.operations.push(op);
+                Ok(())
+            } else {
+                Err("Transaction not found".to_string())
+            }
+        }
+
+        /// Discard `tx_id`'s buffered operations and drop it entirely — the
+        /// counterpart to [`Self::commit`] for a transaction the caller
+        /// decides not to apply (e.g. after a failed commit, or a user
+        /// cancelling mid-transaction). Unlike a failed [`Self::commit`],
+        /// which leaves the transaction in place for a retry, an aborted
+        /// transaction is gone: a later `add_operation`/`commit` against the
+        /// same `tx_id` returns "Transaction not found".
+        pub fn abort(&mut self, tx_id: &str) -> Result<(), String> {
+            let tx = self.transactions.get(tx_id).ok_or("Transaction not found")?;
+            if tx.committed {
+                return Err("Cannot abort a committed transaction".to_string());
+            }
+            self.transactions.remove(tx_id);
+            self.pending.retain(|id| id != tx_id);
+            Ok(())
+        }
+
+        /// Every not-yet-committed transaction with at least one staged
+        /// operation — what a caller like `render_peer_card` shows as a
+        /// "pending transaction" overlay.
+        pub fn pending_transactions(&self) -> Vec<&Transaction> {
+            self.pending.iter()
+                .filter_map(|id| self.transactions.get(id))
+                .filter(|tx| !tx.committed && !tx.operations.is_empty())
+                .collect()
+        }
+
+        /// Commit `tx_id`'s operations against `tables`, enforcing
+        /// `fk_manager`'s constraints for every `TransactionOp::Insert` and
+        /// `ON DELETE` action for every `TransactionOp::Delete`. Inserts are
+        /// checked against both `tables` and this same transaction's own
+        /// earlier inserts (so e.g. a parent and its child can be created in
+        /// one commit), and deletes are expanded and validated against every
+        /// constraint — all *before* anything is mutated, so either kind of
+        /// violation aborts the whole commit (the transaction is left
+        /// uncommitted) the same way an `Err` from earlier in this function
+        /// already did. On success, the returned [`CommitReport`] lists
+        /// every cascaded delete or set-null the FK manager triggered,
+        /// alongside the operations the caller asked for directly.
+        pub fn commit(
+            &mut self,
+            tx_id: &str,
+            tables: &mut HashMap<String, super::crr::LegacyCrrTable>,
+            fk_manager: &super::foreign_keys::ForeignKeyManager,
+        ) -> Result<CommitReport, String> {
+            let tx = self.transactions.get(tx_id).ok_or("Transaction not found")?;
+            if tx.committed {
+                return Err("Transaction already committed".to_string());
+            }
+
+            let operations = tx.operations.clone();
+            let snapshot = tx.snapshot.clone();
+            let tx_version = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+
+            // Optimistic concurrency control: if any cell an operation
+            // reads or writes has advanced past what `snapshot` saw at
+            // `begin`, someone else committed to this row since this
+            // transaction started reading it. Fail the whole commit rather
+            // than silently overwrite that write; the transaction is left
+            // in place (still uncommitted) so the caller can retry it.
+            for op in &operations {
+                if let Some((table, pk)) = op_conflicts(op, &*tables, &snapshot) {
+                    return Err(format!("commit conflict: {}:{} was modified since this transaction's snapshot", table, pk));
+                }
+            }
+
+            // Validate every insert's foreign keys before mutating anything,
+            // so a reference to a non-existent parent aborts the whole
+            // commit atomically instead of leaving an orphaned row behind.
+            // `pending_inserts` tracks rows this same transaction is about
+            // to create, so e.g. inserting a customer and one of their
+            // orders in the same commit validates fine even though the
+            // customer doesn't exist in `tables` yet.
+            let mut pending_inserts: HashMap<String, std::collections::HashSet<String>> = HashMap::new();
+            for op in &operations {
+                if let TransactionOp::Insert { table, pk, columns } = op {
+                    validate_foreign_keys(table, columns, &*tables, fk_manager, &pending_inserts)?;
+                    pending_inserts.entry(table.clone()).or_default().insert(pk.clone());
+                }
+            }
+
+            // Expand and validate every delete's cascade before mutating
+            // anything, so a Restrict/NoAction violation leaves the table
+            // state and this transaction untouched.
+            let mut visited = std::collections::HashSet::new();
+            let mut delete_actions: Vec<Vec<FkAction>> = Vec::new();
+            for op in &operations {
+                if let TransactionOp::Delete { table, pk } = op {
+                    let mut actions = Vec::new();
+                    expand_delete(table, pk, &*tables, fk_manager, &mut visited, &mut actions)?;
+                    delete_actions.push(actions);
+                }
+            }
+
+            let mut report = CommitReport::default();
+            let mut deletes = delete_actions.into_iter();
+
+            for op in &operations {
+                match op {
+                    TransactionOp::Insert { table, pk, columns } => {
+                        let crr_table = tables.entry(table.clone()).or_insert_with(super::crr::LegacyCrrTable::new);
+                        let versions: HashMap<String, u64> = columns.keys().map(|c| (c.clone(), tx_version)).collect();
+                        crr_table.insert_or_update(pk, columns.clone(), versions);
+                    }
+                    TransactionOp::Update { table, pk, columns } => {
+                        let crr_table = tables.entry(table.clone()).or_insert_with(super::crr::LegacyCrrTable::new);
+                        let mut final_columns = crr_table.rows.get(pk)
+                            .map(|r| r.columns.clone())
+                            .unwrap_or_default();
+                        let mut versions = HashMap::new();
+                        for (col, val) in columns {
+                            final_columns.insert(col.clone(), val.clone());
+                            versions.insert(col.clone(), tx_version);
+                        }
+                        crr_table.insert_or_update(pk, final_columns, versions);
+                    }
+                    TransactionOp::Delete { .. } => {
+                        for action in deletes.next().unwrap_or_default() {
+                            apply_fk_action(action, tables, &mut report, tx_version);
+                        }
+                    }
+                }
+            }
+
+            if let Some(tx) = self.transactions.get_mut(tx_id) {
+                tx.committed = true;
+                tx.version = tx_version;
+            }
+            self.pending.retain(|id| id != tx_id);
+            Ok(report)
+        }
+
+    }
+
+    /// What a [`TransactionManager::commit`] call did beyond the operations
+    /// the caller asked for directly: every delete or set-null a foreign-key
+    /// `ON DELETE` action cascaded into.
+    #[derive(Clone, Debug, Default)]
+    pub struct CommitReport {
+        pub cascaded: Vec<TransactionOp>,
+    }
+
+    /// A single foreign-key-triggered effect of deleting a row, queued up
+    /// during validation so it can be applied once the whole cascade is
+    /// known not to hit a `Restrict`/`NoAction` constraint.
+    enum FkAction {
+        Delete { table: String, pk: String },
+        SetNull { table: String, pk: String, column: String },
+    }
+
+    /// Whether `op`'s row has any column whose current version is ahead of
+    /// what `snapshot` saw at `begin` — i.e. a concurrent write landed on
+    /// this row after this transaction's snapshot was taken. Returns the
+    /// conflicting `(table, pk)` so the caller can report it.
+    fn op_conflicts(
+        op: &TransactionOp,
+        tables: &HashMap<String, super::crr::LegacyCrrTable>,
+        snapshot: &HashMap<String, crate::VersionVector>,
+    ) -> Option<(String, String)> {
+        let (table, pk, columns): (&str, &str, Vec<String>) = match op {
+            TransactionOp::Insert { table, pk, columns } | TransactionOp::Update { table, pk, columns } => {
+                (table.as_str(), pk.as_str(), columns.keys().cloned().collect())
+            }
+            TransactionOp::Delete { table, pk } => {
+                let columns = tables.get(table)
+                    .and_then(|t| t.rows.get(pk))
+                    .map(|row| row.versions.keys().cloned().collect())
+                    .unwrap_or_default();
+                (table.as_str(), pk.as_str(), columns)
+            }
+        };
+        let current_row = tables.get(table).and_then(|t| t.rows.get(pk));
+        let table_snapshot = snapshot.get(table);
+        let conflicted = columns.iter().any(|col| {
+            let current_version = current_row.and_then(|row| row.versions.get(col)).copied().unwrap_or(0);
+            let snapshot_version = table_snapshot.map(|v| v.get(pk, col)).unwrap_or(0);
+            current_version > snapshot_version
+        });
+        conflicted.then(|| (table.to_string(), pk.to_string()))
+    }
+
+    /// Check every constraint with `from_table == table` against `columns`:
+    /// if the insert supplies a value for `from_column`, that value must
+    /// already exist as a row primary key in the constraint's `to_table`.
+    /// Called before any operation in a commit is applied, so a missing
+    /// parent aborts the whole transaction rather than leaving an orphaned
+    /// child row for a later cascade to trip over.
+    fn validate_foreign_keys(
+        table: &str,
+        columns: &HashMap<String, String>,
+        tables: &HashMap<String, super::crr::LegacyCrrTable>,
+        fk_manager: &super::foreign_keys::ForeignKeyManager,
+        pending_inserts: &HashMap<String, std::collections::HashSet<String>>,
+    ) -> Result<(), String> {
+        for fk in fk_manager.constraints.values() {
+            if fk.from_table != table {
+                continue;
+            }
+            let Some(referenced_pk) = columns.get(&fk.from_column) else { continue };
+            let parent_exists = tables.get(&fk.to_table)
+                .map(|parent| parent.rows.get(referenced_pk).map(|row| !row.deleted).unwrap_or(false))
+                .unwrap_or(false)
+                || pending_inserts.get(&fk.to_table).map(|pks| pks.contains(referenced_pk)).unwrap_or(false);
+            if !parent_exists {
+                return Err(format!(
+                    "cannot insert {}:{} — {} does not exist in {}",
+                    table, fk.from_column, referenced_pk, fk.to_table
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Every `(from_table, from_pk, from_column)` whose value currently
+    /// references `pk` in `table`.
+    fn referencing_rows(
+        table: &str,
+        pk: &str,
+        tables: &HashMap<String, super::crr::LegacyCrrTable>,
+        fk_manager: &super::foreign_keys::ForeignKeyManager,
+    ) -> Vec<(String, String, String)> {
+        let mut found = Vec::new();
+        for fk in fk_manager.constraints.values() {
+            if fk.to_table != table {
+                continue;
+            }
+            let Some(from_table) = tables.get(&fk.from_table) else { continue };
+            for row in from_table.rows.values() {
+                if row.columns.get(&fk.from_column).map(String::as_str) == Some(pk) {
+                    found.push((fk.from_table.clone(), row.pk.clone(), fk.from_column.clone()));
+                }
+            }
+        }
+        found
+    }
+
+    /// Expand deleting `pk` from `table` into the full set of `FkAction`s it
+    /// requires — itself, plus whatever `Cascade`/`SetNull` constraints
+    /// pull in recursively — or `Err` the moment a `Restrict`/`NoAction`
+    /// constraint is hit. `visited` is shared across every delete in the
+    /// same commit so a row already queued for deletion (including by an
+    /// earlier cascade) is skipped instead of re-expanded, which both
+    /// avoids duplicate actions and breaks reference cycles.
+    fn expand_delete(
+        table: &str,
+        pk: &str,
+        tables: &HashMap<String, super::crr::LegacyCrrTable>,
+        fk_manager: &super::foreign_keys::ForeignKeyManager,
+        visited: &mut std::collections::HashSet<(String, String)>,
+        actions: &mut Vec<FkAction>,
+    ) -> Result<(), String> {
+        if !visited.insert((table.to_string(), pk.to_string())) {
+            return Ok(());
+        }
+        actions.push(FkAction::Delete { table: table.to_string(), pk: pk.to_string() });
+
+        for (from_table, from_pk, from_column) in referencing_rows(table, pk, tables, fk_manager) {
+            let on_delete = fk_manager.constraints.values()
+                .find(|fk| fk.from_table == from_table && fk.from_column == from_column && fk.to_table == table)
+                .map(|fk| fk.on_delete.clone())
+                .ok_or("foreign key constraint disappeared during commit")?;
+
+            match on_delete {
+                super::foreign_keys::OnDeleteAction::Cascade => {
+                    expand_delete(&from_table, &from_pk, tables, fk_manager, visited, actions)?;
+                }
+                super::foreign_keys::OnDeleteAction::SetNull => {
+                    actions.push(FkAction::SetNull { table: from_table, pk: from_pk, column: from_column });
+                }
+                super::foreign_keys::OnDeleteAction::Restrict | super::foreign_keys::OnDeleteAction::NoAction => {
+                    return Err(format!(
+                        "cannot delete {}:{} — referenced by {}:{} via {}",
+                        table, pk, from_table, from_pk, from_column
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply one validated `FkAction` to `tables`, recording it on `report`.
+    /// `version` is the committing transaction's version, the same one
+    /// every `Insert`/`Update` in this commit is stamped with.
+    fn apply_fk_action(
+        action: FkAction,
+        tables: &mut HashMap<String, super::crr::LegacyCrrTable>,
+        report: &mut CommitReport,
+        version: u64,
+    ) {
+        match action {
+            FkAction::Delete { table, pk } => {
+                // A tombstone via `delete_row`, not `rows.remove` — the
+                // latter is a purely local mutation a peer who hasn't seen
+                // this delete yet would never learn about, so the row would
+                // resurrect on its next merge. See `delete_row`'s own doc
+                // comment and `delete_row_cascading`, which this mirrors.
+                if let Some(crr_table) = tables.get_mut(&table) {
+                    crr_table.delete_row(&pk, version);
+                }
+                report.cascaded.push(TransactionOp::Delete { table, pk });
+            }
+            FkAction::SetNull { table, pk, column } => {
+                if let Some(crr_table) = tables.get_mut(&table) {
+                    // An empty-string write at this commit's version through
+                    // the normal `insert_or_update` path, not a
+                    // `columns`/`versions` removal — the latter is invisible
+                    // to `changeset`/`crr_merge` (which only serialize what's
+                    // currently present in those maps) and so never
+                    // replicates to a peer, the same bug class `delete_row`
+                    // exists to avoid for whole-row deletes.
+                    let mut columns = HashMap::new();
+                    columns.insert(column.clone(), String::new());
+                    let mut versions = HashMap::new();
+                    versions.insert(column.clone(), version);
+                    crr_table.insert_or_update(&pk, columns, versions);
+                }
+                let mut columns = HashMap::new();
+                columns.insert(column, String::new());
+                report.cascaded.push(TransactionOp::Update { table, pk, columns });
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::foreign_keys::{ForeignKey, ForeignKeyManager, OnDeleteAction};
+        use crate::merge::TieBreakPolicy;
+
+        fn customer_order_tables() -> HashMap<String, crate::crr::LegacyCrrTable> {
+            let mut customers = crate::crr::LegacyCrrTable::new();
+            let mut cols = HashMap::new();
+            let mut vers = HashMap::new();
+            cols.insert("name".to_string(), "Alice".to_string());
+            vers.insert("name".to_string(), 1);
+            customers.insert_or_update("c1", cols, vers);
+
+            let mut orders = crate::crr::LegacyCrrTable::new();
+            let mut cols = HashMap::new();
+            let mut vers = HashMap::new();
+            cols.insert("customer_id".to_string(), "c1".to_string());
+            vers.insert("customer_id".to_string(), 1);
+            orders.insert_or_update("o1", cols, vers);
+
+            let mut tables = HashMap::new();
+            tables.insert("customers".to_string(), customers);
+            tables.insert("orders".to_string(), orders);
+            tables
+        }
+
+        #[test]
+        fn set_null_cascade_writes_a_replicating_version_instead_of_removing_the_column() {
+            let mut tables = customer_order_tables();
+            let mut fk_manager = ForeignKeyManager::new();
+            fk_manager.add_constraint(ForeignKey {
+                name: "orders_customer_fk".to_string(),
+                from_table: "orders".to_string(),
+                from_column: "customer_id".to_string(),
+                to_table: "customers".to_string(),
+                to_column: String::new(),
+                on_delete: OnDeleteAction::SetNull,
+            });
+
+            let mut tx_manager = TransactionManager::new();
+            let tx_id = tx_manager.begin(&tables);
+            tx_manager.add_operation(&tx_id, TransactionOp::Delete {
+                table: "customers".to_string(),
+                pk: "c1".to_string(),
+            }).unwrap();
+            tx_manager.commit(&tx_id, &mut tables, &fk_manager).unwrap();
+
+            let orders = &tables["orders"];
+            let row = orders.rows.get("o1").expect("o1 survives a set-null cascade");
+            assert_eq!(row.columns.get("customer_id"), Some(&String::new()));
+            assert!(row.versions.contains_key("customer_id"), "set-null must bump the column's version, not remove it");
+
+            // The fix this test guards: `changeset`/`crr_merge` only see
+            // whatever's currently in `columns`/`versions`, so a set-null
+            // that removed the key instead of writing a new version would
+            // be invisible here and never reach a peer.
+            let changeset = orders.changeset();
+            let (cols, _) = &changeset["o1"];
+            assert_eq!(cols.get("customer_id"), Some(&String::new()));
+        }
+
+        #[test]
+        fn set_null_cascade_converges_instead_of_leaving_a_peer_with_the_stale_value() {
+            let mut tables = customer_order_tables();
+            let mut fk_manager = ForeignKeyManager::new();
+            fk_manager.add_constraint(ForeignKey {
+                name: "orders_customer_fk".to_string(),
+                from_table: "orders".to_string(),
+                from_column: "customer_id".to_string(),
+                to_table: "customers".to_string(),
+                to_column: String::new(),
+                on_delete: OnDeleteAction::SetNull,
+            });
+
+            let mut tx_manager = TransactionManager::new();
+            let tx_id = tx_manager.begin(&tables);
+            tx_manager.add_operation(&tx_id, TransactionOp::Delete {
+                table: "customers".to_string(),
+                pk: "c1".to_string(),
+            }).unwrap();
+            tx_manager.commit(&tx_id, &mut tables, &fk_manager).unwrap();
+
+            // A peer that hasn't seen the cascade yet, still holding the
+            // stale `customer_id = "c1"` at the pre-cascade version.
+            let mut peer_orders = crate::crr::LegacyCrrTable::new();
+            let mut cols = HashMap::new();
+            let mut vers = HashMap::new();
+            cols.insert("customer_id".to_string(), "c1".to_string());
+            vers.insert("customer_id".to_string(), 1);
+            peer_orders.insert_or_update("o1", cols, vers);
+
+            peer_orders.crr_merge(&tables["orders"].changeset(), TieBreakPolicy::LastWriteWins);
+
+            let row = peer_orders.rows.get("o1").unwrap();
+            assert_eq!(row.columns.get("customer_id"), Some(&String::new()), "the cascade must win over the peer's stale reference, not get silently dropped");
+        }
+    }
+}
+
+// Legacy delta_sync module
+pub mod delta_sync {
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct VectorClock {
+        pub clocks: HashMap<String, u64>,
+    }
+
+    impl VectorClock {
+        pub fn new() -> Self {
+            Self { clocks: HashMap::new() }
+        }
+
+        pub fn update(&mut self, peer_id: &str, version: u64) {
+            let current = self.clocks.entry(peer_id.to_string()).or_insert(0);
+            if version > *current {
+                *current = version;
+            }
+        }
+
+        pub fn get(&self, peer_id: &str) -> u64 {
+            *self.clocks.get(peer_id).unwrap_or(&0)
+        }
+    }
+
+    /// A single logged write, tagged with the peer that made it and that
+    /// peer's per-replica sequence number, so a [`VectorClock`] can tell
+    /// whether a receiver has already seen it.
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct ChangelogEntry {
+        pub peer_id: String,
+        pub seq: u64,
+        pub pk: String,
+        pub column: String,
+        pub value: String,
+    }
+
+    /// The entries a `changes_since` query found missing, alongside the
+    /// sender's clock at the time it answered — so once the receiver has
+    /// applied every entry, merging `from_clock` into its own clock catches
+    /// it up even past entries the sender had no changelog row for (e.g.
+    /// ones GC'd out of the log already).
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct DeltaBatch {
+        pub from_clock: VectorClock,
+        pub entries: Vec<ChangelogEntry>,
+    }
+
+    #[derive(Clone)]
+    pub struct DeltaTracker {
+        pub changelog: HashMap<u64, ChangelogEntry>,
+        pub next_seq: u64,
+        pub vector_clock: VectorClock,
+        pub peer_id: String,
+    }
+
+    impl DeltaTracker {
+        pub fn new(peer_id: String) -> Self {
+            Self {
+                changelog: HashMap::new(),
+                next_seq: 1,
+                vector_clock: VectorClock::new(),
+                peer_id,
+            }
+        }
+
+        pub fn record_change(&mut self, pk: &str, column: &str, value: &str) -> u64 {
+            let seq = self.next_seq;
+            self.changelog.insert(seq, ChangelogEntry {
+                peer_id: self.peer_id.clone(),
+                seq,
+                pk: pk.to_string(),
+                column: column.to_string(),
+                value: value.to_string(),
+            });
+            self.vector_clock.update(&self.peer_id, seq);
+            self.next_seq += 1;
+            seq
+        }
+
+        /// A since-vector query over the changelog: every entry this
+        /// tracker recorded that `since` hasn't seen yet, i.e. whose `seq`
+        /// exceeds `since`'s counter for its `peer_id`.
+        pub fn changes_since(&self, since: &VectorClock) -> DeltaBatch {
+            let mut entries: Vec<ChangelogEntry> = self.changelog.values()
+                .filter(|e| e.seq > since.get(&e.peer_id))
+                .cloned()
+                .collect();
+            entries.sort_by_key(|e| e.seq);
+
+            DeltaBatch { from_clock: self.vector_clock.clone(), entries }
+        }
+
+        /// Apply a batch received from a peer into `table`, advancing this
+        /// tracker's own vector clock past every entry as it lands so a
+        /// later `changes_since` call against the same peer only ships
+        /// whatever is still missing.
+        pub fn apply_batch(&mut self, batch: &DeltaBatch, table: &mut crate::crr::LegacyCrrTable) {
+            for entry in &batch.entries {
+                let mut columns = HashMap::new();
+                let mut versions = HashMap::new();
+                columns.insert(entry.column.clone(), entry.value.clone());
+                versions.insert(entry.column.clone(), entry.seq);
+                table.insert_or_update(&entry.pk, columns, versions);
+
+                self.vector_clock.update(&entry.peer_id, entry.seq);
+            }
+            for (peer_id, seq) in &batch.from_clock.clocks {
+                self.vector_clock.update(peer_id, *seq);
+            }
+        }
+    }
+}
+
+// Legacy durable-storage backends for SyncEngine
+pub mod backend {
+    //! Pluggable durability for [`super::SyncEngine`]'s in-memory
+    //! [`super::crr::LegacyCrrTable`] — lets a peer survive a process
+    //! restart instead of losing every row the moment it drops, mirroring
+    //! the durability [`crate::storage::SqliteStorage`]/
+    //! [`crate::storage::LmdbStorage`] already give the newer
+    //! [`crate::CrrTable`]. Object-safe so [`super::SyncEngine::with_backend`]
+    //! can hold one behind `Box<dyn StorageBackend>` without committing to a
+    //! single backend type at compile time.
+
+    use super::crr::{CrrRow, LegacyCrrTable};
+    use crate::dag::VersionDag;
+    use crate::error::Result;
+    #[cfg(feature = "lmdb-backend")]
+    use crate::error::Error;
+    use std::collections::HashMap;
+
+    pub trait StorageBackend {
+        /// Rebuild a [`LegacyCrrTable`] from whatever this backend already
+        /// has persisted — empty if this backend has never been written to.
+        fn load_table(&self) -> Result<LegacyCrrTable>;
+        /// Persist `row`'s full current column/version snapshot under `pk`.
+        fn persist_row(&mut self, pk: &str, row: &CrrRow) -> Result<()>;
+        /// Persist a single column write without re-serializing the rest of
+        /// the row — the incremental counterpart to [`Self::persist_row`].
+        fn persist_version(&mut self, pk: &str, col: &str, value: &str, version: u64) -> Result<()>;
+
+        /// Persist `row` under `table`'s own keyspace rather than the
+        /// default table [`Self::load_table`]/[`Self::persist_row`] cover —
+        /// see [`super::SyncEngine::insert_or_update_table`]. Defaults to a
+        /// no-op so a backend that hasn't opted into named-table durability
+        /// still compiles.
+        fn persist_table_row(&mut self, _table: &str, _pk: &str, _row: &CrrRow) -> Result<()> {
+            Ok(())
+        }
+
+        /// Rebuild every named table this backend has persisted via
+        /// [`Self::persist_table_row`], keyed by table name — empty by
+        /// default, the named-table counterpart to [`Self::load_table`].
+        fn load_tables(&self) -> Result<HashMap<String, LegacyCrrTable>> {
+            Ok(HashMap::new())
+        }
+    }
+
+    /// SQLite-backed [`StorageBackend`]: a single `(tbl, pk, col)`-keyed
+    /// table holding each column's latest value and version, `tbl` empty for
+    /// [`Self::load_table`]/[`Self::persist_row`]'s default table and the
+    /// owning table's name for [`Self::load_tables`]/[`Self::persist_table_row`]'s
+    /// named ones. Unlike [`crate::storage::SqliteStorage`], this doesn't
+    /// persist DAG history — a reload seeds each column as a single-node DAG
+    /// rooted at its persisted version, since the legacy engine's conflict
+    /// resolution only ever compares current `(value, version)` pairs, never
+    /// history.
+    pub struct SqliteBackend {
+        conn: rusqlite::Connection,
+    }
+
+    impl SqliteBackend {
+        pub fn open(path: &str) -> Result<Self> {
+            let conn = if path == ":memory:" {
+                rusqlite::Connection::open_in_memory()?
+            } else {
+                rusqlite::Connection::open(path)?
+            };
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS legacy_cells (
+                    tbl TEXT NOT NULL DEFAULT '',
+                    pk TEXT NOT NULL,
+                    col TEXT NOT NULL,
+                    value TEXT NOT NULL,
+                    version INTEGER NOT NULL,
+                    PRIMARY KEY (tbl, pk, col)
+                );",
+            )?;
+            Ok(Self { conn })
+        }
+
+        fn persist_cell(&mut self, tbl: &str, pk: &str, col: &str, value: &str, version: u64) -> Result<()> {
+            self.conn.execute(
+                "INSERT INTO legacy_cells (tbl, pk, col, value, version) VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(tbl, pk, col) DO UPDATE SET value = excluded.value, version = excluded.version",
+                rusqlite::params![tbl, pk, col, value, version],
+            )?;
+            Ok(())
+        }
+
+        fn load_rows(&self, tbl: &str) -> Result<LegacyCrrTable> {
+            let mut stmt = self.conn.prepare("SELECT pk, col, value, version FROM legacy_cells WHERE tbl = ?1")?;
+            let rows = stmt.query_map([tbl], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, u64>(3)?,
+                ))
+            })?;
+
+            let mut table = LegacyCrrTable::new();
+            for row in rows {
+                let (pk, col, value, version) = row?;
+                let entry = table.rows.entry(pk.clone()).or_insert_with(|| CrrRow {
+                    pk: pk.clone(),
+                    columns: HashMap::new(),
+                    versions: HashMap::new(),
+                    dags: HashMap::new(),
+                    crdts: HashMap::new(),
+                    deleted: false,
+                });
+                let mut dag = VersionDag::new();
+                dag.add_node(version, value.clone(), vec![]);
+                entry.columns.insert(col.clone(), value);
+                entry.versions.insert(col.clone(), version);
+                entry.dags.insert(col, dag);
+            }
+            Ok(table)
+        }
+    }
+
+    impl StorageBackend for SqliteBackend {
+        fn load_table(&self) -> Result<LegacyCrrTable> {
+            self.load_rows("")
+        }
+
+        fn persist_row(&mut self, pk: &str, row: &CrrRow) -> Result<()> {
+            for (col, value) in &row.columns {
+                let version = row.versions.get(col).copied().unwrap_or(1);
+                self.persist_version(pk, col, value, version)?;
+            }
+            Ok(())
+        }
+
+        fn persist_version(&mut self, pk: &str, col: &str, value: &str, version: u64) -> Result<()> {
+            self.persist_cell("", pk, col, value, version)
+        }
+
+        fn persist_table_row(&mut self, table: &str, pk: &str, row: &CrrRow) -> Result<()> {
+            for (col, value) in &row.columns {
+                let version = row.versions.get(col).copied().unwrap_or(1);
+                self.persist_cell(table, pk, col, value, version)?;
+            }
+            Ok(())
+        }
+
+        fn load_tables(&self) -> Result<HashMap<String, LegacyCrrTable>> {
+            let mut stmt = self.conn.prepare("SELECT DISTINCT tbl FROM legacy_cells WHERE tbl != ''")?;
+            let names = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            let mut tables = HashMap::new();
+            for name in names {
+                let name = name?;
+                let table = self.load_rows(&name)?;
+                tables.insert(name, table);
+            }
+            Ok(tables)
+        }
+    }
+
+    /// LMDB-backed [`StorageBackend`] (via the `heed` crate), feature-gated
+    /// the same as [`crate::storage::LmdbStorage`]. Stores one row per `pk`,
+    /// wire-encoded as its column/version pairs, rather than
+    /// [`SqliteBackend`]'s one-row-per-cell layout — LMDB has no secondary
+    /// index to group scattered cell rows back by `pk` cheaply, so keeping a
+    /// whole row in one value makes `load_table` a single sequential scan.
+    #[cfg(feature = "lmdb-backend")]
+    pub struct LmdbBackend {
+        env: heed::Env,
+        rows: heed::Database<heed::types::Bytes, heed::types::Bytes>,
+    }
+
+    #[cfg(feature = "lmdb-backend")]
+    impl LmdbBackend {
+        pub fn open(path: &str, map_size_bytes: usize) -> Result<Self> {
+            std::fs::create_dir_all(path)?;
+            // Safety: `map_size_bytes` must stay fixed for the life of this
+            // environment, which it does — `LmdbBackend` never reopens it.
+            let env = unsafe {
+                heed::EnvOpenOptions::new()
+                    .map_size(map_size_bytes)
+                    .max_dbs(1)
+                    .open(path)
+            }
+            .map_err(|e| Error::Storage(e.to_string()))?;
+
+            let mut wtxn = env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+            let rows = env
+                .create_database(&mut wtxn, Some("legacy_rows"))
+                .map_err(|e| Error::Storage(e.to_string()))?;
+            wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+
+            Ok(Self { env, rows })
+        }
+
+        fn encode_row(row: &CrrRow) -> Vec<u8> {
+            use crate::wire::{write_bytes, write_u32};
+            let mut buf = Vec::new();
+            write_u32(&mut buf, row.columns.len() as u32);
+            for (col, value) in &row.columns {
+                let version = row.versions.get(col).copied().unwrap_or(1);
+                write_bytes(&mut buf, col.as_bytes());
+                write_bytes(&mut buf, value.as_bytes());
+                buf.extend_from_slice(&version.to_le_bytes());
+            }
+            buf
+        }
+
+        fn decode_row(pk: &str, bytes: &[u8]) -> Result<CrrRow> {
+            use crate::wire::{read_bytes, read_u32, read_u64};
+            let mut cursor = 0usize;
+            let count = read_u32(bytes, &mut cursor)?;
+            let mut row = CrrRow {
+                pk: pk.to_string(),
+                columns: HashMap::new(),
+                versions: HashMap::new(),
+                dags: HashMap::new(),
+                crdts: HashMap::new(),
+                deleted: false,
+            };
+            for _ in 0..count {
+                let col = String::from_utf8(read_bytes(bytes, &mut cursor)?)
+                    .map_err(|e| Error::InvalidState(e.to_string()))?;
+                let value = String::from_utf8(read_bytes(bytes, &mut cursor)?)
+                    .map_err(|e| Error::InvalidState(e.to_string()))?;
+                let version = read_u64(bytes, &mut cursor)?;
+                let mut dag = VersionDag::new();
+                dag.add_node(version, value.clone(), vec![]);
+                row.columns.insert(col.clone(), value);
+                row.versions.insert(col.clone(), version);
+                row.dags.insert(col, dag);
+            }
+            Ok(row)
+        }
+    }
+
+    /// Byte that can't appear in a table name, separating it from the pk in
+    /// a named-table key — `"{table}\0{pk}"` — so [`LmdbBackend`]'s single
+    /// `legacy_rows` database can hold the default table and every named
+    /// table's rows side by side without a second `heed::Database`.
+    #[cfg(feature = "lmdb-backend")]
+    const LMDB_TABLE_KEY_SEPARATOR: u8 = 0;
+
+    #[cfg(feature = "lmdb-backend")]
+    impl StorageBackend for LmdbBackend {
+        fn load_table(&self) -> Result<LegacyCrrTable> {
+            let rtxn = self.env.read_txn().map_err(|e| Error::Storage(e.to_string()))?;
+            let mut table = LegacyCrrTable::new();
+            for entry in self.rows.iter(&rtxn).map_err(|e| Error::Storage(e.to_string()))? {
+                let (key, value) = entry.map_err(|e| Error::Storage(e.to_string()))?;
+                if key.contains(&LMDB_TABLE_KEY_SEPARATOR) {
+                    continue;
+                }
+                let pk = String::from_utf8(key.to_vec()).map_err(|e| Error::InvalidState(e.to_string()))?;
+                let row = Self::decode_row(&pk, value)?;
+                table.rows.insert(pk, row);
+            }
+            Ok(table)
+        }
+
+        fn persist_row(&mut self, pk: &str, row: &CrrRow) -> Result<()> {
+            let mut wtxn = self.env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+            self.rows
+                .put(&mut wtxn, pk.as_bytes(), &Self::encode_row(row))
+                .map_err(|e| Error::Storage(e.to_string()))?;
+            wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+            Ok(())
+        }
+
+        fn persist_version(&mut self, pk: &str, col: &str, value: &str, version: u64) -> Result<()> {
+            // There's no per-cell storage here to update in place, so fold
+            // this one column into whatever's already persisted for `pk`
+            // rather than overwriting the rest of the row.
+            let mut wtxn = self.env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+            let mut row = match self.rows.get(&wtxn, pk.as_bytes()).map_err(|e| Error::Storage(e.to_string()))? {
+                Some(bytes) => Self::decode_row(pk, bytes)?,
+                None => CrrRow {
+                    pk: pk.to_string(),
+                    columns: HashMap::new(),
+                    versions: HashMap::new(),
+                    dags: HashMap::new(),
+                    crdts: HashMap::new(),
+                    deleted: false,
+                },
+            };
+            row.columns.insert(col.to_string(), value.to_string());
+            row.versions.insert(col.to_string(), version);
+            self.rows
+                .put(&mut wtxn, pk.as_bytes(), &Self::encode_row(&row))
+                .map_err(|e| Error::Storage(e.to_string()))?;
+            wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+            Ok(())
+        }
+
+        fn persist_table_row(&mut self, table: &str, pk: &str, row: &CrrRow) -> Result<()> {
+            let mut key = table.as_bytes().to_vec();
+            key.push(LMDB_TABLE_KEY_SEPARATOR);
+            key.extend_from_slice(pk.as_bytes());
+            let mut wtxn = self.env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+            self.rows
+                .put(&mut wtxn, &key, &Self::encode_row(row))
+                .map_err(|e| Error::Storage(e.to_string()))?;
+            wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+            Ok(())
+        }
+
+        fn load_tables(&self) -> Result<HashMap<String, LegacyCrrTable>> {
+            let rtxn = self.env.read_txn().map_err(|e| Error::Storage(e.to_string()))?;
+            let mut tables: HashMap<String, LegacyCrrTable> = HashMap::new();
+            for entry in self.rows.iter(&rtxn).map_err(|e| Error::Storage(e.to_string()))? {
+                let (key, value) = entry.map_err(|e| Error::Storage(e.to_string()))?;
+                let Some(sep) = key.iter().position(|&b| b == LMDB_TABLE_KEY_SEPARATOR) else { continue };
+                let table_name = String::from_utf8(key[..sep].to_vec()).map_err(|e| Error::InvalidState(e.to_string()))?;
+                let pk = String::from_utf8(key[sep + 1..].to_vec()).map_err(|e| Error::InvalidState(e.to_string()))?;
+                let row = Self::decode_row(&pk, value)?;
+                tables.entry(table_name).or_insert_with(LegacyCrrTable::new).rows.insert(pk, row);
+            }
+            Ok(tables)
+        }
+    }
+}
+
+/// Real networked sync for [`SyncEngine`] — the legacy-engine counterpart
+/// to [`crate::sync::SyncSession::sync_over`], framing
+/// [`super::crr::LegacyChangeset`] the same length-prefixed-CBOR way that
+/// function frames the new engine's [`crate::sync::Changeset`], instead of
+/// the `network_delay_ms`/`packet_loss_rate` simulation
+/// [`super::SyncEngine`]'s demo callers used before this existed.
+pub mod transport {
+    use super::crr::LegacyChangeset;
+    use crate::error::Result;
+    use crate::sync::{from_cbor, read_frame, to_cbor, write_frame};
+    use std::net::{TcpListener, TcpStream};
+
+    /// Sends and receives one [`LegacyChangeset`] over some duplex channel.
+    /// Object-safe so [`super::SyncEngine::sync_via`] can take
+    /// `&mut dyn SyncTransport` without committing to a real socket versus
+    /// an in-process handoff at compile time.
+    pub trait SyncTransport {
+        fn send_changeset(&mut self, changeset: &LegacyChangeset) -> Result<()>;
+        fn recv_changeset(&mut self) -> Result<LegacyChangeset>;
+    }
+
+    /// A real TCP connection to another process, framed exactly like
+    /// [`crate::sync::SyncSession::sync_over`] frames the new engine's
+    /// changesets: a little-endian `u32` length prefix followed by a
+    /// CBOR-encoded body.
+    pub struct TcpTransport {
+        stream: TcpStream,
+    }
+
+    impl TcpTransport {
+        /// Block until a single peer connects on `addr`.
+        pub fn listen(addr: &str) -> Result<Self> {
+            let listener = TcpListener::bind(addr)?;
+            let (stream, _) = listener.accept()?;
+            Ok(Self { stream })
+        }
+
+        /// Connect to a peer already listening on `addr`.
+        pub fn connect(addr: &str) -> Result<Self> {
+            Ok(Self { stream: TcpStream::connect(addr)? })
+        }
+    }
+
+    impl SyncTransport for TcpTransport {
+        fn send_changeset(&mut self, changeset: &LegacyChangeset) -> Result<()> {
+            write_frame(&mut self.stream, &to_cbor(changeset)?)
+        }
+
+        fn recv_changeset(&mut self) -> Result<LegacyChangeset> {
+            from_cbor(&read_frame(&mut self.stream)?)
+        }
+    }
+
+    /// An in-process stand-in for [`TcpTransport`] that hands a changeset
+    /// straight to the other side instead of crossing a socket — the
+    /// "optional in-process transport" that keeps the simulated-latency
+    /// demo scenarios working against the same [`SyncTransport`] interface
+    /// as a real connection, rather than a separate code path.
+    pub struct LoopbackTransport {
+        outgoing: LegacyChangeset,
+        incoming: LegacyChangeset,
+    }
+
+    impl LoopbackTransport {
+        pub fn new(incoming: LegacyChangeset) -> Self {
+            Self { outgoing: LegacyChangeset::new(), incoming }
+        }
+
+        /// What a prior [`SyncTransport::send_changeset`] call handed this
+        /// transport — the half a caller wiring two `LoopbackTransport`s
+        /// together needs to hand to the other side.
+        pub fn sent(&self) -> &LegacyChangeset {
+            &self.outgoing
+        }
+    }
+
+    impl SyncTransport for LoopbackTransport {
+        fn send_changeset(&mut self, changeset: &LegacyChangeset) -> Result<()> {
+            self.outgoing = changeset.clone();
+            Ok(())
+        }
+
+        fn recv_changeset(&mut self) -> Result<LegacyChangeset> {
+            Ok(self.incoming.clone())
+        }
+    }
+}
+
+/// Structured observability for [`SyncEngine`]'s legacy merge path — the
+/// counterpart to [`crate::metrics::Metrics`], which already covers the new
+/// engine, built on the same [`crate::metrics::Counter`]/
+/// [`crate::metrics::Histogram`] primitives so both render as the same
+/// Prometheus text exposition format.
+pub mod sync_metrics {
+    use crate::crr::{LegacyChangeset, LegacyMergeReport, TieBreakPolicy};
+    use crate::metrics::{Counter, Histogram, LatencyHistogram};
+    use std::time::Duration;
+
+    /// Bucket boundaries (bytes) for `changeset_bytes` — wide enough to
+    /// cover a single-row update through a full `StressTest`-sized batch.
+    fn byte_size_buckets() -> Vec<f64> {
+        vec![64.0, 256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0]
+    }
+
+    /// A [`Histogram`] pre-configured with [`byte_size_buckets`].
+    #[derive(Debug)]
+    pub struct ByteSizeHistogram(Histogram);
+
+    impl Default for ByteSizeHistogram {
+        fn default() -> Self {
+            Self(Histogram::new(byte_size_buckets()))
+        }
+    }
+
+    impl ByteSizeHistogram {
+        pub fn observe(&self, bytes: usize) {
+            self.0.observe(bytes as f64);
+        }
+    }
+
+    /// One counter per [`TieBreakPolicy`] variant, incremented whenever
+    /// [`SyncMetrics::record_merge`] sees that policy resolve an
+    /// equal-version conflict.
+    #[derive(Debug, Default)]
+    pub struct TieBreakCounters {
+        pub prefer_existing: Counter,
+        pub prefer_incoming: Counter,
+        pub lexicographic_min: Counter,
+        pub multi_value: Counter,
+        pub last_write_wins: Counter,
+    }
+
+    impl TieBreakCounters {
+        fn counter_for(&self, policy: TieBreakPolicy) -> &Counter {
+            match policy {
+                TieBreakPolicy::PreferExisting => &self.prefer_existing,
+                TieBreakPolicy::PreferIncoming => &self.prefer_incoming,
+                TieBreakPolicy::LexicographicMin => &self.lexicographic_min,
+                TieBreakPolicy::MultiValue => &self.multi_value,
+                TieBreakPolicy::LastWriteWins => &self.last_write_wins,
+            }
+        }
+    }
+
+    /// Observability registry for [`super::SyncEngine`]'s legacy merge
+    /// path: rows merged, conflicts detected, tiebreaks applied per
+    /// [`TieBreakPolicy`], changeset byte sizes, and time-to-convergence.
+    /// Held behind an `Arc` (see [`super::SyncEngine::metrics`]) rather than
+    /// owned directly, the same reason [`crate::table::CrrTable::attach_metrics`]
+    /// takes `Arc<crate::metrics::Metrics>` — a `Counter`'s inner `Mutex`
+    /// can't be cloned, and sharing one registry across a cloned engine
+    /// keeps its counts meaningful instead of silently resetting them.
+    #[derive(Debug, Default)]
+    pub struct SyncMetrics {
+        pub rows_merged: Counter,
+        pub conflicts_detected: Counter,
+        pub tiebreaks: TieBreakCounters,
+        pub changeset_bytes: ByteSizeHistogram,
+        pub convergence_seconds: LatencyHistogram,
+    }
+
+    impl SyncMetrics {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Fold a [`super::crr::LegacyCrrTable::crr_merge`] call's outcome
+        /// into the row/conflict counters, the tiebreak counter for
+        /// `policy`, and `convergence_seconds`.
+        pub fn record_merge(&self, report: &LegacyMergeReport, policy: TieBreakPolicy, elapsed: Duration) {
+            self.rows_merged.add((report.inserted.len() + report.updated.len()) as u64);
+            self.conflicts_detected.add(report.conflicts_equal_version.len() as u64);
+            if !report.conflicts_equal_version.is_empty() {
+                self.tiebreaks.counter_for(policy).add(report.conflicts_equal_version.len() as u64);
+            }
+            self.convergence_seconds.observe(elapsed);
+        }
+
+        /// Record the wire size of a changeset about to be sent or just
+        /// received, estimated the same way [`crate::sync::Changeset::estimate_bytes`]
+        /// does for the new engine: every pk/column/value's byte length plus
+        /// 8 bytes per column for its version, ignoring per-field framing
+        /// overhead.
+        pub fn record_changeset_bytes(&self, changeset: &LegacyChangeset) {
+            let bytes: usize = changeset
+                .iter()
+                .map(|(pk, (cols, _))| pk.len() + cols.iter().map(|(k, v)| k.len() + v.len() + 8).sum::<usize>())
+                .sum();
+            self.changeset_bytes.observe(bytes);
+        }
+
+        /// Render every metric as a Prometheus text exposition format body —
+        /// the legacy-engine counterpart to [`crate::metrics::Metrics::render`].
+        pub fn render(&self) -> String {
+            let mut out = String::new();
+            self.rows_merged.render("crr_sync_rows_merged_total", "Rows touched by SyncEngine merges.", &mut out);
+            self.conflicts_detected.render("crr_sync_conflicts_total", "Equal-version conflicts detected by SyncEngine merges.", &mut out);
+
+            use std::fmt::Write as _;
+            writeln!(out, "# HELP crr_sync_tiebreaks_total Equal-version conflicts resolved by SyncEngine merges, by policy.").unwrap();
+            writeln!(out, "# TYPE crr_sync_tiebreaks_total counter").unwrap();
+            writeln!(out, "crr_sync_tiebreaks_total{{policy=\"prefer_existing\"}} {}", self.tiebreaks.prefer_existing.get()).unwrap();
+            writeln!(out, "crr_sync_tiebreaks_total{{policy=\"prefer_incoming\"}} {}", self.tiebreaks.prefer_incoming.get()).unwrap();
+            writeln!(out, "crr_sync_tiebreaks_total{{policy=\"lexicographic_min\"}} {}", self.tiebreaks.lexicographic_min.get()).unwrap();
+            writeln!(out, "crr_sync_tiebreaks_total{{policy=\"multi_value\"}} {}", self.tiebreaks.multi_value.get()).unwrap();
+            writeln!(out, "crr_sync_tiebreaks_total{{policy=\"last_write_wins\"}} {}", self.tiebreaks.last_write_wins.get()).unwrap();
+
+            self.changeset_bytes.0.render("crr_sync_changeset_bytes", "Size of changesets exchanged between peers.", &mut out);
+            self.convergence_seconds.0.render("crr_sync_convergence_seconds", "Time from merge start to a completed SyncEngine merge.", &mut out);
+            out
+        }
+
+        /// Number of merges folded into `convergence_seconds` so far.
+        pub fn convergence_count(&self) -> u64 {
+            self.convergence_seconds.0.count()
+        }
+
+        /// Mean merge latency across every `convergence_seconds` observation,
+        /// or `0.0` before the first merge.
+        pub fn convergence_avg_seconds(&self) -> f64 {
+            let count = self.convergence_seconds.0.count();
+            if count == 0 {
+                0.0
+            } else {
+                self.convergence_seconds.0.sum() / count as f64
+            }
+        }
+    }
+}
+
+// Legacy SyncEngine for UI compatibility
+pub struct SyncEngine {
+    pub crr_table: crr::LegacyCrrTable,
+    pub tables: std::collections::HashMap<String, crr::LegacyCrrTable>,
+    pub schema_manager: schema::SchemaManager,
+    pub fk_manager: foreign_keys::ForeignKeyManager,
+    pub tx_manager: transactions::TransactionManager,
+    pub delta_tracker: delta_sync::DeltaTracker,
+    pub backend: Option<Box<dyn backend::StorageBackend>>,
+    /// Rows merged, conflicts, tiebreaks, changeset sizes, and
+    /// time-to-convergence for this engine's merges — see
+    /// [`sync_metrics::SyncMetrics`]. Shared via `Arc` rather than owned
+    /// directly so cloning a `SyncEngine` (e.g. `ProfessionalDemo`'s
+    /// per-step bookkeeping) keeps counting into the same registry instead
+    /// of starting a fresh one.
+    pub metrics: std::sync::Arc<sync_metrics::SyncMetrics>,
+    /// Outstanding [`dag::VersionDag`] gaps and which peers have
+    /// advertised holding them — see [`version_requests::VersionRequestQueue`]
+    /// and [`Self::request_missing_versions`].
+    pub version_requests: version_requests::VersionRequestQueue,
+}
+
+impl Clone for SyncEngine {
+    /// Structural clone only: `backend` becomes `None`, since a boxed trait
+    /// object can't be assumed `Clone`. Every other field is duplicated
+    /// normally, same as the `#[derive(Clone)]` this replaces.
+    fn clone(&self) -> Self {
+        Self {
+            crr_table: self.crr_table.clone(),
+            tables: self.tables.clone(),
+            schema_manager: self.schema_manager.clone(),
+            fk_manager: self.fk_manager.clone(),
+            tx_manager: self.tx_manager.clone(),
+            delta_tracker: self.delta_tracker.clone(),
+            backend: None,
+            metrics: self.metrics.clone(),
+            version_requests: self.version_requests.clone(),
+        }
+    }
+}
+
+impl Default for SyncEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncEngine {
+    pub fn new() -> Self {
+        Self::new_with_peer_id("default_peer".to_string())
+    }
+
+    pub fn new_with_peer_id(peer_id: String) -> Self {
+        Self {
+            crr_table: crr::LegacyCrrTable::new(),
+            tables: std::collections::HashMap::new(),
+            schema_manager: schema::SchemaManager::new_with_peer_id(peer_id.clone()),
+            fk_manager: foreign_keys::ForeignKeyManager::new(),
+            tx_manager: transactions::TransactionManager::new(),
+            delta_tracker: delta_sync::DeltaTracker::new(peer_id),
+            backend: None,
+            metrics: std::sync::Arc::new(sync_metrics::SyncMetrics::new()),
+            version_requests: version_requests::VersionRequestQueue::new(),
+        }
+    }
+
+    /// Like [`Self::new_with_peer_id`], but durable: the table is loaded
+    /// from `backend` up front, and every write [`Self::insert_or_update`]
+    /// makes is flushed through it as it happens — so dropping and rebuilding
+    /// a `SyncEngine` against the same backend (e.g. across a process
+    /// restart) picks the CRR state back up instead of starting empty.
+    pub fn with_backend(peer_id: String, backend: Box<dyn backend::StorageBackend>) -> crate::error::Result<Self> {
+        let crr_table = backend.load_table()?;
+        let tables = backend.load_tables()?;
+        Ok(Self {
+            crr_table,
+            tables,
+            schema_manager: schema::SchemaManager::new_with_peer_id(peer_id.clone()),
+            fk_manager: foreign_keys::ForeignKeyManager::new(),
+            tx_manager: transactions::TransactionManager::new(),
+            delta_tracker: delta_sync::DeltaTracker::new(peer_id),
+            backend: Some(backend),
+            metrics: std::sync::Arc::new(sync_metrics::SyncMetrics::new()),
+            version_requests: version_requests::VersionRequestQueue::new(),
+        })
+    }
+
+    pub fn get_table(&mut self, name: &str) -> &mut crr::LegacyCrrTable {
+        self.tables.entry(name.to_string()).or_insert_with(crr::LegacyCrrTable::new)
+    }
+
+    /// Tombstone `pk` in `table` (via [`crr::LegacyCrrTable::delete_row`]),
+    /// then cascade: for every [`foreign_keys::OnDeleteAction::Cascade`]
+    /// constraint in [`Self::fk_manager`] whose `to_table` is `table`,
+    /// tombstone every row in the constraint's `from_table` whose
+    /// `from_column` currently references `pk`, recursing into further
+    /// cascades the same way. Because each step is a tombstone rather than
+    /// a `rows.remove`, the whole cascade rides the normal changeset and
+    /// converges on every peer that syncs it — unlike a hand-rolled scan
+    /// that only mutates the local replica. Returns every `(table, pk)`
+    /// tombstoned, including the row passed in, for the caller to log.
+    pub fn delete_row_cascading(&mut self, table: &str, pk: &str) -> Vec<(String, String)> {
+        let mut tombstoned = Vec::new();
+        let mut visited = std::collections::HashSet::new();
+        let mut queue = vec![(table.to_string(), pk.to_string())];
+
+        while let Some((tbl, row_pk)) = queue.pop() {
+            if !visited.insert((tbl.clone(), row_pk.clone())) {
+                continue;
+            }
+            let Some(crr_table) = self.tables.get_mut(&tbl) else { continue };
+            let Some(row) = crr_table.rows.get(&row_pk) else { continue };
+            if row.deleted {
+                continue;
+            }
+            let version = row.versions.values().copied().max().unwrap_or(0) + 1;
+            crr_table.delete_row(&row_pk, version);
+            tombstoned.push((tbl.clone(), row_pk.clone()));
+
+            for fk in self.fk_manager.constraints.values() {
+                if fk.to_table != tbl || !matches!(fk.on_delete, foreign_keys::OnDeleteAction::Cascade) {
+                    continue;
+                }
+                let Some(child_table) = self.tables.get(&fk.from_table) else { continue };
+                for child_row in child_table.rows.values() {
+                    if !child_row.deleted
+                        && child_row.columns.get(&fk.from_column).map(String::as_str) == Some(row_pk.as_str())
+                    {
+                        queue.push((fk.from_table.clone(), child_row.pk.clone()));
+                    }
+                }
+            }
+        }
+
+        tombstoned
+    }
+
+    /// Apply `migration` to [`Self::schema_manager`], and if it's an
+    /// `AddColumn` declaring a [`schema::ColumnType::PnCounter`] column,
+    /// also [`crr::LegacyCrrTable::declare_crdt_column`] it on
+    /// [`Self::crr_table`]; if it's an `AddForeignKey`, also register it on
+    /// [`Self::fk_manager`] — the entry point a caller (e.g. the demo) should
+    /// use instead of calling `schema_manager.apply_migration` directly
+    /// whenever the migration might be declaring a CRDT column or a foreign
+    /// key, so the two stay in sync without the caller having to remember
+    /// the second step.
+    pub fn apply_schema_migration(&mut self, migration: schema::SchemaMigration) -> u64 {
+        if let schema::SchemaMigration::AddColumn { name, col_type: schema::ColumnType::PnCounter, .. } = &migration {
+            self.crr_table.declare_crdt_column(name, CrdtKind::PnCounter);
+        }
+        if let schema::SchemaMigration::AddForeignKey { table, column, references_table, on_delete } = &migration {
+            self.fk_manager.add_constraint(foreign_keys::ForeignKey {
+                name: format!("{}_{}_fk", table, column),
+                from_table: table.clone(),
+                from_column: column.clone(),
+                to_table: references_table.clone(),
+                to_column: String::new(),
+                on_delete: on_delete.clone(),
+            });
+        }
+        self.schema_manager.apply_migration(migration)
+    }
+
+    /// Merge `other` into [`Self::schema_manager`], then
+    /// [`crr::LegacyCrrTable::declare_crdt_column`] every newly-applied
+    /// `AddColumn` that declares a [`schema::ColumnType::PnCounter`] column —
+    /// the sync-time counterpart to [`Self::apply_schema_migration`], so a
+    /// peer that learns about a PN-Counter column via replicated schema
+    /// (rather than applying the migration itself) still merges that
+    /// column's writes as a CRDT instead of silently dropping them (see
+    /// [`crr::LegacyCrrTable::crdt_merge`]'s `crdt_columns` lookup).
+    pub fn merge_schema_from(&mut self, other: &schema::SchemaManager, policy: crr::TieBreakPolicy) -> schema::SchemaMergeReport {
+        let report = self.schema_manager.merge(other, policy);
+        for version in &report.applied {
+            let Some(staged) = self.schema_manager.migrations.iter().find(|m| &m.version == version) else { continue };
+            match &staged.migration {
+                schema::SchemaMigration::AddColumn { name, col_type: schema::ColumnType::PnCounter, .. } => {
+                    self.crr_table.declare_crdt_column(name, CrdtKind::PnCounter);
+                }
+                schema::SchemaMigration::AddForeignKey { table, column, references_table, on_delete } => {
+                    self.fk_manager.add_constraint(foreign_keys::ForeignKey {
+                        name: format!("{}_{}_fk", table, column),
+                        from_table: table.clone(),
+                        from_column: column.clone(),
+                        to_table: references_table.clone(),
+                        to_column: String::new(),
+                        on_delete: on_delete.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        report
+    }
+
+    /// Like [`crr::LegacyCrrTable::crr_merge`] called directly on
+    /// [`Self::crr_table`], but also folds the outcome into
+    /// [`Self::metrics`] — changeset size, rows merged, conflicts, the
+    /// tiebreak counter for `policy`, and time-to-convergence. The entry
+    /// point a caller (e.g. `ProfessionalDemo::sync_peers`) should use
+    /// instead of `self.crr_table.crr_merge(...)` directly whenever it
+    /// wants those merges to show up in [`Self::metrics_snapshot`].
+    pub fn crr_merge_recorded(
+        &mut self,
+        changeset: &crr::LegacyChangeset,
+        policy: crr::TieBreakPolicy,
+    ) -> crr::LegacyMergeReport {
+        self.metrics.record_changeset_bytes(changeset);
+        let started = std::time::Instant::now();
+        let report = self.crr_table.crr_merge(changeset, policy);
+        self.metrics.record_merge(&report, policy, started.elapsed());
+        report
+    }
+
+    /// This engine's [`crr::LegacyCrrTable::merkle_root`] — see
+    /// [`crr::LegacyCrrTable::merkle_root`] for what comparing it against
+    /// another peer's tells you.
+    pub fn merkle_root(&self) -> crate::merkle::Digest {
+        self.crr_table.merkle_root()
+    }
+
+    /// Render [`Self::metrics`] as a Prometheus text exposition format body.
+    pub fn metrics_snapshot(&self) -> String {
+        self.metrics.render()
+    }
+
+    /// Like [`crr::LegacyCrrTable::insert_or_update`] called directly on
+    /// [`Self::crr_table`], but also flushes the written row through
+    /// [`Self::backend`] (if any) — the durable entry point a caller should
+    /// use instead of `self.crr_table.insert_or_update(...)` whenever this
+    /// engine was built with [`Self::with_backend`].
+    pub fn insert_or_update(
+        &mut self,
+        pk: &str,
+        columns: std::collections::HashMap<String, String>,
+        versions: std::collections::HashMap<String, u64>,
+    ) -> crate::error::Result<()> {
+        self.crr_table.insert_or_update(pk, columns, versions);
+        if let Some(backend) = &mut self.backend {
+            if let Some(row) = self.crr_table.rows.get(pk) {
+                backend.persist_row(pk, row)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Like [`Self::insert_or_update`], but for a [`Self::get_table`]-named
+    /// table rather than the default [`Self::crr_table`] — the durable entry
+    /// point a caller should use instead of
+    /// `self.get_table(table).insert_or_update(...)` whenever this engine
+    /// was built with [`Self::with_backend`], so a named table (e.g. an
+    /// offline peer's local `orders`) survives a process restart the same
+    /// way [`Self::crr_table`] already does.
+    pub fn insert_or_update_table(
+        &mut self,
+        table: &str,
+        pk: &str,
+        columns: std::collections::HashMap<String, String>,
+        versions: std::collections::HashMap<String, u64>,
+    ) -> crate::error::Result<()> {
+        self.get_table(table).insert_or_update(pk, columns, versions);
+        if let Some(backend) = &mut self.backend {
+            if let Some(row) = self.tables.get(table).and_then(|t| t.rows.get(pk)) {
+                backend.persist_table_row(table, pk, row)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Block until a peer connects on `addr`, then run one [`Self::sync_via`]
+    /// round over the resulting socket.
+    pub fn serve(&mut self, addr: &str) -> crate::error::Result<crr::LegacyMergeReport> {
+        let mut transport = transport::TcpTransport::listen(addr)?;
+        self.sync_via(&mut transport)
+    }
+
+    /// Connect to a peer already listening on `addr`, then run one
+    /// [`Self::sync_via`] round over the resulting socket.
+    pub fn connect(&mut self, addr: &str) -> crate::error::Result<crr::LegacyMergeReport> {
+        let mut transport = transport::TcpTransport::connect(addr)?;
+        self.sync_via(&mut transport)
+    }
+
+    /// Exchange this engine's full changeset with whatever's on the other
+    /// end of `transport` and merge what comes back — the legacy-engine
+    /// analogue of [`crate::sync::SyncSession::sync_over`], just carrying
+    /// [`crr::LegacyCrrTable::changeset`] instead of the new engine's framed
+    /// [`crate::sync::Changeset`]. Works the same whether `transport` is a
+    /// real [`transport::TcpTransport`] or an in-process
+    /// [`transport::LoopbackTransport`].
+    pub fn sync_via(
+        &mut self,
+        transport: &mut dyn transport::SyncTransport,
+    ) -> crate::error::Result<crr::LegacyMergeReport> {
+        let outgoing = self.crr_table.changeset();
+        transport.send_changeset(&outgoing)?;
+        let incoming = transport.recv_changeset()?;
+        let report = self.crr_merge_recorded(&incoming, crr::TieBreakPolicy::LastWriteWins);
+        if let Some(backend) = &mut self.backend {
+            for pk in incoming.keys() {
+                if let Some(row) = self.crr_table.rows.get(pk) {
+                    backend.persist_row(pk, row)?;
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Reconcile [`Self::crr_table`] against `other`'s by comparing Merkle
+    /// roots and walking down to the diverging pks (see
+    /// [`crate::merkle::MerkleTree::diverging_pks`]), then return exactly
+    /// the rows this side needs to pull from `other` to catch up — the
+    /// legacy-engine analogue of [`crate::table::CrrTable::diff_against`],
+    /// letting two peers compare a single root hash instead of shipping
+    /// a full [`crr::LegacyCrrTable::changeset`] every round.
+    pub fn reconcile(&self, other: &SyncEngine) -> crr::LegacyChangeset {
+        let local_tree = self.crr_table.merkle_tree();
+        let remote_tree = other.crr_table.merkle_tree();
+        let diverging = local_tree.diverging_pks(&remote_tree);
+        other.crr_table.changeset_for_pks(&diverging)
+    }
+
+    /// Learn that `peer_id` holds every row of `other` up to its current
+    /// head versions, so [`Self::request_missing_versions`] knows who to
+    /// ask for a gap once one shows up. Call this whenever `other`'s state
+    /// becomes known to us — e.g. right before or after [`Self::reconcile`]
+    /// or [`Self::sync_via`] — so peers learn each other's frontiers as a
+    /// side effect of normal syncing rather than needing a separate
+    /// advertisement round-trip.
+    pub fn learn_peer_heads(&mut self, peer_id: &str, other: &SyncEngine) {
+        for (pk, row) in &other.crr_table.rows {
+            for (column, dag) in &row.dags {
+                if let Some(head) = dag.head {
+                    self.version_requests.record_advertisement(peer_id, pk, column, head);
+                }
+            }
+        }
+    }
+
+    /// Walk every row's per-column [`dag::VersionDag::find_missing_versions`]
+    /// and drive [`version_requests::VersionRequestQueue::poll`] for each
+    /// gap found, so a real pull request (rather than
+    /// [`dag::VersionDag::reconstruct_missing_version`]'s guess) goes out
+    /// to whichever peer has advertised holding it. Returns every gap still
+    /// outstanding after polling, whether or not this call issued a fresh
+    /// request for it.
+    pub fn request_missing_versions(&mut self) -> Vec<(String, String, u64)> {
+        self.version_requests.advance_tick();
+        for (pk, row) in &self.crr_table.rows {
+            for (column, dag) in &row.dags {
+                for missing in dag.find_missing_versions() {
+                    self.version_requests.poll(pk, column, missing);
+                }
+            }
+        }
+        self.version_requests.outstanding()
+    }
+
+    /// Resolve a previously-outstanding `(row_id, column, version)` gap by
+    /// linking the recovered `node` into that column's [`dag::VersionDag`]
+    /// and dropping the request. Since [`dag::VersionDag::get_reconstructed_timeline`]
+    /// already only marks a version as inferred when it's absent from
+    /// `nodes`, a node resolved this way is reported as recovered from a
+    /// peer rather than guessed, with no changes needed to the `dag` module.
+    pub fn resolve_missing_version(&mut self, row_id: &str, column: &str, version: u64, node: dag::DagNode) {
+        if let Some(row) = self.crr_table.rows.get_mut(row_id) {
+            if let Some(dag) = row.dags.get_mut(column) {
+                dag.nodes.insert(version, node);
+            }
+        }
+        self.version_requests.resolve(row_id, column, version);
+    }
+}
+
+/// Tracks, per `(row_id, column)`, which peers have advertised which head
+/// version, and drives retry/backoff for pulling a [`dag::VersionDag`]'s
+/// [`dag::VersionDag::find_missing_versions`] gaps from whichever peer
+/// actually holds them — real network recovery instead of
+/// [`dag::VersionDag::reconstruct_missing_version`]'s inference fallback.
+/// The retry/backoff shape (a pending-request map keyed by the missing
+/// item, a cooldown before re-asking, round-robin across candidates)
+/// mirrors Substrate's `extra_requests.rs`, just counted in simulated
+/// ticks instead of block numbers.
+pub mod version_requests {
+    use std::collections::HashMap;
+
+    /// Ticks (see [`VersionRequestQueue::advance_tick`]) to wait before
+    /// retrying a version request — the same cooldown role as Substrate's
+    /// `extra_requests.rs` `RETRY_WAIT`.
+    pub const RETRY_WAIT_TICKS: u64 = 3;
+
+    /// One outstanding `(row_id, column, version)` gap: every peer already
+    /// asked, and when it was last asked.
+    #[derive(Clone, Debug, Default)]
+    pub struct PendingRequest {
+        pub tried_peers: Vec<String>,
+        pub requested_at_tick: u64,
+    }
+
+    /// Which peers claim to hold which `(row_id, column)` head versions,
+    /// and which `(row_id, column, version)` gaps are still outstanding.
+    #[derive(Clone, Debug, Default)]
+    pub struct VersionRequestQueue {
+        tick: u64,
+        advertised_heads: HashMap<(String, String), HashMap<String, u64>>,
+        pending: HashMap<(String, String, u64), PendingRequest>,
+    }
+
+    impl VersionRequestQueue {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Advance the simulated clock [`RETRY_WAIT_TICKS`] compares
+        /// against — call once per sync round or demo step.
+        pub fn advance_tick(&mut self) {
+            self.tick += 1;
+        }
+
+        /// Record that `peer_id` claims to hold `row_id`'s `column` up to
+        /// `head_version`. Any version `<= head_version` is assumed
+        /// reachable on that peer, since a DAG node's causal chain means
+        /// holding a version implies holding everything it descends from.
+        pub fn record_advertisement(&mut self, peer_id: &str, row_id: &str, column: &str, head_version: u64) {
+            let heads = self.advertised_heads.entry((row_id.to_string(), column.to_string())).or_default();
+            let entry = heads.entry(peer_id.to_string()).or_insert(0);
+            if head_version > *entry {
+                *entry = head_version;
+            }
+        }
+
+        /// Peers known to hold `version` of `row_id`'s `column`, in a
+        /// stable order.
+        fn candidates(&self, row_id: &str, column: &str, version: u64) -> Vec<String> {
+            let Some(heads) = self.advertised_heads.get(&(row_id.to_string(), column.to_string())) else {
+                return Vec::new();
+            };
+            let mut peers: Vec<&String> = heads.iter()
+                .filter(|(_, &head)| head >= version)
+                .map(|(peer, _)| peer)
+                .collect();
+            peers.sort();
+            peers.into_iter().cloned().collect()
+        }
+
+        /// Track `(row_id, column, version)` as missing if it isn't
+        /// already, and return the peer (if any) it should be asked next:
+        /// `None` if no candidate has advertised holding it yet, or the
+        /// request is still within [`RETRY_WAIT_TICKS`] of its last
+        /// attempt. Otherwise round-robins to the next untried candidate
+        /// (wrapping back to the first once every candidate's been asked).
+        pub fn poll(&mut self, row_id: &str, column: &str, version: u64) -> Option<String> {
+            let candidates = self.candidates(row_id, column, version);
+            let tick = self.tick;
+            let request = self.pending.entry((row_id.to_string(), column.to_string(), version)).or_default();
+
+            if !request.tried_peers.is_empty() && tick < request.requested_at_tick + RETRY_WAIT_TICKS {
+                return None;
+            }
+
+            let next = candidates.iter().find(|peer| !request.tried_peers.contains(peer)).cloned()
+                .or_else(|| candidates.first().cloned());
+
+            if let Some(peer) = &next {
+                request.tried_peers.push(peer.clone());
+                request.requested_at_tick = tick;
+            }
+
+            next
+        }
+
+        /// Drop `(row_id, column, version)` from the pending set — call
+        /// once the version has arrived and been linked into the DAG.
+        pub fn resolve(&mut self, row_id: &str, column: &str, version: u64) {
+            self.pending.remove(&(row_id.to_string(), column.to_string(), version));
+        }
+
+        /// Every `(row_id, column, version)` still outstanding.
+        pub fn outstanding(&self) -> Vec<(String, String, u64)> {
+            let mut gaps: Vec<_> = self.pending.keys().cloned().collect();
+            gaps.sort();
+            gaps
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn poll_round_robins_across_advertising_peers_after_the_cooldown() {
+            let mut queue = VersionRequestQueue::new();
+            queue.record_advertisement("peer_b", "doc_1", "status", 5);
+            queue.record_advertisement("peer_c", "doc_1", "status", 5);
+
+            let first = queue.poll("doc_1", "status", 3).unwrap();
+            assert!(queue.poll("doc_1", "status", 3).is_none(), "still within RETRY_WAIT_TICKS");
+
+            for _ in 0..RETRY_WAIT_TICKS {
+                queue.advance_tick();
+            }
+            let second = queue.poll("doc_1", "status", 3).unwrap();
+            assert_ne!(first, second, "should round-robin to the other candidate");
+
+            queue.resolve("doc_1", "status", 3);
+            assert!(queue.outstanding().is_empty());
+        }
+    }
+}
+
+/// Pluggable row-selection algorithms for syncing two [`crr::LegacyCrrTable`]s,
+/// so [`Peer::sync_from`] (and callers like the demo UI) can swap which
+/// rows get compared/transferred without touching the merge path itself —
+/// mirrors Substrate's move from a single concrete syncing struct to a
+/// `SyncingStrategy` trait with selectable strategies.
+pub mod sync_strategy {
+    use super::crr::{self, TieBreakPolicy};
+    use std::collections::HashMap;
+
+    /// What [`SyncStrategy::summarize`] produces: just enough of a table's
+    /// shape for [`SyncStrategy::plan`] to compare two peers without either
+    /// side shipping its whole [`crr::LegacyChangeset`].
+    #[derive(Clone, Debug)]
+    pub enum Summary {
+        /// Every pk the peer holds — [`NaiveFullSync`] always requests all
+        /// of them, so no narrower summary is worth computing.
+        Full(Vec<String>),
+        /// A [`crate::merkle::MerkleTree`] over the peer's rows — see
+        /// [`MerkleDiffSync`].
+        Merkle(crate::merkle::MerkleTree),
+        /// Per-pk, per-column `(head version, missing parent versions)`
+        /// pairs, read off each column's [`crate::dag::VersionDag`] — see
+        /// [`DagGapFillSync`].
+        Frontier(HashMap<String, HashMap<String, (u64, Vec<u64>)>>),
     }
 
-    impl TransactionManager {
-        pub fn new() -> Self {
-            Self { transactions: HashMap::new(), pending: Vec::new() }
+    /// A single row [`SyncStrategy::plan`] wants fetched from the peer.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct RowRequest(pub String);
+
+    /// One algorithm for deciding which rows two peers should exchange.
+    /// [`Peer::sync_from`] drives a strategy through all three methods in
+    /// order: summarize both sides, plan the rows that actually need to
+    /// move, then apply the resulting changeset.
+    pub trait SyncStrategy {
+        /// Summarize `table`'s current rows in whatever shape [`Self::plan`]
+        /// needs to compare against a peer's summary.
+        fn summarize(&self, table: &crr::LegacyCrrTable) -> Summary;
+
+        /// Decide which rows to request from the peer, given this side's
+        /// own summary and the peer's. `local` and `remote` are expected to
+        /// be the same [`Summary`] variant (both produced by the same
+        /// strategy); a mismatched pair (e.g. comparing a peer still on
+        /// [`NaiveFullSync`] against one on [`MerkleDiffSync`]) requests
+        /// nothing rather than guessing.
+        fn plan(&self, local: &Summary, remote: &Summary) -> Vec<RowRequest>;
+
+        /// Merge a changeset built from [`Self::plan`]'s requested rows
+        /// into `local`. Every strategy here just delegates to
+        /// [`crr::LegacyCrrTable::crr_merge`] — the strategies differ in
+        /// what gets planned, not in how a changeset is merged once fetched.
+        fn apply(
+            &self,
+            local: &mut crr::LegacyCrrTable,
+            changeset: &crr::LegacyChangeset,
+            policy: TieBreakPolicy,
+        ) -> crr::LegacyMergeReport {
+            local.crr_merge(changeset, policy)
         }
+    }
 
-        pub fn begin(&mut self) -> String {
-            let tx_id = format!("tx_{}", self.transactions.len() + 1);
-            let tx = Transaction {
-                id: tx_id.clone(),
-                operations: Vec::new(),
-                version: 0,
-                timestamp: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_millis() as u64,
-                committed: false,
-            };
-            self.transactions.insert(tx_id.clone(), tx);
-            self.pending.push(tx_id.clone());
-            tx_id
+    /// Current `sync_peers` behavior before Merkle anti-entropy: ship every
+    /// row the peer holds, regardless of whether it actually differs.
+    pub struct NaiveFullSync;
+
+    impl SyncStrategy for NaiveFullSync {
+        fn summarize(&self, table: &crr::LegacyCrrTable) -> Summary {
+            Summary::Full(table.rows.keys().cloned().collect())
         }
 
-        pub fn add_operation(&mut self, tx_id: &str, op: TransactionOp) -> Result<(), String> {
-            if let Some(tx) = self.transactions.get_mut(tx_id) {
-                if tx.committed {
-                    return Err("Transaction already committed".to_string());
-                }
-                tx.operations.push(op);
-                Ok(())
-            } else {
-                Err("Transaction not found".to_string())
+        fn plan(&self, _local: &Summary, remote: &Summary) -> Vec<RowRequest> {
+            match remote {
+                Summary::Full(pks) => pks.iter().cloned().map(RowRequest).collect(),
+                _ => Vec::new(),
             }
         }
+    }
 
-        pub fn commit(
-            &mut self,
-            tx_id: &str,
-            tables: &mut HashMap<String, super::crr::LegacyCrrTable>,
-        ) -> Result<(), String> {
-            let tx = self.transactions.get(tx_id).ok_or("Transaction not found")?;
-            if tx.committed {
-                return Err("Transaction already committed".to_string());
-            }
+    /// Compare [`crate::merkle::MerkleTree`] roots and recurse only into
+    /// diverging subtrees, so only rows that actually differ get requested
+    /// — the strategy [`crate::SyncEngine::reconcile`] and `sync_peers` use.
+    pub struct MerkleDiffSync;
 
-            let operations = tx.operations.clone();
-            let tx_version = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_millis() as u64;
+    impl SyncStrategy for MerkleDiffSync {
+        fn summarize(&self, table: &crr::LegacyCrrTable) -> Summary {
+            Summary::Merkle(table.merkle_tree())
+        }
 
-            for op in &operations {
-                match op {
-                    TransactionOp::Insert { table, pk, columns } => {
-                        let crr_table = tables.entry(table.clone()).or_insert_with(super::crr::LegacyCrrTable::new);
-                        let versions: HashMap<String, u64> = columns.keys().map(|c| (c.clone(), tx_version)).collect();
-                        crr_table.insert_or_update(pk, columns.clone(), versions);
-                    }
-                    TransactionOp::Update { table, pk, columns } => {
-                        let crr_table = tables.entry(table.clone()).or_insert_with(super::crr::LegacyCrrTable::new);
-                        let mut final_columns = crr_table.rows.get(pk)
-                            .map(|r| r.columns.clone())
-                            .unwrap_or_default();
-                        let mut versions = HashMap::new();
-                        for (col, val) in columns {
-                            final_columns.insert(col.clone(), val.clone());
-                            versions.insert(col.clone(), tx_version);
-                        }
-                        crr_table.insert_or_update(pk, final_columns, versions);
-                    }
-                    TransactionOp::Delete { table, pk } => {
-                        if let Some(crr_table) = tables.get_mut(table) {
-                            crr_table.rows.remove(pk);
-                        }
-                    }
+        fn plan(&self, local: &Summary, remote: &Summary) -> Vec<RowRequest> {
+            match (local, remote) {
+                (Summary::Merkle(local_tree), Summary::Merkle(remote_tree)) => {
+                    local_tree.diverging_pks(remote_tree).into_iter().map(RowRequest).collect()
                 }
+                _ => Vec::new(),
             }
+        }
+    }
 
-            if let Some(tx) = self.transactions.get_mut(tx_id) {
-                tx.committed = true;
-                tx.version = tx_version;
+    /// Request a row whenever the peer's version for any of its columns is
+    /// ahead of this side's, or this side's own [`crate::dag::VersionDag`]
+    /// for that column has a gap (a parent version it never received) that
+    /// the peer might be able to fill — the same gap condition
+    /// [`crate::dag::VersionDag::find_missing_versions`] detects for the
+    /// DAG Recovery demo, but resolved by fetching the real row from a
+    /// peer instead of reconstructing a placeholder value.
+    pub struct DagGapFillSync;
+
+    impl SyncStrategy for DagGapFillSync {
+        fn summarize(&self, table: &crr::LegacyCrrTable) -> Summary {
+            let mut frontier = HashMap::new();
+            for (pk, row) in &table.rows {
+                let columns = row.dags.iter()
+                    .map(|(col, dag)| (col.clone(), (dag.head.unwrap_or(0), dag.find_missing_versions())))
+                    .collect();
+                frontier.insert(pk.clone(), columns);
             }
-            self.pending.retain(|id| id != tx_id);
-            Ok(())
+            Summary::Frontier(frontier)
         }
 
-        pub fn rollback(&mut self, tx_id: &str) -> Result<(), String> {
-            if let Some(tx) = self.transactions.get_mut(tx_id) {
-                if tx.committed {
-                    return Err("Cannot rollback committed transaction".to_string());
-                }
-                tx.operations.clear();
-                self.pending.retain(|id| id != tx_id);
-                Ok(())
-            } else {
-                Err("Transaction not found".to_string())
-            }
+        fn plan(&self, local: &Summary, remote: &Summary) -> Vec<RowRequest> {
+            let (Summary::Frontier(local_frontier), Summary::Frontier(remote_frontier)) = (local, remote) else {
+                return Vec::new();
+            };
+
+            remote_frontier.iter()
+                .filter(|(pk, remote_cols)| {
+                    let local_cols = local_frontier.get(*pk);
+                    remote_cols.iter().any(|(col, (remote_head, _))| {
+                        match local_cols.and_then(|cols| cols.get(col)) {
+                            Some((local_head, local_missing)) => local_head < remote_head || !local_missing.is_empty(),
+                            None => true,
+                        }
+                    })
+                })
+                .map(|(pk, _)| RowRequest(pk.clone()))
+                .collect()
         }
     }
-}
 
-// Legacy delta_sync module
-pub mod delta_sync {
-    use serde::{Deserialize, Serialize};
-    use std::collections::HashMap;
+    /// What [`PendingChangeQueue::push`] does once it's holding
+    /// [`PendingChangeQueue::capacity`] distinct `(row_id, column)` cells
+    /// and a write to a new cell arrives — mirrors the two relief valves
+    /// libp2p's gossipsub offers a full outbound queue.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum QueuePolicy {
+        /// Keep accepting — a same-cell write is already coalesced down to
+        /// its highest version for free by [`PendingChangeQueue::push`], so
+        /// in practice this only lets the queue grow past `capacity` when
+        /// a long partition really has touched that many distinct cells.
+        /// Safe because a pending write that later loses a version race
+        /// would never have won the eventual merge anyway.
+        CoalesceLww,
+        /// Refuse writes to cells not already pending once the queue is at
+        /// `capacity`, until [`PendingChangeQueue::drain`] makes room.
+        Backpressure,
+    }
 
-    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
-    pub struct VectorClock {
-        pub clocks: HashMap<String, u64>,
+    /// A single staged cell write, held in [`PendingChangeQueue`] until the
+    /// next [`PendingChangeQueue::drain`].
+    #[derive(Clone, Debug)]
+    pub struct PendingWrite {
+        pub value: String,
+        pub version: u64,
     }
 
-    impl VectorClock {
-        pub fn new() -> Self {
-            Self { clocks: HashMap::new() }
+    /// A bounded, per-[`Peer`] outbound queue of local writes not yet
+    /// applied to [`Peer::table`], keyed by `(row_id, column)` so repeated
+    /// edits to the same cell while offline occupy a single slot instead
+    /// of accumulating unboundedly — the backpressure design libp2p's
+    /// gossipsub uses for its per-peer outbound queues, borrowed here for
+    /// the long-partition case: [`Self::depth`] and [`Self::coalesced`]
+    /// give `sync_peers` something to log before transmission.
+    #[derive(Clone, Debug)]
+    pub struct PendingChangeQueue {
+        capacity: usize,
+        policy: QueuePolicy,
+        pending: HashMap<(String, String), PendingWrite>,
+        coalesced: u64,
+        rejected: u64,
+    }
+
+    impl PendingChangeQueue {
+        pub fn new(capacity: usize, policy: QueuePolicy) -> Self {
+            Self { capacity, policy, pending: HashMap::new(), coalesced: 0, rejected: 0 }
         }
 
-        pub fn update(&mut self, peer_id: &str, version: u64) {
-            let current = self.clocks.entry(peer_id.to_string()).or_insert(0);
-            if version > *current {
-                *current = version;
+        /// Stage a write to `(row_id, col)`. Returns `false` only when
+        /// [`QueuePolicy::Backpressure`] is rejecting a new cell because
+        /// the queue is already at [`Self::capacity`] — the caller should
+        /// treat that as "retry after the next [`Self::drain`]", not as a
+        /// lost write.
+        pub fn push(&mut self, row_id: &str, col: &str, value: String, version: u64) -> bool {
+            let key = (row_id.to_string(), col.to_string());
+            if let Some(existing) = self.pending.get(&key) {
+                if version > existing.version {
+                    self.pending.insert(key, PendingWrite { value, version });
+                }
+                self.coalesced += 1;
+                return true;
             }
+            if self.pending.len() >= self.capacity && self.policy == QueuePolicy::Backpressure {
+                self.rejected += 1;
+                return false;
+            }
+            self.pending.insert(key, PendingWrite { value, version });
+            true
         }
 
-        pub fn get(&self, peer_id: &str) -> u64 {
-            *self.clocks.get(peer_id).unwrap_or(&0)
+        /// Number of distinct `(row_id, column)` cells currently pending.
+        pub fn depth(&self) -> usize {
+            self.pending.len()
+        }
+
+        /// How many [`Self::push`] calls were folded into an
+        /// already-pending cell instead of occupying a new slot.
+        pub fn coalesced(&self) -> u64 {
+            self.coalesced
+        }
+
+        /// How many [`Self::push`] calls [`QueuePolicy::Backpressure`] has
+        /// refused in total; [`Self::drain`] does not reset it.
+        pub fn rejected(&self) -> u64 {
+            self.rejected
+        }
+
+        /// Take every pending write, resetting the queue to empty (the
+        /// counters in [`Self::coalesced`]/[`Self::rejected`] are left
+        /// untouched — they're lifetime totals, not per-drain).
+        pub fn drain(&mut self) -> HashMap<(String, String), PendingWrite> {
+            std::mem::take(&mut self.pending)
         }
     }
 
-    #[derive(Clone)]
-    pub struct DeltaTracker {
-        pub changelog: HashMap<u64, (String, String, String)>,
-        pub next_seq: u64,
-        pub vector_clock: VectorClock,
+    /// A table paired with the [`SyncStrategy`] it uses to decide what to
+    /// request from a peer — lets a caller choose [`NaiveFullSync`],
+    /// [`MerkleDiffSync`], [`DagGapFillSync`], or its own [`SyncStrategy`]
+    /// impl per peer, instead of `sync_peers` hardcoding one algorithm for
+    /// everyone.
+    pub struct Peer {
         pub peer_id: String,
+        pub table: crr::LegacyCrrTable,
+        pub strategy: Box<dyn SyncStrategy>,
+        /// Local writes made while this peer is offline, staged here
+        /// instead of landing in `table` directly — see
+        /// [`PendingChangeQueue`].
+        pub pending: PendingChangeQueue,
     }
 
-    impl DeltaTracker {
-        pub fn new(peer_id: String) -> Self {
-            Self {
-                changelog: HashMap::new(),
-                next_seq: 1,
-                vector_clock: VectorClock::new(),
-                peer_id,
+    impl Peer {
+        pub fn new(peer_id: &str, strategy: Box<dyn SyncStrategy>) -> Self {
+            Self::with_queue(peer_id, strategy, PendingChangeQueue::new(usize::MAX, QueuePolicy::CoalesceLww))
+        }
+
+        /// Like [`Self::new`], but with an explicit [`PendingChangeQueue`]
+        /// capacity/policy instead of the default unbounded coalescing one.
+        pub fn with_queue(peer_id: &str, strategy: Box<dyn SyncStrategy>, pending: PendingChangeQueue) -> Self {
+            Self { peer_id: peer_id.to_string(), table: crr::LegacyCrrTable::new(), strategy, pending }
+        }
+
+        /// Stage a local write to `pk`'s `col` in [`Self::pending`] rather
+        /// than writing [`Self::table`] directly. Returns `false` if the
+        /// queue's [`QueuePolicy::Backpressure`] refused it — the caller
+        /// should retry after the next [`Self::sync_from`] drains it.
+        pub fn write(&mut self, pk: &str, col: &str, value: &str, version: u64) -> bool {
+            self.pending.push(pk, col, value.to_string(), version)
+        }
+
+        /// Apply every write staged in [`Self::pending`] into [`Self::table`],
+        /// draining the queue. [`Self::sync_from`] calls this before
+        /// syncing, so a reconnect after a long partition transmits one
+        /// changeset per distinct changed column rather than replaying
+        /// every individual staged edit.
+        pub fn flush_pending(&mut self) {
+            for ((pk, col), write) in self.pending.drain() {
+                let mut cols = HashMap::new();
+                let mut vers = HashMap::new();
+                cols.insert(col.clone(), write.value);
+                vers.insert(col, write.version);
+                self.table.insert_or_update(&pk, cols, vers);
             }
         }
 
-        pub fn record_change(&mut self, pk: &str, column: &str, value: &str) -> u64 {
-            let seq = self.next_seq;
-            self.changelog.insert(seq, (pk.to_string(), column.to_string(), value.to_string()));
-            self.next_seq += 1;
-            seq
+        /// Sync `self.table` against `remote`: flush any staged local
+        /// writes, summarize both sides via [`Self::strategy`], plan the
+        /// rows that actually need to move, build a changeset covering
+        /// exactly those rows, and merge it in.
+        pub fn sync_from(&mut self, remote: &crr::LegacyCrrTable, policy: TieBreakPolicy) -> crr::LegacyMergeReport {
+            self.flush_pending();
+            let local_summary = self.strategy.summarize(&self.table);
+            let remote_summary = self.strategy.summarize(remote);
+            let requested = self.strategy.plan(&local_summary, &remote_summary);
+            let pks: Vec<String> = requested.into_iter().map(|request| request.0).collect();
+            let changeset = remote.changeset_for_pks(&pks);
+            self.strategy.apply(&mut self.table, &changeset, policy)
         }
     }
-}
 
-// Legacy SyncEngine for UI compatibility
-#[derive(Clone)]
-pub struct SyncEngine {
-    pub crr_table: crr::LegacyCrrTable,
-    pub tables: std::collections::HashMap<String, crr::LegacyCrrTable>,
-    pub schema_manager: schema::SchemaManager,
-    pub fk_manager: foreign_keys::ForeignKeyManager,
-    pub tx_manager: transactions::TransactionManager,
-    pub delta_tracker: delta_sync::DeltaTracker,
-}
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn merkle_diff_sync_only_plans_the_rows_that_actually_differ() {
+            let mut a = Peer::new("a", Box::new(MerkleDiffSync));
+            let mut cols = HashMap::new();
+            let mut vers = HashMap::new();
+            cols.insert("name".to_string(), "Alice".to_string());
+            vers.insert("name".to_string(), 1);
+            a.table.insert_or_update("user_1", cols, vers);
+
+            let mut b_table = crr::LegacyCrrTable::new();
+            let mut cols = HashMap::new();
+            let mut vers = HashMap::new();
+            cols.insert("name".to_string(), "Alice".to_string());
+            vers.insert("name".to_string(), 1);
+            b_table.insert_or_update("user_1", cols, vers);
+
+            let mut cols = HashMap::new();
+            let mut vers = HashMap::new();
+            cols.insert("name".to_string(), "Bob".to_string());
+            vers.insert("name".to_string(), 1);
+            b_table.insert_or_update("user_2", cols, vers);
+
+            let report = a.sync_from(&b_table, TieBreakPolicy::LastWriteWins);
+
+            assert_eq!(report.inserted, vec![("user_2".to_string(), "name".to_string())]);
+            assert_eq!(a.table.rows.len(), 2);
+        }
 
-impl Default for SyncEngine {
-    fn default() -> Self {
-        Self::new()
-    }
-}
+        #[test]
+        fn pending_queue_coalesces_repeated_writes_to_the_same_cell() {
+            let mut queue = PendingChangeQueue::new(4, QueuePolicy::CoalesceLww);
 
-impl SyncEngine {
-    pub fn new() -> Self {
-        Self::new_with_peer_id("default_peer".to_string())
-    }
+            assert!(queue.push("user_1", "name", "Alice".to_string(), 1));
+            assert!(queue.push("user_1", "name", "Alicia".to_string(), 2));
+            assert!(queue.push("user_1", "name", "stale".to_string(), 1));
 
-    pub fn new_with_peer_id(peer_id: String) -> Self {
-        Self {
-            crr_table: crr::LegacyCrrTable::new(),
-            tables: std::collections::HashMap::new(),
-            schema_manager: schema::SchemaManager::new(),
-            fk_manager: foreign_keys::ForeignKeyManager::new(),
-            tx_manager: transactions::TransactionManager::new(),
-            delta_tracker: delta_sync::DeltaTracker::new(peer_id),
+            assert_eq!(queue.depth(), 1);
+            assert_eq!(queue.coalesced(), 2);
+            let drained = queue.drain();
+            assert_eq!(drained[&("user_1".to_string(), "name".to_string())].value, "Alicia");
         }
-    }
 
-    pub fn get_table(&mut self, name: &str) -> &mut crr::LegacyCrrTable {
-        self.tables.entry(name.to_string()).or_insert_with(crr::LegacyCrrTable::new)
+        #[test]
+        fn backpressure_policy_refuses_new_cells_once_at_capacity() {
+            let mut queue = PendingChangeQueue::new(1, QueuePolicy::Backpressure);
+
+            assert!(queue.push("user_1", "name", "Alice".to_string(), 1));
+            assert!(!queue.push("user_2", "name", "Bob".to_string(), 1));
+            assert_eq!(queue.depth(), 1);
+            assert_eq!(queue.rejected(), 1);
+        }
+
+        #[test]
+        fn flush_pending_applies_staged_writes_to_the_table_before_sync() {
+            let mut peer = Peer::with_queue("a", Box::new(NaiveFullSync), PendingChangeQueue::new(8, QueuePolicy::Backpressure));
+
+            assert!(peer.write("user_1", "name", "Alice", 1));
+            assert!(peer.write("user_1", "name", "Alicia", 2));
+            assert_eq!(peer.pending.depth(), 1);
+
+            let remote = crr::LegacyCrrTable::new();
+            peer.sync_from(&remote, TieBreakPolicy::LastWriteWins);
+
+            assert_eq!(peer.pending.depth(), 0);
+            assert_eq!(peer.table.rows["user_1"].columns["name"], "Alicia");
+        }
     }
 }
 
@@ -688,6 +3414,40 @@ pub mod sync_protocol {
             }
             Self { peer_id: peer_id.to_string(), heads }
         }
+
+        /// Like [`Self::from_table`], but only covers `pks` — the candidate
+        /// rows a [`MerkleTree`](crate::merkle::MerkleTree) walk has already
+        /// identified as diverging, rather than every row in the table.
+        pub fn from_table_scoped(peer_id: &str, table: &LegacyCrrTable, pks: &std::collections::HashSet<String>) -> Self {
+            let mut heads = HashMap::new();
+            for pk in pks {
+                if let Some(row) = table.rows.get(pk) {
+                    heads.insert(pk.clone(), row.versions.clone());
+                }
+            }
+            Self { peer_id: peer_id.to_string(), heads }
+        }
+    }
+
+    /// Build a [`crate::merkle::MerkleTree`] over `table`'s current rows, so
+    /// two peers can compare root digests and recurse into only the
+    /// subtrees that differ instead of exchanging every row's version map.
+    /// Each column's string value and version are folded into the same
+    /// `(value, version)` leaf shape [`crate::storage::Cell`] uses, reusing
+    /// the tree's bucketing and digest combination rather than duplicating
+    /// them for the legacy string-keyed column representation.
+    pub fn merkle_tree_for(table: &LegacyCrrTable) -> crate::merkle::MerkleTree {
+        let mut tree = crate::merkle::MerkleTree::new();
+        for (pk, row) in &table.rows {
+            let cells: std::collections::BTreeMap<String, crate::storage::Cell> = row.columns.iter()
+                .map(|(col, val)| {
+                    let version = row.versions.get(col).copied().unwrap_or(0);
+                    (col.clone(), crate::storage::Cell { value: val.as_bytes().to_vec(), version })
+                })
+                .collect();
+            tree.insert(pk, &cells);
+        }
+        tree
     }
 
     #[derive(Clone, Debug)]
@@ -700,9 +3460,32 @@ pub mod sync_protocol {
 
     impl Changeset {
         pub fn compute(sender: &SyncPeer, receiver_heads: &HeadExchange) -> Self {
+            Self::compute_over(sender, receiver_heads, sender.table.rows.keys())
+        }
+
+        /// Like [`Self::compute`], but only considers `pks` — the candidate
+        /// rows a Merkle walk found diverging — instead of every row
+        /// `sender` holds, so the comparison (and `receiver_heads`, if it
+        /// was built with [`HeadExchange::from_table_scoped`]) costs
+        /// bandwidth proportional to how much the peers actually disagree
+        /// on rather than total table size.
+        pub fn compute_scoped(
+            sender: &SyncPeer,
+            receiver_heads: &HeadExchange,
+            pks: &std::collections::HashSet<String>,
+        ) -> Self {
+            Self::compute_over(sender, receiver_heads, pks.iter())
+        }
+
+        fn compute_over<'a>(
+            sender: &SyncPeer,
+            receiver_heads: &HeadExchange,
+            pks: impl Iterator<Item = &'a String>,
+        ) -> Self {
             let mut changes = HashMap::new();
 
-            for (pk, row) in &sender.table.rows {
+            for pk in pks {
+                let Some(row) = sender.table.rows.get(pk) else { continue };
                 let receiver_versions = receiver_heads.heads.get(pk);
                 let mut needed_cols = HashMap::new();
                 let mut needed_vers = HashMap::new();
@@ -739,6 +3522,86 @@ pub mod sync_protocol {
                 .map(|(pk, (cols, vers))| pk.len() + cols.iter().map(|(c, v)| c.len() + v.len()).sum::<usize>() + vers.len() * 8)
                 .sum()
         }
+
+        /// Like [`Self::compute`], but splits each needed column's value into
+        /// content-defined chunks via `store` (see [`crate::chunking`])
+        /// instead of carrying it inline — the legacy-path equivalent of
+        /// [`crate::table::CrrTable::export_chunked_changeset_since`]. Only
+        /// chunk hashes travel in the returned [`ChunkedChangeset`]; the
+        /// receiver reassembles from `store`, which needs every chunk
+        /// fetched out of band first (see [`ChunkStore::missing`]).
+        pub fn compute_chunked(
+            sender: &SyncPeer,
+            receiver_heads: &HeadExchange,
+            store: &mut crate::chunking::ChunkStore,
+            config: &crate::chunking::ChunkConfig,
+        ) -> ChunkedChangeset {
+            let whole = Self::compute(sender, receiver_heads);
+            let mut changes = HashMap::new();
+
+            for (pk, (cols, vers)) in whole.changes {
+                let mut chunked_cols = HashMap::new();
+                for (col, value) in cols {
+                    chunked_cols.insert(col, store.put(value.as_bytes(), config));
+                }
+                changes.insert(pk, (chunked_cols, vers));
+            }
+
+            ChunkedChangeset { from_peer: whole.from_peer, to_peer: whole.to_peer, changes }
+        }
+    }
+
+    /// A [`Changeset`] whose column values have been split into
+    /// content-defined chunks: each column carries the ordered list of
+    /// chunk hashes that reassemble its value, rather than the value
+    /// itself.
+    #[derive(Clone, Debug)]
+    pub struct ChunkedChangeset {
+        pub from_peer: String,
+        pub to_peer: String,
+        pub changes: HashMap<String, (HashMap<String, Vec<crate::chunking::ChunkHash>>, HashMap<String, u64>)>,
+    }
+
+    impl ChunkedChangeset {
+        /// Every chunk hash this changeset references, for the receiver to
+        /// diff against its own [`ChunkStore`] (see [`ChunkStore::missing`])
+        /// before calling [`apply_chunked_changeset`].
+        pub fn chunk_hashes(&self) -> Vec<crate::chunking::ChunkHash> {
+            self.changes.values()
+                .flat_map(|(cols, _)| cols.values().flatten().copied())
+                .collect()
+        }
+    }
+
+    /// Apply a [`ChunkedChangeset`] produced by [`Changeset::compute_chunked`]
+    /// to `receiver`, reassembling each column's value from `store` and then
+    /// merging exactly as [`crate::crr::LegacyCrrTable::crr_merge`] would.
+    /// Fails with [`Error::InvalidState`] if `store` is missing a chunk a
+    /// column needs, or if reassembled bytes aren't valid UTF-8 (every
+    /// column in this legacy representation is a `String`).
+    pub fn apply_chunked_changeset(
+        receiver: &mut SyncPeer,
+        chunked: &ChunkedChangeset,
+        store: &crate::chunking::ChunkStore,
+        policy: TieBreakPolicy,
+    ) -> crate::error::Result<LegacyMergeReport> {
+        use crate::error::Error;
+
+        let mut changes = HashMap::new();
+        for (pk, (cols, vers)) in &chunked.changes {
+            let mut values = HashMap::new();
+            for (col, hashes) in cols {
+                let bytes = store.reassemble(hashes).ok_or_else(|| {
+                    Error::InvalidState(format!("missing chunk(s) for {}:{}", pk, col))
+                })?;
+                let value = String::from_utf8(bytes)
+                    .map_err(|e| Error::InvalidState(format!("non-UTF-8 reassembled value for {}:{}: {}", pk, col, e)))?;
+                values.insert(col.clone(), value);
+            }
+            changes.insert(pk.clone(), (values, vers.clone()));
+        }
+
+        Ok(receiver.table.crr_merge(&changes, policy))
     }
 
     #[derive(Clone, Debug, Default)]
@@ -765,12 +3628,25 @@ pub mod sync_protocol {
             }
         }
 
+        /// Sync `peer_a` and `peer_b` against each other. Rather than
+        /// exchanging a full per-row/per-column version map up front, each
+        /// side first builds a [`MerkleTree`](crate::merkle::MerkleTree)
+        /// over its table and the two roots are diffed to find candidate
+        /// diverging pks — identical subtrees are pruned without either
+        /// side's data ever being inspected. `HeadExchange` and `Changeset`
+        /// are then scoped to just that candidate set, so bandwidth scales
+        /// with how much the peers actually disagree on rather than with
+        /// table size.
         pub fn sync(&self, peer_a: &mut SyncPeer, peer_b: &mut SyncPeer) -> (SyncResult, SyncResult, SyncStats) {
-            let heads_a = HeadExchange::from_table(&peer_a.peer_id, &peer_a.table);
-            let heads_b = HeadExchange::from_table(&peer_b.peer_id, &peer_b.table);
+            let tree_a = merkle_tree_for(&peer_a.table);
+            let tree_b = merkle_tree_for(&peer_b.table);
+            let diverging: std::collections::HashSet<String> = tree_a.diverging_pks(&tree_b).into_iter().collect();
 
-            let changeset_a_to_b = Changeset::compute(peer_a, &heads_b);
-            let changeset_b_to_a = Changeset::compute(peer_b, &heads_a);
+            let heads_a = HeadExchange::from_table_scoped(&peer_a.peer_id, &peer_a.table, &diverging);
+            let heads_b = HeadExchange::from_table_scoped(&peer_b.peer_id, &peer_b.table, &diverging);
+
+            let changeset_a_to_b = Changeset::compute_scoped(peer_a, &heads_b, &diverging);
+            let changeset_b_to_a = Changeset::compute_scoped(peer_b, &heads_a, &diverging);
 
             let report_a = peer_a.table.crr_merge(&changeset_b_to_a.changes, self.policy);
             let report_b = peer_b.table.crr_merge(&changeset_a_to_b.changes, self.policy);
@@ -820,14 +3696,33 @@ pub mod sync_protocol {
         }
     }
 
+    /// How many consecutive gossip rounds a peer can initiate without
+    /// receiving anything new before it stops initiating on its own. It
+    /// still answers if another peer picks it as a partner.
+    const DEFAULT_QUIESCENCE: usize = 3;
+
     pub struct MeshSync {
         pub peers: HashMap<String, SyncPeer>,
         pub policy: TieBreakPolicy,
+        pub quiescence: usize,
+        /// Pks each peer received new columns for in the most recent
+        /// gossip round, used to give that peer priority as a gossip
+        /// partner next round. Replaced wholesale each round rather than
+        /// accumulated, so a row is only "hot" for one round after it
+        /// actually changed.
+        hot: HashMap<String, std::collections::HashSet<String>>,
+        quiet_rounds: HashMap<String, usize>,
     }
 
     impl MeshSync {
         pub fn new(policy: TieBreakPolicy) -> Self {
-            Self { peers: HashMap::new(), policy }
+            Self {
+                peers: HashMap::new(),
+                policy,
+                quiescence: DEFAULT_QUIESCENCE,
+                hot: HashMap::new(),
+                quiet_rounds: HashMap::new(),
+            }
         }
 
         pub fn add_peer(&mut self, peer: SyncPeer) {
@@ -896,6 +3791,109 @@ pub mod sync_protocol {
             }
             true
         }
+
+        /// One round of epidemic (gossip) anti-entropy, as an alternative
+        /// to [`Self::sync_all`]'s O(n²) all-pairs rounds: every peer that
+        /// hasn't gone quiet yet picks up to `fanout` random partners
+        /// (favoring ones with rows hot from the last round, so new data
+        /// spreads before it goes stale) and runs one [`SyncSession`]
+        /// against each.
+        pub fn gossip_round(&mut self, fanout: usize, rng: &mut impl rand::Rng) -> SyncStats {
+            let peer_ids: Vec<String> = self.peers.keys().cloned().collect();
+            let mut round_stats = SyncStats::default();
+            let mut next_hot: HashMap<String, std::collections::HashSet<String>> = HashMap::new();
+
+            for id_a in &peer_ids {
+                if self.quiet_rounds.get(id_a).copied().unwrap_or(0) >= self.quiescence {
+                    continue;
+                }
+
+                let (mut hot_candidates, mut cold_candidates): (Vec<String>, Vec<String>) = peer_ids.iter()
+                    .filter(|id| *id != id_a)
+                    .cloned()
+                    .partition(|id| self.hot.get(id).is_some_and(|h| !h.is_empty()));
+                shuffle(&mut hot_candidates, rng);
+                shuffle(&mut cold_candidates, rng);
+                hot_candidates.extend(cold_candidates);
+                let partners: Vec<String> = hot_candidates.into_iter().take(fanout).collect();
+
+                let mut received_new = false;
+
+                for id_b in partners {
+                    let mut peer_a = self.peers.remove(id_a).unwrap();
+                    let mut peer_b = self.peers.remove(&id_b).unwrap();
+
+                    let session = SyncSession::new(id_a, &id_b, self.policy);
+                    let (result_a, result_b, stats) = session.sync(&mut peer_a, &mut peer_b);
+
+                    if result_a.columns_updated > 0 {
+                        received_new = true;
+                        let hot = next_hot.entry(id_a.clone()).or_default();
+                        hot.extend(result_a.merge_report.inserted.iter().map(|(pk, _)| pk.clone()));
+                        hot.extend(result_a.merge_report.updated.iter().map(|(pk, _, _)| pk.clone()));
+                    }
+                    if result_b.columns_updated > 0 {
+                        let hot = next_hot.entry(id_b.clone()).or_default();
+                        hot.extend(result_b.merge_report.inserted.iter().map(|(pk, _)| pk.clone()));
+                        hot.extend(result_b.merge_report.updated.iter().map(|(pk, _, _)| pk.clone()));
+                    }
+
+                    round_stats.heads_exchanged += stats.heads_exchanged;
+                    round_stats.changeset_a_to_b_columns += stats.changeset_a_to_b_columns;
+                    round_stats.changeset_b_to_a_columns += stats.changeset_b_to_a_columns;
+                    round_stats.changeset_a_to_b_bytes += stats.changeset_a_to_b_bytes;
+                    round_stats.changeset_b_to_a_bytes += stats.changeset_b_to_a_bytes;
+
+                    self.peers.insert(id_a.clone(), peer_a);
+                    self.peers.insert(id_b, peer_b);
+                }
+
+                if received_new {
+                    self.quiet_rounds.insert(id_a.clone(), 0);
+                } else {
+                    *self.quiet_rounds.entry(id_a.clone()).or_insert(0) += 1;
+                }
+            }
+
+            self.hot = next_hot;
+            round_stats
+        }
+
+        /// Run [`Self::gossip_round`] until [`Self::is_converged`], or 200
+        /// rounds pass without converging. Returns the round count and the
+        /// summed stats across every round, for comparison against
+        /// [`Self::sync_all`]'s all-pairs cost.
+        pub fn gossip_until_converged(&mut self, fanout: usize) -> (usize, SyncStats) {
+            let mut rng = rand::thread_rng();
+            let mut rounds = 0;
+            let mut total = SyncStats::default();
+
+            loop {
+                rounds += 1;
+                let stats = self.gossip_round(fanout, &mut rng);
+                total.heads_exchanged += stats.heads_exchanged;
+                total.changeset_a_to_b_columns += stats.changeset_a_to_b_columns;
+                total.changeset_b_to_a_columns += stats.changeset_b_to_a_columns;
+                total.changeset_a_to_b_bytes += stats.changeset_a_to_b_bytes;
+                total.changeset_b_to_a_bytes += stats.changeset_b_to_a_bytes;
+
+                if self.is_converged() || rounds >= 200 {
+                    break;
+                }
+            }
+
+            (rounds, total)
+        }
+    }
+
+    /// Fisher-Yates shuffle, used to randomize gossip partner order within
+    /// each priority tier without pulling in `rand::seq::SliceRandom`.
+    fn shuffle(items: &mut [String], rng: &mut impl rand::Rng) {
+        use rand::Rng as _;
+        for i in (1..items.len()).rev() {
+            let j = rng.gen_range(0..=i);
+            items.swap(i, j);
+        }
     }
 }
 
@@ -930,4 +3928,27 @@ mod tests {
         assert_eq!(table.rows.len(), 1);
         assert_eq!(table.rows.get("user_1").unwrap().columns.get("name").unwrap(), "Alice");
     }
+
+    #[test]
+    fn pn_counter_schema_column_converges_across_concurrent_increments() {
+        let mut store_a = SyncEngine::new_with_peer_id("store_a".to_string());
+        store_a.apply_schema_migration(schema::SchemaMigration::AddColumn {
+            name: "loyalty_points".to_string(),
+            col_type: schema::ColumnType::PnCounter,
+            nullable: true,
+        });
+
+        let mut mobile = SyncEngine::new_with_peer_id("mobile".to_string());
+        mobile.merge_schema_from(&store_a.schema_manager, crr::TieBreakPolicy::LastWriteWins);
+
+        // Concurrent awards from two replicas, neither aware of the other's write.
+        store_a.crr_table.crdt_increment("c1", "loyalty_points", "store_a", 10);
+        mobile.crr_table.crdt_increment("c1", "loyalty_points", "mobile", 5);
+
+        let crdt_changeset = store_a.crr_table.crdt_changeset();
+        let report = mobile.crr_table.crdt_merge(&crdt_changeset);
+
+        assert_eq!(report.counter_merges, vec![("c1".to_string(), "loyalty_points".to_string())]);
+        assert_eq!(mobile.crr_table.rows["c1"].columns["loyalty_points"], "15");
+    }
 }
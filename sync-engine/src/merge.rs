@@ -1,24 +1,88 @@
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Error, Result};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TieBreakPolicy {
     PreferExisting,
     PreferIncoming,
     LexicographicMin,
+    /// Don't pick a winner on a genuine concurrent conflict (neither
+    /// version dominates the other) — keep both values as an MV-Register
+    /// conflict set instead, via [`crate::row::encode_conflict_set`]. A
+    /// later write that causally succeeds every member collapses the set
+    /// back to a single value, the same way any other accepted write
+    /// replaces a cell's prior contents.
+    MultiValue,
+    /// On a same-version conflict, keep whichever side's wall-clock write
+    /// timestamp is higher, falling back to [`LexicographicMin`](Self::LexicographicMin)'s
+    /// byte comparison on an exact timestamp tie. Unlike the other policies
+    /// here, this needs timestamps for *both* candidates, not just their
+    /// values — see [`resolve_conflict`].
+    LastWriteWins,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MergeReport {
     pub inserted: usize,
     pub updated: usize,
     pub skipped: usize,
     pub conflicts: usize,
+    /// Of `updated`, how many were folded via a declared
+    /// [`crate::CrdtKind::PnCounter`] merge rather than version comparison.
+    pub counter_merges: usize,
+    /// Of `updated`, how many were folded via a declared
+    /// [`crate::CrdtKind::OrSet`] merge rather than version comparison.
+    pub set_merges: usize,
 }
 
 impl MergeReport {
     pub fn total_changes(&self) -> usize {
         self.inserted + self.updated
     }
+
+    /// Encode as a self-describing CBOR blob, suitable for shipping this
+    /// summary alongside (or in place of) the [`crate::sync::Changeset`]
+    /// it reports on.
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        ciborium::into_writer(self, &mut buf)
+            .map_err(|e| Error::InvalidState(format!("failed to encode CBOR frame: {}", e)))?;
+        Ok(buf)
+    }
+
+    /// Decode a blob produced by [`Self::to_cbor`].
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        ciborium::from_reader(bytes)
+            .map_err(|e| Error::InvalidState(format!("failed to decode CBOR frame: {}", e)))
+    }
+}
+
+/// Outcome of [`crate::CrrTable::apply_changeset`]. Node-granular rather
+/// than column-granular like [`MergeReport`], since applying a CBOR
+/// changeset replays individual DAG nodes before the winning cell per
+/// `(pk, col)` is recomputed.
+#[derive(Debug, Clone, Default)]
+pub struct MergeStats {
+    pub nodes_applied: usize,
+    pub nodes_skipped: usize,
+    pub cells_updated: usize,
+}
+
+/// Outcome of [`crate::CrrTable::ingest`]: a [`MergeReport`]-style tally,
+/// but folded across every changeset in the batch rather than one per
+/// call, since they all landed in the same transaction under the same
+/// `global_version`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IngestStats {
+    pub applied: usize,
+    pub skipped: usize,
+    pub conflicted: usize,
+    /// The `commit_seq` every cell this ingest wrote was stamped with —
+    /// a resumable high-water mark a caller can hand back on its next
+    /// [`crate::CrrTable::ingest`] call to pick up where this one left off,
+    /// the same role [`crate::table::AsOfBound::CommitSeq`] plays for reads.
+    pub global_version: u64,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,14 +100,30 @@ pub(crate) fn resolve_versions(local_version: u64, remote_version: u64) -> Merge
     }
 }
 
+/// Decide whether an incoming conflicting write should replace the local
+/// one. `local_timestamp`/`remote_timestamp` are each side's DAG node write
+/// timestamp and are only consulted under [`TieBreakPolicy::LastWriteWins`]
+/// — every other policy ignores them, so callers outside that policy's path
+/// (e.g. a same-table history fold where two nodes can't actually share a
+/// version) may pass `0` for both.
 pub(crate) fn resolve_conflict(
     local_value: &[u8],
     remote_value: &[u8],
     policy: TieBreakPolicy,
+    local_timestamp: u64,
+    remote_timestamp: u64,
 ) -> bool {
     match policy {
         TieBreakPolicy::PreferExisting => false,
         TieBreakPolicy::PreferIncoming => true,
         TieBreakPolicy::LexicographicMin => remote_value < local_value,
+        TieBreakPolicy::LastWriteWins => match remote_timestamp.cmp(&local_timestamp) {
+            std::cmp::Ordering::Greater => true,
+            std::cmp::Ordering::Less => false,
+            std::cmp::Ordering::Equal => remote_value < local_value,
+        },
+        // CrrTable::merge forks into a conflict set before ever calling
+        // this resolver for MultiValue — it has no single-winner answer.
+        TieBreakPolicy::MultiValue => unreachable!("MultiValue conflicts are resolved in CrrTable::merge, not here"),
     }
 }
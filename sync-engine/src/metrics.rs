@@ -0,0 +1,253 @@
+//! Prometheus-style metrics for merge/GC/sync operations, modeled on
+//! Garage's `admin/metrics.rs`: a handful of monotonic counters and
+//! histograms an embedder (or [`crate::CrrTable::attach_metrics`] itself)
+//! updates in place, rendered on demand as Prometheus text exposition
+//! format so a `/metrics` HTTP endpoint can just hand back [`Metrics::render`]'s
+//! output.
+//!
+//! Everything here is append-only and `Sync`, so a single [`Metrics`]
+//! instance is meant to be shared via `Arc` across whatever threads are
+//! driving merges, GC rounds, and peer syncs concurrently — the same shape
+//! `network_bench`'s `Arc<Mutex<CrrTable>>` server loop already uses.
+
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::merge::MergeReport;
+
+/// A monotonically increasing count, Prometheus's `counter` type.
+#[derive(Debug, Default)]
+pub struct Counter(Mutex<u64>);
+
+impl Counter {
+    pub fn new() -> Self {
+        Self(Mutex::new(0))
+    }
+
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    pub fn add(&self, n: u64) {
+        *self.0.lock().unwrap() += n;
+    }
+
+    pub fn get(&self) -> u64 {
+        *self.0.lock().unwrap()
+    }
+
+    pub(crate) fn render(&self, name: &str, help: &str, out: &mut String) {
+        writeln!(out, "# HELP {} {}", name, help).unwrap();
+        writeln!(out, "# TYPE {} counter", name).unwrap();
+        writeln!(out, "{} {}", name, self.get()).unwrap();
+    }
+}
+
+/// A Prometheus-style `histogram`: a fixed set of cumulative `le` buckets
+/// plus a running sum and count, enough to derive quantiles and averages
+/// without this process ever having to keep every individual observation.
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    // One count per bound, plus a trailing `+Inf` bucket — not yet
+    // cumulative; [`Self::render`] prefix-sums them on the way out.
+    buckets: Mutex<Vec<u64>>,
+    sum: Mutex<f64>,
+    count: Mutex<u64>,
+}
+
+impl Histogram {
+    pub fn new(bounds: Vec<f64>) -> Self {
+        let bucket_count = bounds.len() + 1;
+        Self { bounds, buckets: Mutex::new(vec![0; bucket_count]), sum: Mutex::new(0.0), count: Mutex::new(0) }
+    }
+
+    pub fn observe(&self, value: f64) {
+        let idx = self.bounds.iter().position(|&bound| value <= bound).unwrap_or(self.bounds.len());
+        self.buckets.lock().unwrap()[idx] += 1;
+        *self.sum.lock().unwrap() += value;
+        *self.count.lock().unwrap() += 1;
+    }
+
+    /// Total of every observed value — the Prometheus histogram `_sum`
+    /// series, also handy for a caller (like [`crate::sync_metrics::SyncMetrics`])
+    /// that wants an average without rendering the whole thing as text.
+    pub(crate) fn sum(&self) -> f64 {
+        *self.sum.lock().unwrap()
+    }
+
+    /// Number of observations recorded — the Prometheus histogram `_count`
+    /// series.
+    pub(crate) fn count(&self) -> u64 {
+        *self.count.lock().unwrap()
+    }
+
+    pub(crate) fn render(&self, name: &str, help: &str, out: &mut String) {
+        writeln!(out, "# HELP {} {}", name, help).unwrap();
+        writeln!(out, "# TYPE {} histogram", name).unwrap();
+        let buckets = self.buckets.lock().unwrap();
+        let mut cumulative = 0u64;
+        for (i, bound) in self.bounds.iter().enumerate() {
+            cumulative += buckets[i];
+            writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, cumulative).unwrap();
+        }
+        cumulative += buckets[self.bounds.len()];
+        writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, cumulative).unwrap();
+        writeln!(out, "{}_sum {}", name, *self.sum.lock().unwrap()).unwrap();
+        writeln!(out, "{}_count {}", name, *self.count.lock().unwrap()).unwrap();
+    }
+}
+
+/// Bucket boundaries (seconds) shared by every latency histogram here —
+/// wide enough to cover a single-row merge and a full-table GC pass alike.
+fn latency_seconds_buckets() -> Vec<f64> {
+    vec![0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0]
+}
+
+/// The full set of metrics a [`crate::CrrTable`] (via [`crate::CrrTable::attach_metrics`])
+/// and/or a sync harness like `network_bench` report into. Share one
+/// instance behind an `Arc` across every table and connection it should
+/// cover, then serve [`Self::render`]'s output from a `/metrics` endpoint.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub cells_inserted: Counter,
+    pub cells_updated: Counter,
+    pub cells_skipped: Counter,
+    pub cells_conflicted: Counter,
+    pub gc_nodes_collected: Counter,
+    pub bytes_sent: Counter,
+    pub bytes_received: Counter,
+    pub merge_latency_seconds: LatencyHistogram,
+    pub gc_latency_seconds: LatencyHistogram,
+    pub peer_rtt_seconds: LatencyHistogram,
+}
+
+/// A [`Histogram`] pre-configured with [`latency_seconds_buckets`], so the
+/// three latency fields on [`Metrics`] don't each have to repeat the bucket
+/// list (and can't accidentally drift out of sync with each other).
+#[derive(Debug)]
+pub struct LatencyHistogram(pub(crate) Histogram);
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self(Histogram::new(latency_seconds_buckets()))
+    }
+}
+
+impl LatencyHistogram {
+    pub fn observe(&self, elapsed: Duration) {
+        self.0.observe(elapsed.as_secs_f64());
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fold a [`crate::CrrTable::merge`] call's outcome into the cell
+    /// counters and `merge_latency_seconds`, so an embedder that doesn't
+    /// call [`crate::CrrTable::attach_metrics`] can still report the same
+    /// numbers by hand from the `MergeReport` it already gets back.
+    pub fn record_merge(&self, report: &MergeReport, elapsed: Duration) {
+        self.cells_inserted.add(report.inserted as u64);
+        self.cells_updated.add(report.updated as u64);
+        self.cells_skipped.add(report.skipped as u64);
+        self.cells_conflicted.add(report.conflicts as u64);
+        self.merge_latency_seconds.observe(elapsed);
+    }
+
+    /// Fold a [`crate::CrrTable::gc`]/[`crate::CrrTable::gc_below_watermark`]
+    /// call's outcome into `gc_nodes_collected` and `gc_latency_seconds`.
+    pub fn record_gc(&self, nodes_removed: usize, elapsed: Duration) {
+        self.gc_nodes_collected.add(nodes_removed as u64);
+        self.gc_latency_seconds.observe(elapsed);
+    }
+
+    pub fn record_bytes_sent(&self, n: u64) {
+        self.bytes_sent.add(n);
+    }
+
+    pub fn record_bytes_received(&self, n: u64) {
+        self.bytes_received.add(n);
+    }
+
+    pub fn record_rtt(&self, elapsed: Duration) {
+        self.peer_rtt_seconds.observe(elapsed);
+    }
+
+    /// Render every metric as a Prometheus text exposition format body,
+    /// suitable for handing straight back as the response of a `/metrics`
+    /// HTTP endpoint.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.cells_inserted.render("crr_cells_inserted_total", "Cells inserted by CrrTable::merge.", &mut out);
+        self.cells_updated.render("crr_cells_updated_total", "Cells updated by CrrTable::merge.", &mut out);
+        self.cells_skipped.render("crr_cells_skipped_total", "Cells CrrTable::merge left unchanged.", &mut out);
+        self.cells_conflicted.render("crr_cells_conflicted_total", "Concurrent-write conflicts CrrTable::merge resolved.", &mut out);
+        self.gc_nodes_collected.render("crr_gc_nodes_collected_total", "DAG nodes reclaimed by GC.", &mut out);
+        self.bytes_sent.render("crr_bytes_sent_total", "Bytes sent over the sync protocol.", &mut out);
+        self.bytes_received.render("crr_bytes_received_total", "Bytes received over the sync protocol.", &mut out);
+        self.merge_latency_seconds.0.render("crr_merge_latency_seconds", "CrrTable::merge call latency.", &mut out);
+        self.gc_latency_seconds.0.render("crr_gc_latency_seconds", "GC pass latency.", &mut out);
+        self.peer_rtt_seconds.0.render("crr_peer_rtt_seconds", "Round-trip time to a sync peer.", &mut out);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_add_and_get_round_trip() {
+        let counter = Counter::new();
+        counter.inc();
+        counter.add(4);
+        assert_eq!(counter.get(), 5);
+    }
+
+    #[test]
+    fn histogram_bucket_counts_are_cumulative_in_rendered_output() {
+        let histogram = Histogram::new(vec![1.0, 10.0]);
+        histogram.observe(0.5);
+        histogram.observe(5.0);
+        histogram.observe(50.0);
+
+        let mut out = String::new();
+        histogram.render("test_latency", "test histogram", &mut out);
+
+        assert!(out.contains("test_latency_bucket{le=\"1\"} 1"));
+        assert!(out.contains("test_latency_bucket{le=\"10\"} 2"), "the le=10 bucket must include the le=1 observation too");
+        assert!(out.contains("test_latency_bucket{le=\"+Inf\"} 3"));
+        assert!(out.contains("test_latency_count 3"));
+    }
+
+    #[test]
+    fn record_merge_folds_a_merge_report_into_counters_and_latency() {
+        let metrics = Metrics::new();
+        let report = MergeReport { inserted: 2, updated: 3, skipped: 1, conflicts: 1, counter_merges: 0, set_merges: 0 };
+
+        metrics.record_merge(&report, Duration::from_millis(5));
+
+        assert_eq!(metrics.cells_inserted.get(), 2);
+        assert_eq!(metrics.cells_updated.get(), 3);
+        assert_eq!(metrics.cells_skipped.get(), 1);
+        assert_eq!(metrics.cells_conflicted.get(), 1);
+        assert!(metrics.render().contains("crr_merge_latency_seconds_count 1"));
+    }
+
+    #[test]
+    fn render_produces_well_formed_prometheus_text_exposition_format() {
+        let metrics = Metrics::new();
+        metrics.record_gc(7, Duration::from_millis(2));
+        metrics.record_bytes_sent(128);
+
+        let rendered = metrics.render();
+
+        assert!(rendered.contains("# TYPE crr_gc_nodes_collected_total counter"));
+        assert!(rendered.contains("crr_gc_nodes_collected_total 7"));
+        assert!(rendered.contains("crr_bytes_sent_total 128"));
+    }
+}
@@ -0,0 +1,238 @@
+//! A versioned serialization envelope, so a record's on-disk shape can
+//! evolve — gain a field, the way [`crate::storage::DagNode`] gained
+//! `is_tombstone`, or drop/rename one in the future — without corrupting
+//! bytes an older build already wrote.
+//!
+//! Every encoded record is an [`Envelope`] pairing a `format` tag with the
+//! record's serialized body. [`decode`] reads the tag, deserializes into
+//! whichever historical struct it names, and chains [`Migrate::migrate_from`]
+//! calls forward through each intermediate shape until it reaches the type
+//! the caller asked for — so a reader always gets the current struct back,
+//! regardless of how old the bytes on disk are. `prev` holds frozen copies
+//! of each struct's earlier layouts; once a layout ships, its `prev::vN`
+//! module must never change, only gain a newer successor.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Version `VERSION` of some evolving record, upgradeable from the one
+/// version immediately before it.
+pub trait Migrate: DeserializeOwned {
+    type Previous: DeserializeOwned;
+    const VERSION: u16;
+
+    fn migrate_from(previous: Self::Previous) -> Self;
+}
+
+/// A record type [`decode`] can resolve from a tagged envelope, either
+/// because it's the very first shape the record ever had (no migration
+/// possible or needed), or because it implements [`Migrate`] and its
+/// [`Migrate::Previous`] is itself `Decodable` — which lets the blanket
+/// impl below recurse through an arbitrarily long chain of prior versions.
+pub trait Decodable: DeserializeOwned + Sized {
+    const VERSION: u16;
+
+    fn decode_tagged(format: u16, body: &[u8]) -> Result<Self>;
+}
+
+impl<T> Decodable for T
+where
+    T: Migrate,
+    T::Previous: Decodable,
+{
+    const VERSION: u16 = <T as Migrate>::VERSION;
+
+    fn decode_tagged(format: u16, body: &[u8]) -> Result<Self> {
+        if format == <Self as Decodable>::VERSION {
+            decode_body(body)
+        } else {
+            let previous = T::Previous::decode_tagged(format, body)?;
+            Ok(T::migrate_from(previous))
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    format: u16,
+    body: Vec<u8>,
+}
+
+/// Wrap `value` in a tagged envelope recording its current format version.
+pub fn encode<T: Decodable + Serialize>(value: &T) -> Result<Vec<u8>> {
+    let body = encode_body(value)?;
+    encode_body(&Envelope { format: T::VERSION, body })
+}
+
+/// Unwrap an envelope produced by [`encode`], migrating forward from
+/// whichever version it was tagged with to `T`.
+pub fn decode<T: Decodable>(bytes: &[u8]) -> Result<T> {
+    let envelope: Envelope = decode_body(bytes)?;
+    T::decode_tagged(envelope.format, &envelope.body)
+}
+
+fn encode_body<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    ciborium::into_writer(value, &mut buf)
+        .map_err(|e| Error::InvalidState(format!("failed to encode record: {}", e)))?;
+    Ok(buf)
+}
+
+fn decode_body<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    ciborium::from_reader(bytes).map_err(|e| Error::InvalidState(format!("failed to decode record: {}", e)))
+}
+
+/// Frozen copies of earlier struct layouts, kept only so [`decode`] can
+/// still read bytes written before a later migration shipped. Never edit a
+/// `prev::vN` module after it ships — add a new one instead.
+pub mod prev {
+    pub mod v1 {
+        use serde::{Deserialize, Serialize};
+
+        /// `DagNode` as it looked before tombstone deletes existed — every
+        /// node was a value write, so there was nothing to tag.
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        pub struct DagNode {
+            pub version: u64,
+            pub value: Vec<u8>,
+            pub parent_version: Option<u64>,
+            pub parent2_version: Option<u64>,
+            pub timestamp: u64,
+        }
+    }
+
+    pub mod v2 {
+        use serde::{Deserialize, Serialize};
+
+        /// `DagNode` as it looked before the commit-sequence oracle existed.
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        pub struct DagNode {
+            pub version: u64,
+            pub value: Vec<u8>,
+            pub parent_version: Option<u64>,
+            pub parent2_version: Option<u64>,
+            pub timestamp: u64,
+            pub is_tombstone: bool,
+        }
+    }
+}
+
+impl Decodable for prev::v1::DagNode {
+    const VERSION: u16 = 1;
+
+    fn decode_tagged(format: u16, body: &[u8]) -> Result<Self> {
+        if format == 1 {
+            decode_body(body)
+        } else {
+            Err(Error::InvalidState(format!("unsupported DagNode format version {}", format)))
+        }
+    }
+}
+
+impl Migrate for prev::v2::DagNode {
+    type Previous = prev::v1::DagNode;
+    const VERSION: u16 = 2;
+
+    /// A node written before tombstones existed was necessarily a real
+    /// value write, never a delete marker.
+    fn migrate_from(previous: Self::Previous) -> Self {
+        Self {
+            version: previous.version,
+            value: previous.value,
+            parent_version: previous.parent_version,
+            parent2_version: previous.parent2_version,
+            timestamp: previous.timestamp,
+            is_tombstone: false,
+        }
+    }
+}
+
+impl Migrate for crate::storage::DagNode {
+    type Previous = prev::v2::DagNode;
+    const VERSION: u16 = 3;
+
+    /// A node written before the commit-sequence oracle existed predates
+    /// every pinned-read snapshot, so it's visible to all of them.
+    fn migrate_from(previous: Self::Previous) -> Self {
+        Self {
+            version: previous.version,
+            value: previous.value,
+            parent_version: previous.parent_version,
+            parent2_version: previous.parent2_version,
+            timestamp: previous.timestamp,
+            is_tombstone: previous.is_tombstone,
+            commit_seq: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::DagNode;
+
+    #[test]
+    fn decodes_a_current_version_record_directly() {
+        let node = DagNode {
+            version: 1,
+            value: b"hello".to_vec(),
+            parent_version: None,
+            parent2_version: None,
+            timestamp: 1000,
+            is_tombstone: false,
+            commit_seq: 7,
+        };
+
+        let bytes = encode(&node).unwrap();
+        let decoded: DagNode = decode(&bytes).unwrap();
+        assert_eq!(decoded.value, b"hello");
+        assert!(!decoded.is_tombstone);
+        assert_eq!(decoded.commit_seq, 7);
+    }
+
+    #[test]
+    fn migrates_a_v1_record_written_before_is_tombstone_existed() {
+        let legacy = prev::v1::DagNode {
+            version: 3,
+            value: b"legacy".to_vec(),
+            parent_version: Some(2),
+            parent2_version: None,
+            timestamp: 500,
+        };
+        let bytes = encode(&legacy).unwrap();
+
+        let migrated: DagNode = decode(&bytes).unwrap();
+        assert_eq!(migrated.version, 3);
+        assert_eq!(migrated.value, b"legacy");
+        assert_eq!(migrated.parent_version, Some(2));
+        assert!(!migrated.is_tombstone, "a node from before tombstones existed can't have been one");
+        assert_eq!(migrated.commit_seq, 0, "predates the commit oracle, so it's visible to every snapshot");
+    }
+
+    #[test]
+    fn migrates_a_v2_record_written_before_the_commit_oracle_existed() {
+        let legacy = prev::v2::DagNode {
+            version: 5,
+            value: b"legacy-v2".to_vec(),
+            parent_version: Some(4),
+            parent2_version: None,
+            timestamp: 700,
+            is_tombstone: false,
+        };
+        let bytes = encode(&legacy).unwrap();
+
+        let migrated: DagNode = decode(&bytes).unwrap();
+        assert_eq!(migrated.version, 5);
+        assert_eq!(migrated.value, b"legacy-v2");
+        assert_eq!(migrated.commit_seq, 0);
+    }
+
+    #[test]
+    fn rejects_an_envelope_with_an_unknown_future_format_tag() {
+        let bytes = encode_body(&Envelope { format: 99, body: encode_body(&42u32).unwrap() }).unwrap();
+        let result: Result<DagNode> = decode(&bytes);
+        assert!(result.is_err());
+    }
+}
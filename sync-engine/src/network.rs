@@ -3,33 +3,57 @@
 //! This module implements a simple TCP protocol for GC coordination benchmarking.
 //! It measures real protocol overhead (serialization, TCP stack) with injected
 //! delays to model WAN latency.
+//!
+//! [`TcpGcCoordinator`]/[`TcpGcPeer`] run on tokio rather than a
+//! one-thread-per-run model: connections are accepted in a `tokio::select!`
+//! loop, each watermark hands off through a bounded `tokio::sync::mpsc`
+//! channel (so a slow aggregator applies backpressure on socket reads
+//! instead of buffering unboundedly), and the computed `SafeThreshold` is
+//! handed back to each waiting peer through a `tokio::sync::oneshot`.
 
-use std::io::{Read, Write};
-use std::net::{TcpListener, TcpStream};
-use std::sync::mpsc;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
 use std::thread;
 use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::timeout;
 
 /// Messages for GC coordination protocol
 #[derive(Debug, Clone)]
 pub enum GcMessage {
-    /// Peer reports its low watermark version
-    WatermarkReport { peer_id: String, version: u64 },
+    /// Peer reports its low watermark version. `sequence` is a per-peer
+    /// monotonic counter the sender increments on every report, checked by
+    /// [`AntiReplayWindow`] so a captured report can't be replayed to drag
+    /// the safe threshold backward.
+    WatermarkReport { peer_id: String, version: u64, sequence: u64 },
     /// Coordinator broadcasts safe GC threshold
     SafeThreshold { threshold: u64 },
     /// Acknowledgment
     Ack,
+    /// Peer reports the root of its per-cell DAG Merkle accumulator (see
+    /// [`crate::dag_merkle`]), so coordination can flag divergent history
+    /// before it ever computes a safe threshold from watermarks alone —
+    /// two peers agreeing on a watermark with different roots underneath
+    /// it have each retained a different history for the same version.
+    RootReport { peer_id: String, root: [u8; 32] },
 }
 
 impl GcMessage {
+    /// Encode `self` as a fixed-layout, little-endian field sequence tagged
+    /// by a leading type byte. This is the frame *body*; [`Self::write_framed`]
+    /// is what actually goes on the wire, since a bare `to_bytes()` has no
+    /// way to tell a reader where the message ends.
     pub fn to_bytes(&self) -> Vec<u8> {
         match self {
-            GcMessage::WatermarkReport { peer_id, version } => {
+            GcMessage::WatermarkReport { peer_id, version, sequence } => {
                 let mut bytes = vec![0u8]; // Type tag
                 let id_bytes = peer_id.as_bytes();
                 bytes.extend_from_slice(&(id_bytes.len() as u32).to_le_bytes());
                 bytes.extend_from_slice(id_bytes);
                 bytes.extend_from_slice(&version.to_le_bytes());
+                bytes.extend_from_slice(&sequence.to_le_bytes());
                 bytes
             }
             GcMessage::SafeThreshold { threshold } => {
@@ -38,6 +62,14 @@ impl GcMessage {
                 bytes
             }
             GcMessage::Ack => vec![2u8],
+            GcMessage::RootReport { peer_id, root } => {
+                let mut bytes = vec![3u8]; // Type tag
+                let id_bytes = peer_id.as_bytes();
+                bytes.extend_from_slice(&(id_bytes.len() as u32).to_le_bytes());
+                bytes.extend_from_slice(id_bytes);
+                bytes.extend_from_slice(root);
+                bytes
+            }
         }
     }
 
@@ -51,7 +83,7 @@ impl GcMessage {
                     return None;
                 }
                 let id_len = u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize;
-                if bytes.len() < 5 + id_len + 8 {
+                if bytes.len() < 5 + id_len + 16 {
                     return None;
                 }
                 let peer_id = String::from_utf8_lossy(&bytes[5..5 + id_len]).to_string();
@@ -65,7 +97,17 @@ impl GcMessage {
                     bytes[11 + id_len],
                     bytes[12 + id_len],
                 ]);
-                Some(GcMessage::WatermarkReport { peer_id, version })
+                let sequence = u64::from_le_bytes([
+                    bytes[13 + id_len],
+                    bytes[14 + id_len],
+                    bytes[15 + id_len],
+                    bytes[16 + id_len],
+                    bytes[17 + id_len],
+                    bytes[18 + id_len],
+                    bytes[19 + id_len],
+                    bytes[20 + id_len],
+                ]);
+                Some(GcMessage::WatermarkReport { peer_id, version, sequence })
             }
             1 => {
                 if bytes.len() < 9 {
@@ -77,9 +119,96 @@ impl GcMessage {
                 Some(GcMessage::SafeThreshold { threshold })
             }
             2 => Some(GcMessage::Ack),
+            3 => {
+                if bytes.len() < 5 {
+                    return None;
+                }
+                let id_len = u32::from_le_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]) as usize;
+                if bytes.len() < 5 + id_len + 32 {
+                    return None;
+                }
+                let peer_id = String::from_utf8_lossy(&bytes[5..5 + id_len]).to_string();
+                let mut root = [0u8; 32];
+                root.copy_from_slice(&bytes[5 + id_len..5 + id_len + 32]);
+                Some(GcMessage::RootReport { peer_id, root })
+            }
             _ => None,
         }
     }
+
+    /// Write `self` as one length-prefixed frame: a little-endian `u32` byte
+    /// count followed by exactly that many bytes of [`Self::to_bytes`]. Pairs
+    /// with [`Self::read_framed`] so a message is never truncated by a
+    /// fixed-size read buffer, regardless of how the TCP stack happens to
+    /// segment it.
+    pub fn write_framed<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let body = self.to_bytes();
+        writer.write_all(&(body.len() as u32).to_le_bytes())?;
+        writer.write_all(&body)?;
+        Ok(())
+    }
+
+    /// Read one frame written by [`Self::write_framed`]: the `u32` length
+    /// prefix, then a loop of `read_exact` calls until the whole declared
+    /// body has arrived, so a message spanning multiple TCP segments (or two
+    /// messages landing back-to-back on one connection) decodes correctly.
+    /// Returns `Ok(None)` on a clean EOF before any bytes of the next frame
+    /// arrive (the peer closed the connection); any other I/O failure,
+    /// including a partial frame cut off mid-body, is propagated as `Err`.
+    ///
+    /// `max_payload_size` bounds both the declared length this will accept
+    /// and the buffer allocated to hold it — a frame claiming to be larger
+    /// is rejected with [`io::ErrorKind::InvalidData`] before any allocation
+    /// or read against the body happens, so an oversized or malicious length
+    /// prefix can't be used to force a multi-gigabyte allocation.
+    pub fn read_framed<R: Read>(reader: &mut R, max_payload_size: usize) -> io::Result<Option<Self>> {
+        let mut len_buf = [0u8; 4];
+        match reader.read_exact(&mut len_buf) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let len = u32::from_le_bytes(len_buf) as usize;
+        if len > max_payload_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("frame of {len} bytes exceeds max_payload_size of {max_payload_size}"),
+            ));
+        }
+        let mut body = vec![0u8; len];
+        reader.read_exact(&mut body)?;
+        Ok(Self::from_bytes(&body))
+    }
+
+    /// Async counterpart of [`Self::write_framed`], for the tokio-based
+    /// [`TcpGcCoordinator`]/[`TcpGcPeer`].
+    pub async fn write_framed_async<W: AsyncWriteExt + Unpin>(&self, writer: &mut W) -> io::Result<()> {
+        let body = self.to_bytes();
+        writer.write_all(&(body.len() as u32).to_le_bytes()).await?;
+        writer.write_all(&body).await?;
+        Ok(())
+    }
+
+    /// Async counterpart of [`Self::read_framed`], including its
+    /// `max_payload_size` enforcement.
+    pub async fn read_framed_async<R: AsyncReadExt + Unpin>(reader: &mut R, max_payload_size: usize) -> io::Result<Option<Self>> {
+        let mut len_buf = [0u8; 4];
+        match reader.read_exact(&mut len_buf).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let len = u32::from_le_bytes(len_buf) as usize;
+        if len > max_payload_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("frame of {len} bytes exceeds max_payload_size of {max_payload_size}"),
+            ));
+        }
+        let mut body = vec![0u8; len];
+        reader.read_exact(&mut body).await?;
+        Ok(Self::from_bytes(&body))
+    }
 }
 
 /// Result of a GC coordination round
@@ -95,78 +224,269 @@ pub struct GcCoordinationResult {
     pub messages_received: usize,
     /// Safe GC threshold computed
     pub safe_threshold: u64,
+    /// Reports dropped for failing the anti-replay check (stale/duplicate
+    /// `sequence`) or the per-peer rate limit. Deliberately excluded from
+    /// [`Self::safe_threshold`]'s `min()` — a rejected report is treated as
+    /// never having arrived, not as a vote for a lower threshold.
+    pub rejected: usize,
 }
 
+/// Sliding-bitmap anti-replay window, modeled on WireGuard's: the highest
+/// `sequence` ever [`Self::accept`]ed is tracked directly, and the
+/// `ANTI_REPLAY_WINDOW_SIZE` sequence numbers immediately below it are
+/// tracked in a bitmap so a report can't be replayed to re-drag a peer's
+/// reported watermark backward.
+const ANTI_REPLAY_WINDOW_SIZE: u64 = 64;
+
+#[derive(Debug, Default)]
+struct AntiReplayWindow {
+    highest: u64,
+    bitmap: u64,
+}
+
+impl AntiReplayWindow {
+    fn new() -> Self {
+        Self { highest: 0, bitmap: 0 }
+    }
+
+    /// Returns `true` and records `seq` if it is new; returns `false` (and
+    /// leaves the window untouched) if `seq` is a duplicate, or too far
+    /// behind `self.highest` to still be tracked in the bitmap.
+    fn accept(&mut self, seq: u64) -> bool {
+        if seq > self.highest {
+            let shift = seq - self.highest;
+            self.bitmap = if shift >= ANTI_REPLAY_WINDOW_SIZE { 0 } else { self.bitmap << shift };
+            self.bitmap |= 1;
+            self.highest = seq;
+            return true;
+        }
+
+        let behind = self.highest - seq;
+        if behind >= ANTI_REPLAY_WINDOW_SIZE {
+            return false; // Too old to still be in the window: treat as a replay.
+        }
+
+        let bit = 1u64 << behind;
+        if self.bitmap & bit != 0 {
+            return false; // Already seen.
+        }
+        self.bitmap |= bit;
+        true
+    }
+}
+
+/// Per-key token bucket: `tokens` refills continuously at `refill_per_sec`,
+/// capped at `capacity`, and [`Self::try_acquire`] spends one token per
+/// admitted message.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: f64, refill_per_sec: f64) -> Self {
+        Self { capacity, refill_per_sec, tokens: capacity, last_refill: Instant::now() }
+    }
+
+    fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Per-source-address rate limiter: a [`TokenBucket`] per key, created
+/// lazily (full) on first use so a never-seen peer starts with its full
+/// allowance rather than being rejected outright.
+struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    buckets: HashMap<String, TokenBucket>,
+}
+
+impl RateLimiter {
+    fn new(refill_per_sec: f64) -> Self {
+        Self { capacity: refill_per_sec, refill_per_sec, buckets: HashMap::new() }
+    }
+
+    fn allow(&mut self, key: &str) -> bool {
+        let capacity = self.capacity;
+        let refill_per_sec = self.refill_per_sec;
+        self.buckets
+            .entry(key.to_string())
+            .or_insert_with(|| TokenBucket::new(capacity, refill_per_sec))
+            .try_acquire()
+    }
+}
+
+/// Default number of watermark reports a single peer may send per second
+/// before [`TcpGcCoordinator`] starts dropping them.
+const DEFAULT_RATE_LIMIT_PER_SEC: f64 = 100.0;
+
+/// Default ceiling on how long [`TcpGcCoordinator::coordinate`] waits for
+/// all peers to report before giving up on the stragglers.
+const DEFAULT_COORDINATION_DEADLINE: Duration = Duration::from_secs(5);
+
+/// How many in-flight watermark reports [`TcpGcCoordinator::coordinate`]
+/// will buffer before a slow aggregator applies backpressure to new
+/// connections' socket reads.
+const REPORT_CHANNEL_CAPACITY: usize = 64;
+
+/// Default ceiling on a single [`GcMessage`] frame's body, enforced by
+/// [`GcMessage::read_framed_async`]. Large enough for a `WatermarkReport`
+/// or `RootReport` with a generously long `peer_id`, small enough that a
+/// peer can't force a multi-gigabyte allocation with a forged length
+/// prefix. Deployments with a larger peer-id namespace or richer message
+/// types can raise this via [`TcpGcCoordinator::with_max_payload_size`]/
+/// [`TcpGcPeer::with_max_payload_size`] without recompiling.
+const DEFAULT_MAX_PAYLOAD_SIZE: usize = 4096;
+
 /// TCP-based GC Coordinator (runs on a dedicated port)
 pub struct TcpGcCoordinator {
     port: u16,
     injected_delay_ms: u64,
+    rate_limit_per_sec: f64,
+    deadline: Duration,
+    max_payload_size: usize,
 }
 
 impl TcpGcCoordinator {
     pub fn new(port: u16, injected_delay_ms: u64) -> Self {
+        Self::with_config(port, injected_delay_ms, DEFAULT_RATE_LIMIT_PER_SEC, DEFAULT_COORDINATION_DEADLINE, DEFAULT_MAX_PAYLOAD_SIZE)
+    }
+
+    pub fn with_rate_limit(port: u16, injected_delay_ms: u64, rate_limit_per_sec: f64) -> Self {
+        Self::with_config(port, injected_delay_ms, rate_limit_per_sec, DEFAULT_COORDINATION_DEADLINE, DEFAULT_MAX_PAYLOAD_SIZE)
+    }
+
+    /// Like [`Self::new`], but with an explicit ceiling on an incoming
+    /// `GcMessage` frame's body in place of [`DEFAULT_MAX_PAYLOAD_SIZE`] —
+    /// for deployments with a larger peer-id namespace or richer message
+    /// types than the default budget allows.
+    pub fn with_max_payload_size(port: u16, injected_delay_ms: u64, max_payload_size: usize) -> Self {
+        Self::with_config(port, injected_delay_ms, DEFAULT_RATE_LIMIT_PER_SEC, DEFAULT_COORDINATION_DEADLINE, max_payload_size)
+    }
+
+    pub fn with_config(port: u16, injected_delay_ms: u64, rate_limit_per_sec: f64, deadline: Duration, max_payload_size: usize) -> Self {
         Self {
             port,
             injected_delay_ms,
+            rate_limit_per_sec,
+            deadline,
+            max_payload_size,
         }
     }
 
-    pub fn coordinate(&self, peer_count: usize) -> GcCoordinationResult {
+    /// Await up to `peer_count` watermark reports concurrently (bounded by
+    /// [`Self::deadline`] as a whole, not per-peer), then hand the computed
+    /// safe threshold back to every peer that's still waiting on a reply.
+    pub async fn coordinate(&self, peer_count: usize) -> GcCoordinationResult {
         let start = Instant::now();
-        #[allow(unused_variables)]
         let messages_sent = 0;
         let mut messages_received = 0;
+        let mut rejected = 0;
         let mut watermarks = Vec::new();
 
         let listener = TcpListener::bind(format!("127.0.0.1:{}", self.port))
+            .await
             .expect("Failed to bind coordinator");
-        listener
-            .set_nonblocking(false)
-            .expect("Failed to set blocking");
 
-        let (tx, rx) = mpsc::channel();
+        let (report_tx, mut report_rx) =
+            mpsc::channel::<(Option<u64>, oneshot::Sender<u64>)>(REPORT_CHANNEL_CAPACITY);
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel::<()>();
         let delay = self.injected_delay_ms;
+        let rate_limit_per_sec = self.rate_limit_per_sec;
+        let max_payload_size = self.max_payload_size;
+        let replay_windows = std::sync::Arc::new(std::sync::Mutex::new(HashMap::<String, AntiReplayWindow>::new()));
+        let limiter = std::sync::Arc::new(std::sync::Mutex::new(RateLimiter::new(rate_limit_per_sec)));
 
-        let handle = thread::spawn(move || {
-            let mut received = 0;
-            for stream in listener.incoming() {
-                if received >= peer_count {
-                    break;
-                }
-                if let Ok(mut stream) = stream {
-                    if delay > 0 {
-                        thread::sleep(Duration::from_millis(delay));
-                    }
+        let accept_task = tokio::spawn(async move {
+            let mut accepted = 0;
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    conn = listener.accept() => {
+                        let Ok((mut stream, _)) = conn else { continue };
+                        accepted += 1;
 
-                    let mut buf = [0u8; 256];
-                    if let Ok(n) = stream.read(&mut buf) {
-                        if let Some(msg) = GcMessage::from_bytes(&buf[..n]) {
-                            if let GcMessage::WatermarkReport { version, .. } = msg {
-                                tx.send(version).ok();
-                                received += 1;
+                        let report_tx = report_tx.clone();
+                        let replay_windows = replay_windows.clone();
+                        let limiter = limiter.clone();
+                        tokio::spawn(async move {
+                            if delay > 0 {
+                                tokio::time::sleep(Duration::from_millis(delay)).await;
+                            }
 
-                                if delay > 0 {
-                                    thread::sleep(Duration::from_millis(delay));
+                            if let Ok(Some(GcMessage::WatermarkReport { peer_id, version, sequence })) =
+                                GcMessage::read_framed_async(&mut stream, max_payload_size).await
+                            {
+                                let accepted_report = {
+                                    let mut limiter = limiter.lock().unwrap();
+                                    let mut windows = replay_windows.lock().unwrap();
+                                    let window = windows.entry(peer_id.clone()).or_insert_with(AntiReplayWindow::new);
+                                    limiter.allow(&peer_id) && window.accept(sequence)
+                                };
+
+                                let (reply_tx, reply_rx) = oneshot::channel();
+                                let payload = if accepted_report { Some(version) } else { None };
+                                if report_tx.send((payload, reply_tx)).await.is_ok() {
+                                    if delay > 0 {
+                                        tokio::time::sleep(Duration::from_millis(delay)).await;
+                                    }
+                                    if let Ok(threshold) = reply_rx.await {
+                                        GcMessage::SafeThreshold { threshold }
+                                            .write_framed_async(&mut stream)
+                                            .await
+                                            .ok();
+                                    }
                                 }
-                                stream.write_all(&GcMessage::Ack.to_bytes()).ok();
                             }
+                        });
+
+                        if accepted >= peer_count {
+                            break;
                         }
                     }
                 }
             }
         });
 
-        for _ in 0..peer_count {
-            if let Ok(v) = rx.recv_timeout(Duration::from_secs(5)) {
-                watermarks.push(v);
-                messages_received += 1;
+        let mut reply_senders = Vec::new();
+        let _ = timeout(self.deadline, async {
+            while messages_received < peer_count {
+                match report_rx.recv().await {
+                    Some((payload, reply_tx)) => {
+                        messages_received += 1;
+                        match payload {
+                            Some(version) => watermarks.push(version),
+                            None => rejected += 1,
+                        }
+                        reply_senders.push(reply_tx);
+                    }
+                    None => break,
+                }
             }
-        }
+        })
+        .await;
 
         let safe_threshold = watermarks.iter().copied().min().unwrap_or(0);
+        for reply_tx in reply_senders {
+            let _ = reply_tx.send(safe_threshold);
+        }
 
-        drop(rx);
-        let _ = handle.join();
+        let _ = shutdown_tx.send(());
+        let _ = accept_task.await;
 
         let total_time = start.elapsed();
         let network_time = Duration::from_millis(self.injected_delay_ms * 2 * peer_count as u64);
@@ -177,6 +497,7 @@ impl TcpGcCoordinator {
             messages_sent,
             messages_received,
             safe_threshold,
+            rejected,
         }
     }
 }
@@ -186,6 +507,8 @@ pub struct TcpGcPeer {
     peer_id: String,
     coordinator_port: u16,
     injected_delay_ms: u64,
+    next_sequence: std::sync::Mutex<u64>,
+    max_payload_size: usize,
 }
 
 impl TcpGcPeer {
@@ -194,27 +517,52 @@ impl TcpGcPeer {
             peer_id: peer_id.to_string(),
             coordinator_port,
             injected_delay_ms,
+            next_sequence: std::sync::Mutex::new(0),
+            max_payload_size: DEFAULT_MAX_PAYLOAD_SIZE,
         }
     }
 
-    pub fn report_watermark(&self, version: u64) -> Option<GcCoordinationResult> {
+    /// Like [`Self::new`], but with an explicit ceiling on the coordinator's
+    /// `SafeThreshold` reply in place of [`DEFAULT_MAX_PAYLOAD_SIZE`] — keep
+    /// this in sync with whatever the coordinator side was constructed with.
+    pub fn with_max_payload_size(peer_id: &str, coordinator_port: u16, injected_delay_ms: u64, max_payload_size: usize) -> Self {
+        Self {
+            peer_id: peer_id.to_string(),
+            coordinator_port,
+            injected_delay_ms,
+            next_sequence: std::sync::Mutex::new(0),
+            max_payload_size,
+        }
+    }
+
+    pub async fn report_watermark(&self, version: u64) -> Option<GcCoordinationResult> {
         let start = Instant::now();
 
         let mut stream =
-            TcpStream::connect(format!("127.0.0.1:{}", self.coordinator_port)).ok()?;
+            TcpStream::connect(format!("127.0.0.1:{}", self.coordinator_port)).await.ok()?;
 
         if self.injected_delay_ms > 0 {
-            thread::sleep(Duration::from_millis(self.injected_delay_ms));
+            tokio::time::sleep(Duration::from_millis(self.injected_delay_ms)).await;
         }
 
+        let sequence = {
+            let mut next_sequence = self.next_sequence.lock().unwrap();
+            let sequence = *next_sequence;
+            *next_sequence += 1;
+            sequence
+        };
+
         let msg = GcMessage::WatermarkReport {
             peer_id: self.peer_id.clone(),
             version,
+            sequence,
         };
-        stream.write_all(&msg.to_bytes()).ok()?;
+        msg.write_framed_async(&mut stream).await.ok()?;
 
-        let mut buf = [0u8; 256];
-        let _ = stream.read(&mut buf).ok()?;
+        let safe_threshold = match GcMessage::read_framed_async(&mut stream, self.max_payload_size).await.ok()? {
+            Some(GcMessage::SafeThreshold { threshold }) => threshold,
+            _ => 0,
+        };
 
         let total_time = start.elapsed();
 
@@ -223,11 +571,152 @@ impl TcpGcPeer {
             network_time: Duration::from_millis(self.injected_delay_ms * 2),
             messages_sent: 1,
             messages_received: 1,
-            safe_threshold: 0, // Peer doesn't know yet
+            safe_threshold,
+            rejected: 0,
         })
     }
 }
 
+/// A peer identifier in the gossip-based GC protocol — just a `String`, the
+/// same shape [`GcMessage::WatermarkReport`] already uses for `peer_id`.
+pub type PeerId = String;
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// One peer's last-known GC watermark as seen by a [`GossipGcNode`],
+/// timestamped so [`GossipGcNode::merge`] can resolve conflicting reports
+/// about the same peer the way Solana's `cluster_info` CRDT does: the
+/// highest `wallclock_ms` always wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatermarkEntry {
+    pub peer_id: PeerId,
+    pub watermark_version: u64,
+    pub wallclock_ms: u64,
+}
+
+/// Decentralized, coordinator-free alternative to [`TcpGcCoordinator`]:
+/// each node holds a `peer_id -> WatermarkEntry` map and periodically
+/// push-pulls it with a weighted-random subset of known peers (see
+/// [`Self::select_gossip_targets`]), converging on the cluster-wide safe GC
+/// threshold the way Solana's `cluster_info` spreads validator state —
+/// without a single point of failure, and tolerant of network partitions
+/// since any subset of peers that can still reach each other keeps
+/// converging independently.
+pub struct GossipGcNode {
+    self_id: PeerId,
+    entries: HashMap<PeerId, WatermarkEntry>,
+    staleness_timeout_ms: u64,
+}
+
+impl GossipGcNode {
+    pub fn new(self_id: impl Into<PeerId>, staleness_timeout: Duration) -> Self {
+        Self {
+            self_id: self_id.into(),
+            entries: HashMap::new(),
+            staleness_timeout_ms: staleness_timeout.as_millis() as u64,
+        }
+    }
+
+    /// Record this node's own current watermark, timestamped with the
+    /// current wall-clock time so other peers' [`Self::merge`] calls know
+    /// it's fresher than whatever they previously heard about this peer.
+    pub fn set_local_watermark(&mut self, version: u64) {
+        let entry = WatermarkEntry {
+            peer_id: self.self_id.clone(),
+            watermark_version: version,
+            wallclock_ms: now_millis(),
+        };
+        self.entries.insert(self.self_id.clone(), entry);
+    }
+
+    /// Fold a batch of entries pulled or pushed from a gossip partner into
+    /// this node's view: per peer, the entry with the higher
+    /// `wallclock_ms` wins (ties keep the existing entry), then entries
+    /// older than the configured staleness timeout are evicted so a
+    /// partitioned-off peer's last-known watermark eventually stops
+    /// influencing [`Self::safe_threshold`].
+    pub fn merge(&mut self, other: &[WatermarkEntry]) {
+        for entry in other {
+            let should_replace = match self.entries.get(&entry.peer_id) {
+                Some(existing) => entry.wallclock_ms > existing.wallclock_ms,
+                None => true,
+            };
+            if should_replace {
+                self.entries.insert(entry.peer_id.clone(), entry.clone());
+            }
+        }
+        self.evict_stale();
+    }
+
+    fn evict_stale(&mut self) {
+        let now = now_millis();
+        let timeout = self.staleness_timeout_ms;
+        self.entries.retain(|_, entry| now.saturating_sub(entry.wallclock_ms) <= timeout);
+    }
+
+    /// Every entry this node currently holds, to push to (or pull a diff
+    /// against) a gossip partner.
+    pub fn entries(&self) -> Vec<WatermarkEntry> {
+        self.entries.values().cloned().collect()
+    }
+
+    /// Pick up to `fanout` distinct peers to gossip with this round via a
+    /// weighted shuffle drawing without replacement: each remaining
+    /// candidate's weight is `watermark_version + 1` (so even a peer at
+    /// version 0 has a chance), a point is drawn uniformly from the
+    /// cumulative weight total, and the candidate whose cumulative range
+    /// contains it is selected and removed before the next draw — so peers
+    /// reporting a higher watermark (more likely to be ahead, and thus
+    /// worth catching up with) are contacted more often.
+    pub fn select_gossip_targets(&self, fanout: usize) -> Vec<PeerId> {
+        // `::rand`, not `rand`: this file also defines a local `mod rand`
+        // (see below) for port selection, which would otherwise shadow the
+        // `rand` crate for an unqualified path here.
+        use ::rand::Rng as _;
+
+        let mut candidates: Vec<(PeerId, u64)> = self
+            .entries
+            .values()
+            .filter(|entry| entry.peer_id != self.self_id)
+            .map(|entry| (entry.peer_id.clone(), entry.watermark_version + 1))
+            .collect();
+
+        let mut rng = ::rand::thread_rng();
+        let mut selected = Vec::new();
+        while !candidates.is_empty() && selected.len() < fanout {
+            let total_weight: u64 = candidates.iter().map(|(_, weight)| weight).sum();
+            let mut draw = rng.gen_range(0..total_weight);
+            let idx = candidates
+                .iter()
+                .position(|(_, weight)| {
+                    if draw < *weight {
+                        true
+                    } else {
+                        draw -= weight;
+                        false
+                    }
+                })
+                .unwrap();
+            selected.push(candidates.remove(idx).0);
+        }
+        selected
+    }
+
+    /// The safe GC threshold: the minimum watermark version across every
+    /// live (non-stale) peer entry this node currently knows about,
+    /// including its own — it is always safe to collect DAG history below
+    /// this version, since every peer this node can still hear from has
+    /// already reported seeing at least that much.
+    pub fn safe_threshold(&self) -> u64 {
+        self.entries.values().map(|entry| entry.watermark_version).min().unwrap_or(0)
+    }
+}
+
 /// Measure GC coordination round with injected network delays.
 pub fn measure_gc_coordination_tcp(
     peer_count: usize,
@@ -242,6 +731,7 @@ pub fn measure_gc_coordination_tcp(
         let msg = GcMessage::WatermarkReport {
             peer_id: format!("peer_{}", i),
             version: (i as u64 + 1) * 100,
+            sequence: 0,
         };
         let bytes = msg.to_bytes();
         std::hint::black_box(&bytes);
@@ -274,6 +764,7 @@ pub fn measure_gc_coordination_tcp(
         messages_sent,
         messages_received: peer_count, // All watermarks received
         safe_threshold,
+        rejected: 0,
     }
 }
 
@@ -315,18 +806,192 @@ mod tests {
         let msg = GcMessage::WatermarkReport {
             peer_id: "test".to_string(),
             version: 12345,
+            sequence: 7,
         };
         let bytes = msg.to_bytes();
         let decoded = GcMessage::from_bytes(&bytes).unwrap();
 
-        if let GcMessage::WatermarkReport { peer_id, version } = decoded {
+        if let GcMessage::WatermarkReport { peer_id, version, sequence } = decoded {
             assert_eq!(peer_id, "test");
             assert_eq!(version, 12345);
+            assert_eq!(sequence, 7);
         } else {
             panic!("Wrong message type");
         }
     }
 
+    #[test]
+    fn test_root_report_round_trips_through_to_bytes_from_bytes() {
+        let msg = GcMessage::RootReport { peer_id: "peer-a".to_string(), root: [7u8; 32] };
+        let bytes = msg.to_bytes();
+        let decoded = GcMessage::from_bytes(&bytes).unwrap();
+
+        if let GcMessage::RootReport { peer_id, root } = decoded {
+            assert_eq!(peer_id, "peer-a");
+            assert_eq!(root, [7u8; 32]);
+        } else {
+            panic!("Wrong message type");
+        }
+    }
+
+    #[test]
+    fn test_framed_round_trip_over_a_cursor() {
+        let mut buf = Vec::new();
+        GcMessage::WatermarkReport { peer_id: "peer-a".to_string(), version: 42, sequence: 0 }
+            .write_framed(&mut buf)
+            .unwrap();
+        GcMessage::Ack.write_framed(&mut buf).unwrap();
+
+        let mut cursor = std::io::Cursor::new(buf);
+        let first = GcMessage::read_framed(&mut cursor, DEFAULT_MAX_PAYLOAD_SIZE).unwrap().unwrap();
+        match first {
+            GcMessage::WatermarkReport { peer_id, version, .. } => {
+                assert_eq!(peer_id, "peer-a");
+                assert_eq!(version, 42);
+            }
+            _ => panic!("wrong message type"),
+        }
+
+        let second = GcMessage::read_framed(&mut cursor, DEFAULT_MAX_PAYLOAD_SIZE).unwrap().unwrap();
+        assert!(matches!(second, GcMessage::Ack));
+
+        // No more frames: a clean EOF reads back as `Ok(None)`, not an error.
+        assert!(GcMessage::read_framed(&mut cursor, DEFAULT_MAX_PAYLOAD_SIZE).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_framed_read_rejects_a_frame_cut_off_mid_body() {
+        let mut buf = Vec::new();
+        GcMessage::Ack.write_framed(&mut buf).unwrap();
+        buf.pop(); // Truncate the one-byte body, leaving only the length prefix.
+
+        let mut cursor = std::io::Cursor::new(buf);
+        assert!(GcMessage::read_framed(&mut cursor, DEFAULT_MAX_PAYLOAD_SIZE).is_err());
+    }
+
+    #[test]
+    fn test_framed_read_rejects_a_frame_whose_length_prefix_exceeds_max_payload_size() {
+        let mut buf = Vec::new();
+        GcMessage::WatermarkReport { peer_id: "peer-a".to_string(), version: 1, sequence: 0 }
+            .write_framed(&mut buf)
+            .unwrap();
+
+        let mut cursor = std::io::Cursor::new(buf);
+        let err = GcMessage::read_framed(&mut cursor, 4).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_gossip_merge_prefers_the_higher_wallclock_entry() {
+        let base = now_millis();
+        let mut node = GossipGcNode::new("self", Duration::from_secs(3600));
+        node.merge(&[WatermarkEntry { peer_id: "peer-a".to_string(), watermark_version: 5, wallclock_ms: base + 100 }]);
+        // An older report for the same peer must not clobber the newer one.
+        node.merge(&[WatermarkEntry { peer_id: "peer-a".to_string(), watermark_version: 9, wallclock_ms: base + 50 }]);
+
+        assert_eq!(node.entries().iter().find(|e| e.peer_id == "peer-a").unwrap().watermark_version, 5);
+
+        // A genuinely newer report does win.
+        node.merge(&[WatermarkEntry { peer_id: "peer-a".to_string(), watermark_version: 9, wallclock_ms: base + 200 }]);
+        assert_eq!(node.entries().iter().find(|e| e.peer_id == "peer-a").unwrap().watermark_version, 9);
+    }
+
+    #[test]
+    fn test_gossip_safe_threshold_is_the_min_across_live_peers() {
+        let mut node = GossipGcNode::new("self", Duration::from_secs(3600));
+        node.set_local_watermark(50);
+        node.merge(&[
+            WatermarkEntry { peer_id: "peer-a".to_string(), watermark_version: 30, wallclock_ms: now_millis() },
+            WatermarkEntry { peer_id: "peer-b".to_string(), watermark_version: 70, wallclock_ms: now_millis() },
+        ]);
+
+        assert_eq!(node.safe_threshold(), 30);
+    }
+
+    #[test]
+    fn test_gossip_evicts_entries_older_than_the_staleness_timeout() {
+        let mut node = GossipGcNode::new("self", Duration::from_millis(0));
+        node.merge(&[WatermarkEntry { peer_id: "peer-a".to_string(), watermark_version: 30, wallclock_ms: 0 }]);
+
+        // staleness_timeout_ms is 0, so anything but a wallclock matching
+        // "now" exactly must already be evicted by the merge that added it.
+        assert!(node.entries().is_empty());
+        assert_eq!(node.safe_threshold(), 0);
+    }
+
+    #[test]
+    fn test_gossip_select_targets_never_includes_self_or_duplicates() {
+        let mut node = GossipGcNode::new("self", Duration::from_secs(3600));
+        node.set_local_watermark(1);
+        node.merge(&[
+            WatermarkEntry { peer_id: "peer-a".to_string(), watermark_version: 1, wallclock_ms: now_millis() },
+            WatermarkEntry { peer_id: "peer-b".to_string(), watermark_version: 2, wallclock_ms: now_millis() },
+            WatermarkEntry { peer_id: "peer-c".to_string(), watermark_version: 3, wallclock_ms: now_millis() },
+        ]);
+
+        let targets = node.select_gossip_targets(10);
+        assert_eq!(targets.len(), 3, "fanout larger than the candidate pool should just return every candidate once");
+        assert!(!targets.contains(&"self".to_string()));
+        let mut unique = targets.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), targets.len());
+    }
+
+    #[test]
+    fn test_anti_replay_window_accepts_increasing_sequences() {
+        let mut window = AntiReplayWindow::new();
+        assert!(window.accept(0));
+        assert!(window.accept(1));
+        assert!(window.accept(5));
+    }
+
+    #[test]
+    fn test_anti_replay_window_rejects_exact_duplicate() {
+        let mut window = AntiReplayWindow::new();
+        assert!(window.accept(10));
+        assert!(!window.accept(10));
+    }
+
+    #[test]
+    fn test_anti_replay_window_accepts_reordered_but_in_window_sequence() {
+        let mut window = AntiReplayWindow::new();
+        assert!(window.accept(10));
+        assert!(window.accept(8)); // Arrived out of order, but still within the window.
+        assert!(!window.accept(8)); // Replaying it now must fail.
+    }
+
+    #[test]
+    fn test_anti_replay_window_rejects_sequence_older_than_the_window() {
+        let mut window = AntiReplayWindow::new();
+        assert!(window.accept(1000));
+        assert!(!window.accept(1000 - ANTI_REPLAY_WINDOW_SIZE));
+    }
+
+    #[test]
+    fn test_rate_limiter_exhausts_then_refills() {
+        let mut limiter = RateLimiter::new(1000.0); // High refill so the test doesn't need to sleep a full second.
+        limiter.buckets.insert("peer-a".to_string(), TokenBucket::new(2.0, 1000.0));
+
+        assert!(limiter.allow("peer-a"));
+        assert!(limiter.allow("peer-a"));
+        assert!(!limiter.allow("peer-a")); // Bucket just went from 2 tokens to 0.
+
+        thread::sleep(Duration::from_millis(10));
+        assert!(limiter.allow("peer-a")); // 1000/sec refill means >1 token back after 10ms.
+    }
+
+    #[test]
+    fn test_rate_limiter_tracks_separate_keys_independently() {
+        let mut limiter = RateLimiter::new(1000.0);
+        limiter.buckets.insert("peer-a".to_string(), TokenBucket::new(1.0, 0.0));
+        limiter.buckets.insert("peer-b".to_string(), TokenBucket::new(1.0, 0.0));
+
+        assert!(limiter.allow("peer-a"));
+        assert!(!limiter.allow("peer-a"));
+        assert!(limiter.allow("peer-b")); // peer-b's bucket is untouched by peer-a's exhaustion.
+    }
+
     #[test]
     fn test_gc_coordination_basic() {
         // Test with 2 peers, no delay (fast path)
@@ -351,4 +1016,87 @@ mod tests {
         // Should be very fast (sub-millisecond)
         assert!(elapsed < Duration::from_millis(10));
     }
+
+    #[test]
+    fn test_async_coordinator_computes_safe_threshold_across_peers() {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let port = 27531;
+            let coordinator = TcpGcCoordinator::new(port, 0);
+            let coordinate = tokio::spawn(async move { coordinator.coordinate(2).await });
+
+            // Give the coordinator a moment to bind before peers dial in.
+            tokio::time::sleep(Duration::from_millis(20)).await;
+
+            let peer_a = TcpGcPeer::new("peer-a", port, 0);
+            let peer_b = TcpGcPeer::new("peer-b", port, 0);
+            let (result_a, result_b) =
+                tokio::join!(peer_a.report_watermark(100), peer_b.report_watermark(50));
+
+            let coordination_result = coordinate.await.unwrap();
+            assert_eq!(coordination_result.safe_threshold, 50);
+            assert_eq!(coordination_result.rejected, 0);
+
+            // Both peers learn the same threshold the coordinator computed.
+            assert_eq!(result_a.unwrap().safe_threshold, 50);
+            assert_eq!(result_b.unwrap().safe_threshold, 50);
+        });
+    }
+
+    #[test]
+    fn test_async_coordinator_rejects_replayed_sequence() {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let port = 27532;
+            let coordinator = TcpGcCoordinator::new(port, 0);
+            let coordinate = tokio::spawn(async move { coordinator.coordinate(2).await });
+
+            tokio::time::sleep(Duration::from_millis(20)).await;
+
+            // Two reports sharing the same `peer_id` and `sequence`: the
+            // second is a replay and must be rejected, not folded into the
+            // safe threshold.
+            let peer = TcpGcPeer::new("peer-a", port, 0);
+            *peer.next_sequence.lock().unwrap() = 0;
+            let a = peer.report_watermark(10).await;
+            *peer.next_sequence.lock().unwrap() = 0;
+            let b = peer.report_watermark(999).await;
+
+            let coordination_result = coordinate.await.unwrap();
+            assert_eq!(coordination_result.rejected, 1);
+            assert!(a.is_some());
+            assert!(b.is_some());
+        });
+    }
+
+    #[test]
+    fn test_coordinator_with_a_small_max_payload_size_drops_an_oversized_report() {
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let port = 27533;
+            let coordinator = TcpGcCoordinator::with_config(
+                port,
+                0,
+                DEFAULT_RATE_LIMIT_PER_SEC,
+                Duration::from_millis(100),
+                8,
+            );
+            let coordinate = tokio::spawn(async move { coordinator.coordinate(1).await });
+
+            tokio::time::sleep(Duration::from_millis(20)).await;
+
+            // A `peer_id` long enough that the encoded `WatermarkReport`
+            // exceeds the coordinator's 8-byte budget.
+            let peer = TcpGcPeer::new("a-peer-id-too-long-to-fit", port, 0);
+            let result = peer.report_watermark(42).await;
+
+            // The coordinator closed the stream without a reply once the
+            // frame's length prefix exceeded `max_payload_size`, so the
+            // peer's own read never produces a threshold.
+            assert_eq!(result.unwrap().safe_threshold, 0);
+
+            // The connection's task dropped its report sender without
+            // sending, so the coordinator's wait ends (at the latest at
+            // `deadline`) without ever counting the report.
+            let coordination_result = coordinate.await.unwrap();
+            assert_eq!(coordination_result.messages_received, 0);
+        });
+    }
 }
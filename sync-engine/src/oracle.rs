@@ -0,0 +1,36 @@
+use std::cell::Cell;
+
+/// Monotonic commit-sequence counter for a single [`crate::table::CrrTable`].
+///
+/// Every `InsertBuilder`/`UpdateBuilder::commit` and every `CrrTable::merge`
+/// call advances this once and stamps every [`crate::storage::DagNode`] it
+/// writes with the resulting number, so the whole batch becomes visible (or
+/// not) to a pinned reader atomically — a concurrent writer can't leave
+/// [`crate::table::CrrTable::changeset_as_of`] or an
+/// [`crate::table::AsOfBound::CommitSeq`] snapshot observing half a commit.
+#[derive(Debug, Default)]
+pub struct VersionOracle {
+    next: Cell<u64>,
+}
+
+impl VersionOracle {
+    pub fn new() -> Self {
+        Self { next: Cell::new(0) }
+    }
+
+    /// Advance to and return the sequence number for the commit now in
+    /// progress.
+    pub fn advance(&self) -> u64 {
+        let seq = self.next.get() + 1;
+        self.next.set(seq);
+        seq
+    }
+
+    /// The sequence number of the most recent committed batch — what a
+    /// caller should pass to [`crate::table::CrrTable::changeset_as_of`] or
+    /// [`crate::table::AsOfBound::CommitSeq`] to pin a read to "everything
+    /// visible right now".
+    pub fn current(&self) -> u64 {
+        self.next.get()
+    }
+}
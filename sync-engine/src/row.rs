@@ -1,7 +1,99 @@
 use std::collections::HashMap;
 
+use crate::chunking::{ChunkConfig, ChunkStore};
+use crate::column_crdt::{ColumnCrdt, OrSet, PnCounter};
+use crate::dictionary::DictionaryRegistry;
 use crate::storage::{Cell, DagNode, Storage, now_millis};
 use crate::error::Result;
+use crate::oracle::VersionOracle;
+use crate::wire::{read_bytes, read_u32, read_u64, write_bytes, write_u32};
+
+/// Prefixed to a cell's value bytes when it holds a
+/// [`crate::TieBreakPolicy::MultiValue`] conflict set rather than a single
+/// resolved value. Four bytes chosen to make an accidental match against
+/// real column data (which would then also need a validly-framed entry
+/// count and entries) vanishingly unlikely for a research prototype; a
+/// format with real users would want a dedicated value-kind field instead.
+const CONFLICT_SET_MARKER: [u8; 4] = [0xC5, 0xE7, 0x5E, 0x71];
+
+/// Pack a set of concurrently-written `(value, version)` pairs into the
+/// bytes stored as a single cell's value, so the existing one-cell-per-column
+/// storage model can represent an as-yet-unresolved MV-Register conflict.
+pub(crate) fn encode_conflict_set(entries: &[(Vec<u8>, u64)]) -> Vec<u8> {
+    let mut buf = CONFLICT_SET_MARKER.to_vec();
+    write_u32(&mut buf, entries.len() as u32);
+    for (value, version) in entries {
+        write_bytes(&mut buf, value);
+        buf.extend_from_slice(&version.to_le_bytes());
+    }
+    buf
+}
+
+/// Inverse of [`encode_conflict_set`]. Returns `None` for an ordinary,
+/// non-conflict-set cell value.
+pub(crate) fn decode_conflict_set(bytes: &[u8]) -> Option<Vec<(Vec<u8>, u64)>> {
+    if !bytes.starts_with(&CONFLICT_SET_MARKER) {
+        return None;
+    }
+    let mut cursor = CONFLICT_SET_MARKER.len();
+    let count = read_u32(bytes, &mut cursor).ok()? as usize;
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        let value = read_bytes(bytes, &mut cursor).ok()?;
+        let version = read_u64(bytes, &mut cursor).ok()?;
+        entries.push((value, version));
+    }
+    Some(entries)
+}
+
+/// A DAG node's write timestamp, advanced past both its parent's own
+/// timestamp and the current wall-clock time — mirroring
+/// [`crate::crdt::Lww::update`] — so two writes to the same column in the
+/// same millisecond, or a write made under clock skew, never produce a
+/// timestamp that goes backwards. [`crate::merge::TieBreakPolicy::LastWriteWins`]
+/// relies on this monotonicity to break conflicts correctly.
+fn advancing_timestamp<S: Storage>(storage: &S, pk: &str, col: &str, parent_version: Option<u64>) -> Result<u64> {
+    let parent_timestamp = match parent_version {
+        Some(version) => storage.get_dag_history(pk, col)?
+            .into_iter()
+            .find(|node| node.version == version)
+            .map(|node| node.timestamp)
+            .unwrap_or(0),
+        None => 0,
+    };
+    Ok((parent_timestamp + 1).max(now_millis()))
+}
+
+/// Fold a local increment/decrement by `replica_id` into `col`'s current
+/// [`PnCounter`] state (or a fresh one, if `col` has no cell yet) and
+/// re-encode — the decode/mutate/encode dance `.column_counter` spares a
+/// caller of a [`crate::CrrTable::declare_crdt_column`]`(col, `[`crate::CrdtKind::PnCounter`]`)`
+/// column from doing by hand.
+fn folded_counter_bytes<S: Storage>(storage: &S, pk: &str, col: &str, replica_id: &str, delta: i64) -> Result<Vec<u8>> {
+    let mut counter = match storage.get_cell(pk, col)? {
+        Some(cell) => PnCounter::from_bytes(&cell.value).unwrap_or_default(),
+        None => PnCounter::default(),
+    };
+    if delta >= 0 {
+        counter.increment(replica_id, delta as u64);
+    } else {
+        counter.decrement(replica_id, delta.unsigned_abs());
+    }
+    Ok(counter.to_bytes().expect("encoding a PnCounter column cannot fail"))
+}
+
+/// Fold a local add of `value`, tagged `(replica_id, token)`, into `col`'s
+/// current [`OrSet`] state (or a fresh one) and re-encode — the
+/// [`crate::CrrTable::declare_crdt_column`]`(col, `[`crate::CrdtKind::OrSet`]`)`
+/// counterpart to [`folded_counter_bytes`].
+fn folded_set_bytes<S: Storage>(storage: &S, pk: &str, col: &str, replica_id: &str, token: u64, value: &str) -> Result<Vec<u8>> {
+    let mut set: OrSet<String> = match storage.get_cell(pk, col)? {
+        Some(cell) => OrSet::from_bytes(&cell.value).unwrap_or_default(),
+        None => OrSet::default(),
+    };
+    set.insert(replica_id, token, value.to_string());
+    Ok(set.to_bytes().expect("encoding an OrSet column cannot fail"))
+}
 
 pub struct RowView {
     pub(crate) pk: String,
@@ -27,6 +119,32 @@ impl RowView {
         self.cells.get(col).map(|c| c.version)
     }
 
+    /// Every value concurrently written to `col` that a
+    /// `TieBreakPolicy::MultiValue` merge hasn't yet collapsed to a single
+    /// winner, or that one value as a single-element vector if the column
+    /// isn't currently in conflict. `None` if the column has no value at
+    /// all. Lets application code surface "this field has conflicting
+    /// edits" instead of silently seeing whichever value `get` happens to
+    /// return.
+    pub fn get_multi(&self, col: &str) -> Option<Vec<Vec<u8>>> {
+        let cell = self.cells.get(col)?;
+        match decode_conflict_set(&cell.value) {
+            Some(entries) => Some(entries.into_iter().map(|(value, _)| value).collect()),
+            None => Some(vec![cell.value.clone()]),
+        }
+    }
+
+    /// Like [`Self::get`], but transparent to a value [`InsertBuilder::column_chunked`]/
+    /// [`UpdateBuilder::column_chunked`] stored as a chunk-hash list rather
+    /// than inline — reassembles it via `store`, or returns the plain
+    /// value unchanged if it was never chunked in the first place. `None`
+    /// if the column has no value, or (nested) if it's chunked but `store`
+    /// is missing one of the chunks it references.
+    pub fn get_chunked(&self, col: &str, store: &ChunkStore) -> Option<Vec<u8>> {
+        let cell = self.cells.get(col)?;
+        store.resolve(&cell.value)
+    }
+
     pub fn columns(&self) -> impl Iterator<Item = (&str, &[u8], u64)> {
         self.cells.iter().map(|(k, v)| (k.as_str(), v.value.as_slice(), v.version))
     }
@@ -42,13 +160,15 @@ impl RowView {
 
 pub struct InsertBuilder<'a, S: Storage> {
     storage: &'a mut S,
+    oracle: &'a VersionOracle,
+    encodings: &'a DictionaryRegistry,
     pk: String,
     columns: Vec<(String, Vec<u8>, u64)>,
 }
 
 impl<'a, S: Storage> InsertBuilder<'a, S> {
-    pub(crate) fn new(storage: &'a mut S, pk: String) -> Self {
-        Self { storage, pk, columns: Vec::new() }
+    pub(crate) fn new(storage: &'a mut S, oracle: &'a VersionOracle, encodings: &'a DictionaryRegistry, pk: String) -> Self {
+        Self { storage, oracle, encodings, pk, columns: Vec::new() }
     }
 
     pub fn column(mut self, name: &str, value: impl AsRef<[u8]>, version: u64) -> Self {
@@ -61,21 +181,63 @@ impl<'a, S: Storage> InsertBuilder<'a, S> {
         self
     }
 
+    /// Fold `delta` into `name`'s [`crate::CrdtKind::PnCounter`] state under
+    /// `replica_id` rather than requiring the caller to encode a
+    /// [`PnCounter`] by hand. `replica_id` must identify this replica
+    /// uniquely among peers — a shared id across distinct replicas would
+    /// make concurrent increments merge by taking the max instead of
+    /// summing them.
+    pub fn column_counter(mut self, name: &str, replica_id: &str, delta: i64, version: u64) -> Result<Self> {
+        let bytes = folded_counter_bytes(self.storage, &self.pk, name, replica_id, delta)?;
+        self.columns.push((name.to_string(), bytes, version));
+        Ok(self)
+    }
+
+    /// Add `value` to `name`'s [`crate::CrdtKind::OrSet`] state under
+    /// `replica_id` rather than requiring the caller to encode an [`OrSet`]
+    /// by hand. See [`UpdateBuilder::column_set_add`] for the same sugar on
+    /// an existing row.
+    pub fn column_set_add(mut self, name: &str, replica_id: &str, value: &str, version: u64) -> Result<Self> {
+        let bytes = folded_set_bytes(self.storage, &self.pk, name, replica_id, version, value)?;
+        self.columns.push((name.to_string(), bytes, version));
+        Ok(self)
+    }
+
+    /// Store `value` in `name`, content-defined-chunked and deduplicated
+    /// against `store` if it's over `threshold` bytes, inline otherwise —
+    /// the write-path counterpart to [`RowView::get_chunked`]. Spares a
+    /// caller of a large column value from every DAG version it ever takes
+    /// keeping an independent full copy.
+    pub fn column_chunked(mut self, name: &str, value: impl AsRef<[u8]>, store: &mut ChunkStore, config: &ChunkConfig, threshold: usize, version: u64) -> Self {
+        let bytes = store.put_above_threshold(value.as_ref(), config, threshold);
+        self.columns.push((name.to_string(), bytes, version));
+        self
+    }
+
     pub fn commit(self) -> Result<()> {
+        // One sequence number for every column in this batch, so a reader
+        // pinned via `AsOfBound::CommitSeq` sees all of them or none.
+        let commit_seq = self.oracle.advance();
+
         for (col, value, version) in self.columns {
             let current = self.storage.get_cell(&self.pk, &col)?;
             let current_version = current.as_ref().map(|c| c.version).unwrap_or(0);
 
+            let parent_version = if current_version > 0 { Some(current_version) } else { None };
+            let timestamp = advancing_timestamp(self.storage, &self.pk, &col, parent_version)?;
+            let value = self.encodings.encode(&col, &value);
+
             let cell = Cell { value: value.clone(), version };
             self.storage.set_cell(&self.pk, &col, cell)?;
 
             let node = DagNode {
                 version,
                 value,
-                parent_version: if current_version > 0 { Some(current_version) } else { None },
+                parent_version,
                 parent2_version: None,
-                timestamp: now_millis(),
+                timestamp,
                 is_tombstone: false,
+                commit_seq,
             };
             self.storage.append_dag_node(&self.pk, &col, node)?;
         }
@@ -85,13 +247,20 @@ impl<'a, S: Storage> InsertBuilder<'a, S> {
 
 pub struct UpdateBuilder<'a, S: Storage> {
     storage: &'a mut S,
+    oracle: &'a VersionOracle,
+    encodings: &'a DictionaryRegistry,
     pk: String,
     columns: Vec<(String, Vec<u8>)>,
+    // Seeded from the version oracle so that two `.column_set_add` calls on
+    // the same column within one builder chain (before `commit` writes
+    // anything back to storage) still get distinct OrSet tokens.
+    next_token: u64,
 }
 
 impl<'a, S: Storage> UpdateBuilder<'a, S> {
-    pub(crate) fn new(storage: &'a mut S, pk: String) -> Self {
-        Self { storage, pk, columns: Vec::new() }
+    pub(crate) fn new(storage: &'a mut S, oracle: &'a VersionOracle, encodings: &'a DictionaryRegistry, pk: String) -> Self {
+        let next_token = oracle.current();
+        Self { storage, oracle, encodings, pk, columns: Vec::new(), next_token }
     }
 
     pub fn column(mut self, name: &str, value: impl AsRef<[u8]>) -> Self {
@@ -104,12 +273,47 @@ impl<'a, S: Storage> UpdateBuilder<'a, S> {
         self
     }
 
+    /// Fold `delta` into `name`'s [`crate::CrdtKind::PnCounter`] state under
+    /// `replica_id` rather than requiring the caller to encode a
+    /// [`PnCounter`] by hand. `replica_id` must identify this replica
+    /// uniquely among peers — a shared id across distinct replicas would
+    /// make concurrent increments merge by taking the max instead of
+    /// summing them.
+    pub fn column_counter(mut self, name: &str, replica_id: &str, delta: i64) -> Result<Self> {
+        let bytes = folded_counter_bytes(self.storage, &self.pk, name, replica_id, delta)?;
+        self.columns.push((name.to_string(), bytes));
+        Ok(self)
+    }
+
+    /// Add `value` to `name`'s [`crate::CrdtKind::OrSet`] state under
+    /// `replica_id` rather than requiring the caller to encode an [`OrSet`]
+    /// by hand.
+    pub fn column_set_add(mut self, name: &str, replica_id: &str, value: &str) -> Result<Self> {
+        let token = self.next_token;
+        self.next_token += 1;
+        let bytes = folded_set_bytes(self.storage, &self.pk, name, replica_id, token, value)?;
+        self.columns.push((name.to_string(), bytes));
+        Ok(self)
+    }
+
+    /// Like [`InsertBuilder::column_chunked`], for an existing row.
+    pub fn column_chunked(mut self, name: &str, value: impl AsRef<[u8]>, store: &mut ChunkStore, config: &ChunkConfig, threshold: usize) -> Self {
+        let bytes = store.put_above_threshold(value.as_ref(), config, threshold);
+        self.columns.push((name.to_string(), bytes));
+        self
+    }
+
     pub fn commit(self) -> Result<()> {
+        let commit_seq = self.oracle.advance();
+
         for (col, value) in self.columns {
             let current = self.storage.get_cell(&self.pk, &col)?;
             let new_version = current.as_ref().map(|c| c.version + 1).unwrap_or(1);
             let parent_version = current.as_ref().map(|c| c.version);
 
+            let timestamp = advancing_timestamp(self.storage, &self.pk, &col, parent_version)?;
+            let value = self.encodings.encode(&col, &value);
+
             let cell = Cell { value: value.clone(), version: new_version };
             self.storage.set_cell(&self.pk, &col, cell)?;
 
@@ -118,8 +322,9 @@ impl<'a, S: Storage> UpdateBuilder<'a, S> {
                 value,
                 parent_version,
                 parent2_version: None,
-                timestamp: now_millis(),
+                timestamp,
                 is_tombstone: false,
+                commit_seq,
             };
             self.storage.append_dag_node(&self.pk, &col, node)?;
         }
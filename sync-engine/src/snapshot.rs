@@ -0,0 +1,75 @@
+//! Copy-on-write, point-in-time snapshots of a [`crate::CrrTable`]'s rows.
+//!
+//! A [`Snapshot`] is a fully owned, reference-counted copy of row state
+//! that never changes after [`crate::CrrTable::snapshot`] hands it out —
+//! a later `insert`/`update`/`merge` on the table builds a *new*
+//! [`TableRoot`], reusing an `Rc` clone of every row it didn't touch and
+//! rebuilding only the rows whose cells actually changed, then swaps the
+//! table's root in one assignment. Any `Snapshot` still held from before
+//! that swap keeps pointing at the old root, so it keeps seeing exactly
+//! what it saw at checkout time regardless of what the table does next.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::merkle::{Digest, MerkleTree};
+use crate::row::RowView;
+use crate::storage::{Cell, DagNode};
+
+/// One row's cells and per-column DAG history, captured as of the last
+/// [`crate::CrrTable::snapshot`] call that noticed it had changed.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RowState {
+    pub(crate) cells: HashMap<String, Cell>,
+    pub(crate) dag_history: HashMap<String, Vec<DagNode>>,
+}
+
+/// The root of a [`Snapshot`]: every row's state as of the instant it was
+/// built. Immutable once constructed — `CrrTable` never mutates a
+/// `TableRoot` in place, only replaces its reference to one.
+#[derive(Clone, Default)]
+pub(crate) struct TableRoot {
+    pub(crate) rows: HashMap<String, Rc<RowState>>,
+}
+
+/// An immutable, point-in-time view of a [`crate::CrrTable`]'s rows.
+/// Reading from a `Snapshot` never touches the table's storage again, so
+/// it costs nothing beyond an `Rc` clone to keep one around across a
+/// `merge` the table performs later.
+#[derive(Clone)]
+pub struct Snapshot {
+    root: Rc<TableRoot>,
+}
+
+impl Snapshot {
+    pub(crate) fn new(root: Rc<TableRoot>) -> Self {
+        Self { root }
+    }
+
+    /// The row as it stood when this snapshot was taken, or `None` if it
+    /// didn't exist yet.
+    pub fn get(&self, pk: &str) -> Option<RowView> {
+        let state = self.root.rows.get(pk)?;
+        Some(RowView {
+            pk: pk.to_string(),
+            cells: state.cells.clone(),
+            dag_history: state.dag_history.clone(),
+        })
+    }
+
+    /// Every primary key present in this snapshot.
+    pub fn pks(&self) -> Vec<String> {
+        self.root.rows.keys().cloned().collect()
+    }
+
+    /// The Merkle root of this snapshot's rows, for reconciliation against
+    /// a peer without either side blocking on the live table.
+    pub fn merkle_root(&self) -> Digest {
+        let mut tree = MerkleTree::new();
+        for (pk, state) in &self.root.rows {
+            let cells: std::collections::BTreeMap<String, Cell> = state.cells.clone().into_iter().collect();
+            tree.insert(pk, &cells);
+        }
+        tree.root_digest()
+    }
+}
@@ -0,0 +1,199 @@
+//! Disk-backed spilling for [`crate::CrrTable::merge_with_options`] —
+//! the same "accumulate, sort, spill a run, k-way merge the runs back"
+//! shape an external sort uses, applied to a changeset's `(pk, col)`
+//! writes instead of whole rows, so a merge's resident set is bounded by
+//! `MergeOptions::spill_threshold_bytes` rather than the changeset's
+//! full size.
+
+use std::path::PathBuf;
+
+use crate::error::Result;
+use crate::wire::{read_bytes, read_string, read_u32, read_u64, read_u8, write_bytes, write_u32};
+
+/// Tunables for [`crate::CrrTable::merge_with_options`]: how large the
+/// resident write buffer is allowed to grow before it's sorted and
+/// flushed to a temporary run file, and where those run files live.
+#[derive(Debug, Clone)]
+pub struct MergeOptions {
+    pub spill_threshold_bytes: usize,
+    pub temp_dir: PathBuf,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            spill_threshold_bytes: 64 * 1024 * 1024,
+            temp_dir: std::env::temp_dir(),
+        }
+    }
+}
+
+/// One pending column write a [`SpillBuffer`] is holding or has spilled,
+/// keyed by `(pk, col)` so runs come back out of [`SpillBuffer::into_sorted`]
+/// in `(pk, col)` order.
+#[derive(Debug, Clone)]
+pub(crate) struct PendingWrite {
+    pub pk: String,
+    pub col: String,
+    pub value: Vec<u8>,
+    pub version: u64,
+    pub timestamp: u64,
+    pub is_tombstone: bool,
+}
+
+fn write_entry(buf: &mut Vec<u8>, entry: &PendingWrite) {
+    write_bytes(buf, entry.pk.as_bytes());
+    write_bytes(buf, entry.col.as_bytes());
+    write_bytes(buf, &entry.value);
+    buf.extend_from_slice(&entry.version.to_le_bytes());
+    buf.extend_from_slice(&entry.timestamp.to_le_bytes());
+    buf.push(entry.is_tombstone as u8);
+}
+
+fn read_entry(bytes: &[u8], cursor: &mut usize) -> Result<PendingWrite> {
+    let pk = read_string(bytes, cursor)?;
+    let col = read_string(bytes, cursor)?;
+    let value = read_bytes(bytes, cursor)?;
+    let version = read_u64(bytes, cursor)?;
+    let timestamp = read_u64(bytes, cursor)?;
+    let is_tombstone = read_u8(bytes, cursor)? != 0;
+    Ok(PendingWrite { pk, col, value, version, timestamp, is_tombstone })
+}
+
+fn entry_key(entry: &PendingWrite) -> (&str, &str) {
+    (entry.pk.as_str(), entry.col.as_str())
+}
+
+/// A single sorted, length-prefixed run of [`PendingWrite`]s flushed to a
+/// temp file under `temp_dir`. Deleted from disk as soon as it's dropped
+/// — whether the merge that spilled it commits or rolls back, the file
+/// never outlives it.
+struct SpillRun {
+    path: PathBuf,
+}
+
+impl SpillRun {
+    fn write(temp_dir: &std::path::Path, buffer_id: u64, id: usize, mut entries: Vec<PendingWrite>) -> Result<Self> {
+        entries.sort_by(|a, b| entry_key(a).cmp(&entry_key(b)));
+
+        let path = temp_dir.join(format!(
+            "crr-merge-spill-{}-{}-{}.bin",
+            std::process::id(),
+            buffer_id,
+            id,
+        ));
+        let mut buf = Vec::new();
+        write_u32(&mut buf, entries.len() as u32);
+        for entry in &entries {
+            write_entry(&mut buf, entry);
+        }
+        std::fs::write(&path, &buf)?;
+        Ok(Self { path })
+    }
+
+    fn read_all(&self) -> Result<Vec<PendingWrite>> {
+        let bytes = std::fs::read(&self.path)?;
+        let mut cursor = 0usize;
+        let count = read_u32(&bytes, &mut cursor)?;
+        (0..count).map(|_| read_entry(&bytes, &mut cursor)).collect()
+    }
+}
+
+impl Drop for SpillRun {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Accumulates [`PendingWrite`]s for [`crate::CrrTable::merge_with_options`],
+/// spilling a sorted run to `options.temp_dir` once the resident buffer
+/// exceeds `options.spill_threshold_bytes` instead of holding an entire
+/// oversized changeset's writes in memory at once. [`Self::into_sorted`]
+/// always returns every entry — spilled or still resident — in
+/// `(pk, col)` order via a k-way merge of the sorted runs, regardless of
+/// whether spilling ever actually happened.
+pub(crate) struct SpillBuffer {
+    options: MergeOptions,
+    /// Unique per `SpillBuffer` instance, not just per process — two
+    /// `merge_with_options` calls running concurrently on separate threads
+    /// of the same process (e.g. reconciling two different tables) would
+    /// otherwise both start their own buffer's run counter at 0 and collide
+    /// on the same `temp_dir` path, each truncating the other's run file
+    /// mid-merge.
+    buffer_id: u64,
+    resident: Vec<PendingWrite>,
+    resident_bytes: usize,
+    runs: Vec<SpillRun>,
+}
+
+impl SpillBuffer {
+    pub(crate) fn new(options: MergeOptions) -> Self {
+        static NEXT_BUFFER_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let buffer_id = NEXT_BUFFER_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Self { options, buffer_id, resident: Vec::new(), resident_bytes: 0, runs: Vec::new() }
+    }
+
+    pub(crate) fn push(&mut self, entry: PendingWrite) -> Result<()> {
+        // pk + col + value bytes, plus version/timestamp (8 bytes each)
+        // and the tombstone flag — a rough accounting, not exact wire size.
+        self.resident_bytes += entry.pk.len() + entry.col.len() + entry.value.len() + 17;
+        self.resident.push(entry);
+        if self.resident_bytes >= self.options.spill_threshold_bytes {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    fn spill(&mut self) -> Result<()> {
+        if self.resident.is_empty() {
+            return Ok(());
+        }
+        std::fs::create_dir_all(&self.options.temp_dir)?;
+        let entries = std::mem::take(&mut self.resident);
+        self.resident_bytes = 0;
+        let run = SpillRun::write(&self.options.temp_dir, self.buffer_id, self.runs.len(), entries)?;
+        self.runs.push(run);
+        Ok(())
+    }
+
+    /// Drain every spilled run plus whatever's still resident into one
+    /// `(pk, col)`-ordered sequence, the same way an external sort's
+    /// final pass merges its runs back together.
+    pub(crate) fn into_sorted(mut self) -> Result<Vec<PendingWrite>> {
+        self.resident.sort_by(|a, b| entry_key(a).cmp(&entry_key(b)));
+
+        let mut streams: Vec<std::vec::IntoIter<PendingWrite>> = Vec::with_capacity(self.runs.len() + 1);
+        for run in &self.runs {
+            streams.push(run.read_all()?.into_iter());
+        }
+        streams.push(std::mem::take(&mut self.resident).into_iter());
+
+        let mut heads: Vec<Option<PendingWrite>> = streams.iter_mut().map(|s| s.next()).collect();
+        let mut merged = Vec::new();
+
+        loop {
+            let mut smallest: Option<usize> = None;
+            for (i, head) in heads.iter().enumerate() {
+                if let Some(entry) = head {
+                    let is_smaller = match smallest {
+                        None => true,
+                        Some(best) => entry_key(entry) < entry_key(heads[best].as_ref().unwrap()),
+                    };
+                    if is_smaller {
+                        smallest = Some(i);
+                    }
+                }
+            }
+
+            match smallest {
+                None => break,
+                Some(i) => {
+                    merged.push(heads[i].take().unwrap());
+                    heads[i] = streams[i].next();
+                }
+            }
+        }
+
+        Ok(merged)
+    }
+}
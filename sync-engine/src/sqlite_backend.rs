@@ -19,6 +19,9 @@ pub mod sqlite {
         PreferExisting,
         PreferIncoming,
         LexicographicMin,
+        /// Keep whichever side's write timestamp is higher, falling back to
+        /// `LexicographicMin`'s byte comparison on an exact tie.
+        LastWriteWins,
     }
 
     /// Result of a merge operation
@@ -36,6 +39,10 @@ pub mod sqlite {
         pub pk: String,
         pub columns: HashMap<String, Vec<u8>>,
         pub versions: HashMap<String, u64>,
+        /// Write timestamp per column, only consulted under
+        /// `TieBreakPolicy::LastWriteWins`; a column missing here is
+        /// treated as timestamp `0`.
+        pub timestamps: HashMap<String, u64>,
     }
 
     impl SqliteDagCrr {
@@ -54,6 +61,7 @@ pub mod sqlite {
                     col TEXT NOT NULL,
                     value BLOB,
                     version INTEGER NOT NULL DEFAULT 0,
+                    timestamp INTEGER NOT NULL DEFAULT 0,
                     PRIMARY KEY (pk, col)
                 );
 
@@ -91,10 +99,11 @@ pub mod sqlite {
                     .ok();
 
                 let new_version = existing.map(|v| v + 1).unwrap_or(1);
+                let timestamp = crate::storage::now_millis();
 
                 tx.execute(
-                    "INSERT OR REPLACE INTO crr_data (pk, col, value, version) VALUES (?, ?, ?, ?)",
-                    params![pk, &col, &value, new_version],
+                    "INSERT OR REPLACE INTO crr_data (pk, col, value, version, timestamp) VALUES (?, ?, ?, ?, ?)",
+                    params![pk, &col, &value, new_version, timestamp as i64],
                 )?;
 
                 let parent = existing;
@@ -118,20 +127,21 @@ pub mod sqlite {
             for entry in changeset {
                 for (col, val) in &entry.columns {
                     let v_r = entry.versions.get(col).copied().unwrap_or(1);
+                    let ts_r = entry.timestamps.get(col).copied().unwrap_or(0);
 
-                    let local: Option<(Vec<u8>, u64)> = tx
+                    let local: Option<(Vec<u8>, u64, u64)> = tx
                         .query_row(
-                            "SELECT value, version FROM crr_data WHERE pk = ? AND col = ?",
+                            "SELECT value, version, timestamp FROM crr_data WHERE pk = ? AND col = ?",
                             params![&entry.pk, col],
-                            |row| Ok((row.get(0)?, row.get(1)?)),
+                            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
                         )
                         .ok();
 
                     match local {
                         None => {
                             tx.execute(
-                                "INSERT INTO crr_data (pk, col, value, version) VALUES (?, ?, ?, ?)",
-                                params![&entry.pk, col, val, v_r],
+                                "INSERT INTO crr_data (pk, col, value, version, timestamp) VALUES (?, ?, ?, ?, ?)",
+                                params![&entry.pk, col, val, v_r, ts_r as i64],
                             )?;
                             tx.execute(
                                 "INSERT INTO crr_dag (pk, col, version, value, parent1, parent2) VALUES (?, ?, ?, ?, NULL, NULL)",
@@ -139,11 +149,11 @@ pub mod sqlite {
                             )?;
                             report.inserted += 1;
                         }
-                        Some((local_val, v_l)) => {
+                        Some((local_val, v_l, ts_l)) => {
                             if v_r > v_l {
                                 tx.execute(
-                                    "UPDATE crr_data SET value = ?, version = ? WHERE pk = ? AND col = ?",
-                                    params![val, v_r, &entry.pk, col],
+                                    "UPDATE crr_data SET value = ?, version = ?, timestamp = ? WHERE pk = ? AND col = ?",
+                                    params![val, v_r, ts_r as i64, &entry.pk, col],
                                 )?;
                                 tx.execute(
                                     "INSERT OR IGNORE INTO crr_dag (pk, col, version, value, parent1, parent2) VALUES (?, ?, ?, ?, ?, ?)",
@@ -164,13 +174,23 @@ pub mod sqlite {
                                             val.clone()
                                         }
                                     }
+                                    TieBreakPolicy::LastWriteWins => {
+                                        match ts_r.cmp(&ts_l) {
+                                            std::cmp::Ordering::Greater => val.clone(),
+                                            std::cmp::Ordering::Less => local_val.clone(),
+                                            std::cmp::Ordering::Equal => {
+                                                if &local_val < val { local_val.clone() } else { val.clone() }
+                                            }
+                                        }
+                                    }
                                 };
 
                                 if winner != local_val {
                                     let v_new = v_r + 1;
+                                    let ts_new = ts_r.max(ts_l);
                                     tx.execute(
-                                        "UPDATE crr_data SET value = ?, version = ? WHERE pk = ? AND col = ?",
-                                        params![&winner, v_new, &entry.pk, col],
+                                        "UPDATE crr_data SET value = ?, version = ?, timestamp = ? WHERE pk = ? AND col = ?",
+                                        params![&winner, v_new, ts_new as i64, &entry.pk, col],
                                     )?;
                                     tx.execute(
                                         "INSERT OR IGNORE INTO crr_dag (pk, col, version, value, parent1, parent2) VALUES (?, ?, ?, ?, ?, ?)",
@@ -251,7 +271,7 @@ pub mod sqlite {
         /// Generate changeset for sync (all rows with version > min_version)
         pub fn generate_changeset(&self, min_version: u64) -> Result<Vec<ChangesetEntry>> {
             let mut stmt = self.conn.prepare(
-                "SELECT pk, col, value, version FROM crr_data WHERE version > ? ORDER BY pk",
+                "SELECT pk, col, value, version, timestamp FROM crr_data WHERE version > ? ORDER BY pk",
             )?;
 
             let mut rows = stmt.query(params![min_version])?;
@@ -262,15 +282,18 @@ pub mod sqlite {
                 let col: String = row.get(1)?;
                 let value: Vec<u8> = row.get(2)?;
                 let version: u64 = row.get(3)?;
+                let timestamp: u64 = row.get(4)?;
 
                 let entry = entries.entry(pk.clone()).or_insert_with(|| ChangesetEntry {
                     pk,
                     columns: HashMap::new(),
                     versions: HashMap::new(),
+                    timestamps: HashMap::new(),
                 });
 
                 entry.columns.insert(col.clone(), value);
-                entry.versions.insert(col, version);
+                entry.versions.insert(col.clone(), version);
+                entry.timestamps.insert(col, timestamp);
             }
 
             Ok(entries.into_values().collect())
@@ -312,6 +335,7 @@ pub mod sqlite {
                     .into_iter()
                     .collect(),
                 versions: [("value".to_string(), 1)].into_iter().collect(),
+                timestamps: HashMap::new(),
             }];
 
             let report = db.merge(&changeset, TieBreakPolicy::LexicographicMin).unwrap();
@@ -322,6 +346,32 @@ pub mod sqlite {
             assert_eq!(row.get("value").unwrap().0, b"alpha");
         }
 
+        #[test]
+        fn test_merge_conflict_last_write_wins() {
+            let mut db = SqliteDagCrr::new(":memory:").unwrap();
+
+            let mut cols = HashMap::new();
+            cols.insert("value".to_string(), b"old".to_vec());
+            db.insert("key1", cols).unwrap();
+
+            // Same version, different value, but a newer timestamp — should
+            // win regardless of how the bytes compare lexicographically.
+            let changeset = vec![ChangesetEntry {
+                pk: "key1".to_string(),
+                columns: [("value".to_string(), b"zzz-new".to_vec())]
+                    .into_iter()
+                    .collect(),
+                versions: [("value".to_string(), 1)].into_iter().collect(),
+                timestamps: [("value".to_string(), u64::MAX)].into_iter().collect(),
+            }];
+
+            let report = db.merge(&changeset, TieBreakPolicy::LastWriteWins).unwrap();
+            assert_eq!(report.conflicts, 1);
+
+            let row = db.get_row("key1").unwrap().unwrap();
+            assert_eq!(row.get("value").unwrap().0, b"zzz-new");
+        }
+
         #[test]
         fn test_history() {
             let mut db = SqliteDagCrr::new(":memory:").unwrap();
@@ -0,0 +1,357 @@
+use std::collections::{HashMap, HashSet};
+
+use heed::types::Bytes;
+use heed::{Database, Env, EnvOpenOptions};
+
+use super::{Cell, DagNode, Row, Storage};
+use crate::dag_merkle::{DagMerkleAccumulator, Digest, MerkleProof};
+use crate::error::{Error, Result};
+use crate::wire::{
+    read_bytes, read_option_u64, read_u64, read_u8, write_bytes, write_option_u64,
+};
+
+/// LMDB-backed `Storage` implementation (via the `heed` crate): an
+/// embedded, memory-mapped store whose readers take an MVCC snapshot
+/// instead of `SqliteStorage`'s single-connection lock, so a long
+/// column-history scan never blocks a concurrent writer — at the cost of
+/// needing its map pre-sized (`map_size_bytes`) rather than growing
+/// unbounded the way a SQLite file does.
+///
+/// Cells live in one database keyed by `pk\0col`; DAG history lives in a
+/// second, keyed by `pk\0col\0version` with `version` stored big-endian so
+/// LMDB's native key ordering already yields oldest-to-newest iteration,
+/// letting a column's whole history be read back with a single prefix
+/// scan instead of a per-node lookup.
+///
+/// Every `Storage` call opens and commits its own single-operation LMDB
+/// transaction rather than holding one open across
+/// `begin_transaction`/`commit_transaction`, since an LMDB transaction
+/// handle borrows from this struct's own `Env` for a lifetime `&mut self`
+/// has no way to thread through. `begin_transaction`/`rollback_transaction`
+/// are therefore markers only: a `CrrTable::merge` that fails partway
+/// through leaves whatever individual cells it already wrote in place —
+/// unlike `MemoryStorage` and `RedisStorage`, which snapshot and restore
+/// around the transaction, this backend does not deliver the rollback
+/// atomicity `CrrTable::merge`'s doc comment otherwise promises.
+pub struct LmdbStorage {
+    env: Env,
+    cells: Database<Bytes, Bytes>,
+    dag: Database<Bytes, Bytes>,
+    in_transaction: bool,
+    /// One [`DagMerkleAccumulator`] per column, built up in this process as
+    /// `append_dag_node` is called — like `SqliteStorage`'s, it isn't
+    /// persisted to the map, so `dag_root`/`dag_proof` only reflect nodes
+    /// appended since this `LmdbStorage` was opened.
+    dag_merkle: HashMap<(String, String), DagMerkleAccumulator>,
+}
+
+impl LmdbStorage {
+    /// Open (creating if needed) an LMDB environment rooted at `path`,
+    /// sized to `map_size_bytes` — LMDB memory-maps the whole map up
+    /// front, so this is a ceiling on the database's eventual size, not
+    /// its current one.
+    pub fn open(path: &str, map_size_bytes: usize) -> Result<Self> {
+        std::fs::create_dir_all(path)?;
+
+        // Safety: `map_size_bytes` must stay fixed for the life of this
+        // environment, which it does — `LmdbStorage` never reopens it.
+        let env = unsafe {
+            EnvOpenOptions::new()
+                .map_size(map_size_bytes)
+                .max_dbs(2)
+                .open(path)
+        }
+        .map_err(|e| Error::Storage(e.to_string()))?;
+
+        let mut wtxn = env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        let cells = env
+            .create_database(&mut wtxn, Some("crr_cells"))
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        let dag = env
+            .create_database(&mut wtxn, Some("crr_dag"))
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+
+        Ok(Self { env, cells, dag, in_transaction: false, dag_merkle: HashMap::new() })
+    }
+
+    fn cell_key(pk: &str, col: &str) -> Vec<u8> {
+        let mut key = pk.as_bytes().to_vec();
+        key.push(0);
+        key.extend_from_slice(col.as_bytes());
+        key
+    }
+
+    fn dag_prefix(pk: &str, col: &str) -> Vec<u8> {
+        let mut key = pk.as_bytes().to_vec();
+        key.push(0);
+        key.extend_from_slice(col.as_bytes());
+        key.push(0);
+        key
+    }
+
+    fn dag_key(pk: &str, col: &str, version: u64) -> Vec<u8> {
+        let mut key = Self::dag_prefix(pk, col);
+        key.extend_from_slice(&version.to_be_bytes());
+        key
+    }
+
+    fn row_prefix(pk: &str) -> Vec<u8> {
+        let mut key = pk.as_bytes().to_vec();
+        key.push(0);
+        key
+    }
+
+    fn encode_cell(cell: &Cell) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_bytes(&mut buf, &cell.value);
+        buf.extend_from_slice(&cell.version.to_le_bytes());
+        buf
+    }
+
+    fn decode_cell(bytes: &[u8]) -> Result<Cell> {
+        let mut cursor = 0usize;
+        let value = read_bytes(bytes, &mut cursor)?;
+        let version = read_u64(bytes, &mut cursor)?;
+        Ok(Cell { value, version })
+    }
+
+    fn encode_node(node: &DagNode) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_bytes(&mut buf, &node.value);
+        write_option_u64(&mut buf, node.parent_version);
+        write_option_u64(&mut buf, node.parent2_version);
+        buf.extend_from_slice(&node.timestamp.to_le_bytes());
+        buf.push(node.is_tombstone as u8);
+        buf.extend_from_slice(&node.commit_seq.to_le_bytes());
+        buf
+    }
+
+    fn decode_node(version: u64, bytes: &[u8]) -> Result<DagNode> {
+        let mut cursor = 0usize;
+        let value = read_bytes(bytes, &mut cursor)?;
+        let parent_version = read_option_u64(bytes, &mut cursor)?;
+        let parent2_version = read_option_u64(bytes, &mut cursor)?;
+        let timestamp = read_u64(bytes, &mut cursor)?;
+        let is_tombstone = read_u8(bytes, &mut cursor)? != 0;
+        let commit_seq = read_u64(bytes, &mut cursor).unwrap_or(0);
+        Ok(DagNode { version, value, parent_version, parent2_version, timestamp, is_tombstone, commit_seq })
+    }
+}
+
+impl Storage for LmdbStorage {
+    fn get_cell(&self, pk: &str, col: &str) -> Result<Option<Cell>> {
+        let rtxn = self.env.read_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        let raw = self.cells.get(&rtxn, &Self::cell_key(pk, col)).map_err(|e| Error::Storage(e.to_string()))?;
+        raw.map(Self::decode_cell).transpose()
+    }
+
+    fn set_cell(&mut self, pk: &str, col: &str, cell: Cell) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        self.cells
+            .put(&mut wtxn, &Self::cell_key(pk, col), &Self::encode_cell(&cell))
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    fn get_row(&self, pk: &str) -> Result<Option<Row>> {
+        let rtxn = self.env.read_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        let prefix = Self::row_prefix(pk);
+
+        let mut cells = HashMap::new();
+        let iter = self.cells.prefix_iter(&rtxn, &prefix).map_err(|e| Error::Storage(e.to_string()))?;
+        for entry in iter {
+            let (key, value) = entry.map_err(|e| Error::Storage(e.to_string()))?;
+            let col = String::from_utf8_lossy(&key[prefix.len()..]).to_string();
+            cells.insert(col, Self::decode_cell(value)?);
+        }
+
+        if cells.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(Row { pk: pk.to_string(), cells }))
+        }
+    }
+
+    fn delete_row(&mut self, pk: &str) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        let prefix = Self::row_prefix(pk);
+
+        let cell_keys: Vec<Vec<u8>> = self.cells
+            .prefix_iter(&wtxn, &prefix)
+            .map_err(|e| Error::Storage(e.to_string()))?
+            .map(|entry| entry.map(|(key, _)| key.to_vec()))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        for key in &cell_keys {
+            self.cells.delete(&mut wtxn, key).map_err(|e| Error::Storage(e.to_string()))?;
+        }
+
+        let dag_keys: Vec<Vec<u8>> = self.dag
+            .prefix_iter(&wtxn, &prefix)
+            .map_err(|e| Error::Storage(e.to_string()))?
+            .map(|entry| entry.map(|(key, _)| key.to_vec()))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        for key in &dag_keys {
+            self.dag.delete(&mut wtxn, key).map_err(|e| Error::Storage(e.to_string()))?;
+        }
+
+        wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    fn row_count(&self) -> Result<usize> {
+        Ok(self.all_pks()?.len())
+    }
+
+    fn all_pks(&self) -> Result<Vec<String>> {
+        let rtxn = self.env.read_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        let mut pks = HashSet::new();
+        for entry in self.cells.iter(&rtxn).map_err(|e| Error::Storage(e.to_string()))? {
+            let (key, _) = entry.map_err(|e| Error::Storage(e.to_string()))?;
+            if let Some(nul) = key.iter().position(|&b| b == 0) {
+                pks.insert(String::from_utf8_lossy(&key[..nul]).to_string());
+            }
+        }
+        let mut pks: Vec<String> = pks.into_iter().collect();
+        pks.sort();
+        Ok(pks)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>> {
+        // `crr_cells` is keyed `pk\0col`, and LMDB iterates keys in raw byte
+        // order — the NUL separator sorts before any other byte, so a
+        // straight byte-prefix match on `prefix` can't cross into a longer
+        // `pk` the way a naive substring check could, making this the same
+        // indexed-cursor walk `SqliteStorage::scan_prefix` does via GLOB.
+        let rtxn = self.env.read_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        let mut entries = Vec::new();
+        for entry in self.cells.prefix_iter(&rtxn, prefix.as_bytes()).map_err(|e| Error::Storage(e.to_string()))? {
+            let (key, value) = entry.map_err(|e| Error::Storage(e.to_string()))?;
+            let nul = key.iter().position(|&b| b == 0)
+                .ok_or_else(|| Error::InvalidState("malformed LMDB cell key".to_string()))?;
+            let pk = String::from_utf8_lossy(&key[..nul]).to_string();
+            let col = String::from_utf8_lossy(&key[nul + 1..]).to_string();
+            entries.push(Ok((pk, col, Self::decode_cell(value)?)));
+        }
+        entries.sort_by(|a, b| match (a, b) {
+            (Ok((pk_a, col_a, _)), Ok((pk_b, col_b, _))) => (pk_a, col_a).cmp(&(pk_b, col_b)),
+            _ => std::cmp::Ordering::Equal,
+        });
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn scan_range(&self, start: &str, end: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>> {
+        let rtxn = self.env.read_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        let mut entries = Vec::new();
+        for entry in self.cells.iter(&rtxn).map_err(|e| Error::Storage(e.to_string()))? {
+            let (key, value) = entry.map_err(|e| Error::Storage(e.to_string()))?;
+            let nul = key.iter().position(|&b| b == 0)
+                .ok_or_else(|| Error::InvalidState("malformed LMDB cell key".to_string()))?;
+            let pk = String::from_utf8_lossy(&key[..nul]).to_string();
+            if pk.as_str() < start || pk.as_str() >= end {
+                continue;
+            }
+            let col = String::from_utf8_lossy(&key[nul + 1..]).to_string();
+            entries.push(Ok((pk, col, Self::decode_cell(value)?)));
+        }
+        entries.sort_by(|a, b| match (a, b) {
+            (Ok((pk_a, col_a, _)), Ok((pk_b, col_b, _))) => (pk_a, col_a).cmp(&(pk_b, col_b)),
+            _ => std::cmp::Ordering::Equal,
+        });
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn append_dag_node(&mut self, pk: &str, col: &str, node: DagNode) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        self.dag
+            .put(&mut wtxn, &Self::dag_key(pk, col, node.version), &Self::encode_node(&node))
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+        self.dag_merkle
+            .entry((pk.to_string(), col.to_string()))
+            .or_default()
+            .append(&node);
+        Ok(())
+    }
+
+    fn get_dag_history(&self, pk: &str, col: &str) -> Result<Vec<DagNode>> {
+        let rtxn = self.env.read_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        let prefix = Self::dag_prefix(pk, col);
+
+        let mut history = Vec::new();
+        for entry in self.dag.prefix_iter(&rtxn, &prefix).map_err(|e| Error::Storage(e.to_string()))? {
+            let (key, value) = entry.map_err(|e| Error::Storage(e.to_string()))?;
+            let version_bytes: [u8; 8] = key[prefix.len()..]
+                .try_into()
+                .map_err(|_| Error::InvalidState("malformed LMDB DAG key".to_string()))?;
+            history.push(Self::decode_node(u64::from_be_bytes(version_bytes), value)?);
+        }
+        Ok(history)
+    }
+
+    fn gc_dag(&mut self, pk: &str, col: &str, keep_versions: usize) -> Result<usize> {
+        let history = self.get_dag_history(pk, col)?;
+        if history.len() <= keep_versions {
+            return Ok(0);
+        }
+        let to_remove = history.len() - keep_versions;
+
+        let mut wtxn = self.env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        for node in &history[..to_remove] {
+            self.dag
+                .delete(&mut wtxn, &Self::dag_key(pk, col, node.version))
+                .map_err(|e| Error::Storage(e.to_string()))?;
+        }
+        wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+        if let Some(merkle) = self.dag_merkle.get_mut(&(pk.to_string(), col.to_string())) {
+            for node in &history[..to_remove] {
+                merkle.forget(node.version);
+            }
+        }
+        Ok(to_remove)
+    }
+
+    fn remove_dag_version(&mut self, pk: &str, col: &str, version: u64) -> Result<()> {
+        let mut wtxn = self.env.write_txn().map_err(|e| Error::Storage(e.to_string()))?;
+        self.dag
+            .delete(&mut wtxn, &Self::dag_key(pk, col, version))
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        wtxn.commit().map_err(|e| Error::Storage(e.to_string()))?;
+        if let Some(merkle) = self.dag_merkle.get_mut(&(pk.to_string(), col.to_string())) {
+            merkle.forget(version);
+        }
+        Ok(())
+    }
+
+    fn dag_root(&self, pk: &str, col: &str) -> Result<Option<Digest>> {
+        Ok(self.dag_merkle.get(&(pk.to_string(), col.to_string())).and_then(|m| m.root()))
+    }
+
+    fn dag_proof(&self, pk: &str, col: &str, version: u64) -> Result<Option<MerkleProof>> {
+        let key = (pk.to_string(), col.to_string());
+        let Some(merkle) = self.dag_merkle.get(&key) else { return Ok(None) };
+        let history = self.get_dag_history(pk, col)?;
+        let Some(node) = history.iter().find(|n| n.version == version) else { return Ok(None) };
+        Ok(merkle.proof(node))
+    }
+
+    fn begin_transaction(&mut self) -> Result<()> {
+        self.in_transaction = true;
+        Ok(())
+    }
+
+    fn commit_transaction(&mut self) -> Result<()> {
+        self.in_transaction = false;
+        Ok(())
+    }
+
+    fn rollback_transaction(&mut self) -> Result<()> {
+        self.in_transaction = false;
+        Ok(())
+    }
+}
+
+unsafe impl Send for LmdbStorage {}
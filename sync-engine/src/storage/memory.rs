@@ -1,18 +1,45 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use super::{Cell, DagNode, Row, Storage};
+use crate::dag_merkle::{DagMerkleAccumulator, Digest, MerkleProof};
 use crate::error::Result;
 
 pub struct MemoryStorage {
-    cells: HashMap<(String, String), Cell>,
+    // A `BTreeMap` (rather than `HashMap`, used everywhere else a key-value
+    // lookup doesn't also need ordering) so `scan_prefix`/`scan_range` can
+    // walk a slice of the table in `(pk, col)` order directly off the map's
+    // own ordering instead of collecting and sorting every key first.
+    cells: BTreeMap<(String, String), Cell>,
     dag: HashMap<(String, String), Vec<DagNode>>,
+    /// One [`DagMerkleAccumulator`] per column, fed every node
+    /// `append_dag_node` accepts — kept alongside `dag` rather than
+    /// rebuilt from it, since `gc_dag`/`remove_dag_version` trim `dag`
+    /// itself but must leave the accumulator's root (and surviving
+    /// proofs) intact.
+    dag_merkle: HashMap<(String, String), DagMerkleAccumulator>,
+    /// Clone of `cells`/`dag` taken by `begin_transaction`, restored by
+    /// `rollback_transaction` — this backend's stand-in for SQLite's real
+    /// transaction log: a full copy, since an in-memory table is small
+    /// enough to clone outright, so a `CrrTable::merge` that fails
+    /// partway through leaves no partial writes behind.
+    ///
+    /// Note: the Merkle accumulator is *not* snapshotted/rolled back —
+    /// `DagMerkleAccumulator` has no way to "unappend" a leaf, so a rolled
+    /// back transaction's nodes stay counted in its root. This matches the
+    /// accumulator's append-only contract (it trees history as it actually
+    /// happened to be appended, not the table's post-rollback view) but
+    /// means `dag_root` can momentarily disagree with `get_dag_history`
+    /// immediately after a rollback.
+    snapshot: Option<(BTreeMap<(String, String), Cell>, HashMap<(String, String), Vec<DagNode>>)>,
 }
 
 impl MemoryStorage {
     pub fn new() -> Self {
         Self {
-            cells: HashMap::new(),
+            cells: BTreeMap::new(),
             dag: HashMap::new(),
+            dag_merkle: HashMap::new(),
+            snapshot: None,
         }
     }
 }
@@ -67,10 +94,30 @@ impl Storage for MemoryStorage {
         Ok(pks)
     }
 
+    fn scan_prefix(&self, prefix: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>> {
+        let start = (prefix.to_string(), String::new());
+        let prefix = prefix.to_string();
+        let entries: Vec<_> = self.cells.range(start..)
+            .take_while(|((pk, _), _)| pk.starts_with(&prefix))
+            .map(|((pk, col), cell)| Ok((pk.clone(), col.clone(), cell.clone())))
+            .collect();
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn scan_range(&self, start: &str, end: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>> {
+        let start_key = (start.to_string(), String::new());
+        let end = end.to_string();
+        let entries: Vec<_> = self.cells.range(start_key..)
+            .take_while(|((pk, _), _)| pk.as_str() < end.as_str())
+            .map(|((pk, col), cell)| Ok((pk.clone(), col.clone(), cell.clone())))
+            .collect();
+        Ok(Box::new(entries.into_iter()))
+    }
+
     fn append_dag_node(&mut self, pk: &str, col: &str, node: DagNode) -> Result<()> {
-        self.dag.entry((pk.to_string(), col.to_string()))
-            .or_default()
-            .push(node);
+        let key = (pk.to_string(), col.to_string());
+        self.dag_merkle.entry(key.clone()).or_default().append(&node);
+        self.dag.entry(key).or_default().push(node);
         Ok(())
     }
 
@@ -87,22 +134,57 @@ impl Storage for MemoryStorage {
                 return Ok(0);
             }
             let removed = history.len() - keep_versions;
-            history.drain(0..removed);
+            let merkle = self.dag_merkle.entry(key).or_default();
+            for node in history.drain(0..removed) {
+                merkle.forget(node.version);
+            }
             Ok(removed)
         } else {
             Ok(0)
         }
     }
 
+    fn remove_dag_version(&mut self, pk: &str, col: &str, version: u64) -> Result<()> {
+        let key = (pk.to_string(), col.to_string());
+        if let Some(history) = self.dag.get_mut(&key) {
+            history.retain(|node| node.version != version);
+        }
+        if let Some(merkle) = self.dag_merkle.get_mut(&key) {
+            merkle.forget(version);
+        }
+        Ok(())
+    }
+
+    fn dag_root(&self, pk: &str, col: &str) -> Result<Option<Digest>> {
+        let key = (pk.to_string(), col.to_string());
+        Ok(self.dag_merkle.get(&key).and_then(|m| m.root()))
+    }
+
+    fn dag_proof(&self, pk: &str, col: &str, version: u64) -> Result<Option<MerkleProof>> {
+        let key = (pk.to_string(), col.to_string());
+        let Some(merkle) = self.dag_merkle.get(&key) else { return Ok(None) };
+        let Some(history) = self.dag.get(&key) else { return Ok(None) };
+        let Some(node) = history.iter().find(|n| n.version == version) else { return Ok(None) };
+        Ok(merkle.proof(node))
+    }
+
     fn begin_transaction(&mut self) -> Result<()> {
+        if self.snapshot.is_none() {
+            self.snapshot = Some((self.cells.clone(), self.dag.clone()));
+        }
         Ok(())
     }
 
     fn commit_transaction(&mut self) -> Result<()> {
+        self.snapshot = None;
         Ok(())
     }
 
     fn rollback_transaction(&mut self) -> Result<()> {
+        if let Some((cells, dag)) = self.snapshot.take() {
+            self.cells = cells;
+            self.dag = dag;
+        }
         Ok(())
     }
 }
@@ -1,13 +1,23 @@
 mod sqlite;
 mod memory;
+#[cfg(feature = "redis-backend")]
+mod redis;
+#[cfg(feature = "lmdb-backend")]
+mod lmdb;
 
-pub use sqlite::SqliteStorage;
+pub use sqlite::{BackupProgress, ChangeEvent, SqliteStorage};
 pub use memory::MemoryStorage;
+#[cfg(feature = "redis-backend")]
+pub use redis::RedisStorage;
+#[cfg(feature = "lmdb-backend")]
+pub use lmdb::LmdbStorage;
 
+use crate::dag_merkle::{Digest, MerkleProof};
 use crate::error::Result;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Cell {
     pub value: Vec<u8>,
     pub version: u64,
@@ -19,7 +29,7 @@ pub struct Row {
     pub cells: HashMap<String, Cell>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DagNode {
     pub version: u64,
     pub value: Vec<u8>,
@@ -27,6 +37,13 @@ pub struct DagNode {
     pub parent2_version: Option<u64>,
     pub timestamp: u64,
     pub is_tombstone: bool,
+    /// Sequence number from this table's [`crate::oracle::VersionOracle`],
+    /// shared by every node an `InsertBuilder`/`UpdateBuilder`/`merge` call
+    /// wrote in the same commit — lets [`crate::table::AsOfBound::CommitSeq`]
+    /// reconstruct exactly what a reader pinned to a sequence number could
+    /// see, independent of `version`/`timestamp`. `0` for nodes written
+    /// before this field existed.
+    pub commit_seq: u64,
 }
 
 pub trait Storage {
@@ -37,9 +54,31 @@ pub trait Storage {
     fn row_count(&self) -> Result<usize>;
     fn all_pks(&self) -> Result<Vec<String>>;
 
+    /// Every cell whose primary key starts with `prefix`, in sorted
+    /// `(pk, col)` order — lets a caller walk a slice of the table without
+    /// first collecting every key via `all_pks`/`get_row`.
+    fn scan_prefix(&self, prefix: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>>;
+    /// Every cell with `pk` in `[start, end)`, in sorted `(pk, col)` order.
+    fn scan_range(&self, start: &str, end: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>>;
+
     fn append_dag_node(&mut self, pk: &str, col: &str, node: DagNode) -> Result<()>;
     fn get_dag_history(&self, pk: &str, col: &str) -> Result<Vec<DagNode>>;
     fn gc_dag(&mut self, pk: &str, col: &str, keep_versions: usize) -> Result<usize>;
+    /// Remove a single DAG node by version number, wherever it falls in the
+    /// column's history (not just the oldest). Unlike `gc_dag`'s "keep last
+    /// N" trim, this supports targeted eviction of whichever version a
+    /// caller (e.g. a byte-budgeted history cache) has decided to drop.
+    fn remove_dag_version(&mut self, pk: &str, col: &str, version: u64) -> Result<()>;
+
+    /// The current root of `(pk, col)`'s [`crate::dag_merkle::DagMerkleAccumulator`],
+    /// or `None` if the column has no DAG history (or this backend hasn't
+    /// tracked any since it was last opened — see each impl's notes).
+    fn dag_root(&self, pk: &str, col: &str) -> Result<Option<Digest>>;
+    /// An inclusion proof that `version` is part of `(pk, col)`'s history
+    /// under [`Self::dag_root`], or `None` if that version isn't currently
+    /// tracked (never appended, or since `gc_dag`/`remove_dag_version`-ed
+    /// away).
+    fn dag_proof(&self, pk: &str, col: &str, version: u64) -> Result<Option<MerkleProof>>;
 
     fn begin_transaction(&mut self) -> Result<()>;
     fn commit_transaction(&mut self) -> Result<()>;
@@ -53,3 +92,102 @@ pub fn now_millis() -> u64 {
         .unwrap()
         .as_millis() as u64
 }
+
+struct HeapEntry {
+    node: DagNode,
+    source: usize,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.node.version == other.node.version && self.node.value == other.node.value
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Reversed so the `BinaryHeap` (a max-heap) pops the causally
+        // earliest node first: lower version wins, then — on a same-version
+        // tie across sources — the lexicographically smaller value, the
+        // same tie-break `TieBreakPolicy::LexicographicMin` applies.
+        other.node.version.cmp(&self.node.version)
+            .then_with(|| other.node.value.cmp(&self.node.value))
+    }
+}
+
+/// Merges several already-sorted [`DagNode`] histories (typically one
+/// per peer, each from [`Storage::get_dag_history`]) into a single
+/// causally-sorted stream, without materializing all of them into one
+/// `Vec` up front — so incremental sync and GC can walk a column's
+/// combined cross-peer history one node at a time.
+pub struct MergingIterator {
+    sources: Vec<Box<dyn Iterator<Item = DagNode>>>,
+    heap: std::collections::BinaryHeap<HeapEntry>,
+}
+
+impl MergingIterator {
+    pub fn new(mut sources: Vec<Box<dyn Iterator<Item = DagNode>>>) -> Self {
+        let mut heap = std::collections::BinaryHeap::new();
+        for (i, source) in sources.iter_mut().enumerate() {
+            if let Some(node) = source.next() {
+                heap.push(HeapEntry { node, source: i });
+            }
+        }
+        Self { sources, heap }
+    }
+}
+
+impl Iterator for MergingIterator {
+    type Item = DagNode;
+
+    fn next(&mut self) -> Option<DagNode> {
+        let HeapEntry { node, source } = self.heap.pop()?;
+        if let Some(next_node) = self.sources[source].next() {
+            self.heap.push(HeapEntry { node: next_node, source });
+        }
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(version: u64, value: &[u8]) -> DagNode {
+        DagNode {
+            version,
+            value: value.to_vec(),
+            parent_version: if version > 1 { Some(version - 1) } else { None },
+            parent2_version: None,
+            timestamp: 0,
+            is_tombstone: false,
+            commit_seq: version,
+        }
+    }
+
+    #[test]
+    fn merging_iterator_interleaves_sources_in_version_order() {
+        let a: Box<dyn Iterator<Item = DagNode>> = Box::new(vec![node(1, b"a1"), node(3, b"a3")].into_iter());
+        let b: Box<dyn Iterator<Item = DagNode>> = Box::new(vec![node(2, b"b2"), node(4, b"b4")].into_iter());
+
+        let merged: Vec<u64> = MergingIterator::new(vec![a, b]).map(|n| n.version).collect();
+        assert_eq!(merged, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn merging_iterator_breaks_same_version_ties_lexicographically() {
+        let a: Box<dyn Iterator<Item = DagNode>> = Box::new(vec![node(1, b"zzz")].into_iter());
+        let b: Box<dyn Iterator<Item = DagNode>> = Box::new(vec![node(1, b"aaa")].into_iter());
+
+        let merged: Vec<Vec<u8>> = MergingIterator::new(vec![a, b]).map(|n| n.value).collect();
+        assert_eq!(merged, vec![b"aaa".to_vec(), b"zzz".to_vec()]);
+    }
+}
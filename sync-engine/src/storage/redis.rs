@@ -0,0 +1,393 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use redis::Commands;
+
+use super::{Cell, DagNode, Row, Storage};
+use crate::dag_merkle::{DagMerkleAccumulator, Digest, MerkleProof};
+use crate::error::{Error, Result};
+use crate::wire::{
+    read_bytes, read_option_u64, read_u64, read_u8, write_bytes, write_option_u64,
+};
+
+/// Full `(pks, per-row cells, per-column dag history)` state as read back
+/// from Redis, taken by `begin_transaction` and restored by
+/// `rollback_transaction`. See [`RedisStorage::snapshot_state`].
+type RedisSnapshot = (
+    Vec<String>,
+    HashMap<String, HashMap<String, Vec<u8>>>,
+    HashMap<(String, String), Vec<Vec<u8>>>,
+);
+
+/// Redis-backed `Storage` implementation: each row's columns+version-vector
+/// live in a Redis hash keyed by primary key, and each column's DAG history
+/// lives in a Redis list. Lets multiple processes on different machines
+/// share one CRR table instead of each holding a private SQLite file.
+///
+/// The connection is wrapped in a `RefCell` because `Storage`'s read methods
+/// take `&self` (to mirror `SqliteStorage`, which gets the same thing for
+/// free from `rusqlite::Connection`'s internal locking) while the `redis`
+/// crate's command methods require `&mut Connection`.
+pub struct RedisStorage {
+    conn: RefCell<redis::Connection>,
+    in_transaction: bool,
+    /// One [`DagMerkleAccumulator`] per column, built up in this process as
+    /// `append_dag_node` is called — like `SqliteStorage`'s, it's not
+    /// persisted to Redis, so `dag_root`/`dag_proof` only reflect nodes
+    /// appended since this `RedisStorage` was opened.
+    dag_merkle: HashMap<(String, String), DagMerkleAccumulator>,
+    /// Read back from Redis by `begin_transaction`, restored by
+    /// `rollback_transaction` — this backend's stand-in for SQLite's real
+    /// `BEGIN IMMEDIATE`/`ROLLBACK`, and the same tradeoff `MemoryStorage`
+    /// makes: a full read-then-rewrite of every affected key instead of a
+    /// native multi-command transaction, since Redis has none that covers
+    /// the HSET/RPUSH/SADD mix a `CrrTable::merge` performs. Like
+    /// `MemoryStorage`'s snapshot, this does *not* cover `dag_merkle` — see
+    /// that field's doc comment for why.
+    snapshot: Option<RedisSnapshot>,
+}
+
+impl RedisStorage {
+    pub fn open(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url).map_err(|e| Error::Storage(e.to_string()))?;
+        let conn = client.get_connection().map_err(|e| Error::Storage(e.to_string()))?;
+        Ok(Self {
+            conn: RefCell::new(conn),
+            in_transaction: false,
+            dag_merkle: HashMap::new(),
+            snapshot: None,
+        })
+    }
+
+    /// Read every pk, every row's cells, and every `(pk, col)` dag history
+    /// currently in Redis into memory, so `rollback_transaction` has
+    /// something to restore. O(rows) in both Redis round-trips and memory,
+    /// same cost `remove_dag_version` already accepts for correctness over
+    /// a rewrite-in-place shortcut.
+    fn snapshot_state(&self) -> Result<RedisSnapshot> {
+        let pks = self.all_pks()?;
+        let mut cells_by_pk = HashMap::with_capacity(pks.len());
+        let mut dag_by_key = HashMap::new();
+        let mut conn = self.conn.borrow_mut();
+        for pk in &pks {
+            let cols: HashMap<String, Vec<u8>> = conn
+                .hgetall(Self::cells_key(pk))
+                .map_err(|e| Error::Storage(e.to_string()))?;
+            for col in cols.keys() {
+                let nodes: Vec<Vec<u8>> = conn
+                    .lrange(Self::dag_key(pk, col), 0, -1)
+                    .map_err(|e| Error::Storage(e.to_string()))?;
+                dag_by_key.insert((pk.clone(), col.clone()), nodes);
+            }
+            cells_by_pk.insert(pk.clone(), cols);
+        }
+        Ok((pks, cells_by_pk, dag_by_key))
+    }
+
+    /// Overwrite Redis's pks/cells/dag keys with a snapshot taken by
+    /// `snapshot_state`, undoing whatever `set_cell`/`delete_row`/
+    /// `append_dag_node`/etc. wrote since that snapshot was taken.
+    fn restore_state(&mut self, snapshot: RedisSnapshot) -> Result<()> {
+        let (pks, cells_by_pk, dag_by_key) = snapshot;
+
+        // Every dag key that might exist right now has to be accounted for
+        // before `cells_key` gets overwritten below, since column names —
+        // half of a dag key — live in the per-row hash we're about to
+        // replace.
+        let mut dag_keys: std::collections::HashSet<(String, String)> =
+            dag_by_key.keys().cloned().collect();
+        let current_pks = self.all_pks()?;
+        for pk in &current_pks {
+            if let Some(row) = self.get_row(pk)? {
+                for col in row.cells.keys() {
+                    dag_keys.insert((pk.clone(), col.clone()));
+                }
+            }
+        }
+
+        let mut all_pks = current_pks;
+        for pk in &pks {
+            if !all_pks.contains(pk) {
+                all_pks.push(pk.clone());
+            }
+        }
+
+        let mut conn = self.conn.borrow_mut();
+
+        let _: () = conn.del(Self::pks_key()).map_err(|e| Error::Storage(e.to_string()))?;
+        if !pks.is_empty() {
+            let _: () = conn.sadd(Self::pks_key(), pks.clone()).map_err(|e| Error::Storage(e.to_string()))?;
+        }
+
+        for pk in &all_pks {
+            let _: () = conn.del(Self::cells_key(pk)).map_err(|e| Error::Storage(e.to_string()))?;
+            if let Some(cols) = cells_by_pk.get(pk) {
+                if !cols.is_empty() {
+                    let pairs: Vec<(String, Vec<u8>)> =
+                        cols.iter().map(|(c, v)| (c.clone(), v.clone())).collect();
+                    let _: () = conn.hset_multiple(Self::cells_key(pk), &pairs)
+                        .map_err(|e| Error::Storage(e.to_string()))?;
+                }
+            }
+        }
+
+        for key in &dag_keys {
+            let (pk, col) = key;
+            let _: () = conn.del(Self::dag_key(pk, col)).map_err(|e| Error::Storage(e.to_string()))?;
+            if let Some(nodes) = dag_by_key.get(key) {
+                if !nodes.is_empty() {
+                    let _: () = conn.rpush(Self::dag_key(pk, col), nodes.clone())
+                        .map_err(|e| Error::Storage(e.to_string()))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn pks_key() -> String {
+        "crr:pks".to_string()
+    }
+
+    fn cells_key(pk: &str) -> String {
+        format!("crr:cells:{}", pk)
+    }
+
+    fn dag_key(pk: &str, col: &str) -> String {
+        format!("crr:dag:{}:{}", pk, col)
+    }
+
+    fn encode_cell(cell: &Cell) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_bytes(&mut buf, &cell.value);
+        buf.extend_from_slice(&cell.version.to_le_bytes());
+        buf
+    }
+
+    fn decode_cell(bytes: &[u8]) -> Result<Cell> {
+        let mut cursor = 0usize;
+        let value = read_bytes(bytes, &mut cursor)?;
+        let version = read_u64(bytes, &mut cursor)?;
+        Ok(Cell { value, version })
+    }
+
+    fn encode_node(node: &DagNode) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&node.version.to_le_bytes());
+        write_bytes(&mut buf, &node.value);
+        write_option_u64(&mut buf, node.parent_version);
+        write_option_u64(&mut buf, node.parent2_version);
+        buf.extend_from_slice(&node.timestamp.to_le_bytes());
+        buf.push(node.is_tombstone as u8);
+        buf.extend_from_slice(&node.commit_seq.to_le_bytes());
+        buf
+    }
+
+    fn decode_node(bytes: &[u8]) -> Result<DagNode> {
+        let mut cursor = 0usize;
+        let version = read_u64(bytes, &mut cursor)?;
+        let value = read_bytes(bytes, &mut cursor)?;
+        let parent_version = read_option_u64(bytes, &mut cursor)?;
+        let parent2_version = read_option_u64(bytes, &mut cursor)?;
+        let timestamp = read_u64(bytes, &mut cursor)?;
+        let is_tombstone = read_u8(bytes, &mut cursor)? != 0;
+        let commit_seq = read_u64(bytes, &mut cursor).unwrap_or(0);
+        Ok(DagNode { version, value, parent_version, parent2_version, timestamp, is_tombstone, commit_seq })
+    }
+}
+
+impl Storage for RedisStorage {
+    fn get_cell(&self, pk: &str, col: &str) -> Result<Option<Cell>> {
+        let raw: Option<Vec<u8>> = self.conn.borrow_mut()
+            .hget(Self::cells_key(pk), col)
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        raw.map(|bytes| Self::decode_cell(&bytes)).transpose()
+    }
+
+    fn set_cell(&mut self, pk: &str, col: &str, cell: Cell) -> Result<()> {
+        let encoded = Self::encode_cell(&cell);
+        let mut conn = self.conn.borrow_mut();
+        let _: () = conn.hset(Self::cells_key(pk), col, encoded).map_err(|e| Error::Storage(e.to_string()))?;
+        let _: () = conn.sadd(Self::pks_key(), pk).map_err(|e| Error::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    fn get_row(&self, pk: &str) -> Result<Option<Row>> {
+        let raw: HashMap<String, Vec<u8>> = self.conn.borrow_mut()
+            .hgetall(Self::cells_key(pk))
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        if raw.is_empty() {
+            return Ok(None);
+        }
+        let mut cells = HashMap::with_capacity(raw.len());
+        for (col, bytes) in raw {
+            cells.insert(col, Self::decode_cell(&bytes)?);
+        }
+        Ok(Some(Row { pk: pk.to_string(), cells }))
+    }
+
+    fn delete_row(&mut self, pk: &str) -> Result<()> {
+        let row = self.get_row(pk)?;
+        let mut conn = self.conn.borrow_mut();
+        let _: () = conn.del(Self::cells_key(pk)).map_err(|e| Error::Storage(e.to_string()))?;
+        if let Some(row) = row {
+            for col in row.cells.keys() {
+                let _: () = conn.del(Self::dag_key(pk, col)).map_err(|e| Error::Storage(e.to_string()))?;
+            }
+        }
+        let _: () = conn.srem(Self::pks_key(), pk).map_err(|e| Error::Storage(e.to_string()))?;
+        Ok(())
+    }
+
+    fn row_count(&self) -> Result<usize> {
+        let count: usize = self.conn.borrow_mut()
+            .scard(Self::pks_key())
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        Ok(count)
+    }
+
+    fn all_pks(&self) -> Result<Vec<String>> {
+        let mut pks: Vec<String> = self.conn.borrow_mut()
+            .smembers(Self::pks_key())
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        pks.sort();
+        Ok(pks)
+    }
+
+    fn scan_prefix(&self, prefix: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>> {
+        // `crr:pks` is an unordered Redis set, unlike `crr_cells`'
+        // `(pk, col)` primary key in `SqliteStorage` — so there's no native
+        // cursor to walk here, and this falls back to collecting every
+        // matching row's cells and sorting them in memory.
+        let pks = self.all_pks()?;
+        let mut entries = Vec::new();
+        for pk in pks.into_iter().filter(|pk| pk.starts_with(prefix)) {
+            if let Some(row) = self.get_row(&pk)? {
+                let mut cols: Vec<String> = row.cells.keys().cloned().collect();
+                cols.sort();
+                for col in cols {
+                    let cell = row.cells[&col].clone();
+                    entries.push(Ok((pk.clone(), col, cell)));
+                }
+            }
+        }
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn scan_range(&self, start: &str, end: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>> {
+        let pks = self.all_pks()?;
+        let mut entries = Vec::new();
+        for pk in pks.into_iter().filter(|pk| pk.as_str() >= start && pk.as_str() < end) {
+            if let Some(row) = self.get_row(&pk)? {
+                let mut cols: Vec<String> = row.cells.keys().cloned().collect();
+                cols.sort();
+                for col in cols {
+                    let cell = row.cells[&col].clone();
+                    entries.push(Ok((pk.clone(), col, cell)));
+                }
+            }
+        }
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn append_dag_node(&mut self, pk: &str, col: &str, node: DagNode) -> Result<()> {
+        let encoded = Self::encode_node(&node);
+        let _: () = self.conn.borrow_mut()
+            .rpush(Self::dag_key(pk, col), encoded)
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        self.dag_merkle
+            .entry((pk.to_string(), col.to_string()))
+            .or_default()
+            .append(&node);
+        Ok(())
+    }
+
+    fn get_dag_history(&self, pk: &str, col: &str) -> Result<Vec<DagNode>> {
+        let raw: Vec<Vec<u8>> = self.conn.borrow_mut()
+            .lrange(Self::dag_key(pk, col), 0, -1)
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        raw.iter().map(|bytes| Self::decode_node(bytes)).collect()
+    }
+
+    fn gc_dag(&mut self, pk: &str, col: &str, keep_versions: usize) -> Result<usize> {
+        let len: usize = self.conn.borrow_mut()
+            .llen(Self::dag_key(pk, col))
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        if len <= keep_versions {
+            return Ok(0);
+        }
+        let removed = len - keep_versions;
+        let dropped: Vec<Vec<u8>> = self.conn.borrow_mut()
+            .lrange(Self::dag_key(pk, col), 0, removed as isize - 1)
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        let _: () = self.conn.borrow_mut()
+            .ltrim(Self::dag_key(pk, col), removed as isize, -1)
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        if let Some(merkle) = self.dag_merkle.get_mut(&(pk.to_string(), col.to_string())) {
+            for bytes in &dropped {
+                if let Ok(node) = Self::decode_node(bytes) {
+                    merkle.forget(node.version);
+                }
+            }
+        }
+        Ok(removed)
+    }
+
+    fn remove_dag_version(&mut self, pk: &str, col: &str, version: u64) -> Result<()> {
+        // Redis lists have no "delete by predicate" op, so rewrite the list
+        // with the matching version dropped. DAG history lists are small
+        // relative to a table's live row data, so this is acceptable.
+        let key = Self::dag_key(pk, col);
+        let raw: Vec<Vec<u8>> = self.conn.borrow_mut()
+            .lrange(&key, 0, -1)
+            .map_err(|e| Error::Storage(e.to_string()))?;
+        let remaining: Vec<Vec<u8>> = raw.into_iter()
+            .filter(|bytes| Self::decode_node(bytes).map(|n| n.version != version).unwrap_or(true))
+            .collect();
+
+        let mut conn = self.conn.borrow_mut();
+        let _: () = conn.del(&key).map_err(|e| Error::Storage(e.to_string()))?;
+        if !remaining.is_empty() {
+            let _: () = conn.rpush(&key, remaining).map_err(|e| Error::Storage(e.to_string()))?;
+        }
+        drop(conn);
+        if let Some(merkle) = self.dag_merkle.get_mut(&(pk.to_string(), col.to_string())) {
+            merkle.forget(version);
+        }
+        Ok(())
+    }
+
+    fn dag_root(&self, pk: &str, col: &str) -> Result<Option<Digest>> {
+        Ok(self.dag_merkle.get(&(pk.to_string(), col.to_string())).and_then(|m| m.root()))
+    }
+
+    fn dag_proof(&self, pk: &str, col: &str, version: u64) -> Result<Option<MerkleProof>> {
+        let key = (pk.to_string(), col.to_string());
+        let Some(merkle) = self.dag_merkle.get(&key) else { return Ok(None) };
+        let history = self.get_dag_history(pk, col)?;
+        let Some(node) = history.iter().find(|n| n.version == version) else { return Ok(None) };
+        Ok(merkle.proof(node))
+    }
+
+    fn begin_transaction(&mut self) -> Result<()> {
+        if !self.in_transaction {
+            self.snapshot = Some(self.snapshot_state()?);
+            self.in_transaction = true;
+        }
+        Ok(())
+    }
+
+    fn commit_transaction(&mut self) -> Result<()> {
+        self.snapshot = None;
+        self.in_transaction = false;
+        Ok(())
+    }
+
+    fn rollback_transaction(&mut self) -> Result<()> {
+        if let Some(snapshot) = self.snapshot.take() {
+            self.restore_state(snapshot)?;
+        }
+        self.in_transaction = false;
+        Ok(())
+    }
+}
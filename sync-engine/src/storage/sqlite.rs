@@ -1,8 +1,14 @@
-use rusqlite::{params, Connection, OptionalExtension};
+use rusqlite::backup::{Backup, StepResult};
+use rusqlite::blob::Blob;
+use rusqlite::{params, Connection, DatabaseName, OptionalExtension};
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
 
 use super::{Cell, DagNode, Row, Storage};
-use crate::error::Result;
+use crate::dag_merkle::{DagMerkleAccumulator, Digest, MerkleProof};
+use crate::error::{Error, Result};
 
 const INIT_SQL: &str = r#"
 CREATE TABLE IF NOT EXISTS crr_cells (
@@ -11,7 +17,7 @@ CREATE TABLE IF NOT EXISTS crr_cells (
     value BLOB NOT NULL,
     version INTEGER NOT NULL,
     PRIMARY KEY (pk, col)
-) WITHOUT ROWID;
+);
 
 CREATE TABLE IF NOT EXISTS crr_dag (
     pk TEXT NOT NULL,
@@ -22,6 +28,7 @@ CREATE TABLE IF NOT EXISTS crr_dag (
     parent2_version INTEGER,
     timestamp INTEGER NOT NULL,
     is_tombstone INTEGER NOT NULL DEFAULT 0,
+    commit_seq INTEGER NOT NULL DEFAULT 0,
     PRIMARY KEY (pk, col, version)
 ) WITHOUT ROWID;
 
@@ -34,6 +41,14 @@ PRAGMA synchronous = NORMAL;
 pub struct SqliteStorage {
     conn: Connection,
     in_transaction: bool,
+    pending_events: Rc<RefCell<Vec<ChangeEvent>>>,
+    subscribers: Rc<RefCell<Vec<Box<dyn FnMut(ChangeEvent)>>>>,
+    /// One [`DagMerkleAccumulator`] per column, built up as `append_dag_node`
+    /// is called. Unlike `crr_dag`, this is in-memory only — reopening an
+    /// existing database starts every column's accumulator fresh, so
+    /// `dag_root`/`dag_proof` only ever reflect nodes appended in the
+    /// current process lifetime, not a database's full on-disk history.
+    dag_merkle: HashMap<(String, String), DagMerkleAccumulator>,
 }
 
 impl SqliteStorage {
@@ -44,12 +59,238 @@ impl SqliteStorage {
             Connection::open(path)?
         };
         conn.execute_batch(INIT_SQL)?;
-        Ok(Self { conn, in_transaction: false })
+        let pending_events = Rc::new(RefCell::new(Vec::new()));
+        let subscribers: Rc<RefCell<Vec<Box<dyn FnMut(ChangeEvent)>>>> = Rc::new(RefCell::new(Vec::new()));
+        register_change_hooks(&conn, pending_events.clone(), subscribers.clone());
+        Ok(Self { conn, in_transaction: false, pending_events, subscribers, dag_merkle: HashMap::new() })
+    }
+
+    /// Register a callback to be notified of change events: a `crr_cells`
+    /// write, a `crr_dag` write, or a rollback. Writes made inside an
+    /// explicit `begin_transaction`/`commit_transaction` pair are buffered
+    /// and delivered together when the transaction commits (mirroring
+    /// SQLite's own commit hook semantics), so a subscriber can push a
+    /// batch of deltas to peers right after a commit instead of polling.
+    /// A `rollback_transaction` discards the buffered events and delivers
+    /// a single [`ChangeEvent::RolledBack`] so subscribers can drop any
+    /// speculative state they built up from the discarded writes.
+    pub fn subscribe(&mut self, cb: impl FnMut(ChangeEvent) + 'static) {
+        self.subscribers.borrow_mut().push(Box::new(cb));
     }
 
     pub fn open_in_memory() -> Result<Self> {
         Self::open(":memory:")
     }
+
+    /// Copy this database to `dest_path` using SQLite's online backup API,
+    /// a handful of pages at a time with no pause between steps. Because
+    /// this store runs in WAL mode, the copy proceeds without blocking
+    /// concurrent readers or writers on the source.
+    pub fn backup_to(&self, dest_path: &str) -> Result<()> {
+        self.backup_to_throttled(dest_path, 100, Duration::ZERO, |_| {})
+    }
+
+    /// Like [`Self::backup_to`], but copies `pages_per_step` pages at a
+    /// time, sleeping `step_interval` between steps, and reporting
+    /// progress via `on_progress` after each step — lets a large DAG
+    /// history be snapshotted without starving foreground merges.
+    pub fn backup_to_throttled(
+        &self,
+        dest_path: &str,
+        pages_per_step: i32,
+        step_interval: Duration,
+        on_progress: impl FnMut(BackupProgress),
+    ) -> Result<()> {
+        let mut dest = Connection::open(dest_path)?;
+        run_backup(&self.conn, &mut dest, pages_per_step, step_interval, on_progress)
+    }
+
+    /// Replace this database's contents with a copy restored from
+    /// `src_path`, using the same online backup mechanism as
+    /// [`Self::backup_to`] but in reverse.
+    pub fn restore_from(&mut self, src_path: &str) -> Result<()> {
+        self.restore_from_throttled(src_path, 100, Duration::ZERO, |_| {})
+    }
+
+    /// Like [`Self::restore_from`], throttled the same way as
+    /// [`Self::backup_to_throttled`].
+    pub fn restore_from_throttled(
+        &mut self,
+        src_path: &str,
+        pages_per_step: i32,
+        step_interval: Duration,
+        on_progress: impl FnMut(BackupProgress),
+    ) -> Result<()> {
+        let src = Connection::open(src_path)?;
+        run_backup(&src, &mut self.conn, pages_per_step, step_interval, on_progress)
+    }
+
+    /// Byte length of a cell's value without reading it into memory.
+    pub fn cell_value_len(&self, pk: &str, col: &str) -> Result<Option<usize>> {
+        let len: Option<i64> = self.conn.query_row(
+            "SELECT length(value) FROM crr_cells WHERE pk = ?1 AND col = ?2",
+            params![pk, col],
+            |row| row.get(0),
+        ).optional()?;
+        Ok(len.map(|n| n as usize))
+    }
+
+    /// Open a cell's value for incremental, read-only streaming via
+    /// SQLite's BLOB I/O, without materializing it in memory. The returned
+    /// handle implements `Read` and `Seek`, so callers can hash, range-read,
+    /// or diff a large value (e.g. file contents keyed by `pk`) a chunk at
+    /// a time.
+    pub fn open_cell_blob(&self, pk: &str, col: &str) -> Result<Blob<'_>> {
+        let rowid = self.cell_rowid(pk, col)?;
+        self.conn.blob_open(DatabaseName::Main, "crr_cells", "value", rowid, true)
+            .map_err(|e| Error::Storage(e.to_string()))
+    }
+
+    /// Pre-size a cell's value to `len` zeroed bytes (creating the row if
+    /// it doesn't exist) and open it for incremental writing. Callers
+    /// stream their data into the returned handle via `Write`/`Seek`
+    /// instead of building the whole value in memory first.
+    pub fn open_cell_blob_writer(&mut self, pk: &str, col: &str, len: usize, version: u64) -> Result<Blob<'_>> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO crr_cells (pk, col, value, version) VALUES (?1, ?2, zeroblob(?3), ?4)",
+            params![pk, col, len as i64, version],
+        )?;
+        let rowid = self.cell_rowid(pk, col)?;
+        self.conn.blob_open(DatabaseName::Main, "crr_cells", "value", rowid, false)
+            .map_err(|e| Error::Storage(e.to_string()))
+    }
+
+    fn cell_rowid(&self, pk: &str, col: &str) -> Result<i64> {
+        self.conn.query_row(
+            "SELECT rowid FROM crr_cells WHERE pk = ?1 AND col = ?2",
+            params![pk, col],
+            |row| row.get(0),
+        ).optional()?
+            .ok_or_else(|| Error::NotFound { pk: pk.to_string(), col: Some(col.to_string()) })
+    }
+}
+
+#[cfg(feature = "sqlcipher")]
+impl SqliteStorage {
+    /// Open a SQLCipher-encrypted database at `path`, keying it via
+    /// `PRAGMA key` before `INIT_SQL` creates the schema, so `crr_cells`
+    /// and `crr_dag` are confidential at rest. Requires building against a
+    /// SQLCipher-enabled SQLite (this crate's `sqlcipher` feature).
+    pub fn open_encrypted(path: &str, key: &[u8]) -> Result<Self> {
+        let conn = Connection::open(path)?;
+        apply_key(&conn, key)?;
+        conn.execute_batch(INIT_SQL)?;
+        let pending_events = Rc::new(RefCell::new(Vec::new()));
+        let subscribers: Rc<RefCell<Vec<Box<dyn FnMut(ChangeEvent)>>>> = Rc::new(RefCell::new(Vec::new()));
+        register_change_hooks(&conn, pending_events.clone(), subscribers.clone());
+        Ok(Self { conn, in_transaction: false, pending_events, subscribers, dag_merkle: HashMap::new() })
+    }
+
+    /// Re-encrypt the database in place under `new_key`, replacing
+    /// whatever key it was opened with.
+    pub fn rekey(&mut self, new_key: &[u8]) -> Result<()> {
+        self.conn.execute_batch(&format!("PRAGMA rekey = \"x'{}'\"", to_hex_key(new_key)))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlcipher")]
+fn apply_key(conn: &Connection, key: &[u8]) -> Result<()> {
+    conn.execute_batch(&format!("PRAGMA key = \"x'{}'\"", to_hex_key(key)))?;
+    Ok(())
+}
+
+#[cfg(feature = "sqlcipher")]
+fn to_hex_key(key: &[u8]) -> String {
+    key.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Snapshot of SQLite's backup progress after one `step()` call.
+#[derive(Debug, Clone, Copy)]
+pub struct BackupProgress {
+    pub pages_remaining: i32,
+    pub pages_total: i32,
+}
+
+/// A structured notification delivered to [`SqliteStorage::subscribe`]rs.
+///
+/// `Cell`/`Dag` events report the write's `pk`, `col`, and new `version`
+/// once the transaction that made it has committed. `RolledBack` carries
+/// no row data — it's a cancellation signal telling subscribers to discard
+/// whatever speculative state they derived from the writes that were just
+/// undone.
+#[derive(Debug, Clone)]
+pub enum ChangeEvent {
+    Cell { pk: String, col: String, version: u64 },
+    Dag { pk: String, col: String, version: u64 },
+    RolledBack,
+}
+
+/// Hook SQLite's real commit/rollback callbacks so buffered change events
+/// are delivered at the actual transaction boundary. We don't hang the
+/// per-row notification off SQLite's `update_hook`: it never fires for
+/// `WITHOUT ROWID` tables, and re-querying the connection from inside the
+/// hook to resolve a rowid back to `(pk, col, version)` is exactly the
+/// kind of reentrant access the hook forbids. Instead `set_cell` and
+/// `append_dag_node` push a `ChangeEvent` onto `pending_events` as they
+/// write, and this commit hook flushes that buffer to subscribers once
+/// SQLite confirms the transaction actually landed.
+fn register_change_hooks(
+    conn: &Connection,
+    pending_events: Rc<RefCell<Vec<ChangeEvent>>>,
+    subscribers: Rc<RefCell<Vec<Box<dyn FnMut(ChangeEvent)>>>>,
+) {
+    let commit_pending = pending_events.clone();
+    let commit_subs = subscribers.clone();
+    conn.commit_hook(Some(move || {
+        let events: Vec<ChangeEvent> = commit_pending.borrow_mut().drain(..).collect();
+        let mut subs = commit_subs.borrow_mut();
+        for event in events {
+            for cb in subs.iter_mut() {
+                cb(event.clone());
+            }
+        }
+        false
+    }));
+
+    conn.rollback_hook(Some(move || {
+        pending_events.borrow_mut().clear();
+        let mut subs = subscribers.borrow_mut();
+        for cb in subs.iter_mut() {
+            cb(ChangeEvent::RolledBack);
+        }
+    }));
+}
+
+fn run_backup(
+    src: &Connection,
+    dest: &mut Connection,
+    pages_per_step: i32,
+    step_interval: Duration,
+    mut on_progress: impl FnMut(BackupProgress),
+) -> Result<()> {
+    let backup = Backup::new(src, dest).map_err(|e| Error::Storage(e.to_string()))?;
+
+    loop {
+        let result = backup.step(pages_per_step).map_err(|e| Error::Storage(e.to_string()))?;
+        let progress = backup.progress();
+        on_progress(BackupProgress {
+            pages_remaining: progress.remaining,
+            pages_total: progress.pagecount,
+        });
+
+        match result {
+            StepResult::Done => return Ok(()),
+            StepResult::More => {
+                if !step_interval.is_zero() {
+                    std::thread::sleep(step_interval);
+                }
+            }
+            StepResult::Busy | StepResult::Locked => {
+                std::thread::sleep(step_interval.max(Duration::from_millis(10)));
+            }
+        }
+    }
 }
 
 impl Storage for SqliteStorage {
@@ -63,10 +304,24 @@ impl Storage for SqliteStorage {
     }
 
     fn set_cell(&mut self, pk: &str, col: &str, cell: Cell) -> Result<()> {
-        self.conn.execute(
+        // Buffered before the write, not after: in autocommit mode the
+        // `execute` below is its own implicit transaction, and the commit
+        // hook fires as part of it returning. Queuing the event first
+        // means it's already in `pending_events` by the time that hook
+        // runs; queuing it after would miss the flush entirely.
+        self.pending_events.borrow_mut().push(ChangeEvent::Cell {
+            pk: pk.to_string(),
+            col: col.to_string(),
+            version: cell.version,
+        });
+        let result = self.conn.execute(
             "INSERT OR REPLACE INTO crr_cells (pk, col, value, version) VALUES (?1, ?2, ?3, ?4)",
             params![pk, col, cell.value, cell.version],
-        )?;
+        );
+        if result.is_err() {
+            self.pending_events.borrow_mut().pop();
+        }
+        result?;
         Ok(())
     }
 
@@ -113,10 +368,46 @@ impl Storage for SqliteStorage {
         Ok(pks)
     }
 
+    fn scan_prefix(&self, prefix: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>> {
+        // `crr_cells`' `(pk, col)` primary key is already the index this
+        // walks: GLOB uses the same binary, byte-wise ordering `BTreeMap`
+        // does in `MemoryStorage`, so this is the SQLite equivalent of that
+        // backend's `range` cursor rather than a full-table scan.
+        let mut stmt = self.conn.prepare(
+            "SELECT pk, col, value, version FROM crr_cells WHERE pk GLOB ?1 ORDER BY pk, col"
+        )?;
+        // `*`, `?` and `[` are GLOB wildcards; bracket each one so it
+        // matches only itself.
+        let escaped: String = prefix.chars().map(|c| match c {
+            '*' | '?' | '[' => format!("[{}]", c),
+            _ => c.to_string(),
+        }).collect();
+        let pattern = format!("{}*", escaped);
+        let rows = stmt.query_map(params![pattern], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, Cell { value: row.get(2)?, version: row.get(3)? }))
+        })?.collect::<std::result::Result<Vec<(String, String, Cell)>, _>>()?;
+        Ok(Box::new(rows.into_iter().map(Ok)))
+    }
+
+    fn scan_range(&self, start: &str, end: &str) -> Result<Box<dyn Iterator<Item = Result<(String, String, Cell)>>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT pk, col, value, version FROM crr_cells WHERE pk >= ?1 AND pk < ?2 ORDER BY pk, col"
+        )?;
+        let rows = stmt.query_map(params![start, end], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, Cell { value: row.get(2)?, version: row.get(3)? }))
+        })?.collect::<std::result::Result<Vec<(String, String, Cell)>, _>>()?;
+        Ok(Box::new(rows.into_iter().map(Ok)))
+    }
+
     fn append_dag_node(&mut self, pk: &str, col: &str, node: DagNode) -> Result<()> {
-        self.conn.execute(
-            "INSERT OR REPLACE INTO crr_dag (pk, col, version, value, parent_version, parent2_version, timestamp, is_tombstone)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        self.pending_events.borrow_mut().push(ChangeEvent::Dag {
+            pk: pk.to_string(),
+            col: col.to_string(),
+            version: node.version,
+        });
+        let result = self.conn.execute(
+            "INSERT OR REPLACE INTO crr_dag (pk, col, version, value, parent_version, parent2_version, timestamp, is_tombstone, commit_seq)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 pk,
                 col,
@@ -126,14 +417,23 @@ impl Storage for SqliteStorage {
                 node.parent2_version,
                 node.timestamp,
                 node.is_tombstone as i32,
+                node.commit_seq,
             ],
-        )?;
+        );
+        if result.is_err() {
+            self.pending_events.borrow_mut().pop();
+        }
+        result?;
+        self.dag_merkle
+            .entry((pk.to_string(), col.to_string()))
+            .or_default()
+            .append(&node);
         Ok(())
     }
 
     fn get_dag_history(&self, pk: &str, col: &str) -> Result<Vec<DagNode>> {
         let mut stmt = self.conn.prepare(
-            "SELECT version, value, parent_version, parent2_version, timestamp, is_tombstone
+            "SELECT version, value, parent_version, parent2_version, timestamp, is_tombstone, commit_seq
              FROM crr_dag WHERE pk = ?1 AND col = ?2 ORDER BY version"
         )?;
         let nodes = stmt.query_map(params![pk, col], |row| {
@@ -144,6 +444,7 @@ impl Storage for SqliteStorage {
                 parent2_version: row.get(3)?,
                 timestamp: row.get(4)?,
                 is_tombstone: row.get::<_, i32>(5)? != 0,
+                commit_seq: row.get(6)?,
             })
         })?.collect::<std::result::Result<Vec<_>, _>>()?;
         Ok(nodes)
@@ -162,12 +463,45 @@ impl Storage for SqliteStorage {
             "DELETE FROM crr_dag WHERE pk = ?1 AND col = ?2 AND version < ?3",
             params![pk, col, cutoff_version],
         )?;
+        if let Some(merkle) = self.dag_merkle.get_mut(&(pk.to_string(), col.to_string())) {
+            for node in history.iter().take(cutoff_idx) {
+                merkle.forget(node.version);
+            }
+        }
         Ok(deleted)
     }
 
+    fn remove_dag_version(&mut self, pk: &str, col: &str, version: u64) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM crr_dag WHERE pk = ?1 AND col = ?2 AND version = ?3",
+            params![pk, col, version],
+        )?;
+        if let Some(merkle) = self.dag_merkle.get_mut(&(pk.to_string(), col.to_string())) {
+            merkle.forget(version);
+        }
+        Ok(())
+    }
+
+    fn dag_root(&self, pk: &str, col: &str) -> Result<Option<Digest>> {
+        Ok(self.dag_merkle.get(&(pk.to_string(), col.to_string())).and_then(|m| m.root()))
+    }
+
+    fn dag_proof(&self, pk: &str, col: &str, version: u64) -> Result<Option<MerkleProof>> {
+        let key = (pk.to_string(), col.to_string());
+        let Some(merkle) = self.dag_merkle.get(&key) else { return Ok(None) };
+        let history = self.get_dag_history(pk, col)?;
+        let Some(node) = history.iter().find(|n| n.version == version) else { return Ok(None) };
+        Ok(merkle.proof(node))
+    }
+
     fn begin_transaction(&mut self) -> Result<()> {
         if !self.in_transaction {
-            self.conn.execute("BEGIN", [])?;
+            // IMMEDIATE rather than DEFERRED: grab the write lock up front so
+            // a `CrrTable::merge` can't start applying a changeset only to
+            // find a concurrent writer got there first partway through —
+            // the whole merge either sees a consistent, exclusive view or
+            // blocks before touching anything.
+            self.conn.execute("BEGIN IMMEDIATE", [])?;
             self.in_transaction = true;
         }
         Ok(())
@@ -222,6 +556,7 @@ mod tests {
                 parent2_version: None,
                 timestamp: now_millis(),
                 is_tombstone: false,
+                commit_seq: v,
             };
             storage.append_dag_node("row1", "col1", node).unwrap();
         }
@@ -235,4 +570,136 @@ mod tests {
         let history = storage.get_dag_history("row1", "col1").unwrap();
         assert_eq!(history.len(), 2);
     }
+
+    #[test]
+    fn dag_root_and_proof_survive_gc_of_older_versions() {
+        let mut storage = SqliteStorage::open_in_memory().unwrap();
+
+        for v in 1..=5 {
+            let node = DagNode {
+                version: v,
+                value: format!("value_{}", v).into_bytes(),
+                parent_version: if v > 1 { Some(v - 1) } else { None },
+                parent2_version: None,
+                timestamp: now_millis(),
+                is_tombstone: false,
+                commit_seq: v,
+            };
+            storage.append_dag_node("row1", "col1", node).unwrap();
+        }
+
+        let root = storage.dag_root("row1", "col1").unwrap().unwrap();
+        storage.gc_dag("row1", "col1", 2).unwrap();
+
+        assert_eq!(storage.dag_root("row1", "col1").unwrap(), Some(root));
+        let proof = storage.dag_proof("row1", "col1", 5).unwrap().unwrap();
+        assert!(crate::dag_merkle::verify_dag_proof(root, 5, b"value_5", &proof));
+        assert!(storage.dag_proof("row1", "col1", 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn scan_prefix_and_scan_range_walk_cells_in_sorted_order() {
+        let mut storage = SqliteStorage::open_in_memory().unwrap();
+        storage.set_cell("user:1", "name", Cell { value: b"Alice".to_vec(), version: 1 }).unwrap();
+        storage.set_cell("user:2", "name", Cell { value: b"Bob".to_vec(), version: 1 }).unwrap();
+        storage.set_cell("user:2", "email", Cell { value: b"bob@example.com".to_vec(), version: 1 }).unwrap();
+        storage.set_cell("order:1", "total", Cell { value: b"9.99".to_vec(), version: 1 }).unwrap();
+
+        let prefixed: Vec<_> = storage.scan_prefix("user:").unwrap()
+            .collect::<crate::error::Result<Vec<_>>>().unwrap();
+        assert_eq!(
+            prefixed.iter().map(|(pk, col, _)| (pk.as_str(), col.as_str())).collect::<Vec<_>>(),
+            vec![("user:1", "name"), ("user:2", "email"), ("user:2", "name")],
+        );
+
+        let ranged: Vec<_> = storage.scan_range("order:", "user:").unwrap()
+            .collect::<crate::error::Result<Vec<_>>>().unwrap();
+        assert_eq!(ranged.len(), 1);
+        assert_eq!(ranged[0].0, "order:1");
+    }
+
+    #[test]
+    fn backup_and_restore_roundtrip() {
+        let mut storage = SqliteStorage::open_in_memory().unwrap();
+        storage.set_cell("row1", "name", Cell { value: b"Alice".to_vec(), version: 1 }).unwrap();
+
+        let backup_path = std::env::temp_dir()
+            .join(format!("crr_backup_test_{}_{}.db", std::process::id(), now_millis()));
+        let backup_path = backup_path.to_str().unwrap();
+
+        storage.backup_to(backup_path).unwrap();
+
+        let mut restored = SqliteStorage::open_in_memory().unwrap();
+        restored.restore_from(backup_path).unwrap();
+
+        let cell = restored.get_cell("row1", "name").unwrap().unwrap();
+        assert_eq!(cell.value, b"Alice");
+
+        std::fs::remove_file(backup_path).ok();
+    }
+
+    #[test]
+    fn streams_a_cell_value_via_incremental_blob_io() {
+        use std::io::{Read, Seek, SeekFrom, Write};
+
+        let mut storage = SqliteStorage::open_in_memory().unwrap();
+        let payload = b"large file contents, streamed in chunks";
+
+        {
+            let mut blob = storage.open_cell_blob_writer("file_1", "contents", payload.len(), 1).unwrap();
+            blob.write_all(payload).unwrap();
+        }
+
+        assert_eq!(storage.cell_value_len("file_1", "contents").unwrap(), Some(payload.len()));
+
+        let mut blob = storage.open_cell_blob("file_1", "contents").unwrap();
+        let mut buf = vec![0u8; payload.len()];
+        blob.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, payload);
+
+        blob.seek(SeekFrom::Start(6)).unwrap();
+        let mut tail = Vec::new();
+        blob.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, &payload[6..]);
+    }
+
+    #[test]
+    fn subscriber_receives_cell_and_dag_events_in_autocommit_mode() {
+        let mut storage = SqliteStorage::open_in_memory().unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let collected = events.clone();
+        storage.subscribe(move |event| collected.borrow_mut().push(event));
+
+        storage.set_cell("row1", "name", Cell { value: b"Alice".to_vec(), version: 1 }).unwrap();
+        storage.append_dag_node("row1", "name", DagNode {
+            version: 1,
+            value: b"Alice".to_vec(),
+            parent_version: None,
+            parent2_version: None,
+            timestamp: now_millis(),
+            is_tombstone: false,
+            commit_seq: 1,
+        }).unwrap();
+
+        let seen = events.borrow();
+        assert_eq!(seen.len(), 2);
+        assert!(matches!(&seen[0], ChangeEvent::Cell { pk, col, version: 1 } if pk == "row1" && col == "name"));
+        assert!(matches!(&seen[1], ChangeEvent::Dag { pk, col, version: 1 } if pk == "row1" && col == "name"));
+    }
+
+    #[test]
+    fn rollback_fires_a_cancellation_event_and_discards_buffered_writes() {
+        let mut storage = SqliteStorage::open_in_memory().unwrap();
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let collected = events.clone();
+        storage.subscribe(move |event| collected.borrow_mut().push(event));
+
+        storage.begin_transaction().unwrap();
+        storage.set_cell("row1", "name", Cell { value: b"Alice".to_vec(), version: 1 }).unwrap();
+        storage.rollback_transaction().unwrap();
+
+        let seen = events.borrow();
+        assert_eq!(seen.len(), 1);
+        assert!(matches!(seen[0], ChangeEvent::RolledBack));
+    }
 }
@@ -1,18 +1,76 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
 
-use crate::error::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
 use crate::merge::{MergeReport, TieBreakPolicy};
 use crate::storage::Storage;
 use crate::table::CrrTable;
+use crate::wire::{crc32c, read_bytes, read_string, read_u32, read_u64, write_bytes, write_u32};
+
+/// Encode `value` as a self-describing CBOR blob — the format [`Changeset`],
+/// [`HeadExchange`], [`MergeReport`], and [`SyncResult`] all use for
+/// `to_cbor`, chosen (over [`Changeset::serialize`]'s hand-rolled format)
+/// because it's a good fit for nesting arbitrary-length column blobs and
+/// per-pk/per-column maps without hand-written length prefixes for each.
+pub(crate) fn to_cbor<T: Serialize>(value: &T) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    ciborium::into_writer(value, &mut buf)
+        .map_err(|e| Error::InvalidState(format!("failed to encode CBOR frame: {}", e)))?;
+    Ok(buf)
+}
 
-#[derive(Debug, Clone)]
+pub(crate) fn from_cbor<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    ciborium::from_reader(bytes)
+        .map_err(|e| Error::InvalidState(format!("failed to decode CBOR frame: {}", e)))
+}
+
+/// Write `bytes` as a length-prefixed frame: a little-endian `u32` byte
+/// count followed by the bytes themselves. Used to send a CBOR-encoded
+/// [`HeadExchange`] or [`Changeset`] over any [`Write`] stream, where
+/// message boundaries otherwise wouldn't survive the trip. Also reused by
+/// [`crate::transport::TcpTransport`] to frame the legacy engine's
+/// changesets the same way.
+pub(crate) fn write_frame<W: Write>(writer: &mut W, bytes: &[u8]) -> Result<()> {
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(bytes)?;
+    Ok(())
+}
+
+/// Read back a frame written by [`write_frame`].
+pub(crate) fn read_frame<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut len_buf = [0u8; 4];
+    reader.read_exact(&mut len_buf)?;
+    let len = u32::from_le_bytes(len_buf) as usize;
+    let mut buf = vec![0u8; len];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Changeset {
     pub changes: HashMap<String, (HashMap<String, Vec<u8>>, HashMap<String, u64>)>,
+    /// Per-`(pk, col)` write timestamp of the DAG node each column in
+    /// `changes` came from, only consulted by [`TieBreakPolicy::LastWriteWins`]
+    /// — every other policy ignores it. A `pk`/`col` missing here (e.g. a
+    /// `Changeset` built by hand, like in tests) is treated as timestamp
+    /// `0` by [`CrrTable::merge`], the oldest possible value.
+    pub origins: HashMap<String, HashMap<String, u64>>,
+    /// Per-pk set of columns in `changes` that are deletions rather than
+    /// live values — written by [`CrrTable::delete`] as a versioned
+    /// tombstone so it propagates through [`CrrTable::merge`] like any
+    /// other write instead of the row silently reappearing once a peer
+    /// that never saw the delete syncs back. Absent here (the default for
+    /// a hand-built `Changeset`, e.g. in tests) means none of it is.
+    #[serde(default)]
+    pub tombstones: HashMap<String, HashSet<String>>,
 }
 
 impl Changeset {
     pub fn new() -> Self {
-        Self { changes: HashMap::new() }
+        Self { changes: HashMap::new(), origins: HashMap::new(), tombstones: HashMap::new() }
     }
 
     pub fn len(&self) -> usize {
@@ -34,6 +92,102 @@ impl Changeset {
             })
             .sum()
     }
+
+    /// Encode this changeset as a compact, length-prefixed binary blob:
+    /// row count, then per row the pk, column count, and per column the
+    /// name, value, version, origin timestamp, and a tombstone flag byte
+    /// as a (name, value, version-u64, timestamp-u64, tombstone-u8) quint,
+    /// followed by a trailing CRC-32C over everything that precedes it.
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        write_u32(&mut buf, self.changes.len() as u32);
+
+        for (pk, (cols, vers)) in &self.changes {
+            write_bytes(&mut buf, pk.as_bytes());
+            write_u32(&mut buf, cols.len() as u32);
+
+            for (col, value) in cols {
+                write_bytes(&mut buf, col.as_bytes());
+                write_bytes(&mut buf, value);
+                let version = vers.get(col).copied().unwrap_or(0);
+                buf.extend_from_slice(&version.to_le_bytes());
+                let timestamp = self.origins.get(pk).and_then(|m| m.get(col)).copied().unwrap_or(0);
+                buf.extend_from_slice(&timestamp.to_le_bytes());
+                let is_tombstone = self.tombstones.get(pk).is_some_and(|set| set.contains(col));
+                buf.push(is_tombstone as u8);
+            }
+        }
+
+        let checksum = crc32c(&buf);
+        buf.extend_from_slice(&checksum.to_le_bytes());
+        buf
+    }
+
+    /// Decode a changeset produced by [`Changeset::serialize`], verifying
+    /// its trailing CRC-32C first. Returns `Error::ChangesetCorrupt` rather
+    /// than decoding (and the caller merging) a body that's been flipped
+    /// by a bad transport or a faulty disk.
+    pub fn deserialize(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 4 {
+            return Err(crate::error::Error::InvalidState("truncated wire data".to_string()));
+        }
+        let (body, trailer) = bytes.split_at(bytes.len() - 4);
+        let expected_crc = u32::from_le_bytes(trailer.try_into().unwrap());
+        let actual_crc = crc32c(body);
+        if actual_crc != expected_crc {
+            return Err(Error::ChangesetCorrupt { expected_crc, actual_crc });
+        }
+
+        let mut cursor = 0usize;
+        let row_count = read_u32(body, &mut cursor)?;
+        let mut changes = HashMap::with_capacity(row_count as usize);
+        let mut origins = HashMap::with_capacity(row_count as usize);
+        let mut tombstones = HashMap::new();
+
+        for _ in 0..row_count {
+            let pk = read_string(body, &mut cursor)?;
+            let col_count = read_u32(body, &mut cursor)?;
+
+            let mut cols = HashMap::with_capacity(col_count as usize);
+            let mut vers = HashMap::with_capacity(col_count as usize);
+            let mut timestamps = HashMap::with_capacity(col_count as usize);
+            let mut tombstoned_cols = HashSet::new();
+            for _ in 0..col_count {
+                let name = read_string(body, &mut cursor)?;
+                let value = read_bytes(body, &mut cursor)?;
+                let version = read_u64(body, &mut cursor)?;
+                let timestamp = read_u64(body, &mut cursor)?;
+                if *body.get(cursor).ok_or_else(|| Error::InvalidState("truncated wire data".to_string()))? != 0 {
+                    tombstoned_cols.insert(name.clone());
+                }
+                cursor += 1;
+                vers.insert(name.clone(), version);
+                timestamps.insert(name.clone(), timestamp);
+                cols.insert(name, value);
+            }
+
+            changes.insert(pk.clone(), (cols, vers));
+            origins.insert(pk.clone(), timestamps);
+            if !tombstoned_cols.is_empty() {
+                tombstones.insert(pk, tombstoned_cols);
+            }
+        }
+
+        Ok(Self { changes, origins, tombstones })
+    }
+
+    /// Encode as a self-describing CBOR blob — unlike [`Self::serialize`],
+    /// this carries field names and can gain new ones without breaking an
+    /// older reader, at the cost of being less compact. Preferred for
+    /// crossing a process boundary (see [`SyncSession::sync_over`]).
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        to_cbor(self)
+    }
+
+    /// Decode a blob produced by [`Self::to_cbor`].
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        from_cbor(bytes)
+    }
 }
 
 impl Default for Changeset {
@@ -42,7 +196,7 @@ impl Default for Changeset {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HeadExchange {
     pub peer_id: String,
     pub heads: HashMap<String, HashMap<String, u64>>,
@@ -65,6 +219,17 @@ impl HeadExchange {
 
         Ok(Self { peer_id: peer_id.to_string(), heads })
     }
+
+    /// Encode as a self-describing CBOR blob, the request half of the
+    /// framed protocol [`SyncSession::sync_over`] drives.
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        to_cbor(self)
+    }
+
+    /// Decode a blob produced by [`Self::to_cbor`].
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        from_cbor(bytes)
+    }
 }
 
 pub struct SyncSession {
@@ -76,29 +241,79 @@ impl SyncSession {
         Self { policy }
     }
 
+    /// Sync `peer_a` and `peer_b` against each other. Each side first
+    /// advertises its per-column versions via [`HeadExchange`], then builds
+    /// its outgoing [`Changeset`] with [`CrrTable::changeset_since_as_of`]
+    /// scoped to just what the other side hasn't seen — so, unlike
+    /// exchanging each side's full [`CrrTable::changeset`], a round costs
+    /// bandwidth proportional to the actual divergence rather than table
+    /// size, and the fixed point [`MeshSync::sync_all`] loops to converge
+    /// on is "deltas in both directions are empty" rather than "full
+    /// changesets stopped changing anything."
+    ///
+    /// Each side's commit sequence is pinned (via [`CrrTable::commit_seq`])
+    /// before [`HeadExchange::from_table`] runs, so a local writer that
+    /// commits to `peer_a` or `peer_b` after this call starts can't land
+    /// half-applied in the changeset the other side reads — `merge` either
+    /// sees the whole batch next round or not at all.
     pub fn sync<S: Storage>(
         &self,
         peer_a: &mut CrrTable<S>,
         peer_b: &mut CrrTable<S>,
     ) -> Result<SyncResult> {
-        let changeset_a = peer_a.changeset()?;
-        let changeset_b = peer_b.changeset()?;
+        let seq_a = peer_a.commit_seq();
+        let seq_b = peer_b.commit_seq();
 
-        let report_a = peer_a.merge(&changeset_b, self.policy)?;
-        let report_b = peer_b.merge(&changeset_a, self.policy)?;
+        let heads_a = HeadExchange::from_table("a", peer_a)?;
+        let heads_b = HeadExchange::from_table("b", peer_b)?;
+
+        let changeset_a_to_b = peer_a.changeset_since_as_of(&heads_b, seq_a)?;
+        let changeset_b_to_a = peer_b.changeset_since_as_of(&heads_a, seq_b)?;
+
+        let report_b = peer_b.merge(&changeset_a_to_b, self.policy)?;
+        let report_a = peer_a.merge(&changeset_b_to_a, self.policy)?;
 
         Ok(SyncResult {
             a_to_b: report_b,
             b_to_a: report_a,
-            bytes_transferred: changeset_a.estimate_bytes() + changeset_b.estimate_bytes(),
+            bytes_transferred: changeset_a_to_b.estimate_bytes() + changeset_b_to_a.estimate_bytes(),
         })
     }
+
+    /// Drive one side of a sync over `stream` instead of holding both
+    /// [`CrrTable`]s in the same process like [`Self::sync`] does: send this
+    /// side's [`HeadExchange`] as a length-prefixed CBOR request frame, read
+    /// back the peer's, send the [`Changeset`] it hasn't seen as a response
+    /// frame, then read and merge the peer's own response changeset. Both
+    /// ends must call this the same way — over a real duplex transport (a
+    /// TCP socket, a pair of pipes) the two sides' writes and reads don't
+    /// need to be externally coordinated beyond that.
+    pub fn sync_over<S: Storage, T: Read + Write>(
+        &self,
+        table: &mut CrrTable<S>,
+        stream: &mut T,
+        peer_id: &str,
+    ) -> Result<MergeReport> {
+        let seq = table.commit_seq();
+        let heads = HeadExchange::from_table(peer_id, table)?;
+        write_frame(stream, &heads.to_cbor()?)?;
+
+        let peer_heads = HeadExchange::from_cbor(&read_frame(stream)?)?;
+
+        let outgoing = table.changeset_since_as_of(&peer_heads, seq)?;
+        write_frame(stream, &outgoing.to_cbor()?)?;
+
+        let incoming = Changeset::from_cbor(&read_frame(stream)?)?;
+        table.merge(&incoming, self.policy)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct SyncResult {
     pub a_to_b: MergeReport,
     pub b_to_a: MergeReport,
+    /// Combined size of the two deltas actually exchanged this round (see
+    /// [`SyncSession::sync`]), not the two peers' full table sizes.
     pub bytes_transferred: usize,
 }
 
@@ -110,6 +325,137 @@ impl SyncResult {
     pub fn total_conflicts(&self) -> usize {
         self.a_to_b.conflicts + self.b_to_a.conflicts
     }
+
+    /// Encode as a self-describing CBOR blob.
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        to_cbor(self)
+    }
+
+    /// Decode a blob produced by [`Self::to_cbor`].
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        from_cbor(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_changeset() -> Changeset {
+        let mut changes = HashMap::new();
+        let mut cols = HashMap::new();
+        let mut vers = HashMap::new();
+        cols.insert("name".to_string(), b"Alice".to_vec());
+        vers.insert("name".to_string(), 1);
+        changes.insert("user_1".to_string(), (cols, vers));
+
+        let mut timestamps = HashMap::new();
+        timestamps.insert("name".to_string(), 12345);
+        let mut origins = HashMap::new();
+        origins.insert("user_1".to_string(), timestamps);
+
+        Changeset { changes, origins, tombstones: HashMap::new() }
+    }
+
+    #[test]
+    fn roundtrips_through_serialize_and_deserialize() {
+        let changeset = sample_changeset();
+        let bytes = changeset.serialize();
+        let decoded = Changeset::deserialize(&bytes).unwrap();
+        assert_eq!(decoded.changes, changeset.changes);
+        assert_eq!(decoded.origins, changeset.origins);
+    }
+
+    #[test]
+    fn rejects_flipped_bit_in_body() {
+        let mut bytes = sample_changeset().serialize();
+        bytes[0] ^= 0xff;
+
+        match Changeset::deserialize(&bytes) {
+            Err(Error::ChangesetCorrupt { .. }) => {}
+            other => panic!("expected ChangesetCorrupt, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cbor_round_trips_a_changeset() {
+        let changeset = sample_changeset();
+        let bytes = changeset.to_cbor().unwrap();
+        assert_eq!(Changeset::from_cbor(&bytes).unwrap(), changeset);
+    }
+
+    #[test]
+    fn cbor_round_trips_a_changeset_with_no_columns() {
+        let mut changes = HashMap::new();
+        changes.insert("row1".to_string(), (HashMap::new(), HashMap::new()));
+        let changeset = Changeset { changes, origins: HashMap::new(), tombstones: HashMap::new() };
+
+        let bytes = changeset.to_cbor().unwrap();
+        assert_eq!(Changeset::from_cbor(&bytes).unwrap(), changeset);
+    }
+
+    #[test]
+    fn cbor_round_trips_a_changeset_with_a_large_blob_value() {
+        let large_blob = vec![0xabu8; 1_000_000];
+        let mut cols = HashMap::new();
+        cols.insert("payload".to_string(), large_blob);
+        let mut vers = HashMap::new();
+        vers.insert("payload".to_string(), 1);
+        let mut changes = HashMap::new();
+        changes.insert("row1".to_string(), (cols, vers));
+        let changeset = Changeset { changes, origins: HashMap::new(), tombstones: HashMap::new() };
+
+        let bytes = changeset.to_cbor().unwrap();
+        let decoded = Changeset::from_cbor(&bytes).unwrap();
+        assert_eq!(decoded, changeset);
+    }
+
+    #[test]
+    fn cbor_round_trips_head_exchange() {
+        let mut heads = HashMap::new();
+        heads.insert("row1".to_string(), [("name".to_string(), 3u64)].into_iter().collect());
+        let exchange = HeadExchange { peer_id: "a".to_string(), heads };
+
+        let bytes = exchange.to_cbor().unwrap();
+        assert_eq!(HeadExchange::from_cbor(&bytes).unwrap(), exchange);
+    }
+
+    #[test]
+    fn cbor_round_trips_a_merge_report() {
+        let report = MergeReport { inserted: 1, updated: 2, skipped: 3, conflicts: 4, counter_merges: 5, set_merges: 6 };
+        let bytes = report.to_cbor().unwrap();
+        assert_eq!(MergeReport::from_cbor(&bytes).unwrap(), report);
+    }
+
+    #[test]
+    fn sync_over_drives_a_full_sync_across_a_tcp_socket() {
+        use std::net::{TcpListener, TcpStream};
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let server = std::thread::spawn(move || {
+            let mut peer_b = CrrTable::open_in_memory().unwrap();
+            peer_b.insert("row1").column_str("owner", "bob", 1).commit().unwrap();
+
+            let (mut stream, _) = listener.accept().unwrap();
+            let session = SyncSession::new(TieBreakPolicy::LastWriteWins);
+            session.sync_over(&mut peer_b, &mut stream, "b").unwrap();
+            peer_b
+        });
+
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.insert("row2").column_str("owner", "alice", 1).commit().unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let session = SyncSession::new(TieBreakPolicy::LastWriteWins);
+        session.sync_over(&mut peer_a, &mut stream, "a").unwrap();
+
+        let peer_b = server.join().unwrap();
+
+        assert_eq!(peer_a.get("row1").unwrap().unwrap().cells["owner"].value, b"bob");
+        assert_eq!(peer_b.get("row2").unwrap().unwrap().cells["owner"].value, b"alice");
+    }
 }
 
 pub struct MeshSync<S: Storage> {
@@ -126,6 +472,11 @@ impl<S: Storage> MeshSync<S> {
         self.peers.push(peer);
     }
 
+    /// All-pairs sync rounds until a round leaves every pair's deltas
+    /// empty. Each [`SyncSession::sync`] call only ships what that pair
+    /// actually diverges on, so the fixed point this converges to is
+    /// "nobody has anything new to send," not "nobody's full table
+    /// changed."
     pub fn sync_all(&mut self) -> Result<usize> {
         let mut total_changes = 0;
         let session = SyncSession::new(self.policy);
@@ -1,38 +1,319 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Instant;
 
-use crate::error::Result;
-use crate::merge::{MergeReport, TieBreakPolicy, resolve_versions, resolve_conflict, MergeDecision};
-use crate::row::{InsertBuilder, RowView, UpdateBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::chunking::{ChunkConfig, ChunkHash, ChunkStore};
+use crate::column_crdt::{ColumnCrdt, CrdtKind, OrSet, PnCounter};
+use crate::dictionary::{ColumnEncoding, DictionaryRegistry};
+use crate::error::{Error, Result};
+use crate::frontier::VersionVector;
+use crate::hlc::{pack_version, unpack_version, HybridLogicalClock};
+use crate::iblt::{fingerprint, versioned_row_key, Iblt};
+use crate::merge::{
+    resolve_conflict, resolve_versions, IngestStats, MergeDecision, MergeReport, MergeStats, TieBreakPolicy,
+};
+use crate::merkle::{Digest, MerkleTree};
+use crate::metrics::Metrics;
+use crate::oracle::VersionOracle;
+use crate::row::{decode_conflict_set, encode_conflict_set, InsertBuilder, RowView, UpdateBuilder};
+use crate::secure::{SecureChangeset, SessionKey};
+use crate::snapshot::{RowState, Snapshot, TableRoot};
+use crate::spill::{MergeOptions, PendingWrite, SpillBuffer};
 use crate::storage::{Cell, DagNode, SqliteStorage, Storage, now_millis};
-use crate::sync::Changeset;
+use crate::sync::{Changeset, HeadExchange};
+use crate::wire::{
+    read_bytes, read_option_u64, read_string, read_u32, read_u64, read_u8,
+    write_bytes, write_option_u64, write_u32,
+};
+
+/// On-wire CBOR envelope for [`CrrTable::export_changeset_since`] /
+/// [`CrrTable::apply_changeset`]. Self-describing and forward-compatible
+/// (new fields can be added to either struct without breaking older
+/// readers) in a way the hand-rolled length-prefixed formats elsewhere in
+/// this crate aren't, at the cost of a CBOR dependency.
+const CHANGESET_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct ChangesetEnvelope {
+    format_version: u32,
+    sender_frontier: VersionVector,
+    nodes: Vec<NodeEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NodeEntry {
+    pk: String,
+    col: String,
+    version: u64,
+    value: Vec<u8>,
+    parent_version: Option<u64>,
+    parent2_version: Option<u64>,
+    timestamp: u64,
+    is_tombstone: bool,
+    #[serde(default)]
+    commit_seq: u64,
+}
+
+/// Like [`ChangesetEnvelope`], but a node's value is addressed by the
+/// ordered list of [`ChunkHash`]es [`ChunkStore::put`] split it into
+/// rather than carried inline — built for large column values where most
+/// of the bytes are unchanged from a version the receiver already has.
+#[derive(Serialize, Deserialize)]
+struct ChunkedChangesetEnvelope {
+    format_version: u32,
+    sender_frontier: VersionVector,
+    nodes: Vec<ChunkedNodeEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkedNodeEntry {
+    pk: String,
+    col: String,
+    version: u64,
+    value_chunks: Vec<ChunkHash>,
+    parent_version: Option<u64>,
+    parent2_version: Option<u64>,
+    timestamp: u64,
+    is_tombstone: bool,
+    #[serde(default)]
+    commit_seq: u64,
+}
+
+/// Highest-version node wins; among nodes tied on version (a conflict),
+/// `policy` breaks the tie. Mirrors `resolve_versions`/`resolve_conflict`
+/// in `merge()`, generalized from "two candidates" to "a whole history".
+fn pick_winner(history: &[DagNode], policy: TieBreakPolicy) -> Option<DagNode> {
+    history.iter().cloned().reduce(|acc, node| match node.version.cmp(&acc.version) {
+        std::cmp::Ordering::Greater => node,
+        std::cmp::Ordering::Less => acc,
+        std::cmp::Ordering::Equal => {
+            if resolve_conflict(&acc.value, &node.value, policy, acc.timestamp, node.timestamp) {
+                node
+            } else {
+                acc
+            }
+        }
+    })
+}
+
+/// Tracks which `(pk, col, version)` DAG nodes were most recently touched by
+/// `get`/`merge`, and optionally enforces a byte budget on the table's DAG
+/// history by evicting the least-recently-touched nodes first.
+///
+/// This is separate from `gc()`, which is a manual, caller-triggered
+/// "keep the last N versions" trim. `HistoryTracker` instead lets a table
+/// cap its own history footprint continuously as it's used, trading the
+/// oldest *unaccessed* versions for headroom rather than a fixed count.
+#[derive(Default)]
+struct HistoryTracker {
+    budget_bytes: Option<usize>,
+    current_bytes: usize,
+    clock: u64,
+    access_clock: HashMap<(String, String, u64), u64>,
+    on_evict: Option<Box<dyn FnMut(&str, &str, u64, usize)>>,
+}
+
+impl HistoryTracker {
+    fn touch(&mut self, pk: &str, col: &str, version: u64) {
+        self.clock += 1;
+        self.access_clock.insert((pk.to_string(), col.to_string(), version), self.clock);
+    }
+
+    /// Recency rank for eviction ordering: explicit accesses win over the
+    /// fallback, and within the fallback an older version is evicted before
+    /// a newer one, since a version nobody has touched yet is more likely
+    /// to be superseded history than the live tip.
+    fn recency(&self, pk: &str, col: &str, version: u64) -> (bool, u64) {
+        self.access_clock
+            .get(&(pk.to_string(), col.to_string(), version))
+            .map(|&tick| (true, tick))
+            .unwrap_or((false, version))
+    }
+
+    fn forget(&mut self, pk: &str, col: &str, version: u64) {
+        self.access_clock.remove(&(pk.to_string(), col.to_string(), version));
+    }
+}
+
+/// Approximate in-memory/on-disk footprint of a single DAG node, used to
+/// weigh history against a configured byte budget. Doesn't need to be
+/// exact — just consistent enough that the budget means something.
+fn node_bytes(node: &DagNode) -> usize {
+    node.value.len() + 8 + 8 + 8 + 8 + 1
+}
+
+/// Decode `local` (if present, else that CRDT's default) and `remote` as
+/// `kind`, fold `remote` into `local` via [`ColumnCrdt::merge`], and
+/// re-encode — the whole of what a CRDT-declared column's merge does in
+/// place of [`resolve_versions`]/[`resolve_conflict`].
+fn merge_crdt_bytes(kind: CrdtKind, local: Option<&[u8]>, remote: &[u8]) -> Result<Vec<u8>> {
+    match kind {
+        CrdtKind::PnCounter => {
+            let mut local = match local {
+                Some(bytes) => PnCounter::from_bytes(bytes)?,
+                None => PnCounter::default(),
+            };
+            local.merge(&PnCounter::from_bytes(remote)?);
+            local.to_bytes()
+        }
+        CrdtKind::OrSet => {
+            let mut local: OrSet<String> = match local {
+                Some(bytes) => OrSet::from_bytes(bytes)?,
+                None => OrSet::default(),
+            };
+            local.merge(&OrSet::from_bytes(remote)?);
+            local.to_bytes()
+        }
+    }
+}
+
+/// A past point to reconstruct a [`TableSnapshot`] at: a column version
+/// number, a wall-clock timestamp (matching [`DagNode::timestamp`]), or a
+/// commit sequence number from this table's [`VersionOracle`]
+/// ([`CrrTable::commit_seq`]) — the only one of the three that's immune to
+/// a concurrent writer landing a batch mid-read, since every node in a
+/// batch shares one `commit_seq` and is stamped atomically with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsOfBound {
+    Version(u64),
+    Timestamp(u64),
+    CommitSeq(u64),
+}
+
+impl AsOfBound {
+    fn admits(&self, node: &DagNode) -> bool {
+        match self {
+            AsOfBound::Version(v) => node.version <= *v,
+            AsOfBound::Timestamp(ts) => node.timestamp <= *ts,
+            AsOfBound::CommitSeq(s) => node.commit_seq <= *s,
+        }
+    }
+}
+
+/// A read-only, point-in-time view of a [`CrrTable`] produced by
+/// [`CrrTable::as_of`]. Each column is reconstructed on demand from its DAG
+/// history rather than up front, so querying one row costs proportional to
+/// that row's history, not the whole table's.
+pub struct TableSnapshot<'a, S: Storage> {
+    table: &'a CrrTable<S>,
+    bound: AsOfBound,
+}
+
+impl<'a, S: Storage> TableSnapshot<'a, S> {
+    /// The row as it stood at this snapshot's bound, or `None` if the row
+    /// didn't exist yet (every one of its columns' first write is after
+    /// the bound).
+    pub fn get(&self, pk: &str) -> Result<Option<RowView>> {
+        let Some(current) = self.table.storage.get_row(pk)? else { return Ok(None) };
+
+        let mut cells = HashMap::new();
+        let mut dag_history = HashMap::new();
+        for col in current.cells.keys() {
+            let history = self.table.storage.get_dag_history(pk, col)?;
+            if let Some(node) = history.iter().rev().find(|node| self.bound.admits(node)) {
+                if !node.is_tombstone {
+                    let value = self.table.encodings.decode(col, &node.value);
+                    cells.insert(col.clone(), Cell { value, version: node.version });
+                }
+            }
+            dag_history.insert(col.clone(), history);
+        }
+
+        if cells.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(RowView { pk: pk.to_string(), cells, dag_history }))
+        }
+    }
+}
 
 pub struct CrrTable<S: Storage = SqliteStorage> {
     storage: S,
+    history: RefCell<HistoryTracker>,
+    clock: RefCell<Option<HybridLogicalClock>>,
+    root: RefCell<Rc<TableRoot>>,
+    crdt_columns: HashMap<String, CrdtKind>,
+    oracle: VersionOracle,
+    metrics: Option<Arc<Metrics>>,
+    encodings: DictionaryRegistry,
 }
 
 impl CrrTable<SqliteStorage> {
     pub fn open(path: &str) -> Result<Self> {
         let storage = SqliteStorage::open(path)?;
-        Ok(Self { storage })
+        Ok(Self { storage, history: RefCell::new(HistoryTracker::default()), clock: RefCell::new(None), root: RefCell::new(Rc::new(TableRoot::default())), crdt_columns: HashMap::new(), oracle: VersionOracle::new(), metrics: None, encodings: DictionaryRegistry::default() })
     }
 
     pub fn open_in_memory() -> Result<Self> {
         let storage = SqliteStorage::open_in_memory()?;
-        Ok(Self { storage })
+        Ok(Self { storage, history: RefCell::new(HistoryTracker::default()), clock: RefCell::new(None), root: RefCell::new(Rc::new(TableRoot::default())), crdt_columns: HashMap::new(), oracle: VersionOracle::new(), metrics: None, encodings: DictionaryRegistry::default() })
     }
 }
 
 impl<S: Storage> CrrTable<S> {
     pub fn with_storage(storage: S) -> Self {
-        Self { storage }
+        Self { storage, history: RefCell::new(HistoryTracker::default()), clock: RefCell::new(None), root: RefCell::new(Rc::new(TableRoot::default())), crdt_columns: HashMap::new(), oracle: VersionOracle::new(), metrics: None, encodings: DictionaryRegistry::default() }
+    }
+
+    /// Report every [`Self::merge`]/[`Self::gc`]/[`Self::gc_below_watermark`]
+    /// call's outcome into `metrics` from here on, so an embedder gets the
+    /// same counters/histograms `network_bench` reports without having to
+    /// call [`Metrics::record_merge`]/[`Metrics::record_gc`] by hand at
+    /// every call site.
+    pub fn attach_metrics(&mut self, metrics: Arc<Metrics>) {
+        self.metrics = Some(metrics);
+    }
+
+    /// Declare that `col` merges as `kind` instead of by version
+    /// comparison: every [`Self::merge`] call folds the incoming and
+    /// existing bytes through that CRDT's [`ColumnCrdt::merge`] rather
+    /// than [`crate::merge::resolve_versions`]/[`crate::merge::resolve_conflict`],
+    /// so concurrent writes to this column converge structurally and
+    /// never land in [`MergeReport::conflicts`].
+    pub fn declare_crdt_column(&mut self, col: &str, kind: CrdtKind) {
+        self.crdt_columns.insert(col.to_string(), kind);
+    }
+
+    /// Declare that `col`'s cell values should be stored per `encoding`
+    /// from here on — in particular, [`ColumnEncoding::Dictionary`] interns
+    /// repeated values into a per-column id table instead of storing each
+    /// occurrence's bytes in full. Worth it for low-cardinality columns
+    /// (`mime_type`, `owner`, `permissions`) that repeat across thousands
+    /// of rows; leave high-cardinality columns (`checksum`, `path`) at the
+    /// default [`ColumnEncoding::Raw`], since there's nothing to intern.
+    /// [`Self::insert`]/[`Self::update`] encode on write and [`Self::get`]
+    /// decodes on read, so callers never see the interned id directly.
+    pub fn declare_column_encoding(&mut self, col: &str, encoding: ColumnEncoding) {
+        self.encodings.declare(col, encoding);
+    }
+
+    /// Select a storage backend explicitly. An alias for [`Self::with_storage`]
+    /// that reads better at call sites choosing between `SqliteStorage`,
+    /// `MemoryStorage`, and (with the `redis-backend` feature) `RedisStorage` —
+    /// `merge`/tiebreak logic is identical regardless of which one is passed.
+    pub fn open_with_backend(backend: S) -> Self {
+        Self::with_storage(backend)
     }
 
     pub fn insert(&mut self, pk: &str) -> InsertBuilder<'_, S> {
-        InsertBuilder::new(&mut self.storage, pk.to_string())
+        InsertBuilder::new(&mut self.storage, &self.oracle, &self.encodings, pk.to_string())
     }
 
     pub fn update(&mut self, pk: &str) -> UpdateBuilder<'_, S> {
-        UpdateBuilder::new(&mut self.storage, pk.to_string())
+        UpdateBuilder::new(&mut self.storage, &self.oracle, &self.encodings, pk.to_string())
+    }
+
+    /// The commit sequence number of the most recent committed batch —
+    /// capture this before a sync round and pass it to
+    /// [`Self::as_of`]`(`[`AsOfBound::CommitSeq`]`)` or
+    /// [`Self::changeset_as_of`] to pin the read against concurrent local
+    /// writes that land afterward.
+    pub fn commit_seq(&self) -> u64 {
+        self.oracle.current()
     }
 
     pub fn get(&self, pk: &str) -> Result<Option<RowView>> {
@@ -40,23 +321,79 @@ impl<S: Storage> CrrTable<S> {
         match row {
             None => Ok(None),
             Some(row) => {
+                let mut cells = HashMap::new();
                 let mut dag_history = HashMap::new();
-                for col in row.cells.keys() {
-                    if let Ok(history) = self.storage.get_dag_history(pk, col) {
+                for (col, cell) in row.cells {
+                    if let Ok(history) = self.storage.get_dag_history(pk, &col) {
+                        let is_tombstone = history.last().is_some_and(|node| node.is_tombstone);
                         dag_history.insert(col.clone(), history);
+                        if is_tombstone {
+                            continue;
+                        }
+                        self.history.borrow_mut().touch(pk, &col, cell.version);
+                        let value = self.encodings.decode(&col, &cell.value);
+                        cells.insert(col, Cell { value, version: cell.version });
                     }
                 }
-                Ok(Some(RowView {
-                    pk: pk.to_string(),
-                    cells: row.cells,
-                    dag_history,
-                }))
+                if cells.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(RowView {
+                        pk: pk.to_string(),
+                        cells,
+                        dag_history,
+                    }))
+                }
             }
         }
     }
 
+    /// Every value `col` has ever held on `pk`, oldest first, read
+    /// straight out of the column's DAG history rather than a separate
+    /// audit log — the DAG already is one. Tombstoned writes (from a
+    /// deleted row) are omitted.
+    pub fn column_history(&self, pk: &str, col: &str) -> Result<Vec<(u64, Vec<u8>)>> {
+        let history = self.storage.get_dag_history(pk, col)?;
+        Ok(history.into_iter()
+            .filter(|node| !node.is_tombstone)
+            .map(|node| (node.version, node.value))
+            .collect())
+    }
+
+    /// A read-only view of this table as it stood at `bound`, reconstructed
+    /// by replaying each column's DAG history up to that point rather than
+    /// maintaining a separate append-only log.
+    pub fn as_of(&self, bound: AsOfBound) -> TableSnapshot<'_, S> {
+        TableSnapshot { table: self, bound }
+    }
+
+    /// Delete `pk` by tombstoning every column it currently holds, one
+    /// version ahead with `is_tombstone: true`, rather than wiping its
+    /// storage outright: the deletion becomes a versioned write like any
+    /// other, so [`Self::changeset`]/[`Self::merge`] propagate and resolve
+    /// it the same way — a peer that concurrently wrote an older version
+    /// of a column loses to the tombstone, one with a newer version keeps
+    /// its value, and the row only disappears from storage for good once
+    /// [`Self::gc_tombstones`] decides every peer has already seen it.
     pub fn delete(&mut self, pk: &str) -> Result<()> {
-        self.storage.delete_row(pk)
+        let Some(row) = self.storage.get_row(pk)? else { return Ok(()) };
+        let commit_seq = self.oracle.advance();
+
+        for (col, cell) in &row.cells {
+            let new_version = cell.version + 1;
+            self.storage.set_cell(pk, col, Cell { value: Vec::new(), version: new_version })?;
+            self.storage.append_dag_node(pk, col, DagNode {
+                version: new_version,
+                value: Vec::new(),
+                parent_version: Some(cell.version),
+                parent2_version: None,
+                timestamp: now_millis(),
+                is_tombstone: true,
+                commit_seq,
+            })?;
+        }
+
+        Ok(())
     }
 
     pub fn len(&self) -> Result<usize> {
@@ -71,139 +408,1365 @@ impl<S: Storage> CrrTable<S> {
         self.storage.all_pks()
     }
 
+    /// Write timestamp of the DAG node that currently holds `(pk, col,
+    /// version)`, or `0` if history doesn't have that version (e.g. already
+    /// GC'd) — used to populate [`Changeset::origins`] for
+    /// [`crate::merge::TieBreakPolicy::LastWriteWins`].
+    fn cell_timestamp(&self, pk: &str, col: &str, version: u64) -> Result<u64> {
+        Ok(self.storage.get_dag_history(pk, col)?
+            .into_iter()
+            .find(|node| node.version == version)
+            .map(|node| node.timestamp)
+            .unwrap_or(0))
+    }
+
+    /// Whether `col`'s current value on `pk` is a [`Self::delete`]
+    /// tombstone rather than a live value — the DAG node matching
+    /// `version`, which by construction is always the last one appended.
+    fn cell_is_tombstone(&self, pk: &str, col: &str, version: u64) -> Result<bool> {
+        Ok(self.storage.get_dag_history(pk, col)?
+            .into_iter()
+            .find(|node| node.version == version)
+            .map(|node| node.is_tombstone)
+            .unwrap_or(false))
+    }
+
     pub fn changeset(&self) -> Result<Changeset> {
         let mut changes = HashMap::new();
+        let mut origins = HashMap::new();
+        let mut tombstones = HashMap::new();
         let pks = self.storage.all_pks()?;
 
         for pk in pks {
             if let Some(row) = self.storage.get_row(&pk)? {
                 let mut columns = HashMap::new();
                 let mut versions = HashMap::new();
+                let mut timestamps = HashMap::new();
+                let mut tombstoned_cols = HashSet::new();
 
                 for (col, cell) in row.cells {
+                    timestamps.insert(col.clone(), self.cell_timestamp(&pk, &col, cell.version)?);
+                    if self.cell_is_tombstone(&pk, &col, cell.version)? {
+                        tombstoned_cols.insert(col.clone());
+                    }
                     columns.insert(col.clone(), cell.value);
                     versions.insert(col, cell.version);
                 }
 
+                if !tombstoned_cols.is_empty() {
+                    tombstones.insert(pk.clone(), tombstoned_cols);
+                }
+                origins.insert(pk.clone(), timestamps);
                 changes.insert(pk, (columns, versions));
             }
         }
 
-        Ok(Changeset { changes })
+        Ok(Changeset { changes, origins, tombstones })
     }
 
-    pub fn merge(&mut self, changeset: &Changeset, policy: TieBreakPolicy) -> Result<MergeReport> {
-        let mut report = MergeReport::default();
+    /// Like [`Self::changeset`], but only includes a column if its local
+    /// version is ahead of what `heads` says the other side already has —
+    /// the version-vector equivalent of [`Self::export_changeset_since`]
+    /// for the row-level [`Changeset`] that [`crate::sync::SyncSession`]
+    /// exchanges, so a sync round costs bandwidth proportional to what
+    /// actually changed instead of the whole table. A column [`Self::delete`]
+    /// tombstoned is included like any other write (see
+    /// [`Changeset::tombstones`]), so a peer that still holds an older copy
+    /// learns the row was deleted instead of the delete getting silently
+    /// dropped once it's ahead of what `heads` already has.
+    pub fn changeset_since(&self, heads: &HeadExchange) -> Result<Changeset> {
+        let mut changes = HashMap::new();
+        let mut origins = HashMap::new();
+        let mut tombstones = HashMap::new();
+        let pks = self.storage.all_pks()?;
 
-        self.storage.begin_transaction()?;
+        for pk in pks {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                let remote_versions = heads.heads.get(&pk);
+                let mut columns = HashMap::new();
+                let mut versions = HashMap::new();
+                let mut timestamps = HashMap::new();
+                let mut tombstoned_cols = HashSet::new();
 
-        for (pk, (remote_columns, remote_versions)) in &changeset.changes {
-            for (col, remote_value) in remote_columns {
-                let remote_version = *remote_versions.get(col).unwrap_or(&1);
-                let local = self.storage.get_cell(pk, col)?;
+                for (col, cell) in row.cells {
+                    let remote_version = remote_versions.and_then(|rv| rv.get(&col).copied()).unwrap_or(0);
+                    if cell.version > remote_version {
+                        timestamps.insert(col.clone(), self.cell_timestamp(&pk, &col, cell.version)?);
+                        if self.cell_is_tombstone(&pk, &col, cell.version)? {
+                            tombstoned_cols.insert(col.clone());
+                        }
+                        versions.insert(col.clone(), cell.version);
+                        columns.insert(col, cell.value);
+                    }
+                }
 
-                let (local_value, local_version) = match &local {
-                    Some(cell) => (Some(cell.value.clone()), cell.version),
-                    None => (None, 0),
-                };
+                if !columns.is_empty() {
+                    if !tombstoned_cols.is_empty() {
+                        tombstones.insert(pk.clone(), tombstoned_cols);
+                    }
+                    origins.insert(pk.clone(), timestamps);
+                    changes.insert(pk, (columns, versions));
+                }
+            }
+        }
 
-                match resolve_versions(local_version, remote_version) {
-                    MergeDecision::Accept => {
-                        let cell = Cell { value: remote_value.clone(), version: remote_version };
-                        self.storage.set_cell(pk, col, cell)?;
+        Ok(Changeset { changes, origins, tombstones })
+    }
 
-                        let node = DagNode {
-                            version: remote_version,
-                            value: remote_value.clone(),
-                            parent_version: if local_version > 0 { Some(local_version) } else { None },
-                            parent2_version: None,
-                            timestamp: now_millis(),
-                            is_tombstone: false,
-                        };
-                        self.storage.append_dag_node(pk, col, node)?;
+    /// Like [`Self::changeset_since`], but keyed by a flat [`VersionVector`]
+    /// frontier instead of a [`HeadExchange`] snapshot — the same delta this
+    /// table's [`Self::export_changeset_since`] computes, just returned as
+    /// an in-memory [`Changeset`] so it can go through [`Self::merge`]
+    /// (and [`crate::sync::SyncSession`]) like any other changeset instead
+    /// of the DAG-node-level wire format. Reads each column's *current*
+    /// cell value rather than replaying its DAG history, so a peer that's
+    /// since run [`Self::gc_below_watermark`] or [`Self::gc_tombstones`]
+    /// still answers correctly: the caller gets the full surviving value
+    /// for any column ahead of `frontier`, never a partial diff built from
+    /// history that's no longer there.
+    pub fn changeset_since_frontier(&self, frontier: &VersionVector) -> Result<Changeset> {
+        let mut changes = HashMap::new();
+        let mut origins = HashMap::new();
+        let mut tombstones = HashMap::new();
+        let pks = self.storage.all_pks()?;
+
+        for pk in pks {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                let mut columns = HashMap::new();
+                let mut versions = HashMap::new();
+                let mut timestamps = HashMap::new();
+                let mut tombstoned_cols = HashSet::new();
 
-                        if local_version == 0 {
-                            report.inserted += 1;
-                        } else {
-                            report.updated += 1;
+                for (col, cell) in row.cells {
+                    let remote_version = frontier.get(&pk, &col);
+                    if cell.version > remote_version {
+                        timestamps.insert(col.clone(), self.cell_timestamp(&pk, &col, cell.version)?);
+                        if self.cell_is_tombstone(&pk, &col, cell.version)? {
+                            tombstoned_cols.insert(col.clone());
                         }
+                        versions.insert(col.clone(), cell.version);
+                        columns.insert(col, cell.value);
                     }
-                    MergeDecision::Reject => {
-                        report.skipped += 1;
+                }
+
+                if !columns.is_empty() {
+                    if !tombstoned_cols.is_empty() {
+                        tombstones.insert(pk.clone(), tombstoned_cols);
                     }
-                    MergeDecision::Conflict => {
-                        let local_val = local_value.as_ref().unwrap();
-                        if local_val == remote_value {
-                            report.skipped += 1;
-                        } else {
-                            report.conflicts += 1;
-                            let accept_remote = resolve_conflict(local_val, remote_value, policy);
-
-                            if accept_remote {
-                                let new_version = remote_version + 1;
-                                let cell = Cell { value: remote_value.clone(), version: new_version };
-                                self.storage.set_cell(pk, col, cell)?;
-
-                                let node = DagNode {
-                                    version: new_version,
-                                    value: remote_value.clone(),
-                                    parent_version: Some(local_version),
-                                    parent2_version: Some(remote_version),
-                                    timestamp: now_millis(),
-                                    is_tombstone: false,
-                                };
-                                self.storage.append_dag_node(pk, col, node)?;
-                                report.updated += 1;
-                            }
-                        }
+                    origins.insert(pk.clone(), timestamps);
+                    changes.insert(pk, (columns, versions));
+                }
+            }
+        }
+
+        Ok(Changeset { changes, origins, tombstones })
+    }
+
+    /// Like [`Self::changeset_since`], but additionally pinned to `seq`
+    /// (typically this table's own [`Self::commit_seq`] captured before
+    /// [`HeadExchange::from_table`] ran): each column's value is the one
+    /// visible at [`AsOfBound::CommitSeq`]`(seq)` rather than its current
+    /// one, so a write that lands on this table after the sync round
+    /// already started can't sneak into the changeset being sent out.
+    /// [`crate::sync::SyncSession::sync`] uses this instead of plain
+    /// [`Self::changeset_since`] for exactly that reason.
+    pub fn changeset_since_as_of(&self, heads: &HeadExchange, seq: u64) -> Result<Changeset> {
+        let bound = AsOfBound::CommitSeq(seq);
+        let mut changes = HashMap::new();
+        let mut origins = HashMap::new();
+        let mut tombstones = HashMap::new();
+        let pks = self.storage.all_pks()?;
+
+        for pk in pks {
+            let Some(row) = self.storage.get_row(&pk)? else { continue };
+            let remote_versions = heads.heads.get(&pk);
+            let mut columns = HashMap::new();
+            let mut versions = HashMap::new();
+            let mut timestamps = HashMap::new();
+            let mut tombstoned_cols = HashSet::new();
+
+            for col in row.cells.keys() {
+                let history = self.storage.get_dag_history(&pk, col)?;
+                let Some(node) = history.iter().rev().find(|node| bound.admits(node)) else { continue };
+                let remote_version = remote_versions.and_then(|rv| rv.get(col).copied()).unwrap_or(0);
+                if node.version > remote_version {
+                    timestamps.insert(col.clone(), node.timestamp);
+                    if node.is_tombstone {
+                        tombstoned_cols.insert(col.clone());
                     }
+                    versions.insert(col.clone(), node.version);
+                    columns.insert(col.clone(), node.value.clone());
+                }
+            }
+
+            if !columns.is_empty() {
+                if !tombstoned_cols.is_empty() {
+                    tombstones.insert(pk.clone(), tombstoned_cols);
                 }
+                origins.insert(pk.clone(), timestamps);
+                changes.insert(pk, (columns, versions));
             }
         }
 
-        self.storage.commit_transaction()?;
-        Ok(report)
+        Ok(Changeset { changes, origins, tombstones })
     }
 
-    pub fn gc(&mut self, keep_versions: usize) -> Result<usize> {
-        let mut total_removed = 0;
+    /// Like [`Self::changeset`], but pinned to `seq`: each column is
+    /// reconstructed from its DAG history as of [`AsOfBound::CommitSeq`]`(seq)`
+    /// rather than read as its current value, so a writer that commits
+    /// after `seq` (typically captured via [`Self::commit_seq`] right
+    /// before a sync round) can't leak a partial or newer batch into the
+    /// changeset a concurrent [`crate::sync::SyncSession`] is building.
+    pub fn changeset_as_of(&self, seq: u64) -> Result<Changeset> {
+        let bound = AsOfBound::CommitSeq(seq);
+        let mut changes = HashMap::new();
+        let mut origins = HashMap::new();
+        let mut tombstones = HashMap::new();
         let pks = self.storage.all_pks()?;
 
         for pk in pks {
-            if let Some(row) = self.storage.get_row(&pk)? {
-                for col in row.cells.keys() {
-                    total_removed += self.storage.gc_dag(&pk, col, keep_versions)?;
+            let Some(row) = self.storage.get_row(&pk)? else { continue };
+            let mut columns = HashMap::new();
+            let mut versions = HashMap::new();
+            let mut timestamps = HashMap::new();
+            let mut tombstoned_cols = HashSet::new();
+
+            for col in row.cells.keys() {
+                let history = self.storage.get_dag_history(&pk, col)?;
+                if let Some(node) = history.iter().rev().find(|node| bound.admits(node)) {
+                    timestamps.insert(col.clone(), node.timestamp);
+                    if node.is_tombstone {
+                        tombstoned_cols.insert(col.clone());
+                    }
+                    versions.insert(col.clone(), node.version);
+                    columns.insert(col.clone(), node.value.clone());
                 }
             }
+
+            if !columns.is_empty() {
+                if !tombstoned_cols.is_empty() {
+                    tombstones.insert(pk.clone(), tombstoned_cols);
+                }
+                origins.insert(pk.clone(), timestamps);
+                changes.insert(pk, (columns, versions));
+            }
         }
 
-        Ok(total_removed)
+        Ok(Changeset { changes, origins, tombstones })
     }
 
-    #[deprecated(note = "Use insert() builder instead")]
-    pub fn insert_or_update(
+    /// Apply `changeset` as a single transaction: every column it touches
+    /// either lands or, if any step fails partway through, none of them do
+    /// — `self.storage.rollback_transaction()` undoes whatever was already
+    /// written before the error propagates, so a caller never observes a
+    /// `CrrTable` that's merged half a changeset.
+    pub fn merge(&mut self, changeset: &Changeset, policy: TieBreakPolicy) -> Result<MergeReport> {
+        let start = Instant::now();
+        self.storage.begin_transaction()?;
+        match self.merge_locked(changeset, policy) {
+            Ok(report) => {
+                self.storage.commit_transaction()?;
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_merge(&report, start.elapsed());
+                }
+                Ok(report)
+            }
+            Err(e) => {
+                self.storage.rollback_transaction()?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Apply every changeset in `changesets`, in order, as a single
+    /// transaction sharing one `global_version` — unlike calling
+    /// [`Self::merge`] once per changeset (see `bench_multi_peer_sync`),
+    /// a failure partway through rolls the whole batch back rather than
+    /// leaving earlier changesets applied, and every cell any of them
+    /// wrote is stamped with the same sequence number instead of one
+    /// bump per changeset.
+    pub fn ingest(&mut self, changesets: &[Changeset], policy: TieBreakPolicy) -> Result<IngestStats> {
+        let start = Instant::now();
+        self.storage.begin_transaction()?;
+        match self.ingest_locked(changesets, policy) {
+            Ok(stats) => {
+                self.storage.commit_transaction()?;
+                if let Some(metrics) = &self.metrics {
+                    let report = MergeReport {
+                        inserted: 0,
+                        updated: stats.applied,
+                        skipped: stats.skipped,
+                        conflicts: stats.conflicted,
+                        counter_merges: 0,
+                        set_merges: 0,
+                    };
+                    metrics.record_merge(&report, start.elapsed());
+                }
+                Ok(stats)
+            }
+            Err(e) => {
+                self.storage.rollback_transaction()?;
+                Err(e)
+            }
+        }
+    }
+
+    fn ingest_locked(&mut self, changesets: &[Changeset], policy: TieBreakPolicy) -> Result<IngestStats> {
+        let commit_seq = self.oracle.advance();
+        let mut stats = IngestStats { applied: 0, skipped: 0, conflicted: 0, global_version: commit_seq };
+
+        for changeset in changesets {
+            let report = self.merge_with_commit_seq(changeset, policy, commit_seq)?;
+            stats.applied += report.total_changes();
+            stats.skipped += report.skipped;
+            stats.conflicted += report.conflicts;
+        }
+
+        Ok(stats)
+    }
+
+    fn merge_locked(&mut self, changeset: &Changeset, policy: TieBreakPolicy) -> Result<MergeReport> {
+        // One sequence number for the whole merge, so a reader pinned via
+        // `AsOfBound::CommitSeq` sees every column this changeset touched,
+        // or none of them, never a partial merge.
+        let commit_seq = self.oracle.advance();
+        self.merge_with_commit_seq(changeset, policy, commit_seq)
+    }
+
+    /// Like [`Self::merge`], but for a `changeset` too large to comfortably
+    /// hold resident alongside the rest of the merge's bookkeeping: writes
+    /// are staged through a [`crate::spill::SpillBuffer`] that spills sorted
+    /// runs to `options.temp_dir` once `options.spill_threshold_bytes` is
+    /// exceeded, then applies the k-way-merged result column by column — the
+    /// same per-column accept/reject/conflict logic [`Self::merge`] uses, just
+    /// fed from spilled runs instead of iterating `changeset` directly. Spilled
+    /// temp files are removed on both success and rollback (see
+    /// [`crate::spill::SpillBuffer`]'s `Drop` impls), so a failed merge leaves
+    /// nothing behind.
+    pub fn merge_with_options(&mut self, changeset: &Changeset, policy: TieBreakPolicy, options: &MergeOptions) -> Result<MergeReport> {
+        let start = Instant::now();
+        self.storage.begin_transaction()?;
+        match self.merge_with_options_locked(changeset, policy, options) {
+            Ok(report) => {
+                self.storage.commit_transaction()?;
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_merge(&report, start.elapsed());
+                }
+                Ok(report)
+            }
+            Err(e) => {
+                self.storage.rollback_transaction()?;
+                Err(e)
+            }
+        }
+    }
+
+    fn merge_with_options_locked(&mut self, changeset: &Changeset, policy: TieBreakPolicy, options: &MergeOptions) -> Result<MergeReport> {
+        let commit_seq = self.oracle.advance();
+        let mut buffer = SpillBuffer::new(options.clone());
+
+        for (pk, (remote_columns, remote_versions)) in &changeset.changes {
+            let tombstoned = changeset.tombstones.get(pk);
+            for (col, remote_value) in remote_columns {
+                let remote_version = *remote_versions.get(col).unwrap_or(&1);
+                let remote_is_tombstone = tombstoned.is_some_and(|set| set.contains(col));
+                let remote_timestamp = changeset.origins.get(pk)
+                    .and_then(|m| m.get(col))
+                    .copied()
+                    .unwrap_or(0);
+
+                buffer.push(PendingWrite {
+                    pk: pk.clone(),
+                    col: col.clone(),
+                    value: remote_value.clone(),
+                    version: remote_version,
+                    timestamp: remote_timestamp,
+                    is_tombstone: remote_is_tombstone,
+                })?;
+            }
+        }
+
+        let mut report = MergeReport::default();
+        for entry in buffer.into_sorted()? {
+            self.merge_one_column(
+                &entry.pk,
+                &entry.col,
+                &entry.value,
+                entry.version,
+                entry.timestamp,
+                entry.is_tombstone,
+                policy,
+                commit_seq,
+                &mut report,
+            )?;
+        }
+
+        Ok(report)
+    }
+
+    /// The body of [`Self::merge_locked`], taking `commit_seq` from the
+    /// caller instead of advancing [`Self::oracle`] itself — lets
+    /// [`Self::ingest_locked`] share one sequence number across every
+    /// changeset in a batch instead of one per changeset.
+    fn merge_with_commit_seq(&mut self, changeset: &Changeset, policy: TieBreakPolicy, commit_seq: u64) -> Result<MergeReport> {
+        let mut report = MergeReport::default();
+
+        for (pk, (remote_columns, remote_versions)) in &changeset.changes {
+            let tombstoned = changeset.tombstones.get(pk);
+            for (col, remote_value) in remote_columns {
+                let remote_version = *remote_versions.get(col).unwrap_or(&1);
+                let remote_is_tombstone = tombstoned.is_some_and(|set| set.contains(col));
+                let remote_timestamp = changeset.origins.get(pk)
+                    .and_then(|m| m.get(col))
+                    .copied()
+                    .unwrap_or(0);
+
+                self.merge_one_column(pk, col, remote_value, remote_version, remote_timestamp, remote_is_tombstone, policy, commit_seq, &mut report)?;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// The per-`(pk, col)` body [`Self::merge_with_commit_seq`] and
+    /// [`Self::merge_with_options_locked`] both drive: fold one incoming
+    /// column write into local storage under `commit_seq`, deciding
+    /// accept/reject/conflict the same way regardless of whether the
+    /// caller walked there via an in-memory [`Changeset`] or a
+    /// [`crate::spill::SpillBuffer`]'s sorted runs.
+    #[allow(clippy::too_many_arguments)]
+    fn merge_one_column(
         &mut self,
         pk: &str,
-        columns: HashMap<String, String>,
-        versions: HashMap<String, u64>,
+        col: &str,
+        remote_value: &[u8],
+        remote_version: u64,
+        remote_timestamp: u64,
+        remote_is_tombstone: bool,
+        policy: TieBreakPolicy,
+        commit_seq: u64,
+        report: &mut MergeReport,
     ) -> Result<()> {
-        for (col, value) in columns {
-            let version = versions.get(&col).copied().unwrap_or(1);
-            let current = self.storage.get_cell(pk, &col)?;
-            let parent_version = current.as_ref().map(|c| c.version);
+        // HLC receive rule: fold every incoming stamp into the local
+        // clock, whether or not this particular column ends up in
+        // conflict, so the clock never drifts behind a peer it has
+        // merely heard from.
+        if let Some(clock) = self.clock.borrow_mut().as_mut() {
+            clock.receive(unpack_version(remote_version));
+        }
 
-            let cell = Cell { value: value.as_bytes().to_vec(), version };
-            self.storage.set_cell(pk, &col, cell)?;
+        let local = self.storage.get_cell(pk, col)?;
+
+        let (local_value, local_version) = match &local {
+            Some(cell) => (Some(cell.value.clone()), cell.version),
+            None => (None, 0),
+        };
+
+        if let Some(&kind) = self.crdt_columns.get(col) {
+            let merged_value = merge_crdt_bytes(kind, local_value.as_deref(), remote_value)?;
+            let new_version = local_version.max(remote_version) + 1;
+            let cell = Cell { value: merged_value.clone(), version: new_version };
+            self.storage.set_cell(pk, col, cell)?;
 
             let node = DagNode {
-                version,
-                value: value.into_bytes(),
-                parent_version,
-                parent2_version: None,
+                version: new_version,
+                value: merged_value,
+                parent_version: if local_version > 0 { Some(local_version) } else { None },
+                parent2_version: if remote_version > 0 { Some(remote_version) } else { None },
                 timestamp: now_millis(),
                 is_tombstone: false,
+                commit_seq,
             };
-            self.storage.append_dag_node(pk, &col, node)?;
+            let size = node_bytes(&node);
+            self.storage.append_dag_node(pk, col, node)?;
+            self.history.get_mut().touch(pk, col, new_version);
+            self.history.get_mut().current_bytes += size;
+            self.enforce_history_budget()?;
+            report.updated += 1;
+            match kind {
+                CrdtKind::PnCounter => report.counter_merges += 1,
+                CrdtKind::OrSet => report.set_merges += 1,
+            }
+            return Ok(());
+        }
+
+        match resolve_versions(local_version, remote_version) {
+            MergeDecision::Accept => {
+                let cell = Cell { value: remote_value.to_vec(), version: remote_version };
+                self.storage.set_cell(pk, col, cell)?;
+
+                let node = DagNode {
+                    version: remote_version,
+                    value: remote_value.to_vec(),
+                    parent_version: if local_version > 0 { Some(local_version) } else { None },
+                    parent2_version: None,
+                    timestamp: now_millis(),
+                    is_tombstone: remote_is_tombstone,
+                    commit_seq,
+                };
+                let size = node_bytes(&node);
+                self.storage.append_dag_node(pk, col, node)?;
+                self.history.get_mut().touch(pk, col, remote_version);
+                self.history.get_mut().current_bytes += size;
+                self.enforce_history_budget()?;
+
+                if local_version == 0 {
+                    report.inserted += 1;
+                } else {
+                    report.updated += 1;
+                }
+            }
+            MergeDecision::Reject => {
+                report.skipped += 1;
+            }
+            MergeDecision::Conflict => {
+                let local_val = local_value.as_ref().unwrap();
+                let local_is_tombstone = self.cell_is_tombstone(pk, col, local_version)?;
+
+                if local_is_tombstone != remote_is_tombstone {
+                    // Delete-vs-update tie: the tombstone always wins
+                    // regardless of `policy` — letting a concurrent
+                    // update win here would resurrect a row the other
+                    // side has already observed-removed.
+                    if remote_is_tombstone {
+                        let new_version = remote_version + 1;
+                        let cell = Cell { value: Vec::new(), version: new_version };
+                        self.storage.set_cell(pk, col, cell)?;
+
+                        let node = DagNode {
+                            version: new_version,
+                            value: Vec::new(),
+                            parent_version: Some(local_version),
+                            parent2_version: Some(remote_version),
+                            timestamp: now_millis(),
+                            is_tombstone: true,
+                            commit_seq,
+                        };
+                        let size = node_bytes(&node);
+                        self.storage.append_dag_node(pk, col, node)?;
+                        self.history.get_mut().touch(pk, col, new_version);
+                        self.history.get_mut().current_bytes += size;
+                        self.enforce_history_budget()?;
+                        report.updated += 1;
+                    } else {
+                        report.skipped += 1;
+                    }
+                } else if local_val.as_slice() == remote_value {
+                    report.skipped += 1;
+                } else if policy == TieBreakPolicy::MultiValue {
+                    report.conflicts += 1;
+
+                    let mut entries = decode_conflict_set(local_val)
+                        .unwrap_or_else(|| vec![(local_val.clone(), local_version)]);
+                    entries.push((remote_value.to_vec(), remote_version));
+
+                    // Garbage-collect: once any member is superseded by a
+                    // strictly newer version, it's dominated and drops
+                    // out, keeping the set bounded by however many
+                    // writers are racing right now rather than growing
+                    // over the table's whole lifetime.
+                    let max_version = entries.iter().map(|(_, v)| *v).max().unwrap();
+                    entries.retain(|(_, v)| *v == max_version);
+                    entries.sort();
+                    entries.dedup();
+
+                    let new_version = remote_version + 1;
+                    let merged_value = encode_conflict_set(&entries);
+                    let cell = Cell { value: merged_value.clone(), version: new_version };
+                    self.storage.set_cell(pk, col, cell)?;
+
+                    let node = DagNode {
+                        version: new_version,
+                        value: merged_value,
+                        parent_version: Some(local_version),
+                        parent2_version: Some(remote_version),
+                        timestamp: now_millis(),
+                        is_tombstone: false,
+                        commit_seq,
+                    };
+                    let size = node_bytes(&node);
+                    self.storage.append_dag_node(pk, col, node)?;
+                    self.history.get_mut().touch(pk, col, new_version);
+                    self.history.get_mut().current_bytes += size;
+                    self.enforce_history_budget()?;
+                    report.updated += 1;
+                } else {
+                    report.conflicts += 1;
+                    let local_timestamp = self.cell_timestamp(pk, col, local_version)?;
+                    let accept_remote = resolve_conflict(local_val, remote_value, policy, local_timestamp, remote_timestamp);
+
+                    if accept_remote {
+                        let new_version = remote_version + 1;
+                        let cell = Cell { value: remote_value.to_vec(), version: new_version };
+                        self.storage.set_cell(pk, col, cell)?;
+
+                        let node = DagNode {
+                            version: new_version,
+                            value: remote_value.to_vec(),
+                            parent_version: Some(local_version),
+                            parent2_version: Some(remote_version),
+                            timestamp: remote_timestamp.max(local_timestamp).max(now_millis()),
+                            is_tombstone: remote_is_tombstone,
+                            commit_seq,
+                        };
+                        let size = node_bytes(&node);
+                        self.storage.append_dag_node(pk, col, node)?;
+                        self.history.get_mut().touch(pk, col, new_version);
+                        self.history.get_mut().current_bytes += size;
+                        self.enforce_history_budget()?;
+                        report.updated += 1;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write a full snapshot of this table (every row's columns, version
+    /// vectors, and DAG history, including `parent_version`/`parent2_version`
+    /// edges) to `path` as a deterministic binary blob. The format only
+    /// mentions cell/node fields, never `S` itself, so it's this crate's
+    /// export subsystem for moving a table between backends: save a
+    /// `CrrTable<SqliteStorage>` and [`Self::load`] it into a
+    /// `CrrTable<MemoryStorage>` or `CrrTable<LmdbStorage>` and every DAG
+    /// edge and per-column version survives identically, the way
+    /// `crr_migrate` (the CLI built on this pair) relies on.
+    pub fn save(&self, path: &str) -> Result<()> {
+        std::fs::write(path, self.serialize_to_bytes()?)?;
+        Ok(())
+    }
+
+    /// Load a snapshot previously written by [`CrrTable::save`] into this
+    /// table — on any `Storage` backend, not just the one that wrote it.
+    /// Every saved DAG node is appended and each saved column's cell is set
+    /// from it; existing rows are not cleared first, so rows the snapshot
+    /// does not contain stay in place.
+    pub fn load(&mut self, path: &str) -> Result<()> {
+        let bytes = std::fs::read(path)?;
+        self.restore(&bytes)
+    }
+
+    fn serialize_to_bytes(&self) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        let pks = self.storage.all_pks()?;
+        write_u32(&mut buf, pks.len() as u32);
+
+        for pk in &pks {
+            write_bytes(&mut buf, pk.as_bytes());
+            let row = self.storage.get_row(pk)?
+                .ok_or_else(|| crate::error::Error::InvalidState(format!("missing row {}", pk)))?;
+
+            write_u32(&mut buf, row.cells.len() as u32);
+            for col in row.cells.keys() {
+                write_bytes(&mut buf, col.as_bytes());
+
+                let history = self.storage.get_dag_history(pk, col)?;
+                write_u32(&mut buf, history.len() as u32);
+                for node in &history {
+                    write_bytes(&mut buf, &node.value);
+                    buf.extend_from_slice(&node.version.to_le_bytes());
+                    write_option_u64(&mut buf, node.parent_version);
+                    write_option_u64(&mut buf, node.parent2_version);
+                    buf.extend_from_slice(&node.timestamp.to_le_bytes());
+                    buf.push(node.is_tombstone as u8);
+                    buf.extend_from_slice(&node.commit_seq.to_le_bytes());
+                }
+            }
+        }
+
+        Ok(buf)
+    }
+
+    fn restore(&mut self, bytes: &[u8]) -> Result<()> {
+        let mut cursor = 0usize;
+        let row_count = read_u32(bytes, &mut cursor)?;
+
+        for _ in 0..row_count {
+            let pk = read_string(bytes, &mut cursor)?;
+            let col_count = read_u32(bytes, &mut cursor)?;
+
+            for _ in 0..col_count {
+                let col = read_string(bytes, &mut cursor)?;
+                let node_count = read_u32(bytes, &mut cursor)?;
+
+                for _ in 0..node_count {
+                    let value = read_bytes(bytes, &mut cursor)?;
+                    let version = read_u64(bytes, &mut cursor)?;
+                    let parent_version = read_option_u64(bytes, &mut cursor)?;
+                    let parent2_version = read_option_u64(bytes, &mut cursor)?;
+                    let timestamp = read_u64(bytes, &mut cursor)?;
+                    let is_tombstone = read_u8(bytes, &mut cursor)? != 0;
+                    let commit_seq = read_u64(bytes, &mut cursor).unwrap_or(0);
+
+                    self.storage.set_cell(&pk, &col, Cell { value: value.clone(), version })?;
+                    self.storage.append_dag_node(&pk, &col, DagNode {
+                        version,
+                        value,
+                        parent_version,
+                        parent2_version,
+                        timestamp,
+                        is_tombstone,
+                        commit_seq,
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Feed a recorded trace of changesets into `merge`, one at a time, in
+    /// order. Lets large recorded operation traces be replayed from disk
+    /// for reproducible benchmarks instead of regenerating synthetic
+    /// changesets on every run.
+    pub fn replay(&mut self, changesets: &[Changeset], policy: TieBreakPolicy) -> Result<Vec<MergeReport>> {
+        changesets.iter().map(|changeset| self.merge(changeset, policy)).collect()
+    }
+
+    /// Open a `SecureChangeset` envelope and merge it, in one step.
+    pub fn merge_sealed(
+        &mut self,
+        sealed: &[u8],
+        key: &SessionKey,
+        sender_peer_id: &str,
+        sequence: u64,
+        policy: TieBreakPolicy,
+    ) -> Result<MergeReport> {
+        let changeset = SecureChangeset::open(sealed, key, sender_peer_id, sequence)?;
+        self.merge(&changeset, policy)
+    }
+
+    /// Build an IBLT sketch of this table's `(pk, column, version)` key
+    /// space, suitable for shipping to a peer instead of a full changeset.
+    /// Keyed on `(pk, col, version)` rather than just `(pk, col)` — see
+    /// [`crate::iblt::versioned_row_key`] for why a value/version change on
+    /// an otherwise-shared key needs its own distinct key to peel.
+    pub fn reconcile_sketch(&self) -> Result<Iblt> {
+        let mut sketch = Iblt::with_defaults();
+
+        for pk in self.storage.all_pks()? {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                for (col, cell) in &row.cells {
+                    let key = versioned_row_key(&pk, col, cell.version);
+                    let value_fingerprint = fingerprint(&cell.value);
+                    sketch.insert(key, value_fingerprint);
+                }
+            }
+        }
+
+        Ok(sketch)
+    }
+
+    /// Reconcile this table's rows against a peer's sketch and return the
+    /// minimal changeset of columns this side holds that the peer is
+    /// missing or out of date on. The result's size scales with the number
+    /// of differing rows, not with table size.
+    pub fn diff_from_sketch(&self, remote: &Iblt) -> Result<Changeset> {
+        let local = self.reconcile_sketch()?;
+        let decoded = local.subtract(remote).decode().ok_or_else(|| {
+            crate::error::Error::InvalidState(
+                "IBLT failed to peel; sketch is too small for how much the replicas diverge".to_string(),
+            )
+        })?;
+
+        let only_local: std::collections::HashSet<u64> = decoded.iter()
+            .filter(|(_, _, present_on_local)| *present_on_local)
+            .map(|(key, _, _)| *key)
+            .collect();
+
+        let mut changes = HashMap::new();
+        let mut origins = HashMap::new();
+        for pk in self.storage.all_pks()? {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                for (col, cell) in row.cells {
+                    if only_local.contains(&versioned_row_key(&pk, &col, cell.version)) {
+                        let entry = changes.entry(pk.clone())
+                            .or_insert_with(|| (HashMap::new(), HashMap::new()));
+                        entry.1.insert(col.clone(), cell.version);
+                        let timestamp = self.cell_timestamp(&pk, &col, cell.version)?;
+                        entry.0.insert(col.clone(), cell.value);
+                        origins.entry(pk.clone()).or_insert_with(HashMap::new).insert(col, timestamp);
+                    }
+                }
+            }
+        }
+
+        Ok(Changeset { changes, origins, tombstones: HashMap::new() })
+    }
+
+    /// Build a Merkle tree over this table's rows, suitable for shipping
+    /// to a peer so both sides can narrow down to their divergent pks
+    /// with O(log n) round trips instead of exchanging a full changeset.
+    pub fn merkle_tree(&self) -> Result<MerkleTree> {
+        let mut tree = MerkleTree::new();
+
+        for pk in self.storage.all_pks()? {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                let cells: std::collections::BTreeMap<String, Cell> = row.cells.into_iter().collect();
+                tree.insert(&pk, &cells);
+            }
+        }
+
+        Ok(tree)
+    }
+
+    /// This table's Merkle root digest. Two tables are identical iff their
+    /// roots match — a cheap first check before either side pays for a
+    /// full [`Self::merkle_tree`] exchange.
+    pub fn merkle_root(&self) -> Result<Digest> {
+        Ok(self.merkle_tree()?.root_digest())
+    }
+
+    /// The non-empty immediate children of this table's Merkle tree at
+    /// `path` — see [`MerkleTree::child_digests`]. Lets a remote peer walk
+    /// this table's tree one level at a time over the network instead of
+    /// shipping [`Self::merkle_tree`] in full, so a reconciliation round
+    /// transfers bytes proportional to how much the two tables actually
+    /// diverge rather than to table size.
+    pub fn merkle_children(&self, path: &[u8]) -> Result<Vec<(u8, Digest)>> {
+        Ok(self.merkle_tree()?.child_digests(path))
+    }
+
+    /// The `(pk, row digest)` pairs in this table's Merkle leaf bucket at
+    /// `path` — see [`MerkleTree::leaf_entries`].
+    pub fn merkle_leaf(&self, path: &[u8]) -> Result<Vec<(String, Digest)>> {
+        Ok(self.merkle_tree()?.leaf_entries(path))
+    }
+
+    /// Build a [`Changeset`] covering exactly `pks`, the way
+    /// [`Self::diff_against`] does once it's narrowed down to the rows
+    /// that actually diverge, so fetching the real cells for a Merkle
+    /// reconciliation's diverging leaves doesn't require a second,
+    /// differently-shaped code path.
+    pub fn changeset_for_pks(&self, pks: &[String]) -> Result<Changeset> {
+        let mut changes = HashMap::new();
+        let mut origins = HashMap::new();
+        let mut tombstones = HashMap::new();
+        for pk in pks {
+            if let Some(row) = self.storage.get_row(pk)? {
+                let mut columns = HashMap::new();
+                let mut versions = HashMap::new();
+                let mut timestamps = HashMap::new();
+                let mut tombstoned_cols = HashSet::new();
+
+                for (col, cell) in row.cells {
+                    timestamps.insert(col.clone(), self.cell_timestamp(pk, &col, cell.version)?);
+                    if self.cell_is_tombstone(pk, &col, cell.version)? {
+                        tombstoned_cols.insert(col.clone());
+                    }
+                    versions.insert(col.clone(), cell.version);
+                    columns.insert(col, cell.value);
+                }
+
+                if !tombstoned_cols.is_empty() {
+                    tombstones.insert(pk.clone(), tombstoned_cols);
+                }
+                origins.insert(pk.clone(), timestamps);
+                changes.insert(pk.clone(), (columns, versions));
+            }
+        }
+        Ok(Changeset { changes, origins, tombstones })
+    }
+
+    /// Reconcile this table's Merkle tree against a peer's and return the
+    /// minimal changeset of columns this side holds that the peer is
+    /// missing or out of date on, by walking both trees from the root and
+    /// pruning any subtree whose digest already matches.
+    pub fn diff_against(&self, remote_tree: &MerkleTree) -> Result<Changeset> {
+        let local_tree = self.merkle_tree()?;
+        let diverging = local_tree.diverging_pks(remote_tree);
+        self.changeset_for_pks(&diverging)
+    }
+
+    /// A lock-free, point-in-time [`Snapshot`] of this table's rows.
+    ///
+    /// Readers holding a `Snapshot` never touch storage again, so they're
+    /// unaffected by any `insert`/`update`/`merge` this table does
+    /// afterwards — a snapshot taken before a `merge` keeps showing exactly
+    /// what it showed at checkout, never a half-applied changeset, because
+    /// `merge` only ever sees its own effects once it has fully returned.
+    ///
+    /// Internally this reconciles against a cached root rather than
+    /// rebuilding from scratch: a row whose cells haven't changed since the
+    /// last `snapshot()` call shares its `Rc` with the previous root, and
+    /// only rows this table actually wrote to get their DAG history
+    /// re-read and rebuilt. The new root then replaces the cached one in a
+    /// single assignment, so a `Snapshot` handed out a moment earlier keeps
+    /// pointing at the old one undisturbed.
+    pub fn snapshot(&self) -> Result<Snapshot> {
+        let previous = self.root.borrow().clone();
+        let mut rows = HashMap::with_capacity(previous.rows.len());
+
+        for pk in self.storage.all_pks()? {
+            let Some(row) = self.storage.get_row(&pk)? else { continue };
+
+            let unchanged = previous.rows.get(&pk).map_or(false, |state| state.cells == row.cells);
+            if unchanged {
+                rows.insert(pk.clone(), previous.rows[&pk].clone());
+                continue;
+            }
+
+            let mut dag_history = HashMap::new();
+            for col in row.cells.keys() {
+                dag_history.insert(col.clone(), self.storage.get_dag_history(&pk, col)?);
+            }
+            rows.insert(pk, Rc::new(RowState { cells: row.cells, dag_history }));
+        }
+
+        let root = Rc::new(TableRoot { rows });
+        *self.root.borrow_mut() = root.clone();
+        Ok(Snapshot::new(root))
+    }
+
+    /// Switch this table from caller-supplied integer versions to an
+    /// HLC-backed version source seeded with `node_id`. Existing rows keep
+    /// whatever plain integer versions they already have — `merge` treats
+    /// an HLC-packed version exactly like an integer one, since a packed
+    /// stamp just reads as a very large `u64`, strictly greater than any
+    /// small manually-assigned version would be.
+    pub fn enable_hlc(&self, node_id: u64) {
+        *self.clock.borrow_mut() = Some(HybridLogicalClock::new(node_id));
+    }
+
+    /// Mint the next HLC version stamp for a local write, for use in place
+    /// of a hardcoded integer version in `insert`/`update`. Returns `None`
+    /// if [`Self::enable_hlc`] hasn't been called.
+    pub fn next_hlc_version(&self) -> Option<u64> {
+        self.clock.borrow_mut().as_mut().map(|clock| pack_version(clock.tick()))
+    }
+
+    /// Cap this table's DAG history at roughly `bytes` of node payload,
+    /// evicting the least-recently-touched versions (via `get`/`merge`) as
+    /// new history is appended. `None` disables the cap (the default).
+    pub fn set_history_budget(&self, bytes: Option<usize>) {
+        self.history.borrow_mut().budget_bytes = bytes;
+    }
+
+    /// Register a callback invoked with `(pk, col, version, bytes_freed)`
+    /// each time `enforce_history_budget` evicts a node. Useful for tests
+    /// and bench harnesses that want to observe eviction pressure directly.
+    pub fn on_history_evict(&self, callback: impl FnMut(&str, &str, u64, usize) + 'static) {
+        self.history.borrow_mut().on_evict = Some(Box::new(callback));
+    }
+
+    /// Approximate current size, in bytes, of this table's tracked DAG
+    /// history payload.
+    pub fn current_history_bytes(&self) -> usize {
+        self.history.borrow().current_bytes
+    }
+
+    /// Evict least-recently-touched DAG versions until history fits within
+    /// the configured budget (a no-op if no budget is set). The live tip of
+    /// each column (its current `Cell::version`) is never evicted, even if
+    /// it hasn't been explicitly touched, since losing it would make the
+    /// row unreadable.
+    pub fn enforce_history_budget(&mut self) -> Result<()> {
+        let budget = match self.history.get_mut().budget_bytes {
+            Some(b) => b,
+            None => return Ok(()),
+        };
+
+        while self.history.get_mut().current_bytes > budget {
+            let mut candidate: Option<(String, String, u64, usize, (bool, u64))> = None;
+
+            for pk in self.storage.all_pks()? {
+                let row = match self.storage.get_row(&pk)? {
+                    Some(row) => row,
+                    None => continue,
+                };
+                for (col, live_cell) in &row.cells {
+                    for node in self.storage.get_dag_history(&pk, col)? {
+                        if node.version == live_cell.version {
+                            continue;
+                        }
+                        let rank = self.history.get_mut().recency(&pk, col, node.version);
+                        let better = match &candidate {
+                            Some((_, _, _, _, best_rank)) => rank < *best_rank,
+                            None => true,
+                        };
+                        if better {
+                            candidate = Some((pk.clone(), col.clone(), node.version, node_bytes(&node), rank));
+                        }
+                    }
+                }
+            }
+
+            let (pk, col, version, freed, _) = match candidate {
+                Some(c) => c,
+                None => break,
+            };
+
+            self.storage.remove_dag_version(&pk, &col, version)?;
+            let history = self.history.get_mut();
+            history.forget(&pk, &col, version);
+            history.current_bytes = history.current_bytes.saturating_sub(freed);
+            if let Some(on_evict) = history.on_evict.as_mut() {
+                on_evict(&pk, &col, version, freed);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This table's current per-`(pk, col)` version frontier, suitable for
+    /// sending to a peer as the starting point of [`Self::export_changeset_since`].
+    pub fn current_frontier(&self) -> Result<VersionVector> {
+        let mut frontier = VersionVector::new();
+        for pk in self.storage.all_pks()? {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                for (col, cell) in row.cells {
+                    frontier.set(&pk, &col, cell.version);
+                }
+            }
+        }
+        Ok(frontier)
+    }
+
+    /// Export every DAG node more recent than `frontier` as a CBOR-encoded
+    /// changeset, prefixed with a format version and this table's own
+    /// frontier so the receiver knows exactly what it's still missing.
+    pub fn export_changeset_since(&self, frontier: &VersionVector) -> Result<Vec<u8>> {
+        let mut nodes = Vec::new();
+
+        for pk in self.storage.all_pks()? {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                for col in row.cells.keys() {
+                    let seen = frontier.get(&pk, col);
+                    for node in self.storage.get_dag_history(&pk, col)? {
+                        if node.version > seen {
+                            nodes.push(NodeEntry {
+                                pk: pk.clone(),
+                                col: col.clone(),
+                                version: node.version,
+                                value: node.value,
+                                parent_version: node.parent_version,
+                                parent2_version: node.parent2_version,
+                                timestamp: node.timestamp,
+                                is_tombstone: node.is_tombstone,
+                                commit_seq: node.commit_seq,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let envelope = ChangesetEnvelope {
+            format_version: CHANGESET_FORMAT_VERSION,
+            sender_frontier: self.current_frontier()?,
+            nodes,
+        };
+
+        let mut buf = Vec::new();
+        ciborium::into_writer(&envelope, &mut buf)
+            .map_err(|e| Error::InvalidState(format!("failed to encode changeset: {}", e)))?;
+        Ok(buf)
+    }
+
+    /// Apply a changeset produced by [`Self::export_changeset_since`]:
+    /// insert every DAG node this table doesn't already have, then
+    /// recompute and write the winning cell for each `(pk, col)` touched,
+    /// using `policy` to break any version ties.
+    pub fn apply_changeset(&mut self, bytes: &[u8], policy: TieBreakPolicy) -> Result<MergeStats> {
+        let envelope: ChangesetEnvelope = ciborium::from_reader(bytes)
+            .map_err(|e| Error::InvalidState(format!("failed to decode changeset: {}", e)))?;
+
+        let mut stats = MergeStats::default();
+        let mut touched: HashSet<(String, String)> = HashSet::new();
+
+        self.storage.begin_transaction()?;
+
+        for entry in envelope.nodes {
+            let existing = self.storage.get_dag_history(&entry.pk, &entry.col)?;
+            if existing.iter().any(|node| node.version == entry.version) {
+                stats.nodes_skipped += 1;
+                continue;
+            }
+
+            self.storage.append_dag_node(&entry.pk, &entry.col, DagNode {
+                version: entry.version,
+                value: entry.value,
+                parent_version: entry.parent_version,
+                parent2_version: entry.parent2_version,
+                timestamp: entry.timestamp,
+                is_tombstone: entry.is_tombstone,
+                commit_seq: entry.commit_seq,
+            })?;
+            stats.nodes_applied += 1;
+            touched.insert((entry.pk, entry.col));
+        }
+
+        for (pk, col) in touched {
+            let history = self.storage.get_dag_history(&pk, &col)?;
+            if let Some(winner) = pick_winner(&history, policy) {
+                self.storage.set_cell(&pk, &col, Cell { value: winner.value, version: winner.version })?;
+                stats.cells_updated += 1;
+            }
+        }
+
+        self.storage.commit_transaction()?;
+        Ok(stats)
+    }
+
+    /// Like [`Self::export_changeset_since`], but every node's value is
+    /// split into content-defined chunks via `store` instead of carried
+    /// inline. Only the chunk hashes travel in the returned bytes; the
+    /// chunk bytes themselves live in `store`, deduplicated against
+    /// anything already there (from an earlier export, possibly of a
+    /// different version of the same column) — a peer exchanging `store`
+    /// out of band only has to transfer chunks it's actually missing.
+    pub fn export_chunked_changeset_since(
+        &self,
+        frontier: &VersionVector,
+        store: &mut ChunkStore,
+        config: &ChunkConfig,
+    ) -> Result<Vec<u8>> {
+        let mut nodes = Vec::new();
+
+        for pk in self.storage.all_pks()? {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                for col in row.cells.keys() {
+                    let seen = frontier.get(&pk, col);
+                    for node in self.storage.get_dag_history(&pk, col)? {
+                        if node.version > seen {
+                            let value_chunks = store.put(&node.value, config);
+                            nodes.push(ChunkedNodeEntry {
+                                pk: pk.clone(),
+                                col: col.clone(),
+                                version: node.version,
+                                value_chunks,
+                                parent_version: node.parent_version,
+                                parent2_version: node.parent2_version,
+                                timestamp: node.timestamp,
+                                is_tombstone: node.is_tombstone,
+                                commit_seq: node.commit_seq,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        let envelope = ChunkedChangesetEnvelope {
+            format_version: CHANGESET_FORMAT_VERSION,
+            sender_frontier: self.current_frontier()?,
+            nodes,
+        };
+
+        let mut buf = Vec::new();
+        ciborium::into_writer(&envelope, &mut buf)
+            .map_err(|e| Error::InvalidState(format!("failed to encode chunked changeset: {}", e)))?;
+        Ok(buf)
+    }
+
+    /// Apply a changeset produced by [`Self::export_chunked_changeset_since`],
+    /// reassembling each node's value from `store`. Fails with
+    /// [`Error::InvalidState`] if `store` is missing any chunk a node
+    /// needs — the caller is expected to have fetched everything
+    /// [`ChunkStore::missing`] reported before calling this.
+    pub fn apply_chunked_changeset(
+        &mut self,
+        bytes: &[u8],
+        store: &ChunkStore,
+        policy: TieBreakPolicy,
+    ) -> Result<MergeStats> {
+        let envelope: ChunkedChangesetEnvelope = ciborium::from_reader(bytes)
+            .map_err(|e| Error::InvalidState(format!("failed to decode chunked changeset: {}", e)))?;
+
+        let mut stats = MergeStats::default();
+        let mut touched: HashSet<(String, String)> = HashSet::new();
+
+        self.storage.begin_transaction()?;
+
+        for entry in envelope.nodes {
+            let existing = self.storage.get_dag_history(&entry.pk, &entry.col)?;
+            if existing.iter().any(|node| node.version == entry.version) {
+                stats.nodes_skipped += 1;
+                continue;
+            }
+
+            let value = store.reassemble(&entry.value_chunks).ok_or_else(|| {
+                Error::InvalidState(format!(
+                    "missing chunk(s) for {}:{} v{}",
+                    entry.pk, entry.col, entry.version
+                ))
+            })?;
+
+            self.storage.append_dag_node(&entry.pk, &entry.col, DagNode {
+                version: entry.version,
+                value,
+                parent_version: entry.parent_version,
+                parent2_version: entry.parent2_version,
+                timestamp: entry.timestamp,
+                is_tombstone: entry.is_tombstone,
+                commit_seq: entry.commit_seq,
+            })?;
+            stats.nodes_applied += 1;
+            touched.insert((entry.pk, entry.col));
+        }
+
+        for (pk, col) in touched {
+            let history = self.storage.get_dag_history(&pk, &col)?;
+            if let Some(winner) = pick_winner(&history, policy) {
+                self.storage.set_cell(&pk, &col, Cell { value: winner.value, version: winner.version })?;
+                stats.cells_updated += 1;
+            }
+        }
+
+        self.storage.commit_transaction()?;
+        Ok(stats)
+    }
+
+    pub fn gc(&mut self, keep_versions: usize) -> Result<usize> {
+        let start = Instant::now();
+        let mut total_removed = 0;
+        let pks = self.storage.all_pks()?;
+
+        for pk in pks {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                for col in row.cells.keys() {
+                    total_removed += self.storage.gc_dag(&pk, col, keep_versions)?;
+                }
+            }
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_gc(total_removed, start.elapsed());
+        }
+        Ok(total_removed)
+    }
+
+    /// The minimum version retained anywhere in this table's DAG history.
+    /// Everything below it has already been superseded at every
+    /// `(pk, col)`, so a multi-peer coordinated GC round is safe to
+    /// collect up to `min` of every peer's own `min_watermark()` without
+    /// any peer losing a version another peer might still be depending
+    /// on (see `network_bench`'s `WatermarkRequest`/`GcThreshold`).
+    pub fn min_watermark(&self) -> Result<u64> {
+        let mut watermark = None;
+        for pk in self.storage.all_pks()? {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                for col in row.cells.keys() {
+                    for node in self.storage.get_dag_history(&pk, col)? {
+                        watermark = Some(watermark.map_or(node.version, |w: u64| w.min(node.version)));
+                    }
+                }
+            }
+        }
+        Ok(watermark.unwrap_or(0))
+    }
+
+    /// Physically remove every DAG node whose version is strictly below
+    /// `watermark`, for every column — the same reclamation [`Self::gc`]
+    /// does, but bounded by an absolute version line all peers have
+    /// agreed is safe rather than each peer independently keeping its
+    /// own last N versions.
+    pub fn gc_below_watermark(&mut self, watermark: u64) -> Result<usize> {
+        let start = Instant::now();
+        let mut total_removed = 0;
+        for pk in self.storage.all_pks()? {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                for col in row.cells.keys() {
+                    for node in self.storage.get_dag_history(&pk, col)? {
+                        if node.version < watermark {
+                            self.storage.remove_dag_version(&pk, col, node.version)?;
+                            total_removed += 1;
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(metrics) = &self.metrics {
+            metrics.record_gc(total_removed, start.elapsed());
+        }
+        Ok(total_removed)
+    }
+
+    /// Second-phase GC for [`Self::delete`]d rows: once every column a row
+    /// holds is a tombstone at a version below `watermark` (i.e. every peer
+    /// has already merged the delete, the same safety bar
+    /// [`Self::gc_below_watermark`] uses for trimming history), forget the
+    /// row from storage entirely instead of merely trimming its DAG
+    /// history — the hard delete [`Self::delete`] itself no longer does. A
+    /// row with any live column, or any tombstone still at or above
+    /// `watermark`, is left untouched.
+    pub fn gc_tombstones(&mut self, watermark: u64) -> Result<usize> {
+        let mut removed = 0;
+        for pk in self.storage.all_pks()? {
+            let Some(row) = self.storage.get_row(&pk)? else { continue };
+            if row.cells.is_empty() {
+                continue;
+            }
+
+            let mut safe_to_forget = true;
+            for (col, cell) in &row.cells {
+                if cell.version >= watermark || !self.cell_is_tombstone(&pk, col, cell.version)? {
+                    safe_to_forget = false;
+                    break;
+                }
+            }
+
+            if safe_to_forget {
+                self.storage.delete_row(&pk)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Refcount-based GC for a [`ChunkStore`] backing this table's
+    /// [`InsertBuilder::column_chunked`]/[`UpdateBuilder::column_chunked`]
+    /// columns: walk every DAG node this table still retains (not just
+    /// each column's current cell — an older version a peer hasn't synced
+    /// yet can still reference a chunk), collect every chunk hash any of
+    /// them points to, and drop everything in `store` that isn't among
+    /// them. Run [`Self::gc_below_watermark`]/[`Self::gc_tombstones`]
+    /// first so this only has to consider history actually worth keeping.
+    pub fn gc_chunks(&self, store: &mut ChunkStore) -> Result<usize> {
+        let mut referenced: HashSet<ChunkHash> = HashSet::new();
+
+        for pk in self.storage.all_pks()? {
+            if let Some(row) = self.storage.get_row(&pk)? {
+                for col in row.cells.keys() {
+                    for node in self.storage.get_dag_history(&pk, col)? {
+                        if let Some(hashes) = crate::chunking::decode_chunk_refs(&node.value) {
+                            referenced.extend(hashes);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(store.gc_unreferenced(&referenced))
+    }
+
+    #[deprecated(note = "Use insert() builder instead")]
+    pub fn insert_or_update(
+        &mut self,
+        pk: &str,
+        columns: HashMap<String, String>,
+        versions: HashMap<String, u64>,
+    ) -> Result<()> {
+        let commit_seq = self.oracle.advance();
+        for (col, value) in columns {
+            let version = versions.get(&col).copied().unwrap_or(1);
+            let current = self.storage.get_cell(pk, &col)?;
+            let parent_version = current.as_ref().map(|c| c.version);
+
+            let cell = Cell { value: value.as_bytes().to_vec(), version };
+            self.storage.set_cell(pk, &col, cell)?;
+
+            let node = DagNode {
+                version,
+                value: value.into_bytes(),
+                parent_version,
+                parent2_version: None,
+                timestamp: now_millis(),
+                is_tombstone: false,
+                commit_seq,
+            };
+            self.storage.append_dag_node(pk, &col, node)?;
         }
         Ok(())
     }
@@ -223,7 +1786,663 @@ impl<S: Storage> CrrTable<S> {
                     (pk.clone(), (columns, vers.clone()))
                 })
                 .collect(),
+            origins: HashMap::new(),
+            tombstones: HashMap::new(),
         };
         self.merge(&converted, policy)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_and_applies_changeset_since_frontier() {
+        let mut source = CrrTable::open_in_memory().unwrap();
+        source.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        let bytes = source.export_changeset_since(&VersionVector::new()).unwrap();
+        let stats = dest.apply_changeset(&bytes, TieBreakPolicy::LexicographicMin).unwrap();
+
+        assert_eq!(stats.nodes_applied, 1);
+        assert_eq!(stats.cells_updated, 1);
+        assert_eq!(dest.get("row1").unwrap().unwrap().cells["name"].value, b"Alice");
+    }
+
+    #[test]
+    fn export_since_frontier_excludes_already_seen_versions() {
+        let mut source = CrrTable::open_in_memory().unwrap();
+        source.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+
+        let frontier = source.current_frontier().unwrap();
+        source.update("row1").column_str("name", "Bob").commit().unwrap();
+
+        let bytes = source.export_changeset_since(&frontier).unwrap();
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        dest.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+        let stats = dest.apply_changeset(&bytes, TieBreakPolicy::LexicographicMin).unwrap();
+
+        assert_eq!(stats.nodes_applied, 1);
+        assert_eq!(dest.get("row1").unwrap().unwrap().cells["name"].value, b"Bob");
+    }
+
+    #[test]
+    fn ingest_applies_a_batch_of_changesets_under_one_global_version() {
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.insert("row_a").column_str("name", "Alice", 1).commit().unwrap();
+        let cs_a = peer_a.changeset().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.insert("row_b").column_str("name", "Bob", 1).commit().unwrap();
+        let cs_b = peer_b.changeset().unwrap();
+
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        let stats = dest.ingest(&[cs_a, cs_b], TieBreakPolicy::LexicographicMin).unwrap();
+
+        assert_eq!(stats.applied, 2);
+        assert_eq!(stats.skipped, 0);
+        assert_eq!(stats.conflicted, 0);
+        assert_eq!(dest.get("row_a").unwrap().unwrap().cells["name"].value, b"Alice");
+        assert_eq!(dest.get("row_b").unwrap().unwrap().cells["name"].value, b"Bob");
+    }
+
+    #[test]
+    fn ingest_stamps_every_changeset_in_the_batch_with_the_same_commit_seq() {
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.insert("row_a").column_str("name", "Alice", 1).commit().unwrap();
+        let cs_a = peer_a.changeset().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.insert("row_b").column_str("name", "Bob", 1).commit().unwrap();
+        let cs_b = peer_b.changeset().unwrap();
+
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        let stats = dest.ingest(&[cs_a, cs_b], TieBreakPolicy::LexicographicMin).unwrap();
+
+        // Both rows landed under the same `global_version`, even though
+        // they came from two different changesets in the batch — a
+        // reader pinned to it sees either both or neither, never one
+        // without the other.
+        let pinned = dest.as_of(AsOfBound::CommitSeq(stats.global_version));
+        assert!(pinned.get("row_a").unwrap().is_some());
+        assert!(pinned.get("row_b").unwrap().is_some());
+    }
+
+    #[test]
+    fn merge_with_options_spills_and_still_applies_every_change() {
+        let mut source = CrrTable::open_in_memory().unwrap();
+        for i in 0..20 {
+            source.insert(&format!("row{}", i)).column_str("name", &format!("value{}", i), 1).commit().unwrap();
+        }
+        let changeset = source.changeset().unwrap();
+
+        // A threshold far smaller than the changeset forces several spills
+        // (each push is ~31-33 bytes, so this spills after every entry),
+        // exercising the multi-run k-way merge path rather than the
+        // single-resident-buffer case.
+        let options = MergeOptions { spill_threshold_bytes: 16, temp_dir: std::env::temp_dir() };
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        let report = dest.merge_with_options(&changeset, TieBreakPolicy::LexicographicMin, &options).unwrap();
+
+        assert_eq!(report.inserted, 20);
+        for i in 0..20 {
+            assert_eq!(dest.get(&format!("row{}", i)).unwrap().unwrap().cells["name"].value, format!("value{}", i).as_bytes());
+        }
+    }
+
+    #[test]
+    fn merge_with_options_leaves_no_temp_files_behind() {
+        let mut source = CrrTable::open_in_memory().unwrap();
+        for i in 0..10 {
+            source.insert(&format!("row{}", i)).column_str("name", "x", 1).commit().unwrap();
+        }
+        let changeset = source.changeset().unwrap();
+
+        let temp_dir = std::env::temp_dir().join(format!("crr-spill-test-{}", std::process::id()));
+        let options = MergeOptions { spill_threshold_bytes: 32, temp_dir: temp_dir.clone() };
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        dest.merge_with_options(&changeset, TieBreakPolicy::LexicographicMin, &options).unwrap();
+
+        let leftover = std::fs::read_dir(&temp_dir)
+            .map(|entries| entries.count())
+            .unwrap_or(0);
+        assert_eq!(leftover, 0, "spilled run files must be cleaned up once the merge completes");
+    }
+
+    #[test]
+    fn reapplying_a_changeset_skips_duplicate_nodes() {
+        let mut source = CrrTable::open_in_memory().unwrap();
+        source.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+
+        let bytes = source.export_changeset_since(&VersionVector::new()).unwrap();
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        dest.apply_changeset(&bytes, TieBreakPolicy::LexicographicMin).unwrap();
+        let stats = dest.apply_changeset(&bytes, TieBreakPolicy::LexicographicMin).unwrap();
+
+        assert_eq!(stats.nodes_applied, 0);
+        assert_eq!(stats.nodes_skipped, 1);
+    }
+
+    #[test]
+    fn bidirectional_merge_converges_on_a_concurrent_delete_outliving_an_older_update() {
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+
+        // Peer A writes once more, then deletes — its tombstone lands at a
+        // higher version than the concurrent update peer B is about to make.
+        peer_a.update("row1").column_str("name", "Bob").commit().unwrap();
+        peer_a.delete("row1").unwrap();
+        assert!(peer_a.get("row1").unwrap().is_none());
+
+        // Peer B, unaware of either of peer A's writes, makes its own
+        // concurrent update — landing at a lower version than A's tombstone.
+        peer_b.update("row1").column_str("name", "Carol").commit().unwrap();
+
+        let changeset_from_b = peer_b.changeset().unwrap();
+        let changeset_from_a = peer_a.changeset().unwrap();
+
+        peer_a.merge(&changeset_from_b, TieBreakPolicy::LastWriteWins).unwrap();
+        peer_b.merge(&changeset_from_a, TieBreakPolicy::LastWriteWins).unwrap();
+
+        assert!(peer_a.get("row1").unwrap().is_none());
+        assert!(peer_b.get("row1").unwrap().is_none());
+    }
+
+    #[test]
+    fn delete_vs_update_at_the_same_version_always_resolves_to_the_delete() {
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+
+        // Both peers land their next write at version 2 — a genuine tie
+        // between a tombstone and a live update, which must resolve the
+        // same way regardless of `policy` (PreferIncoming here would
+        // otherwise favor whichever side looks like "the update").
+        peer_a.delete("row1").unwrap();
+        peer_b.update("row1").column_str("name", "Carol").commit().unwrap();
+
+        let changeset_from_b = peer_b.changeset().unwrap();
+        peer_a.merge(&changeset_from_b, TieBreakPolicy::PreferIncoming).unwrap();
+
+        assert!(peer_a.get("row1").unwrap().is_none(), "a same-version tombstone must win over a concurrent update, not just an outdated one");
+    }
+
+    #[test]
+    fn multi_value_policy_keeps_both_sides_of_a_genuine_conflict() {
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.insert("row1").column_str("owner", "alice", 1).commit().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.insert("row1").column_str("owner", "alice", 1).commit().unwrap();
+
+        peer_a.update("row1").column_str("owner", "bob").commit().unwrap();
+        peer_b.update("row1").column_str("owner", "carol").commit().unwrap();
+
+        let cs_b = peer_b.changeset().unwrap();
+        peer_a.merge(&cs_b, TieBreakPolicy::MultiValue).unwrap();
+
+        let row = peer_a.get("row1").unwrap().unwrap();
+        let mut values = row.get_multi("owner").unwrap();
+        values.sort();
+        assert_eq!(values, vec![b"bob".to_vec(), b"carol".to_vec()]);
+    }
+
+    #[test]
+    fn last_write_wins_keeps_the_newer_timestamped_value_even_against_lexicographic_order() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        table.insert("row1").column_str("owner", "alice", 1).commit().unwrap();
+
+        // Same version as the local write, but a remote value that would
+        // lose under LexicographicMin ("alice" < "zzz-remote") — LastWriteWins
+        // should still pick it because its timestamp is newer.
+        let mut changes = HashMap::new();
+        changes.insert(
+            "row1".to_string(),
+            (
+                [("owner".to_string(), b"zzz-remote".to_vec())].into_iter().collect(),
+                [("owner".to_string(), 1)].into_iter().collect(),
+            ),
+        );
+        let mut origins = HashMap::new();
+        origins.insert("row1".to_string(), [("owner".to_string(), u64::MAX)].into_iter().collect());
+        let remote = Changeset { changes, origins, tombstones: HashMap::new() };
+
+        table.merge(&remote, TieBreakPolicy::LastWriteWins).unwrap();
+
+        assert_eq!(table.get("row1").unwrap().unwrap().cells["owner"].value, b"zzz-remote");
+    }
+
+    #[test]
+    fn a_column_declared_pn_counter_merges_by_summing_instead_of_by_version() {
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.declare_crdt_column("likes", CrdtKind::PnCounter);
+        let mut counter_a = PnCounter::new();
+        counter_a.increment("a", 3);
+        peer_a.insert("post1").column("likes", counter_a.to_bytes().unwrap(), 1).commit().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.declare_crdt_column("likes", CrdtKind::PnCounter);
+        let mut counter_b = PnCounter::new();
+        counter_b.increment("b", 5);
+        peer_b.insert("post1").column("likes", counter_b.to_bytes().unwrap(), 1).commit().unwrap();
+
+        let cs_b = peer_b.changeset().unwrap();
+        let report = peer_a.merge(&cs_b, TieBreakPolicy::LexicographicMin).unwrap();
+
+        assert_eq!(report.conflicts, 0, "a conflict-free CRDT merge must never be counted as a conflict");
+        assert_eq!(report.updated, 1);
+
+        let merged_bytes = &peer_a.get("post1").unwrap().unwrap().cells["likes"].value;
+        assert_eq!(PnCounter::from_bytes(merged_bytes).unwrap().value(), 8);
+    }
+
+    #[test]
+    fn a_column_declared_or_set_merges_by_unioning_instead_of_by_version() {
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.declare_crdt_column("tags", CrdtKind::OrSet);
+        let mut set_a: OrSet<String> = OrSet::new();
+        set_a.insert("a", 1, "urgent".to_string());
+        peer_a.insert("row1").column("tags", set_a.to_bytes().unwrap(), 1).commit().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.declare_crdt_column("tags", CrdtKind::OrSet);
+        let mut set_b: OrSet<String> = OrSet::new();
+        set_b.insert("b", 1, "blocked".to_string());
+        peer_b.insert("row1").column("tags", set_b.to_bytes().unwrap(), 1).commit().unwrap();
+
+        let cs_b = peer_b.changeset().unwrap();
+        let report = peer_a.merge(&cs_b, TieBreakPolicy::LexicographicMin).unwrap();
+
+        assert_eq!(report.conflicts, 0);
+        assert_eq!(report.updated, 1);
+
+        let merged_bytes = &peer_a.get("row1").unwrap().unwrap().cells["tags"].value;
+        let merged: OrSet<String> = OrSet::from_bytes(merged_bytes).unwrap();
+        assert!(merged.contains(&"urgent".to_string()));
+        assert!(merged.contains(&"blocked".to_string()));
+    }
+
+    #[test]
+    fn column_counter_and_column_set_add_spare_the_caller_a_manual_encode() {
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.declare_crdt_column("likes", CrdtKind::PnCounter);
+        peer_a.declare_crdt_column("tags", CrdtKind::OrSet);
+        peer_a.insert("post1")
+            .column_counter("likes", "a", 3, 1).unwrap()
+            .column_set_add("tags", "a", "urgent", 1).unwrap()
+            .commit().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.declare_crdt_column("likes", CrdtKind::PnCounter);
+        peer_b.declare_crdt_column("tags", CrdtKind::OrSet);
+        peer_b.insert("post1")
+            .column_counter("likes", "b", 5, 1).unwrap()
+            .column_set_add("tags", "b", "blocked", 1).unwrap()
+            .commit().unwrap();
+
+        peer_a.update("post1").column_counter("likes", "a", 2).unwrap().commit().unwrap();
+
+        let cs_a = peer_a.changeset().unwrap();
+        let report = peer_b.merge(&cs_a, TieBreakPolicy::LexicographicMin).unwrap();
+
+        assert_eq!(report.counter_merges, 1);
+        assert_eq!(report.set_merges, 1);
+
+        let likes = &peer_b.get("post1").unwrap().unwrap().cells["likes"].value;
+        assert_eq!(PnCounter::from_bytes(likes).unwrap().value(), 10);
+
+        let tags: OrSet<String> = OrSet::from_bytes(&peer_b.get("post1").unwrap().unwrap().cells["tags"].value).unwrap();
+        assert!(tags.contains(&"urgent".to_string()));
+        assert!(tags.contains(&"blocked".to_string()));
+    }
+
+    #[test]
+    fn save_and_load_round_trip_dag_edges_across_different_backends() {
+        use crate::storage::MemoryStorage;
+
+        let mut source = CrrTable::with_storage(MemoryStorage::default());
+        source.insert("row1").column_str("owner", "alice", 1).commit().unwrap();
+
+        let mut peer = CrrTable::with_storage(MemoryStorage::default());
+        peer.insert("row1").column_str("owner", "alice", 1).commit().unwrap();
+        peer.update("row1").column_str("owner", "bob").commit().unwrap();
+
+        // A merge from a second peer gives "owner" a node with both
+        // parent_version and parent2_version set, so the round trip below
+        // actually exercises the two-parent edge, not just a linear chain.
+        let cs_peer = peer.changeset().unwrap();
+        source.merge(&cs_peer, TieBreakPolicy::LastWriteWins).unwrap();
+
+        let snapshot_path = std::env::temp_dir()
+            .join(format!("crr_migrate_test_{}_{}.bin", std::process::id(), now_millis()));
+        let snapshot_path = snapshot_path.to_str().unwrap();
+        source.save(snapshot_path).unwrap();
+
+        let mut dest = CrrTable::<SqliteStorage>::open_in_memory().unwrap();
+        dest.load(snapshot_path).unwrap();
+        std::fs::remove_file(snapshot_path).ok();
+
+        let source_row = source.get("row1").unwrap().unwrap();
+        let dest_row = dest.get("row1").unwrap().unwrap();
+        assert_eq!(dest_row.get("owner"), source_row.get("owner"));
+        assert_eq!(dest_row.version("owner"), source_row.version("owner"));
+
+        let source_history = &source_row.dag_history["owner"];
+        let dest_history = &dest_row.dag_history["owner"];
+        assert_eq!(dest_history.len(), source_history.len());
+        for (expected, actual) in source_history.iter().zip(dest_history.iter()) {
+            assert_eq!(actual.version, expected.version);
+            assert_eq!(actual.value, expected.value);
+            assert_eq!(actual.parent_version, expected.parent_version);
+            assert_eq!(actual.parent2_version, expected.parent2_version);
+            assert_eq!(actual.is_tombstone, expected.is_tombstone);
+        }
+        assert!(source_history.iter().any(|n| n.parent2_version.is_some()));
+    }
+
+    #[test]
+    fn a_dominating_write_collapses_a_multi_value_conflict_set() {
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.insert("row1").column_str("owner", "alice", 1).commit().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.insert("row1").column_str("owner", "alice", 1).commit().unwrap();
+
+        peer_a.update("row1").column_str("owner", "bob").commit().unwrap();
+        peer_b.update("row1").column_str("owner", "carol").commit().unwrap();
+
+        let cs_b = peer_b.changeset().unwrap();
+        peer_a.merge(&cs_b, TieBreakPolicy::MultiValue).unwrap();
+        assert_eq!(peer_a.get("row1").unwrap().unwrap().get_multi("owner").unwrap().len(), 2);
+
+        // A subsequent write that starts from the merged state's version
+        // causally succeeds both forked values, so it should replace the
+        // whole conflict set with a single resolved value.
+        peer_a.update("row1").column_str("owner", "dave").commit().unwrap();
+
+        let row = peer_a.get("row1").unwrap().unwrap();
+        assert_eq!(row.get_multi("owner").unwrap(), vec![b"dave".to_vec()]);
+    }
+
+    #[test]
+    fn hlc_packed_versions_merge_by_causal_order_not_insertion_order() {
+        let earlier = crate::hlc::pack_version(crate::hlc::HlcStamp { wall_time: 1000, logical: 0, node_id: 1 });
+        let later = crate::hlc::pack_version(crate::hlc::HlcStamp { wall_time: 2000, logical: 0, node_id: 2 });
+        assert!(later > earlier);
+
+        let mut peer_a = CrrTable::open_in_memory().unwrap();
+        peer_a.insert("row1").column_str("owner", "alice", earlier).commit().unwrap();
+
+        let mut peer_b = CrrTable::open_in_memory().unwrap();
+        peer_b.insert("row1").column_str("owner", "bob", later).commit().unwrap();
+
+        // Merging the earlier-stamped write into the later table must not
+        // overwrite bob's value, even though it's presented second.
+        let cs_a = peer_a.changeset().unwrap();
+        peer_b.merge(&cs_a, TieBreakPolicy::LexicographicMin).unwrap();
+        assert_eq!(peer_b.get("row1").unwrap().unwrap().get_string("owner"), Some("bob".to_string()));
+    }
+
+    #[test]
+    fn enable_hlc_mints_monotonically_increasing_versions() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        assert!(table.next_hlc_version().is_none());
+
+        table.enable_hlc(7);
+        let v1 = table.next_hlc_version().unwrap();
+        let v2 = table.next_hlc_version().unwrap();
+        assert!(v2 > v1, "same-clock ticks always advance, even within the same millisecond");
+    }
+
+    #[test]
+    fn column_history_returns_every_value_a_column_ever_held() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        table.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+        table.update("row1").column_str("name", "Alicia").commit().unwrap();
+        table.update("row1").column_str("name", "Ali").commit().unwrap();
+
+        let history = table.column_history("row1", "name").unwrap();
+        let values: Vec<&[u8]> = history.iter().map(|(_, v)| v.as_slice()).collect();
+        assert_eq!(values, vec![b"Alice".as_slice(), b"Alicia".as_slice(), b"Ali".as_slice()]);
+    }
+
+    #[test]
+    fn as_of_reconstructs_a_past_version_of_a_row() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        table.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+        let after_insert = table.get("row1").unwrap().unwrap().version("name").unwrap();
+        table.update("row1").column_str("name", "Alicia").commit().unwrap();
+
+        let past = table.as_of(AsOfBound::Version(after_insert)).get("row1").unwrap().unwrap();
+        assert_eq!(past.get_string("name"), Some("Alice".to_string()));
+
+        let present = table.get("row1").unwrap().unwrap();
+        assert_eq!(present.get_string("name"), Some("Alicia".to_string()));
+    }
+
+    #[test]
+    fn as_of_before_a_rows_first_write_sees_nothing() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        table.insert("row1").column_str("name", "Alice", 5).commit().unwrap();
+
+        assert!(table.as_of(AsOfBound::Version(0)).get("row1").unwrap().is_none());
+    }
+
+    #[test]
+    fn commit_seq_pins_a_read_against_a_later_concurrent_write() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        table.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+        let seq = table.commit_seq();
+
+        // Simulates a concurrent writer landing a batch after the snapshot
+        // was pinned but before it's read.
+        table.update("row1").column_str("name", "Alicia").commit().unwrap();
+
+        let pinned = table.as_of(AsOfBound::CommitSeq(seq)).get("row1").unwrap().unwrap();
+        assert_eq!(pinned.get_string("name"), Some("Alice".to_string()));
+
+        let present = table.get("row1").unwrap().unwrap();
+        assert_eq!(present.get_string("name"), Some("Alicia".to_string()));
+    }
+
+    #[test]
+    fn changeset_as_of_matches_a_commit_seq_pinned_snapshot() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        table.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+        let seq = table.commit_seq();
+        table.update("row1").column_str("name", "Alicia").commit().unwrap();
+
+        let pinned_changeset = table.changeset_as_of(seq).unwrap();
+        let (columns, _) = pinned_changeset.changes.get("row1").unwrap();
+        assert_eq!(columns.get("name"), Some(&b"Alice".to_vec()));
+
+        let current_changeset = table.changeset().unwrap();
+        let (columns, _) = current_changeset.changes.get("row1").unwrap();
+        assert_eq!(columns.get("name"), Some(&b"Alicia".to_vec()));
+    }
+
+    #[test]
+    fn a_snapshot_keeps_its_view_after_a_later_merge_changes_the_row() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        table.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+
+        let before = table.snapshot().unwrap();
+        assert_eq!(before.get("row1").unwrap().get_string("name"), Some("Alice".to_string()));
+
+        let mut remote = Changeset { changes: HashMap::new(), origins: HashMap::new(), tombstones: HashMap::new() };
+        remote.changes.insert(
+            "row1".to_string(),
+            (
+                [("name".to_string(), b"Alicia".to_vec())].into_iter().collect(),
+                [("name".to_string(), 2)].into_iter().collect(),
+            ),
+        );
+        table.merge(&remote, TieBreakPolicy::PreferIncoming).unwrap();
+
+        assert_eq!(before.get("row1").unwrap().get_string("name"), Some("Alice".to_string()));
+        assert_eq!(table.get("row1").unwrap().unwrap().get_string("name"), Some("Alicia".to_string()));
+
+        let after = table.snapshot().unwrap();
+        assert_eq!(after.get("row1").unwrap().get_string("name"), Some("Alicia".to_string()));
+    }
+
+    #[test]
+    fn snapshot_reuses_unchanged_rows_and_only_rebuilds_the_one_that_was_written() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        table.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+        table.insert("row2").column_str("name", "Bob", 1).commit().unwrap();
+
+        let first = table.snapshot().unwrap();
+        table.update("row1").column_str("name", "Alicia").commit().unwrap();
+        let second = table.snapshot().unwrap();
+
+        assert_eq!(first.get("row2").unwrap().get_string("name"), second.get("row2").unwrap().get_string("name"));
+        assert_ne!(first.get("row1").unwrap().get_string("name"), second.get("row1").unwrap().get_string("name"));
+        assert_eq!(second.merkle_root(), table.merkle_root().unwrap());
+    }
+
+    #[test]
+    fn exports_and_applies_a_chunked_changeset() {
+        let mut source = CrrTable::open_in_memory().unwrap();
+        let blob: Vec<u8> = (0..2000).map(|i| (i % 199) as u8).collect();
+        source.insert("row1").column("data", &blob, 1).commit().unwrap();
+
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        let bytes = source
+            .export_chunked_changeset_since(&VersionVector::new(), &mut store, &config)
+            .unwrap();
+
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        let stats = dest.apply_chunked_changeset(&bytes, &store, TieBreakPolicy::LexicographicMin).unwrap();
+
+        assert_eq!(stats.nodes_applied, 1);
+        assert_eq!(dest.get("row1").unwrap().unwrap().cells["data"].value, blob);
+    }
+
+    #[test]
+    fn applying_a_chunked_changeset_without_its_chunks_fails() {
+        let mut source = CrrTable::open_in_memory().unwrap();
+        let blob: Vec<u8> = (0..2000).map(|i| (i % 199) as u8).collect();
+        source.insert("row1").column("data", &blob, 1).commit().unwrap();
+
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        let bytes = source
+            .export_chunked_changeset_since(&VersionVector::new(), &mut store, &config)
+            .unwrap();
+
+        let empty_store = ChunkStore::new();
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        assert!(dest.apply_chunked_changeset(&bytes, &empty_store, TieBreakPolicy::LexicographicMin).is_err());
+    }
+
+    #[test]
+    fn chunked_export_deduplicates_chunks_on_re_export() {
+        let mut source = CrrTable::open_in_memory().unwrap();
+        let blob: Vec<u8> = (0..2000).map(|i| (i % 199) as u8).collect();
+        source.insert("row1").column("data", &blob, 1).commit().unwrap();
+
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        source
+            .export_chunked_changeset_since(&VersionVector::new(), &mut store, &config)
+            .unwrap();
+        let after_first = store.len();
+
+        // Re-exporting the same row's existing version again must not
+        // grow the store.
+        source
+            .export_chunked_changeset_since(&VersionVector::new(), &mut store, &config)
+            .unwrap();
+        assert_eq!(store.len(), after_first);
+    }
+
+    #[test]
+    fn column_chunked_stores_large_values_out_of_line_and_small_ones_inline() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        let blob: Vec<u8> = (0..2000).map(|i| (i % 199) as u8).collect();
+
+        table.insert("row1")
+            .column_chunked("data", &blob, &mut store, &config, 64, 1)
+            .column_chunked("tiny", b"ok", &mut store, &config, 64, 1)
+            .commit().unwrap();
+
+        let row = table.get("row1").unwrap().unwrap();
+        assert_eq!(row.get_chunked("data", &store).unwrap(), blob);
+        assert_eq!(row.get_chunked("tiny", &store).unwrap(), b"ok");
+        assert_eq!(row.get("tiny").unwrap(), b"ok", "a value under the threshold must be stored inline, unmarked");
+        assert!(!store.is_empty());
+    }
+
+    #[test]
+    fn gc_chunks_reclaims_only_chunks_no_retained_version_still_references() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        let mut store = ChunkStore::new();
+        let config = ChunkConfig::default();
+        let old_blob: Vec<u8> = (0..2000).map(|i| (i % 199) as u8).collect();
+        let new_blob: Vec<u8> = (0..2000).map(|i| (i * 7 % 233) as u8).collect();
+
+        table.insert("row1").column_chunked("data", &old_blob, &mut store, &config, 64, 1).commit().unwrap();
+        table.update("row1").column_chunked("data", &new_blob, &mut store, &config, 64).commit().unwrap();
+
+        // Both versions are still in the DAG history, so nothing is
+        // collectible yet.
+        assert_eq!(table.gc_chunks(&mut store).unwrap(), 0);
+
+        // Once the history GC drops the old version, its now-unreferenced
+        // chunks become collectible too.
+        table.gc(1).unwrap();
+        let removed = table.gc_chunks(&mut store).unwrap();
+        assert!(removed > 0);
+
+        let row = table.get("row1").unwrap().unwrap();
+        assert_eq!(row.get_chunked("data", &store).unwrap(), new_blob);
+    }
+
+    #[test]
+    fn attach_metrics_records_merge_and_gc_outcomes() {
+        let metrics = std::sync::Arc::new(crate::metrics::Metrics::new());
+
+        let mut source = CrrTable::open_in_memory().unwrap();
+        source.insert("row1").column_str("name", "Alice", 1).commit().unwrap();
+
+        let mut dest = CrrTable::open_in_memory().unwrap();
+        dest.attach_metrics(metrics.clone());
+        let changeset = source.changeset().unwrap();
+        dest.merge(&changeset, TieBreakPolicy::LexicographicMin).unwrap();
+
+        assert_eq!(metrics.cells_inserted.get(), 1);
+        assert!(metrics.render().contains("crr_merge_latency_seconds_count 1"));
+
+        dest.update("row1").column_str("name", "Bob").commit().unwrap();
+        dest.gc(1).unwrap();
+        assert!(metrics.render().contains("crr_gc_nodes_collected_total"));
+    }
+
+    #[test]
+    fn a_dictionary_encoded_column_round_trips_transparently() {
+        let mut table = CrrTable::open_in_memory().unwrap();
+        table.declare_column_encoding("mime_type", ColumnEncoding::Dictionary);
+
+        table.insert("file1").column_str("mime_type", "application/octet-stream", 1).commit().unwrap();
+        table.insert("file2").column_str("mime_type", "application/octet-stream", 1).commit().unwrap();
+        table.insert("file3").column_str("mime_type", "text/plain", 1).commit().unwrap();
+
+        assert_eq!(table.get("file1").unwrap().unwrap().get_string("mime_type").unwrap(), "application/octet-stream");
+        assert_eq!(table.get("file2").unwrap().unwrap().get_string("mime_type").unwrap(), "application/octet-stream");
+        assert_eq!(table.get("file3").unwrap().unwrap().get_string("mime_type").unwrap(), "text/plain");
+
+        table.update("file3").column_str("mime_type", "application/octet-stream").commit().unwrap();
+        assert_eq!(table.get("file3").unwrap().unwrap().get_string("mime_type").unwrap(), "application/octet-stream");
+    }
+}
@@ -0,0 +1,199 @@
+//! A sorted, append-only history of writes to a single column, merged by
+//! walking both sides' entries instead of comparing two bare version
+//! integers. Where [`crate::crr::LegacyCrrTable::crr_merge`] and
+//! [`crate::dag::VersionDag`] key nodes by version number in a `HashMap` and
+//! leave "equal version, different value" as an ad-hoc tiebreak, a
+//! [`VersionList`] keeps every entry it has ever seen, in order, so merging
+//! two lists is a structural fold rather than a single version comparison:
+//! associative and commutative regardless of what order peers exchange
+//! changesets in, and idempotent if the same entry arrives twice.
+//!
+//! A delete is just an entry whose [`EntryState`] is
+//! [`EntryState::Tombstoned`] rather than the row disappearing from the
+//! table outright — [`crate::transactions::TransactionManager::commit`]'s
+//! `TransactionOp::Delete` still removes the row from
+//! [`crate::crr::LegacyCrrTable`] wholesale today, which has no way to let a
+//! concurrent write from another peer resurrect it in the right order.
+//! [`VersionList`] is offered as a standalone building block for that
+//! instead of forcing it into `CrrRow`'s `columns`/`versions` maps, which
+//! `src/ui/demo.rs` reads and writes directly as flat strings.
+
+use serde::{Deserialize, Serialize};
+
+/// How far along a [`VersionEntry`] is. Ordered so that merging two entries
+/// for the same version can just take the max: a tombstone always beats a
+/// commit, and a commit always beats a pending write, no matter which side
+/// of the merge it arrived from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EntryState {
+    Pending,
+    Committed,
+    Tombstoned,
+}
+
+/// A single write to a column: the version it was written at, the value
+/// (irrelevant once `state` is `Tombstoned`, but kept so a tombstoned entry
+/// still records what it overwrote), when it was written, and how far along
+/// it is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionEntry {
+    pub version: u64,
+    pub value: String,
+    pub timestamp: u64,
+    pub state: EntryState,
+}
+
+/// An append-only, version-sorted history for one column. Two replicas that
+/// merge their lists in either order, any number of times, end up with the
+/// same entries.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VersionList {
+    entries: Vec<VersionEntry>,
+}
+
+impl VersionList {
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    pub fn entries(&self) -> &[VersionEntry] {
+        &self.entries
+    }
+
+    /// Fold a single incoming entry into this list: if no entry exists yet
+    /// for its version, insert it in sorted order; if one does, keep the
+    /// higher of the two states (a tombstone or a commit always wins over a
+    /// pending write, idempotently — merging the same entry twice is a
+    /// no-op).
+    pub fn merge_entry(&mut self, incoming: VersionEntry) {
+        match self.entries.binary_search_by(|e| e.version.cmp(&incoming.version)) {
+            Ok(index) => {
+                let existing = &mut self.entries[index];
+                if incoming.state > existing.state {
+                    *existing = incoming;
+                }
+            }
+            Err(index) => self.entries.insert(index, incoming),
+        }
+    }
+
+    /// Merge every entry from `other` into this list.
+    pub fn merge(&mut self, other: &VersionList) {
+        for entry in &other.entries {
+            self.merge_entry(entry.clone());
+        }
+    }
+
+    /// The materialized current value: the highest-version entry that has
+    /// reached `Committed` (a `Tombstoned` entry has no visible value; a
+    /// `Pending` one isn't settled yet).
+    pub fn current(&self) -> Option<&VersionEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.state != EntryState::Pending)
+            .filter(|e| e.state == EntryState::Committed)
+    }
+
+    /// Whether the column's latest settled entry is a delete.
+    pub fn is_tombstoned(&self) -> bool {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| e.state != EntryState::Pending)
+            .is_some_and(|e| e.state == EntryState::Tombstoned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(version: u64, value: &str, state: EntryState) -> VersionEntry {
+        VersionEntry { version, value: value.to_string(), timestamp: version, state }
+    }
+
+    #[test]
+    fn merging_inserts_a_new_version_in_sorted_order() {
+        let mut list = VersionList::new();
+        list.merge_entry(entry(3, "c", EntryState::Committed));
+        list.merge_entry(entry(1, "a", EntryState::Committed));
+        list.merge_entry(entry(2, "b", EntryState::Committed));
+
+        let versions: Vec<u64> = list.entries().iter().map(|e| e.version).collect();
+        assert_eq!(versions, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn merging_the_same_version_keeps_the_higher_state() {
+        let mut list = VersionList::new();
+        list.merge_entry(entry(1, "draft", EntryState::Pending));
+        list.merge_entry(entry(1, "draft", EntryState::Committed));
+        assert_eq!(list.entries()[0].state, EntryState::Committed);
+
+        // A later merge carrying the same pending entry again is a no-op.
+        list.merge_entry(entry(1, "draft", EntryState::Pending));
+        assert_eq!(list.entries()[0].state, EntryState::Committed);
+    }
+
+    #[test]
+    fn a_tombstone_beats_a_commit_regardless_of_merge_order() {
+        let mut a = VersionList::new();
+        a.merge_entry(entry(1, "alice", EntryState::Committed));
+        let mut b = VersionList::new();
+        b.merge_entry(entry(1, "alice", EntryState::Tombstoned));
+
+        let mut merged_a_then_b = a.clone();
+        merged_a_then_b.merge(&b);
+        let mut merged_b_then_a = b.clone();
+        merged_b_then_a.merge(&a);
+
+        assert_eq!(merged_a_then_b, merged_b_then_a, "merging is commutative");
+        assert_eq!(merged_a_then_b.entries()[0].state, EntryState::Tombstoned);
+    }
+
+    #[test]
+    fn merging_is_associative_and_idempotent() {
+        let mut a = VersionList::new();
+        a.merge_entry(entry(1, "a", EntryState::Committed));
+        let mut b = VersionList::new();
+        b.merge_entry(entry(2, "b", EntryState::Committed));
+        let mut c = VersionList::new();
+        c.merge_entry(entry(2, "b", EntryState::Tombstoned));
+
+        let mut ab_then_c = a.clone();
+        ab_then_c.merge(&b);
+        ab_then_c.merge(&c);
+
+        let mut bc = b.clone();
+        bc.merge(&c);
+        let mut a_then_bc = a.clone();
+        a_then_bc.merge(&bc);
+
+        assert_eq!(ab_then_c, a_then_bc, "merging is associative");
+
+        let mut merged_twice = ab_then_c.clone();
+        merged_twice.merge(&ab_then_c.clone());
+        assert_eq!(merged_twice, ab_then_c, "merging the same state again is a no-op");
+    }
+
+    #[test]
+    fn current_is_the_highest_version_committed_entry() {
+        let mut list = VersionList::new();
+        list.merge_entry(entry(1, "a", EntryState::Committed));
+        list.merge_entry(entry(2, "b", EntryState::Committed));
+        list.merge_entry(entry(3, "c", EntryState::Pending));
+
+        assert_eq!(list.current().unwrap().value, "b");
+    }
+
+    #[test]
+    fn current_is_none_once_the_latest_write_is_tombstoned() {
+        let mut list = VersionList::new();
+        list.merge_entry(entry(1, "a", EntryState::Committed));
+        list.merge_entry(entry(2, "a", EntryState::Tombstoned));
+
+        assert!(list.current().is_none());
+        assert!(list.is_tombstoned());
+    }
+}
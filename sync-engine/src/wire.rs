@@ -0,0 +1,99 @@
+//! Length-prefixed binary encoding helpers shared by the snapshot and
+//! changeset wire formats.
+
+use crate::error::{Error, Result};
+
+pub(crate) fn write_u32(buf: &mut Vec<u8>, v: u32) {
+    buf.extend_from_slice(&v.to_le_bytes());
+}
+
+pub(crate) fn write_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    write_u32(buf, bytes.len() as u32);
+    buf.extend_from_slice(bytes);
+}
+
+pub(crate) fn write_option_u64(buf: &mut Vec<u8>, v: Option<u64>) {
+    match v {
+        Some(v) => {
+            buf.push(1);
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        None => buf.push(0),
+    }
+}
+
+fn truncated() -> Error {
+    Error::InvalidState("truncated wire data".to_string())
+}
+
+pub(crate) fn read_u8(bytes: &[u8], cursor: &mut usize) -> Result<u8> {
+    let b = *bytes.get(*cursor).ok_or_else(truncated)?;
+    *cursor += 1;
+    Ok(b)
+}
+
+pub(crate) fn read_u32(bytes: &[u8], cursor: &mut usize) -> Result<u32> {
+    let end = *cursor + 4;
+    let slice = bytes.get(*cursor..end).ok_or_else(truncated)?;
+    *cursor = end;
+    Ok(u32::from_le_bytes(slice.try_into().unwrap()))
+}
+
+pub(crate) fn read_u64(bytes: &[u8], cursor: &mut usize) -> Result<u64> {
+    let end = *cursor + 8;
+    let slice = bytes.get(*cursor..end).ok_or_else(truncated)?;
+    *cursor = end;
+    Ok(u64::from_le_bytes(slice.try_into().unwrap()))
+}
+
+pub(crate) fn read_option_u64(bytes: &[u8], cursor: &mut usize) -> Result<Option<u64>> {
+    match read_u8(bytes, cursor)? {
+        0 => Ok(None),
+        _ => Ok(Some(read_u64(bytes, cursor)?)),
+    }
+}
+
+pub(crate) fn read_bytes(bytes: &[u8], cursor: &mut usize) -> Result<Vec<u8>> {
+    let len = read_u32(bytes, cursor)? as usize;
+    let end = *cursor + len;
+    let slice = bytes.get(*cursor..end).ok_or_else(truncated)?;
+    *cursor = end;
+    Ok(slice.to_vec())
+}
+
+pub(crate) fn read_string(bytes: &[u8], cursor: &mut usize) -> Result<String> {
+    String::from_utf8(read_bytes(bytes, cursor)?)
+        .map_err(|e| Error::InvalidState(format!("invalid utf8 in wire data: {}", e)))
+}
+
+const CRC32C_POLY: u32 = 0x82f63b78;
+
+fn crc32c_table() -> [u32; 256] {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ CRC32C_POLY } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// CRC-32 using the Castagnoli polynomial (as used by iSCSI/SCTP), over
+/// `bytes`. Used to detect bit flips on the wire, not for cryptographic
+/// integrity — pair with [`crate::secure::SecureChangeset`] if tamper
+/// resistance against an active attacker is needed.
+pub(crate) fn crc32c(bytes: &[u8]) -> u32 {
+    let table = crc32c_table();
+    let mut crc = !0u32;
+    for &byte in bytes {
+        let idx = ((crc ^ byte as u32) & 0xff) as usize;
+        crc = (crc >> 8) ^ table[idx];
+    }
+    !crc
+}